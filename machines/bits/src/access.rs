@@ -0,0 +1,75 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Get -------------------------------------------------------------------------
+
+// bits/get(x, i) is true when bit i of x is set, counting from the least
+// significant bit at 0.
+impl_bits_fxns!($, BitGet, BitsInt::bits_get, bool);
+
+fn impl_bits_get_fxn(lhs_value: Value, rhs_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_binop_match_arms!(
+    BitGet,
+    (lhs_value, rhs_value),
+    I8,   bool, "i8";
+    I16,  bool, "i16";
+    I32,  bool, "i32";
+    I64,  bool, "i64";
+    I128, bool, "i128";
+    U8,   bool, "u8";
+    U16,  bool, "u16";
+    U32,  bool, "u32";
+    U64,  bool, "u64";
+    U128, bool, "u128";
+  )
+}
+
+impl_mech_binop_fxn!(BitsGet,impl_bits_get_fxn,"bits/get");
+
+// Set -------------------------------------------------------------------------
+
+impl_bits_fxns!($, BitSet, BitsInt::bits_set);
+
+fn impl_bits_set_fxn(lhs_value: Value, rhs_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_binop_match_arms!(
+    BitSet,
+    (lhs_value, rhs_value),
+    I8,   i8,   "i8";
+    I16,  i16,  "i16";
+    I32,  i32,  "i32";
+    I64,  i64,  "i64";
+    I128, i128, "i128";
+    U8,   u8,   "u8";
+    U16,  u16,  "u16";
+    U32,  u32,  "u32";
+    U64,  u64,  "u64";
+    U128, u128, "u128";
+  )
+}
+
+impl_mech_binop_fxn!(BitsSet,impl_bits_set_fxn,"bits/set");
+
+// Clear -----------------------------------------------------------------------
+
+impl_bits_fxns!($, BitClear, BitsInt::bits_clear);
+
+fn impl_bits_clear_fxn(lhs_value: Value, rhs_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_binop_match_arms!(
+    BitClear,
+    (lhs_value, rhs_value),
+    I8,   i8,   "i8";
+    I16,  i16,  "i16";
+    I32,  i32,  "i32";
+    I64,  i64,  "i64";
+    I128, i128, "i128";
+    U8,   u8,   "u8";
+    U16,  u16,  "u16";
+    U32,  u32,  "u32";
+    U64,  u64,  "u64";
+    U128, u128, "u128";
+  )
+}
+
+impl_mech_binop_fxn!(BitsClear,impl_bits_clear_fxn,"bits/clear");
@@ -0,0 +1,27 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// And -------------------------------------------------------------------------
+
+impl_bits_fxns!($, BitAnd, BitAnd::bitand);
+
+fn impl_bits_and_fxn(lhs_value: Value, rhs_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_binop_match_arms!(
+    BitAnd,
+    (lhs_value, rhs_value),
+    I8,   i8,   "i8";
+    I16,  i16,  "i16";
+    I32,  i32,  "i32";
+    I64,  i64,  "i64";
+    I128, i128, "i128";
+    U8,   u8,   "u8";
+    U16,  u16,  "u16";
+    U32,  u32,  "u32";
+    U64,  u64,  "u64";
+    U128, u128, "u128";
+  )
+}
+
+impl_mech_binop_fxn!(BitsAnd,impl_bits_and_fxn,"bits/and");
@@ -0,0 +1,75 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Counts are given in the kind of the argument.
+
+// Popcount --------------------------------------------------------------------
+
+impl_bits_unop_fxns!($, PopCount, BitsInt::bits_popcount);
+
+fn impl_bits_popcount_fxn(arg_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_urnop_match_arms!(
+    PopCount,
+    (arg_value),
+    I8 => MatrixI8, "i8";
+    I16 => MatrixI16, "i16";
+    I32 => MatrixI32, "i32";
+    I64 => MatrixI64, "i64";
+    I128 => MatrixI128, "i128";
+    U8 => MatrixU8, "u8";
+    U16 => MatrixU16, "u16";
+    U32 => MatrixU32, "u32";
+    U64 => MatrixU64, "u64";
+    U128 => MatrixU128, "u128";
+  )
+}
+
+impl_mech_urnop_fxn!(BitsPopcount,impl_bits_popcount_fxn,"bits/popcount");
+
+// Leading Zeros ---------------------------------------------------------------
+
+impl_bits_unop_fxns!($, LeadingZeros, BitsInt::bits_leading_zeros);
+
+fn impl_bits_leading_zeros_fxn(arg_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_urnop_match_arms!(
+    LeadingZeros,
+    (arg_value),
+    I8 => MatrixI8, "i8";
+    I16 => MatrixI16, "i16";
+    I32 => MatrixI32, "i32";
+    I64 => MatrixI64, "i64";
+    I128 => MatrixI128, "i128";
+    U8 => MatrixU8, "u8";
+    U16 => MatrixU16, "u16";
+    U32 => MatrixU32, "u32";
+    U64 => MatrixU64, "u64";
+    U128 => MatrixU128, "u128";
+  )
+}
+
+impl_mech_urnop_fxn!(BitsLeadingZeros,impl_bits_leading_zeros_fxn,"bits/leading-zeros");
+
+// Trailing Zeros --------------------------------------------------------------
+
+impl_bits_unop_fxns!($, TrailingZeros, BitsInt::bits_trailing_zeros);
+
+fn impl_bits_trailing_zeros_fxn(arg_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_urnop_match_arms!(
+    TrailingZeros,
+    (arg_value),
+    I8 => MatrixI8, "i8";
+    I16 => MatrixI16, "i16";
+    I32 => MatrixI32, "i32";
+    I64 => MatrixI64, "i64";
+    I128 => MatrixI128, "i128";
+    U8 => MatrixU8, "u8";
+    U16 => MatrixU16, "u16";
+    U32 => MatrixU32, "u32";
+    U64 => MatrixU64, "u64";
+    U128 => MatrixU128, "u128";
+  )
+}
+
+impl_mech_urnop_fxn!(BitsTrailingZeros,impl_bits_trailing_zeros_fxn,"bits/trailing-zeros");
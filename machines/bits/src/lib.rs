@@ -0,0 +1,496 @@
+#![no_main]
+#![allow(warnings)]
+#[macro_use]
+extern crate mech_core;
+#[cfg(feature = "matrix")]
+extern crate nalgebra as na;
+extern crate paste;
+
+use mech_core::*;
+
+#[cfg(feature = "vector3")]
+use nalgebra::Vector3;
+#[cfg(feature = "vectord")]
+use nalgebra::DVector;
+#[cfg(feature = "vector2")]
+use nalgebra::Vector2;
+#[cfg(feature = "vector4")]
+use nalgebra::Vector4;
+#[cfg(feature = "row_vectord")]
+use nalgebra::RowDVector;
+#[cfg(feature = "matrix1")]
+use nalgebra::Matrix1;
+#[cfg(feature = "matrix3")]
+use nalgebra::Matrix3;
+#[cfg(feature = "matrix4")]
+use nalgebra::Matrix4;
+#[cfg(feature = "row_vector3")]
+use nalgebra::RowVector3;
+#[cfg(feature = "row_vector4")]
+use nalgebra::RowVector4;
+#[cfg(feature = "row_vector2")]
+use nalgebra::RowVector2;
+#[cfg(feature = "matrixd")]
+use nalgebra::DMatrix;
+#[cfg(feature = "matrix2x3")]
+use nalgebra::Matrix2x3;
+#[cfg(feature = "matrix3x2")]
+use nalgebra::Matrix3x2;
+#[cfg(feature = "matrix2")]
+use nalgebra::Matrix2;
+
+use paste::paste;
+use std::fmt::Debug;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+#[cfg(feature = "and")]
+pub mod and;
+#[cfg(feature = "or")]
+pub mod or;
+#[cfg(feature = "xor")]
+pub mod xor;
+#[cfg(feature = "not")]
+pub mod not;
+#[cfg(feature = "shift")]
+pub mod shift;
+#[cfg(feature = "rotate")]
+pub mod rotate;
+#[cfg(feature = "count")]
+pub mod count;
+#[cfg(feature = "access")]
+pub mod access;
+
+#[cfg(feature = "and")]
+pub use self::and::*;
+#[cfg(feature = "or")]
+pub use self::or::*;
+#[cfg(feature = "xor")]
+pub use self::xor::*;
+#[cfg(feature = "not")]
+pub use self::not::*;
+#[cfg(feature = "shift")]
+pub use self::shift::*;
+#[cfg(feature = "rotate")]
+pub use self::rotate::*;
+#[cfg(feature = "count")]
+pub use self::count::*;
+#[cfg(feature = "access")]
+pub use self::access::*;
+
+// ----------------------------------------------------------------------------
+// Bits Library
+// ----------------------------------------------------------------------------
+
+// Bitwise operations on the signed and unsigned integer kinds. Shift amounts
+// and bit indices are integers of the same kind as the value, counted from
+// the least significant bit. Shifting by the width of the kind or more, or by
+// a negative amount, shifts every bit out. Rotations wrap around, so a
+// negative amount rotates the other way. Bit indices outside the value read
+// as false and leave it unchanged when set or cleared.
+
+pub trait BitsInt: Copy + Debug + Clone + Sync + Send + PartialEq + PartialOrd + 'static +
+  BitAnd<Output = Self> + BitOr<Output = Self> + BitXor<Output = Self> + Not<Output = Self>
+{
+  fn bits_shl(self, n: Self) -> Self;
+  fn bits_shr(self, n: Self) -> Self;
+  fn bits_rotl(self, n: Self) -> Self;
+  fn bits_rotr(self, n: Self) -> Self;
+  fn bits_popcount(self) -> Self;
+  fn bits_leading_zeros(self) -> Self;
+  fn bits_trailing_zeros(self) -> Self;
+  fn bits_get(self, i: Self) -> bool;
+  fn bits_set(self, i: Self) -> Self;
+  fn bits_clear(self, i: Self) -> Self;
+}
+
+macro_rules! impl_bits_int {
+  ($($type:ty),+ $(,)?) => {
+    $(
+      impl BitsInt for $type {
+        fn bits_shl(self, n: Self) -> Self {
+          match bits_index::<$type>(n) {
+            Some(n) => self << n,
+            None => 0,
+          }
+        }
+        fn bits_shr(self, n: Self) -> Self {
+          match bits_index::<$type>(n) {
+            Some(n) => self >> n,
+            // Arithmetic for signed kinds, so everything shifts out to the sign.
+            None => (self >> (<$type>::BITS - 1)) >> 1,
+          }
+        }
+        fn bits_rotl(self, n: Self) -> Self {
+          self.rotate_left(n.rem_euclid(<$type>::BITS as $type) as u32)
+        }
+        fn bits_rotr(self, n: Self) -> Self {
+          self.rotate_right(n.rem_euclid(<$type>::BITS as $type) as u32)
+        }
+        fn bits_popcount(self) -> Self { self.count_ones() as $type }
+        fn bits_leading_zeros(self) -> Self { self.leading_zeros() as $type }
+        fn bits_trailing_zeros(self) -> Self { self.trailing_zeros() as $type }
+        fn bits_get(self, i: Self) -> bool {
+          match bits_index::<$type>(i) {
+            Some(i) => (self >> i) & 1 == 1,
+            None => false,
+          }
+        }
+        fn bits_set(self, i: Self) -> Self {
+          match bits_index::<$type>(i) {
+            Some(i) => self | (1 << i),
+            None => self,
+          }
+        }
+        fn bits_clear(self, i: Self) -> Self {
+          match bits_index::<$type>(i) {
+            Some(i) => self & !(1 << i),
+            None => self,
+          }
+        }
+      }
+    )+
+  };
+}
+
+impl_bits_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+// A shift amount or bit index that falls inside a value of the kind.
+pub fn bits_index<T>(n: T) -> Option<u32>
+where
+  u32: TryFrom<T>,
+{
+  let width = (std::mem::size_of::<T>() * 8) as u32;
+  u32::try_from(n).ok().filter(|n| *n < width)
+}
+
+#[macro_export]
+macro_rules! impl_bits_binop {
+  ($struct_name:ident, $arg1_type:ty, $arg2_type:ty, $out_type:ty, $op:ident, $feature_flag:expr) => {
+    #[derive(Debug)]
+    struct $struct_name<T> {
+      lhs: Ref<$arg1_type>,
+      rhs: Ref<$arg2_type>,
+      out: Ref<$out_type>,
+    }
+    impl<T> MechFunctionFactory for $struct_name<T>
+    where
+      T: BitsInt + ConstElem + CompileConst + AsValueKind,
+      Ref<$out_type>: ToValue
+    {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Binary(out, arg1, arg2) => {
+            let lhs: Ref<$arg1_type> = unsafe { arg1.as_unchecked() }.clone();
+            let rhs: Ref<$arg2_type> = unsafe { arg2.as_unchecked() }.clone();
+            let out: Ref<$out_type> = unsafe { out.as_unchecked() }.clone();
+            Ok(Box::new(Self {lhs, rhs, out }))
+          },
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 2, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl<T> MechFunctionImpl for $struct_name<T>
+    where
+      T: BitsInt,
+      Ref<$out_type>: ToValue
+    {
+    fn solve(&self) {
+      let lhs_ptr = self.lhs.as_ptr();
+      let rhs_ptr = self.rhs.as_ptr();
+      let out_ptr = self.out.as_mut_ptr();
+      $op!(lhs_ptr,rhs_ptr,out_ptr);
+    }
+    fn out(&self) -> Value { self.out.to_value() }
+    fn to_string(&self) -> String { format!("{:#?}", self) }
+  }
+  #[cfg(feature = "compiler")]
+  impl<T> MechFunctionCompiler for $struct_name<T>
+  where
+    T: ConstElem + CompileConst + AsValueKind
+  {
+    fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+      let name = format!("{}<{}>", stringify!($struct_name), T::as_value_kind());
+      compile_binop!(name, self.out, self.lhs, self.rhs, ctx, $feature_flag);
+    }
+  }
+  register_fxn_descriptor!($struct_name, u8, "u8", u16, "u16", u32, "u32", u64, "u64", u128, "u128", i8, "i8", i16, "i16", i32, "i32", i64, "i64", i128, "i128");
+};}
+
+// The descriptors for every integer kind are registered along with each
+// function by impl_bits_binop.
+#[macro_export]
+macro_rules! register_bits_descriptor {
+  ($struct_name:ident, $type:ty, $type_string:tt) => {};
+}
+
+// Defines the element-wise op macros that impl_fxns expects for a binary
+// function, from a function that combines two elements. It is called with a
+// `$` so that the macros it defines can take arguments.
+#[macro_export]
+macro_rules! impl_bits_binop_ops {
+  ($d:tt, $lib:ident, $fxn:path) => {
+    paste!{
+      macro_rules! [<$lib:lower _op>] {
+        ($d lhs:expr, $d rhs:expr, $d out:expr) => {
+          unsafe { *$d out = $fxn(*$d lhs, *$d rhs); }
+        };}
+
+      macro_rules! [<$lib:lower _vec_op>] {
+        ($d lhs:expr, $d rhs:expr, $d out:expr) => {
+          unsafe {
+            let lhs_deref = &(*$d lhs);
+            let rhs_deref = &(*$d rhs);
+            let mut out_deref = &mut (*$d out);
+            for i in 0..lhs_deref.len() {
+              out_deref[i] = $fxn(lhs_deref[i], rhs_deref[i]);
+            }
+          }
+        };}
+
+      macro_rules! [<$lib:lower _scalar_lhs_op>] {
+        ($d lhs:expr, $d rhs:expr, $d out:expr) => {
+          unsafe {
+            let lhs_deref = &(*$d lhs);
+            let rhs_deref = *$d rhs;
+            let mut out_deref = &mut (*$d out);
+            for i in 0..lhs_deref.len() {
+              out_deref[i] = $fxn(lhs_deref[i], rhs_deref);
+            }
+          }
+        };}
+
+      macro_rules! [<$lib:lower _scalar_rhs_op>] {
+        ($d lhs:expr, $d rhs:expr, $d out:expr) => {
+          unsafe {
+            let lhs_deref = *$d lhs;
+            let rhs_deref = &(*$d rhs);
+            let mut out_deref = &mut (*$d out);
+            for i in 0..rhs_deref.len() {
+              out_deref[i] = $fxn(lhs_deref, rhs_deref[i]);
+            }
+          }
+        };}
+
+      macro_rules! [<$lib:lower _mat_vec_op>] {
+        ($d lhs:expr, $d rhs:expr, $d out:expr) => {
+          unsafe {
+            let mut out_deref = &mut (*$d out);
+            let lhs_deref = &(*$d lhs);
+            let rhs_deref = &(*$d rhs);
+            for (mut col, lhs_col) in out_deref.column_iter_mut().zip(lhs_deref.column_iter()) {
+              for i in 0..col.len() {
+                col[i] = $fxn(lhs_col[i], rhs_deref[i]);
+              }
+            }
+          }
+        };}
+
+      macro_rules! [<$lib:lower _vec_mat_op>] {
+        ($d lhs:expr, $d rhs:expr, $d out:expr) => {
+          unsafe {
+            let mut out_deref = &mut (*$d out);
+            let lhs_deref = &(*$d lhs);
+            let rhs_deref = &(*$d rhs);
+            for (mut col, rhs_col) in out_deref.column_iter_mut().zip(rhs_deref.column_iter()) {
+              for i in 0..col.len() {
+                col[i] = $fxn(lhs_deref[i], rhs_col[i]);
+              }
+            }
+          }
+        };}
+
+      macro_rules! [<$lib:lower _mat_row_op>] {
+        ($d lhs:expr, $d rhs:expr, $d out:expr) => {
+          unsafe {
+            let mut out_deref = &mut (*$d out);
+            let lhs_deref = &(*$d lhs);
+            let rhs_deref = &(*$d rhs);
+            for (mut row, lhs_row) in out_deref.row_iter_mut().zip(lhs_deref.row_iter()) {
+              for i in 0..row.len() {
+                row[i] = $fxn(lhs_row[i], rhs_deref[i]);
+              }
+            }
+          }
+        };}
+
+      macro_rules! [<$lib:lower _row_mat_op>] {
+        ($d lhs:expr, $d rhs:expr, $d out:expr) => {
+          unsafe {
+            let mut out_deref = &mut (*$d out);
+            let lhs_deref = &(*$d lhs);
+            let rhs_deref = &(*$d rhs);
+            for (mut row, rhs_row) in out_deref.row_iter_mut().zip(rhs_deref.row_iter()) {
+              for i in 0..row.len() {
+                row[i] = $fxn(lhs_deref[i], rhs_row[i]);
+              }
+            }
+          }
+        };}
+    }
+  };
+}
+
+// A binary bits function over every integer kind and matrix shape, where the
+// result has the kind of the arguments, or is a bool. Like the op macros, it is
+// called with a leading `$`.
+#[macro_export]
+macro_rules! impl_bits_fxns {
+  ($d:tt, $lib:ident, $fxn:path) => {
+    impl_bits_binop_ops!($d, $lib, $fxn);
+    impl_fxns!($lib,T,T,impl_bits_binop);
+  };
+  ($d:tt, $lib:ident, $fxn:path, bool) => {
+    impl_bits_binop_ops!($d, $lib, $fxn);
+    impl_fxns!($lib,T,bool,impl_bits_binop);
+  };
+}
+
+#[macro_export]
+macro_rules! impl_bits_binop_match_arms {
+  ($lib:ident, $arg:expr, $($lhs_type:ident, $target_type:ident, $value_string:tt);+ $(;)?) => {
+    impl_binop_match_arms!(
+      $lib,
+      register_bits_descriptor,
+      $arg,
+      $($lhs_type, $target_type, $value_string;)+
+    )
+  };
+}
+
+// Unary bits functions are defined for each kind and matrix shape, like the
+// math functions, from a function of one element.
+#[macro_export]
+macro_rules! impl_bits_unop {
+  ($lib:ident, $type:ident) => {
+    paste!{
+      impl_unop!([<$lib $type:camel S>], $type, $type, [<$lib:lower _op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "matrix1")]
+      impl_unop!([<$lib $type:camel M1>], Matrix1<$type>, Matrix1<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "matrix2")]
+      impl_unop!([<$lib $type:camel M2>], Matrix2<$type>, Matrix2<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "matrix3")]
+      impl_unop!([<$lib $type:camel M3>], Matrix3<$type>, Matrix3<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "matrix4")]
+      impl_unop!([<$lib $type:camel M4>], Matrix4<$type>, Matrix4<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "matrix2x3")]
+      impl_unop!([<$lib $type:camel M2x3>], Matrix2x3<$type>, Matrix2x3<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "matrix3x2")]
+      impl_unop!([<$lib $type:camel M3x2>], Matrix3x2<$type>, Matrix3x2<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "matrixd")]
+      impl_unop!([<$lib $type:camel MD>], DMatrix<$type>, DMatrix<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "row_vector2")]
+      impl_unop!([<$lib $type:camel R2>], RowVector2<$type>, RowVector2<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "row_vector3")]
+      impl_unop!([<$lib $type:camel R3>], RowVector3<$type>, RowVector3<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "row_vector4")]
+      impl_unop!([<$lib $type:camel R4>], RowVector4<$type>, RowVector4<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "row_vectord")]
+      impl_unop!([<$lib $type:camel RD>], RowDVector<$type>, RowDVector<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "vector2")]
+      impl_unop!([<$lib $type:camel V2>], Vector2<$type>, Vector2<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "vector3")]
+      impl_unop!([<$lib $type:camel V3>], Vector3<$type>, Vector3<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "vector4")]
+      impl_unop!([<$lib $type:camel V4>], Vector4<$type>, Vector4<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+      #[cfg(feature = "vectord")]
+      impl_unop!([<$lib $type:camel VD>], DVector<$type>, DVector<$type>, [<$lib:lower _vec_op>], FeatureFlag::Builtin(FeatureKind::$lib));
+    }
+  };
+}
+
+#[macro_export]
+macro_rules! impl_bits_unop_fxns {
+  ($d:tt, $lib:ident, $fxn:path) => {
+    paste!{
+      macro_rules! [<$lib:lower _op>] {
+        ($d arg:expr, $d out:expr) => {
+          unsafe { *$d out = $fxn(*$d arg); }
+        };}
+
+      macro_rules! [<$lib:lower _vec_op>] {
+        ($d arg:expr, $d out:expr) => {
+          unsafe {
+            let arg_deref = &(*$d arg);
+            let mut out_deref = &mut (*$d out);
+            for i in 0..arg_deref.len() {
+              out_deref[i] = $fxn(arg_deref[i]);
+            }
+          }
+        };}
+    }
+    #[cfg(feature = "u8")]
+    impl_bits_unop!($lib, u8);
+    #[cfg(feature = "u16")]
+    impl_bits_unop!($lib, u16);
+    #[cfg(feature = "u32")]
+    impl_bits_unop!($lib, u32);
+    #[cfg(feature = "u64")]
+    impl_bits_unop!($lib, u64);
+    #[cfg(feature = "u128")]
+    impl_bits_unop!($lib, u128);
+    #[cfg(feature = "i8")]
+    impl_bits_unop!($lib, i8);
+    #[cfg(feature = "i16")]
+    impl_bits_unop!($lib, i16);
+    #[cfg(feature = "i32")]
+    impl_bits_unop!($lib, i32);
+    #[cfg(feature = "i64")]
+    impl_bits_unop!($lib, i64);
+    #[cfg(feature = "i128")]
+    impl_bits_unop!($lib, i128);
+  };
+}
+
+#[macro_export]
+macro_rules! impl_bits_urnop_match_arms {
+  ($lib:ident, $arg:expr, $($lhs_type:ident => $matrix_kind:ident, $value_string:tt);+ $(;)?) => {
+    paste!{
+      match $arg {
+        $(
+            #[cfg(feature = $value_string)]
+            (Value::$lhs_type(arg)) => Ok(Box::new([<$lib $lhs_type:camel S>]{arg: arg.clone(), out: Ref::new(0) })),
+            #[cfg(all(feature = $value_string, feature = "matrix1"))]
+            (Value::$matrix_kind(Matrix::Matrix1(arg))) => Ok(Box::new([<$lib $lhs_type:camel M1>]{arg, out: Ref::new(Matrix1::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "matrix2"))]
+            (Value::$matrix_kind(Matrix::Matrix2(arg))) => Ok(Box::new([<$lib $lhs_type:camel M2>]{arg, out: Ref::new(Matrix2::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "matrix3"))]
+            (Value::$matrix_kind(Matrix::Matrix3(arg))) => Ok(Box::new([<$lib $lhs_type:camel M3>]{arg, out: Ref::new(Matrix3::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "matrix4"))]
+            (Value::$matrix_kind(Matrix::Matrix4(arg))) => Ok(Box::new([<$lib $lhs_type:camel M4>]{arg, out: Ref::new(Matrix4::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "matrix2x3"))]
+            (Value::$matrix_kind(Matrix::Matrix2x3(arg))) => Ok(Box::new([<$lib $lhs_type:camel M2x3>]{arg, out: Ref::new(Matrix2x3::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "matrix3x2"))]
+            (Value::$matrix_kind(Matrix::Matrix3x2(arg))) => Ok(Box::new([<$lib $lhs_type:camel M3x2>]{arg, out: Ref::new(Matrix3x2::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "matrixd"))]
+            (Value::$matrix_kind(Matrix::DMatrix(arg))) => {
+              let (rows,cols) = {arg.borrow().shape()};
+              Ok(Box::new([<$lib $lhs_type:camel MD>]{arg, out: Ref::new(DMatrix::from_element(rows, cols, 0))}))},
+            #[cfg(all(feature = $value_string, feature = "row_vector2"))]
+            (Value::$matrix_kind(Matrix::RowVector2(arg))) => Ok(Box::new([<$lib $lhs_type:camel R2>]{arg, out: Ref::new(RowVector2::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "row_vector3"))]
+            (Value::$matrix_kind(Matrix::RowVector3(arg))) => Ok(Box::new([<$lib $lhs_type:camel R3>]{arg, out: Ref::new(RowVector3::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "row_vector4"))]
+            (Value::$matrix_kind(Matrix::RowVector4(arg))) => Ok(Box::new([<$lib $lhs_type:camel R4>]{arg, out: Ref::new(RowVector4::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "row_vectord"))]
+            (Value::$matrix_kind(Matrix::RowDVector(arg))) => Ok(Box::new([<$lib $lhs_type:camel RD>]{arg: arg.clone(), out: Ref::new(RowDVector::from_element(arg.borrow().len(), 0))})),
+            #[cfg(all(feature = $value_string, feature = "vector2"))]
+            (Value::$matrix_kind(Matrix::Vector2(arg))) => Ok(Box::new([<$lib $lhs_type:camel V2>]{arg, out: Ref::new(Vector2::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "vector3"))]
+            (Value::$matrix_kind(Matrix::Vector3(arg))) => Ok(Box::new([<$lib $lhs_type:camel V3>]{arg, out: Ref::new(Vector3::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "vector4"))]
+            (Value::$matrix_kind(Matrix::Vector4(arg))) => Ok(Box::new([<$lib $lhs_type:camel V4>]{arg, out: Ref::new(Vector4::from_element(0))})),
+            #[cfg(all(feature = $value_string, feature = "vectord"))]
+            (Value::$matrix_kind(Matrix::DVector(arg))) => Ok(Box::new([<$lib $lhs_type:camel VD>]{arg: arg.clone(), out: Ref::new(DVector::from_element(arg.borrow().len(), 0))})),
+        )+
+        x => Err(MechError::new(
+          UnhandledFunctionArgumentKind1{arg: x.kind(), fxn_name: stringify!($lib).to_string()},
+          None
+        ).with_compiler_loc()),
+      }
+    }
+  };
+}
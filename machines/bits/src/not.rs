@@ -0,0 +1,27 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Not -------------------------------------------------------------------------
+
+impl_bits_unop_fxns!($, BitNot, Not::not);
+
+fn impl_bits_not_fxn(arg_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_urnop_match_arms!(
+    BitNot,
+    (arg_value),
+    I8 => MatrixI8, "i8";
+    I16 => MatrixI16, "i16";
+    I32 => MatrixI32, "i32";
+    I64 => MatrixI64, "i64";
+    I128 => MatrixI128, "i128";
+    U8 => MatrixU8, "u8";
+    U16 => MatrixU16, "u16";
+    U32 => MatrixU32, "u32";
+    U64 => MatrixU64, "u64";
+    U128 => MatrixU128, "u128";
+  )
+}
+
+impl_mech_urnop_fxn!(BitsNot,impl_bits_not_fxn,"bits/not");
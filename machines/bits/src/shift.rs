@@ -0,0 +1,53 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Shift Left ------------------------------------------------------------------
+
+// Shifting by the width of the kind or more clears every bit.
+impl_bits_fxns!($, ShiftLeft, BitsInt::bits_shl);
+
+fn impl_bits_shift_left_fxn(lhs_value: Value, rhs_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_binop_match_arms!(
+    ShiftLeft,
+    (lhs_value, rhs_value),
+    I8,   i8,   "i8";
+    I16,  i16,  "i16";
+    I32,  i32,  "i32";
+    I64,  i64,  "i64";
+    I128, i128, "i128";
+    U8,   u8,   "u8";
+    U16,  u16,  "u16";
+    U32,  u32,  "u32";
+    U64,  u64,  "u64";
+    U128, u128, "u128";
+  )
+}
+
+impl_mech_binop_fxn!(BitsShiftLeft,impl_bits_shift_left_fxn,"bits/shift-left");
+
+// Shift Right -----------------------------------------------------------------
+
+// Signed kinds shift in copies of the sign bit, so by the width of the kind
+// or more a negative value becomes -1.
+impl_bits_fxns!($, ShiftRight, BitsInt::bits_shr);
+
+fn impl_bits_shift_right_fxn(lhs_value: Value, rhs_value: Value) -> MResult<Box<dyn MechFunction>> {
+  impl_bits_binop_match_arms!(
+    ShiftRight,
+    (lhs_value, rhs_value),
+    I8,   i8,   "i8";
+    I16,  i16,  "i16";
+    I32,  i32,  "i32";
+    I64,  i64,  "i64";
+    I128, i128, "i128";
+    U8,   u8,   "u8";
+    U16,  u16,  "u16";
+    U32,  u32,  "u32";
+    U64,  u64,  "u64";
+    U128, u128, "u128";
+  )
+}
+
+impl_mech_binop_fxn!(BitsShiftRight,impl_bits_shift_right_fxn,"bits/shift-right");
@@ -0,0 +1,93 @@
+use crate::*;
+use mech_core::*;
+
+// Integrate -------------------------------------------------------------------
+
+// calculus/integrate(f, a, b) integrates f from a to b with Simpson's rule
+// over 100 intervals. calculus/integrate(f, a, b, method) picks "simpson" or
+// "trapezoid", and calculus/integrate(f, a, b, method, n) sets the number of
+// intervals. Simpson's rule needs an even number, so an odd n is rounded up.
+
+pub const DEFAULT_INTERVALS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntegrationMethod {
+  Trapezoid,
+  Simpson,
+}
+
+pub fn integration_method(fxn_name: &str, args: &[Value]) -> MResult<IntegrationMethod> {
+  let value = match args.get(2) {
+    Some(value) => value,
+    None => return Ok(IntegrationMethod::Simpson),
+  };
+  match calculus_deref(value) {
+    Value::String(method) if method.borrow().as_str() == "simpson" => Ok(IntegrationMethod::Simpson),
+    Value::String(method) if method.borrow().as_str() == "trapezoid" => Ok(IntegrationMethod::Trapezoid),
+    _ => Err(calculus_argument_error(fxn_name, value, "\"simpson\" or \"trapezoid\"")),
+  }
+}
+
+pub fn integration_intervals(fxn_name: &str, args: &[Value]) -> MResult<usize> {
+  let value = match args.get(3) {
+    Some(value) => value,
+    None => return Ok(DEFAULT_INTERVALS),
+  };
+  match calculus_param(value) {
+    n if n >= 1.0 && n.fract() == 0.0 => Ok(n as usize),
+    _ => Err(calculus_argument_error(fxn_name, value, "a positive whole number of intervals")),
+  }
+}
+
+pub fn trapezoid(f: &mut dyn FnMut(f64) -> MResult<f64>, a: f64, b: f64, n: usize) -> MResult<f64> {
+  let h = (b - a) / n as f64;
+  let mut sum = (f(a)? + f(b)?) / 2.0;
+  for i in 1..n {
+    sum += f(a + i as f64 * h)?;
+  }
+  Ok(sum * h)
+}
+
+pub fn simpson(f: &mut dyn FnMut(f64) -> MResult<f64>, a: f64, b: f64, n: usize) -> MResult<f64> {
+  let n = n + n % 2;
+  let h = (b - a) / n as f64;
+  let mut sum = f(a)? + f(b)?;
+  for i in 1..n {
+    let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+    sum += weight * f(a + i as f64 * h)?;
+  }
+  Ok(sum * h / 3.0)
+}
+
+#[derive(Debug, Default)]
+pub struct IntegrateOp {}
+
+impl CalculusOp for IntegrateOp {
+  const NAME: &'static str = "calculus/integrate";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    calculus_check_args(args, 2, 4)?;
+    calculus_finite(Self::NAME, &args[0], "a finite lower bound")?;
+    calculus_finite(Self::NAME, &args[1], "a finite upper bound")?;
+    integration_method(Self::NAME, args)?;
+    integration_intervals(Self::NAME, args)?;
+    Ok(None)
+  }
+  fn apply(&self, f: &CalculusFunction, args: &[Value]) -> MResult<Vec<f64>> {
+    let a = calculus_finite(Self::NAME, &args[0], "a finite lower bound")?;
+    let b = calculus_finite(Self::NAME, &args[1], "a finite upper bound")?;
+    let n = integration_intervals(Self::NAME, args)?;
+    let mut integrand = |x: f64| f.eval_f64(Self::NAME, vec![Value::F64(Ref::new(x))]);
+    let area = match integration_method(Self::NAME, args)? {
+      IntegrationMethod::Trapezoid => trapezoid(&mut integrand, a, b, n)?,
+      IntegrationMethod::Simpson => simpson(&mut integrand, a, b, n)?,
+    };
+    Ok(vec![area])
+  }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "calculus/integrate",
+    ptr: &CalculusFxnCompiler::<IntegrateOp> { op: PhantomData },
+  }
+}
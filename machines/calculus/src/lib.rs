@@ -0,0 +1,323 @@
+#![no_main]
+#![allow(warnings)]
+#[macro_use]
+extern crate mech_core;
+#[cfg(feature = "matrix")]
+extern crate nalgebra as na;
+extern crate paste;
+
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use paste::paste;
+
+#[cfg(feature = "integrate")]
+pub mod integrate;
+#[cfg(feature = "ode")]
+pub mod ode;
+#[cfg(feature = "root")]
+pub mod root;
+#[cfg(feature = "minimize")]
+pub mod minimize;
+
+#[cfg(feature = "integrate")]
+pub use self::integrate::*;
+#[cfg(feature = "ode")]
+pub use self::ode::*;
+#[cfg(feature = "root")]
+pub use self::root::*;
+#[cfg(feature = "minimize")]
+pub use self::minimize::*;
+
+// ----------------------------------------------------------------------------
+// Calculus Library
+// ----------------------------------------------------------------------------
+
+// Calculus functions take a Mech function as their first argument: a lambda,
+// or the name of a user function or lambda as a string. Running it needs an
+// interpreter, so the interpreter resolves it to a callback when the function
+// is compiled. The remaining arguments are kept as values, so a parameter held
+// in a mutable variable is read again on every solve;
+// `y = calculus/rk4-step(f, t, y, h)` takes one step per step of the
+// interpreter.
+
+// A Mech function that a calculus function evaluates.
+#[derive(Debug)]
+pub struct CalculusFunction(pub MechCallback);
+
+impl CalculusFunction {
+  pub fn call(&self, args: Vec<Value>) -> MResult<Value> {
+    self.0.call(args)
+  }
+
+  // Calls the function and reads its result as `len` numbers.
+  pub fn eval(&self, fxn_name: &str, args: Vec<Value>, len: usize) -> MResult<Vec<f64>> {
+    let result = self.call(args)?;
+    match calculus_samples(&result) {
+      Some(samples) if samples.len() == len => Ok(samples),
+      _ => {
+        let expected = if len == 1 { "a f64".to_string() } else { format!("{} f64 values", len) };
+        Err(MechError::new(
+          CalculusResultError { fxn_name: fxn_name.to_string(), function: self.0.name.clone(), expected, found: format!("{}", result.kind()) },
+          None
+        ).with_compiler_loc())
+      }
+    }
+  }
+
+  // Calls the function and reads its result as a single number.
+  pub fn eval_f64(&self, fxn_name: &str, args: Vec<Value>) -> MResult<f64> {
+    Ok(self.eval(fxn_name, args, 1)?[0])
+  }
+}
+
+pub fn calculus_deref(value: &Value) -> Value {
+  match value {
+    Value::MutableReference(value) => calculus_deref(&value.borrow()),
+    value => value.clone(),
+  }
+}
+
+// The numbers of a scalar or matrix argument, read each time the function
+// solves.
+pub fn calculus_samples(value: &Value) -> Option<Vec<f64>> {
+  match calculus_deref(value) {
+    Value::F64(x) => Some(vec![*x.borrow()]),
+    Value::MatrixF64(m) => Some(m.as_vec()),
+    _ => None,
+  }
+}
+
+// A numeric parameter, read each time the function solves.
+pub fn calculus_param(value: &Value) -> f64 {
+  value.as_f64().map(|x| *x.borrow()).unwrap_or(f64::NAN)
+}
+
+// None for a scalar, or the rows and columns of a matrix.
+pub fn calculus_shape(fxn_name: &str, value: &Value, expected: &str) -> MResult<Option<(usize, usize)>> {
+  match calculus_deref(value) {
+    Value::F64(_) => Ok(None),
+    Value::MatrixF64(m) if m.rows() * m.cols() > 0 => Ok(Some((m.rows(), m.cols()))),
+    _ => Err(calculus_argument_error(fxn_name, value, expected)),
+  }
+}
+
+// A value of the given shape, to pass to a user function.
+pub fn calculus_value(mut samples: Vec<f64>, shape: Option<(usize, usize)>) -> Value {
+  match shape {
+    Some((rows, cols)) => Matrix::from_vec(samples, rows, cols).to_value(),
+    None => Value::F64(Ref::new(samples.remove(0))),
+  }
+}
+
+// Checks that a parameter is a finite number.
+pub fn calculus_finite(fxn_name: &str, value: &Value, expected: &str) -> MResult<f64> {
+  match calculus_deref(value) {
+    Value::F64(x) if x.borrow().is_finite() => Ok(*x.borrow()),
+    _ => Err(calculus_argument_error(fxn_name, value, expected)),
+  }
+}
+
+// Checks an optional positive tolerance, or gives the default.
+pub fn calculus_tolerance(fxn_name: &str, args: &[Value], ix: usize, default: f64) -> MResult<f64> {
+  match args.get(ix) {
+    Some(value) => match calculus_param(value) {
+      tol if tol > 0.0 && tol.is_finite() => Ok(tol),
+      _ => Err(calculus_argument_error(fxn_name, value, "a positive tolerance")),
+    },
+    None => Ok(default),
+  }
+}
+
+pub fn calculus_check_args(args: &[Value], min: usize, max: usize) -> MResult<()> {
+  if args.len() < min || args.len() > max {
+    let expected = if args.len() < min { min } else { max };
+    return Err(MechError::new(IncorrectNumberOfArguments { expected, found: args.len() }, None).with_compiler_loc());
+  }
+  Ok(())
+}
+
+pub fn calculus_argument_error(fxn_name: &str, value: &Value, expected: &str) -> MechError {
+  let found = match value.as_f64() {
+    Ok(x) => format!("{}", x.borrow()),
+    Err(_) => format!("{}", value.kind()),
+  };
+  MechError::new(
+    CalculusArgumentError { fxn_name: fxn_name.to_string(), expected: expected.to_string(), found },
+    None
+  ).with_compiler_loc()
+}
+
+pub fn calculus_convergence_error(fxn_name: &str, reason: &str) -> MechError {
+  MechError::new(
+    CalculusConvergenceError { fxn_name: fxn_name.to_string(), reason: reason.to_string() },
+    None
+  ).with_compiler_loc()
+}
+
+// Functions -------------------------------------------------------------------
+
+#[cfg(feature = "matrix")]
+#[derive(Debug)]
+pub enum CalculusCells {
+  Scalar(Ref<f64>),
+  Matrix(Matrix<f64>),
+}
+
+#[cfg(feature = "matrix")]
+impl CalculusCells {
+  pub fn new(shape: Option<(usize, usize)>) -> Self {
+    match shape {
+      Some((rows, cols)) => CalculusCells::Matrix(Matrix::from_vec(vec![0.0; rows * cols], rows, cols)),
+      None => CalculusCells::Scalar(Ref::new(0.0)),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    match self {
+      CalculusCells::Scalar(_) => 1,
+      CalculusCells::Matrix(m) => m.shape().iter().product(),
+    }
+  }
+
+  pub fn set(&self, mut cells: Vec<f64>) {
+    match self {
+      CalculusCells::Scalar(x) => *x.borrow_mut() = cells.remove(0),
+      CalculusCells::Matrix(m) => m.set(cells),
+    }
+  }
+
+  pub fn to_value(&self) -> Value {
+    match self {
+      CalculusCells::Scalar(x) => x.to_value(),
+      CalculusCells::Matrix(m) => m.to_value(),
+    }
+  }
+}
+
+#[cfg(feature = "matrix")]
+pub trait CalculusOp: Debug + Default + 'static {
+  const NAME: &'static str;
+  // Checks the arguments after the user function when the function is built,
+  // and gives the shape of the result.
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>>;
+  // The result for the current arguments, in column-major order.
+  fn apply(&self, f: &CalculusFunction, args: &[Value]) -> MResult<Vec<f64>>;
+}
+
+#[cfg(feature = "matrix")]
+#[derive(Debug)]
+pub struct CalculusFxn<O: CalculusOp> {
+  op: O,
+  f: CalculusFunction,
+  args: Vec<Value>,
+  out: CalculusCells,
+}
+
+#[cfg(feature = "matrix")]
+impl<O: CalculusOp> CalculusFxn<O> {
+  // Builds the function and computes its first result, so that a user
+  // function that fails, or a root that isn't bracketed, is reported where
+  // the call is written. `args` are the arguments after the user function.
+  pub fn build(f: CalculusFunction, args: Vec<Value>) -> MResult<Self> {
+    let op = O::default();
+    let out = CalculusCells::new(op.shape(&args)?);
+    out.set(op.apply(&f, &args)?);
+    Ok(Self { op, f, args, out })
+  }
+}
+
+#[cfg(feature = "matrix")]
+impl<O: CalculusOp> MechFunctionImpl for CalculusFxn<O> {
+  // When the arguments change so that the result can't be computed, the last
+  // result is kept.
+  fn solve(&self) {
+    match self.op.apply(&self.f, &self.args) {
+      Ok(cells) if cells.len() == self.out.len() => self.out.set(cells),
+      _ => (),
+    }
+  }
+  fn out(&self) -> Value { self.out.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+
+// The user function can't be carried into bytecode yet.
+#[cfg(all(feature = "matrix", feature = "compiler"))]
+impl<O: CalculusOp> MechFunctionCompiler for CalculusFxn<O> {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    Err(MechError::new(CallbackNotCompilableError { fxn_name: O::NAME.to_string() }, None).with_compiler_loc())
+  }
+}
+
+#[cfg(feature = "matrix")]
+pub struct CalculusFxnCompiler<O: CalculusOp> {
+  pub op: PhantomData<O>,
+}
+
+#[cfg(feature = "matrix")]
+impl<O: CalculusOp> NativeFunctionCompiler for CalculusFxnCompiler<O> {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    self.compile_with_callbacks(arguments, &mut |_| {
+      Err(MechError::new(CallbackNotCompilableError { fxn_name: O::NAME.to_string() }, None).with_compiler_loc())
+    })
+  }
+  fn compile_with_callbacks(&self, arguments: &Vec<Value>, resolve: &mut CallbackResolver) -> MResult<Box<dyn MechFunction>> {
+    let (f, args) = match arguments.split_first() {
+      Some((f, args)) => (f, args.to_vec()),
+      None => return Err(MechError::new(IncorrectNumberOfArguments { expected: 1, found: 0 }, None).with_compiler_loc()),
+    };
+    match calculus_deref(f) {
+      Value::Lambda(_) | Value::String(_) => (),
+      _ => return Err(calculus_argument_error(O::NAME, f, "a lambda or the name of a function")),
+    }
+    let f = CalculusFunction(resolve(f)?);
+    Ok(Box::new(CalculusFxn::<O>::build(f, args)?))
+  }
+}
+
+// ----------------------------------------------------------------------------
+// Errors
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct CalculusArgumentError {
+  pub fxn_name: String,
+  pub expected: String,
+  pub found: String,
+}
+impl MechErrorKind for CalculusArgumentError {
+  fn name(&self) -> &str { "CalculusArgument" }
+  fn message(&self) -> String {
+    format!("{} expected {}, found {}", self.fxn_name, self.expected, self.found)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CalculusResultError {
+  pub fxn_name: String,
+  pub function: String,
+  pub expected: String,
+  pub found: String,
+}
+impl MechErrorKind for CalculusResultError {
+  fn name(&self) -> &str { "CalculusResult" }
+  fn message(&self) -> String {
+    format!("{} expected {} to return {}, found {}", self.fxn_name, self.function, self.expected, self.found)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CalculusConvergenceError {
+  pub fxn_name: String,
+  pub reason: String,
+}
+impl MechErrorKind for CalculusConvergenceError {
+  fn name(&self) -> &str { "CalculusConvergence" }
+  fn message(&self) -> String {
+    format!("{} did not converge: {}", self.fxn_name, self.reason)
+  }
+}
@@ -0,0 +1,112 @@
+use crate::*;
+use mech_core::*;
+
+// Minimize --------------------------------------------------------------------
+
+// calculus/minimize(f, x0) searches for an x near x0 where f(x) is smallest
+// with the Nelder-Mead simplex method, which needs no derivatives. x0 is a
+// f64 or a [f64] of any shape, f is called with an x shaped like it and
+// returns a f64, and the result is shaped like x0. The search stops when the
+// corners of the simplex, and the values of f at them, agree to within tol,
+// 1e-8 by default, set with calculus/minimize(f, x0, tol). It finds a local
+// minimum, so a different x0 may find a different one.
+
+pub const MINIMIZE_TOLERANCE: f64 = 1e-8;
+pub const MINIMIZE_MAX_ITERATIONS: usize = 2000;
+
+const REFLECT: f64 = 1.0;
+const EXPAND: f64 = 2.0;
+const CONTRACT: f64 = 0.5;
+const SHRINK: f64 = 0.5;
+
+// from + t * (to - from)
+fn simplex_point(from: &[f64], to: &[f64], t: f64) -> Vec<f64> {
+  from.iter().zip(to.iter()).map(|(a, b)| a + t * (b - a)).collect()
+}
+
+pub fn nelder_mead(f: &mut dyn FnMut(&[f64]) -> MResult<f64>, x0: &[f64], tol: f64) -> MResult<Vec<f64>> {
+  let n = x0.len();
+  // Start from x0 and a step along each axis.
+  let mut simplex = vec![x0.to_vec()];
+  for i in 0..n {
+    let mut x = x0.to_vec();
+    x[i] = if x[i] != 0.0 { x[i] * 1.05 } else { 0.00025 };
+    simplex.push(x);
+  }
+  let mut values = simplex.iter().map(|x| f(x)).collect::<MResult<Vec<f64>>>()?;
+  for _ in 0..MINIMIZE_MAX_ITERATIONS * n.max(1) {
+    let mut order = (0..=n).collect::<Vec<usize>>();
+    order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+    simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+    values = order.iter().map(|&i| values[i]).collect();
+    let (best, worst) = (values[0], values[n]);
+    let size = simplex[1..].iter()
+      .flat_map(|x| x.iter().zip(simplex[0].iter()).map(|(a, b)| (a - b).abs()))
+      .fold(0.0, f64::max);
+    if (worst - best).abs() <= tol && size <= tol {
+      break;
+    }
+    let centroid = (0..n).map(|j| simplex[..n].iter().map(|x| x[j]).sum::<f64>() / n as f64).collect::<Vec<f64>>();
+    let reflected = simplex_point(&centroid, &simplex[n], -REFLECT);
+    let f_reflected = f(&reflected)?;
+    if f_reflected < best {
+      let expanded = simplex_point(&centroid, &simplex[n], -EXPAND);
+      let f_expanded = f(&expanded)?;
+      if f_expanded < f_reflected {
+        simplex[n] = expanded;
+        values[n] = f_expanded;
+      } else {
+        simplex[n] = reflected;
+        values[n] = f_reflected;
+      }
+      continue;
+    }
+    if f_reflected < values[n - 1] {
+      simplex[n] = reflected;
+      values[n] = f_reflected;
+      continue;
+    }
+    // Contract towards the better of the reflected and worst points.
+    let (toward, f_toward) = if f_reflected < worst { (reflected, f_reflected) } else { (simplex[n].clone(), worst) };
+    let contracted = simplex_point(&centroid, &toward, CONTRACT);
+    let f_contracted = f(&contracted)?;
+    if f_contracted < f_toward {
+      simplex[n] = contracted;
+      values[n] = f_contracted;
+      continue;
+    }
+    // Shrink everything towards the best point.
+    for i in 1..=n {
+      simplex[i] = simplex_point(&simplex[0], &simplex[i], SHRINK);
+      values[i] = f(&simplex[i])?;
+    }
+  }
+  let best = (0..=n).min_by(|&i, &j| values[i].total_cmp(&values[j])).unwrap_or(0);
+  Ok(simplex.swap_remove(best))
+}
+
+#[derive(Debug, Default)]
+pub struct MinimizeOp {}
+
+impl CalculusOp for MinimizeOp {
+  const NAME: &'static str = "calculus/minimize";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    calculus_check_args(args, 1, 2)?;
+    calculus_tolerance(Self::NAME, args, 1, MINIMIZE_TOLERANCE)?;
+    calculus_shape(Self::NAME, &args[0], "a f64 or [f64] starting point")
+  }
+  fn apply(&self, f: &CalculusFunction, args: &[Value]) -> MResult<Vec<f64>> {
+    let shape = calculus_shape(Self::NAME, &args[0], "a f64 or [f64] starting point")?;
+    let tol = calculus_tolerance(Self::NAME, args, 1, MINIMIZE_TOLERANCE)?;
+    let x0 = calculus_samples(&args[0]).unwrap_or_default();
+    let mut objective = |x: &[f64]| f.eval_f64(Self::NAME, vec![calculus_value(x.to_vec(), shape)]);
+    nelder_mead(&mut objective, &x0, tol)
+  }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "calculus/minimize",
+    ptr: &CalculusFxnCompiler::<MinimizeOp> { op: PhantomData },
+  }
+}
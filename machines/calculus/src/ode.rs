@@ -0,0 +1,180 @@
+use crate::*;
+use mech_core::*;
+
+// The right-hand side of an ODE, dy/dt = f(t, y), is a user function called
+// with t and a y shaped like the initial state, which returns a derivative
+// with as many values as y.
+
+pub fn ode_rhs<'a>(fxn_name: &'a str, f: &'a CalculusFunction, shape: Option<(usize, usize)>) -> impl FnMut(f64, &[f64]) -> MResult<Vec<f64>> + 'a {
+  move |t, y| f.eval(fxn_name, vec![Value::F64(Ref::new(t)), calculus_value(y.to_vec(), shape)], y.len())
+}
+
+// y + h * sum(weights[i] * k[i])
+fn ode_combine(y: &[f64], h: f64, weights: &[f64], k: &[Vec<f64>]) -> Vec<f64> {
+  y.iter().enumerate().map(|(i, yi)| {
+    yi + h * weights.iter().zip(k.iter()).map(|(w, ki)| w * ki[i]).sum::<f64>()
+  }).collect()
+}
+
+// RK4 Step --------------------------------------------------------------------
+
+// calculus/rk4-step(f, t, y, h) takes one classical Runge-Kutta step of size
+// h from y at time t, and gives the state at t + h shaped like y. Assigning
+// the result back to a mutable y advances it by one step each time the
+// interpreter steps.
+
+pub fn rk4_step(f: &mut dyn FnMut(f64, &[f64]) -> MResult<Vec<f64>>, t: f64, y: &[f64], h: f64) -> MResult<Vec<f64>> {
+  let k1 = f(t, y)?;
+  let k2 = f(t + h / 2.0, &ode_combine(y, h / 2.0, &[1.0], &[k1.clone()]))?;
+  let k3 = f(t + h / 2.0, &ode_combine(y, h / 2.0, &[1.0], &[k2.clone()]))?;
+  let k4 = f(t + h, &ode_combine(y, h, &[1.0], &[k3.clone()]))?;
+  Ok(ode_combine(y, h / 6.0, &[1.0, 2.0, 2.0, 1.0], &[k1, k2, k3, k4]))
+}
+
+#[derive(Debug, Default)]
+pub struct Rk4StepOp {}
+
+impl CalculusOp for Rk4StepOp {
+  const NAME: &'static str = "calculus/rk4-step";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    calculus_check_args(args, 3, 3)?;
+    calculus_finite(Self::NAME, &args[0], "a finite time")?;
+    calculus_finite(Self::NAME, &args[2], "a finite step size")?;
+    calculus_shape(Self::NAME, &args[1], "a f64 or [f64] state")
+  }
+  fn apply(&self, f: &CalculusFunction, args: &[Value]) -> MResult<Vec<f64>> {
+    let t = calculus_finite(Self::NAME, &args[0], "a finite time")?;
+    let h = calculus_finite(Self::NAME, &args[2], "a finite step size")?;
+    let shape = calculus_shape(Self::NAME, &args[1], "a f64 or [f64] state")?;
+    let y = calculus_samples(&args[1]).unwrap_or_default();
+    rk4_step(&mut ode_rhs(Self::NAME, f, shape), t, &y, h)
+  }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "calculus/rk4-step",
+    ptr: &CalculusFxnCompiler::<Rk4StepOp> { op: PhantomData },
+  }
+}
+
+// ODE45 -----------------------------------------------------------------------
+
+// calculus/ode45(f, tspan, y0) solves dy/dt = f(t, y) from y(tspan[1]) = y0
+// with the adaptive Dormand-Prince method, and gives the state at each time
+// in tspan as the rows of a matrix, one column per value of y0. The times
+// must all increase or all decrease. calculus/ode45(f, tspan, y0, tol) sets
+// the relative and absolute error allowed in each step, 1e-6 by default.
+
+pub const ODE45_TOLERANCE: f64 = 1e-6;
+pub const ODE45_MAX_STEPS: usize = 100_000;
+
+const DP_C: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const DP_A: [&[f64]; 7] = [
+  &[],
+  &[1.0 / 5.0],
+  &[3.0 / 40.0, 9.0 / 40.0],
+  &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+  &[19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0],
+  &[9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0],
+  &[35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+// The difference between the fifth and fourth order solutions.
+const DP_E: [f64; 7] = [
+  71.0 / 57600.0, 0.0, -71.0 / 16695.0, 71.0 / 1920.0, -17253.0 / 339200.0, 22.0 / 525.0, -1.0 / 40.0,
+];
+
+// One Dormand-Prince step: the fifth order solution and its error estimate.
+pub fn dormand_prince_step(f: &mut dyn FnMut(f64, &[f64]) -> MResult<Vec<f64>>, t: f64, y: &[f64], h: f64) -> MResult<(Vec<f64>, Vec<f64>)> {
+  let mut k: Vec<Vec<f64>> = Vec::with_capacity(7);
+  for stage in 0..7 {
+    let yi = ode_combine(y, h, DP_A[stage], &k);
+    k.push(f(t + DP_C[stage] * h, &yi)?);
+  }
+  let y_next = ode_combine(y, h, DP_A[6], &k);
+  let error = ode_combine(&vec![0.0; y.len()], h, &DP_E, &k);
+  Ok((y_next, error))
+}
+
+pub fn ode45(fxn_name: &str, f: &mut dyn FnMut(f64, &[f64]) -> MResult<Vec<f64>>, tspan: &[f64], y0: &[f64], tol: f64) -> MResult<Vec<Vec<f64>>> {
+  let mut rows = vec![y0.to_vec()];
+  let mut t = tspan[0];
+  let mut y = y0.to_vec();
+  let mut h = (tspan[tspan.len() - 1] - tspan[0]) / 100.0;
+  let mut steps = 0;
+  for &t_out in &tspan[1..] {
+    while t != t_out {
+      let remaining = t_out - t;
+      let step = if h.abs() > remaining.abs() { remaining } else { h };
+      let (y_next, error) = dormand_prince_step(f, t, &y, step)?;
+      let scaled_error = y.iter().zip(y_next.iter()).zip(error.iter())
+        .map(|((yi, yn), ei)| ei.abs() / (tol + tol * yi.abs().max(yn.abs())))
+        .fold(0.0, f64::max);
+      if !scaled_error.is_finite() {
+        return Err(calculus_convergence_error(fxn_name, "the solution is not finite"));
+      }
+      let factor = if scaled_error == 0.0 { 5.0 } else { (0.9 * scaled_error.powf(-0.2)).clamp(0.2, 5.0) };
+      let clipped = step != h;
+      if scaled_error <= 1.0 {
+        t = if step == remaining { t_out } else { t + step };
+        y = y_next;
+      }
+      // A step cut short to land on an output time says little about how
+      // large the next one can be, so it only ever grows the step size.
+      if !(clipped && scaled_error <= 1.0 && (step * factor).abs() < h.abs()) {
+        h = step * factor;
+      }
+      steps += 1;
+      if steps > ODE45_MAX_STEPS || h.abs() <= f64::EPSILON * t.abs() {
+        return Err(calculus_convergence_error(fxn_name, "the step size became too small"));
+      }
+    }
+    rows.push(y.clone());
+  }
+  Ok(rows)
+}
+
+pub fn ode45_times(fxn_name: &str, value: &Value) -> MResult<Vec<f64>> {
+  let expected = "two or more finite times that all increase or all decrease";
+  let tspan = match calculus_shape(fxn_name, value, expected)? {
+    Some(_) => calculus_samples(value).unwrap_or_default(),
+    None => vec![],
+  };
+  let increasing = tspan.windows(2).all(|w| w[1] > w[0]);
+  let decreasing = tspan.windows(2).all(|w| w[1] < w[0]);
+  if tspan.len() < 2 || !(increasing || decreasing) || tspan.iter().any(|t| !t.is_finite()) {
+    return Err(calculus_argument_error(fxn_name, value, expected));
+  }
+  Ok(tspan)
+}
+
+#[derive(Debug, Default)]
+pub struct Ode45Op {}
+
+impl CalculusOp for Ode45Op {
+  const NAME: &'static str = "calculus/ode45";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    calculus_check_args(args, 2, 3)?;
+    let tspan = ode45_times(Self::NAME, &args[0])?;
+    calculus_shape(Self::NAME, &args[1], "a f64 or [f64] initial state")?;
+    calculus_tolerance(Self::NAME, args, 2, ODE45_TOLERANCE)?;
+    let states = calculus_samples(&args[1]).unwrap_or_default().len();
+    Ok(Some((tspan.len(), states)))
+  }
+  fn apply(&self, f: &CalculusFunction, args: &[Value]) -> MResult<Vec<f64>> {
+    let tspan = ode45_times(Self::NAME, &args[0])?;
+    let shape = calculus_shape(Self::NAME, &args[1], "a f64 or [f64] initial state")?;
+    let tol = calculus_tolerance(Self::NAME, args, 2, ODE45_TOLERANCE)?;
+    let y0 = calculus_samples(&args[1]).unwrap_or_default();
+    let rows = ode45(Self::NAME, &mut ode_rhs(Self::NAME, f, shape), &tspan, &y0, tol)?;
+    // Column-major, one column per state.
+    Ok((0..y0.len()).flat_map(|j| rows.iter().map(move |row| row[j])).collect())
+  }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "calculus/ode45",
+    ptr: &CalculusFxnCompiler::<Ode45Op> { op: PhantomData },
+  }
+}
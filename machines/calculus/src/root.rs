@@ -0,0 +1,109 @@
+use crate::*;
+use mech_core::*;
+
+// Find Root -------------------------------------------------------------------
+
+// calculus/find-root(f, a, b) finds an x between a and b where f(x) = 0 with
+// Brent's method, which falls back on bisection whenever interpolation does
+// not make progress. f(a) and f(b) must have opposite signs.
+// calculus/find-root(f, a, b, tol) sets how closely x is pinned down, 1e-12
+// by default.
+
+pub const ROOT_TOLERANCE: f64 = 1e-12;
+pub const ROOT_MAX_ITERATIONS: usize = 200;
+
+pub fn brent(fxn_name: &str, f: &mut dyn FnMut(f64) -> MResult<f64>, x1: f64, x2: f64, tol: f64) -> MResult<f64> {
+  let (mut a, mut b) = (x1, x2);
+  let (mut fa, mut fb) = (f(a)?, f(b)?);
+  if fa == 0.0 {
+    return Ok(a);
+  }
+  if fb == 0.0 {
+    return Ok(b);
+  }
+  if fa.signum() == fb.signum() || fa.is_nan() || fb.is_nan() {
+    return Err(calculus_convergence_error(fxn_name, "f(a) and f(b) have the same sign"));
+  }
+  let (mut c, mut fc) = (b, fb);
+  let (mut d, mut e) = (0.0, 0.0);
+  for _ in 0..ROOT_MAX_ITERATIONS {
+    // Keep the root between b and c.
+    if fb.signum() == fc.signum() {
+      c = a;
+      fc = fa;
+      d = b - a;
+      e = d;
+    }
+    // Keep b the best estimate.
+    if fc.abs() < fb.abs() {
+      a = b;
+      b = c;
+      c = a;
+      fa = fb;
+      fb = fc;
+      fc = fa;
+    }
+    let tol1 = 2.0 * f64::EPSILON * b.abs() + 0.5 * tol;
+    let xm = 0.5 * (c - b);
+    if xm.abs() <= tol1 || fb == 0.0 {
+      return Ok(b);
+    }
+    if e.abs() >= tol1 && fa.abs() > fb.abs() {
+      // Secant or inverse quadratic interpolation.
+      let s = fb / fa;
+      let (mut p, mut q) = if a == c {
+        (2.0 * xm * s, 1.0 - s)
+      } else {
+        let (q, r) = (fa / fc, fb / fc);
+        (s * (2.0 * xm * q * (q - r) - (b - a) * (r - 1.0)), (q - 1.0) * (r - 1.0) * (s - 1.0))
+      };
+      if p > 0.0 {
+        q = -q;
+      }
+      p = p.abs();
+      if 2.0 * p < (3.0 * xm * q - (tol1 * q).abs()).min((e * q).abs()) {
+        e = d;
+        d = p / q;
+      } else {
+        d = xm;
+        e = d;
+      }
+    } else {
+      d = xm;
+      e = d;
+    }
+    a = b;
+    fa = fb;
+    b += if d.abs() > tol1 { d } else { tol1.copysign(xm) };
+    fb = f(b)?;
+  }
+  Err(calculus_convergence_error(fxn_name, "too many iterations"))
+}
+
+#[derive(Debug, Default)]
+pub struct FindRootOp {}
+
+impl CalculusOp for FindRootOp {
+  const NAME: &'static str = "calculus/find-root";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    calculus_check_args(args, 2, 3)?;
+    calculus_finite(Self::NAME, &args[0], "a finite lower bound")?;
+    calculus_finite(Self::NAME, &args[1], "a finite upper bound")?;
+    calculus_tolerance(Self::NAME, args, 2, ROOT_TOLERANCE)?;
+    Ok(None)
+  }
+  fn apply(&self, f: &CalculusFunction, args: &[Value]) -> MResult<Vec<f64>> {
+    let a = calculus_finite(Self::NAME, &args[0], "a finite lower bound")?;
+    let b = calculus_finite(Self::NAME, &args[1], "a finite upper bound")?;
+    let tol = calculus_tolerance(Self::NAME, args, 2, ROOT_TOLERANCE)?;
+    let mut g = |x: f64| f.eval_f64(Self::NAME, vec![Value::F64(Ref::new(x))]);
+    Ok(vec![brent(Self::NAME, &mut g, a, b, tol)?])
+  }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "calculus/find-root",
+    ptr: &CalculusFxnCompiler::<FindRootOp> { op: PhantomData },
+  }
+}
@@ -0,0 +1,282 @@
+use crate::*;
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::{Array, ArrayRef, BooleanArray, PrimitiveArray, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use indexmap::map::IndexMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Arrow
+// ----------------------------------------------------------------------------
+
+// Converts tables to and from Arrow record batches. Scalar column kinds map to
+// Arrow types one to one:
+//
+//   bool    Boolean       u8 .. u64   UInt8 .. UInt64     f32   Float32
+//   string  Utf8          i8 .. i64   Int8 .. Int64       f64   Float64
+//
+// An optional kind such as `u8?` maps to a nullable field of its inner kind,
+// with empty cells stored as nulls, and a nullable field reads back as an
+// optional column. LargeUtf8 also reads as string. Any other kind or type is
+// rejected.
+
+pub fn arrow_type_from_kind(kind: &ValueKind) -> Option<DataType> {
+  match kind {
+    #[cfg(feature = "bool")]
+    ValueKind::Bool => Some(DataType::Boolean),
+    ValueKind::String => Some(DataType::Utf8),
+    #[cfg(feature = "u8")]
+    ValueKind::U8 => Some(DataType::UInt8),
+    #[cfg(feature = "u16")]
+    ValueKind::U16 => Some(DataType::UInt16),
+    #[cfg(feature = "u32")]
+    ValueKind::U32 => Some(DataType::UInt32),
+    #[cfg(feature = "u64")]
+    ValueKind::U64 => Some(DataType::UInt64),
+    #[cfg(feature = "i8")]
+    ValueKind::I8 => Some(DataType::Int8),
+    #[cfg(feature = "i16")]
+    ValueKind::I16 => Some(DataType::Int16),
+    #[cfg(feature = "i32")]
+    ValueKind::I32 => Some(DataType::Int32),
+    #[cfg(feature = "i64")]
+    ValueKind::I64 => Some(DataType::Int64),
+    #[cfg(feature = "f32")]
+    ValueKind::F32 => Some(DataType::Float32),
+    #[cfg(feature = "f64")]
+    ValueKind::F64 => Some(DataType::Float64),
+    _ => None,
+  }
+}
+
+pub fn kind_from_arrow_type(data_type: &DataType) -> Option<ValueKind> {
+  match data_type {
+    #[cfg(feature = "bool")]
+    DataType::Boolean => Some(ValueKind::Bool),
+    DataType::Utf8 | DataType::LargeUtf8 => Some(ValueKind::String),
+    #[cfg(feature = "u8")]
+    DataType::UInt8 => Some(ValueKind::U8),
+    #[cfg(feature = "u16")]
+    DataType::UInt16 => Some(ValueKind::U16),
+    #[cfg(feature = "u32")]
+    DataType::UInt32 => Some(ValueKind::U32),
+    #[cfg(feature = "u64")]
+    DataType::UInt64 => Some(ValueKind::U64),
+    #[cfg(feature = "i8")]
+    DataType::Int8 => Some(ValueKind::I8),
+    #[cfg(feature = "i16")]
+    DataType::Int16 => Some(ValueKind::I16),
+    #[cfg(feature = "i32")]
+    DataType::Int32 => Some(ValueKind::I32),
+    #[cfg(feature = "i64")]
+    DataType::Int64 => Some(ValueKind::I64),
+    #[cfg(feature = "f32")]
+    DataType::Float32 => Some(ValueKind::F32),
+    #[cfg(feature = "f64")]
+    DataType::Float64 => Some(ValueKind::F64),
+    _ => None,
+  }
+}
+
+// The Arrow field for a column; optional kinds become nullable fields.
+pub fn arrow_field_from_column(name: &str, kind: &ValueKind) -> MResult<Field> {
+  let (inner, nullable) = match kind {
+    ValueKind::Option(inner) => (inner.as_ref(), true),
+    kind => (kind, false),
+  };
+  match arrow_type_from_kind(inner) {
+    Some(data_type) => Ok(Field::new(name, data_type, nullable)),
+    None => Err(MechError::new(
+        ArrowUnsupportedKindError { column: name.to_string(), kind: kind.clone() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+// The column kind for an Arrow field; nullable fields become optional kinds.
+pub fn kind_from_arrow_field(field: &Field) -> MResult<ValueKind> {
+  match kind_from_arrow_type(field.data_type()) {
+    Some(kind) if field.is_nullable() => Ok(ValueKind::Option(Box::new(kind))),
+    Some(kind) => Ok(kind),
+    None => Err(MechError::new(
+        ArrowUnsupportedTypeError { column: field.name().clone(), data_type: field.data_type().to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+pub fn table_to_record_batch(table: &MechTable) -> MResult<RecordBatch> {
+  let mut fields = Vec::with_capacity(table.data.len());
+  let mut arrays: Vec<ArrayRef> = Vec::with_capacity(table.data.len());
+  for (id, (kind, column)) in table.data.iter() {
+    let name = table.col_names.get(id).cloned().unwrap_or_default();
+    let field = arrow_field_from_column(&name, kind)?;
+    arrays.push(column_to_array(column, field.data_type()));
+    fields.push(field);
+  }
+  RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).map_err(|err| MechError::new(
+      ArrowConversionError { source: err.to_string() },
+      None
+    ).with_compiler_loc()
+  )
+}
+
+pub fn record_batch_to_table(batch: &RecordBatch) -> MResult<MechTable> {
+  record_batches_to_table(&batch.schema(), std::slice::from_ref(batch))
+}
+
+// Concatenates batches that share `schema` into one table.
+pub fn record_batches_to_table(schema: &Schema, batches: &[RecordBatch]) -> MResult<MechTable> {
+  let rows = batches.iter().map(|batch| batch.num_rows()).sum();
+  let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+  let mut col_names: HashMap<u64, String> = HashMap::new();
+  for (ix, field) in schema.fields().iter().enumerate() {
+    let kind = kind_from_arrow_field(field)?;
+    let mut cells = Vec::with_capacity(rows);
+    for batch in batches {
+      array_cells(batch.column(ix).as_ref(), &mut cells);
+    }
+    let id = hash_str(field.name());
+    if data.contains_key(&id) {
+      return Err(MechError::new(ArrowDuplicateColumnError { column: field.name().clone() }, None).with_compiler_loc());
+    }
+    data.insert(id, (kind.clone(), Column::from_values(&kind, cells)));
+    col_names.insert(id, field.name().clone());
+  }
+  Ok(MechTable::new(rows, data.len(), data, col_names))
+}
+
+// Optional cells may be wrapped with their kind.
+fn arrow_cell(cell: &Value) -> &Value {
+  match cell {
+    Value::Typed(value, _) => arrow_cell(value),
+    cell => cell,
+  }
+}
+
+macro_rules! primitive_array {
+  ($column:expr, $data_type:expr, $($arrow_type:ident, $arrow:ty, $variant:ident, $feature:tt);+ $(;)?) => {
+    match $data_type {
+      $(
+        #[cfg(feature = $feature)]
+        DataType::$arrow_type => match $column {
+          Column::$variant(m) => Arc::new(PrimitiveArray::<$arrow>::from_iter_values(m.as_vec())) as ArrayRef,
+          column => Arc::new(column.as_vec().iter().map(|cell| match arrow_cell(cell) {
+            Value::$variant(x) => Some(*x.borrow()),
+            _ => None,
+          }).collect::<PrimitiveArray<$arrow>>()) as ArrayRef,
+        },
+      )+
+      _ => unreachable!(),
+    }
+  };
+}
+
+// Builds the array for a column whose kind maps to `data_type`. Empty cells
+// become nulls.
+fn column_to_array(column: &Column, data_type: &DataType) -> ArrayRef {
+  match (data_type, column) {
+    #[cfg(feature = "bool")]
+    (DataType::Boolean, Column::Bool(m)) => Arc::new(BooleanArray::from(m.as_vec())),
+    #[cfg(feature = "bool")]
+    (DataType::Boolean, column) => Arc::new(column.as_vec().iter().map(|cell| match arrow_cell(cell) {
+      Value::Bool(x) => Some(*x.borrow()),
+      _ => None,
+    }).collect::<BooleanArray>()),
+    (DataType::Utf8, Column::String(m)) => Arc::new(StringArray::from(m.as_vec())),
+    (DataType::Utf8, column) => Arc::new(column.as_vec().iter().map(|cell| match arrow_cell(cell) {
+      Value::String(x) => Some(x.borrow().clone()),
+      _ => None,
+    }).collect::<StringArray>()),
+    (data_type, column) => primitive_array!(column, data_type,
+      UInt8, UInt8Type, U8, "u8"; UInt16, UInt16Type, U16, "u16"; UInt32, UInt32Type, U32, "u32"; UInt64, UInt64Type, U64, "u64";
+      Int8, Int8Type, I8, "i8"; Int16, Int16Type, I16, "i16"; Int32, Int32Type, I32, "i32"; Int64, Int64Type, I64, "i64";
+      Float32, Float32Type, F32, "f32"; Float64, Float64Type, F64, "f64"),
+  }
+}
+
+macro_rules! primitive_cells {
+  ($array:expr, $cells:expr, $($data_type:ident, $arrow:ty, $variant:ident, $feature:tt);+ $(;)?) => {
+    match $array.data_type() {
+      $(
+        #[cfg(feature = $feature)]
+        DataType::$data_type => $cells.extend($array.as_primitive::<$arrow>().iter().map(|x| match x {
+          Some(x) => Value::$variant(Ref::new(x)),
+          None => Value::Empty,
+        })),
+      )+
+      _ => (),
+    }
+  };
+}
+
+// Appends the cells of an array whose type has a column kind. Nulls become
+// empty cells.
+fn array_cells(array: &dyn Array, cells: &mut Vec<Value>) {
+  let string_cell = |x: Option<&str>| match x {
+    Some(x) => Value::String(Ref::new(x.to_string())),
+    None => Value::Empty,
+  };
+  match array.data_type() {
+    #[cfg(feature = "bool")]
+    DataType::Boolean => cells.extend(array.as_boolean().iter().map(|x| match x {
+      Some(x) => Value::Bool(Ref::new(x)),
+      None => Value::Empty,
+    })),
+    DataType::Utf8 => cells.extend(array.as_string::<i32>().iter().map(string_cell)),
+    DataType::LargeUtf8 => cells.extend(array.as_string::<i64>().iter().map(string_cell)),
+    _ => primitive_cells!(array, cells,
+      UInt8, UInt8Type, U8, "u8"; UInt16, UInt16Type, U16, "u16"; UInt32, UInt32Type, U32, "u32"; UInt64, UInt64Type, U64, "u64";
+      Int8, Int8Type, I8, "i8"; Int16, Int16Type, I16, "i16"; Int32, Int32Type, I32, "i32"; Int64, Int64Type, I64, "i64";
+      Float32, Float32Type, F32, "f32"; Float64, Float64Type, F64, "f64"),
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrowUnsupportedKindError {
+  pub column: String,
+  pub kind: ValueKind,
+}
+impl MechErrorKind for ArrowUnsupportedKindError {
+  fn name(&self) -> &str { "ArrowUnsupportedKind" }
+  fn message(&self) -> String {
+    format!("Column \"{}\" has kind {}, which has no Arrow type", self.column, self.kind)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrowUnsupportedTypeError {
+  pub column: String,
+  pub data_type: String,
+}
+impl MechErrorKind for ArrowUnsupportedTypeError {
+  fn name(&self) -> &str { "ArrowUnsupportedType" }
+  fn message(&self) -> String {
+    format!("Column \"{}\" has Arrow type {}, which has no Mech kind", self.column, self.data_type)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrowDuplicateColumnError {
+  pub column: String,
+}
+impl MechErrorKind for ArrowDuplicateColumnError {
+  fn name(&self) -> &str { "ArrowDuplicateColumn" }
+  fn message(&self) -> String {
+    format!("Column \"{}\" appears more than once in the Arrow schema", self.column)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrowConversionError {
+  pub source: String,
+}
+impl MechErrorKind for ArrowConversionError {
+  fn name(&self) -> &str { "ArrowConversion" }
+  fn message(&self) -> String {
+    format!("Failed to convert table to an Arrow record batch: {}", self.source)
+  }
+}
@@ -0,0 +1,617 @@
+use crate::*;
+use mech_core::matrix::Matrix;
+use std::cell::RefCell;
+
+// Files
+// ----------------------------------------------------------------------------
+
+// Whole-file reads and writes, as text or as `[u8]` bytes, plus directory
+// listings and existence checks. Paths are fixed when the function is
+// compiled and are kept as arguments in compiled programs; reads are repeated
+// on every step so they pick up changes to the file, and writes are repeated
+// whenever their contents change. All of these touch the host, so the
+// interpreter must be granted Capability::FileSystem.
+
+pub fn file_path_argument(value: &Value, fxn_name: &str) -> MResult<String> {
+  match value {
+    Value::String(path) => Ok(path.borrow().clone()),
+    Value::MutableReference(value) => file_path_argument(&value.borrow(), fxn_name),
+    x => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+pub fn file_arguments(arguments: &[Value], expected: usize) -> MResult<()> {
+  if arguments.len() != expected {
+    return Err(MechError::new(
+        IncorrectNumberOfArguments { expected, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    );
+  }
+  Ok(())
+}
+
+// Text is written as UTF-8; byte matrices are written in column-major order.
+pub fn file_contents_bytes(value: &Value) -> Option<Vec<u8>> {
+  match value {
+    Value::String(text) => Some(text.borrow().as_bytes().to_vec()),
+    Value::MatrixU8(bytes) => Some(bytes.as_vec()),
+    Value::MutableReference(value) => file_contents_bytes(&value.borrow()),
+    _ => None,
+  }
+}
+
+fn file_contents_argument(value: &Value, fxn_name: &str) -> MResult<Value> {
+  match file_contents_bytes(value) {
+    Some(_) => Ok(value.clone()),
+    None => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: value.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+fn file_read_error(path: &str, err: std::io::Error) -> MechError {
+  MechError::new(
+    FileReadError { file_path: path.to_string(), source: err.to_string() },
+    None
+  ).with_compiler_loc()
+}
+
+fn file_write_error(path: &str, err: std::io::Error) -> MechError {
+  MechError::new(
+    FileWriteError { file_path: path.to_string(), source: err.to_string() },
+    None
+  ).with_compiler_loc()
+}
+
+pub fn read_file_string(path: &str) -> MResult<String> {
+  std::fs::read_to_string(path).map_err(|err| file_read_error(path, err))
+}
+
+pub fn read_file_bytes(path: &str) -> MResult<Vec<u8>> {
+  std::fs::read(path).map_err(|err| file_read_error(path, err))
+}
+
+pub fn write_file_bytes(path: &str, bytes: &[u8]) -> MResult<()> {
+  std::fs::write(path, bytes).map_err(|err| file_write_error(path, err))
+}
+
+pub fn append_file_bytes(path: &str, bytes: &[u8]) -> MResult<()> {
+  use std::io::Write;
+  std::fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .and_then(|mut file| file.write_all(bytes))
+    .map_err(|err| file_write_error(path, err))
+}
+
+// Entry names, sorted so listings are stable across platforms.
+pub fn list_dir_names(path: &str) -> MResult<Vec<String>> {
+  let entries = std::fs::read_dir(path).map_err(|err| file_read_error(path, err))?;
+  let mut names = Vec::new();
+  for entry in entries {
+    let entry = entry.map_err(|err| file_read_error(path, err))?;
+    names.push(entry.file_name().to_string_lossy().to_string());
+  }
+  names.sort();
+  Ok(names)
+}
+
+// Read File
+// ----------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub struct IoReadFileString {
+  pub path: Ref<String>,
+  pub out: Ref<String>,
+}
+impl MechFunctionFactory for IoReadFileString {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(out, arguments) => {
+        file_arguments(&arguments, 1)?;
+        let path = file_path_argument(&arguments[0], "io/read-file")?;
+        let out: Ref<String> = unsafe { out.as_unchecked() }.clone();
+        *out.borrow_mut() = read_file_string(&path)?;
+        Ok(Box::new(Self { path: Ref::new(path), out }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoReadFileString {
+  fn solve(&self) {
+    // Keep the last contents if the file has become unreadable.
+    if let Ok(text) = read_file_string(&self.path.borrow()) {
+      *self.out.borrow_mut() = text;
+    }
+  }
+  fn out(&self) -> Value { Value::String(self.out.clone()) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoReadFileString {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let path = Value::String(self.path.clone());
+    compile_value_varop!("IoReadFileString", Value::String(self.out.clone()), [path], ctx, FeatureFlag::Custom(hash_str("io/read-file")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoReadFileString",
+    ptr: IoReadFileString::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoReadFileString",
+    capability: Capability::FileSystem,
+  }
+}
+
+#[derive(Debug)]
+pub struct IoReadFileBytes {
+  pub path: Ref<String>,
+  pub out: Ref<RowDVector<u8>>,
+}
+impl MechFunctionFactory for IoReadFileBytes {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(Value::MatrixU8(Matrix::RowDVector(out)), arguments) => {
+        file_arguments(&arguments, 1)?;
+        let path = file_path_argument(&arguments[0], "io/read-file")?;
+        *out.borrow_mut() = RowDVector::from_vec(read_file_bytes(&path)?);
+        Ok(Box::new(Self { path: Ref::new(path), out }))
+      },
+      FunctionArgs::Variadic(out, _) => Err(MechError::new(
+          UnhandledFunctionArgumentKind1 { arg: out.kind(), fxn_name: "io/read-file".to_string() },
+          None
+        ).with_compiler_loc()
+      ),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoReadFileBytes {
+  fn solve(&self) {
+    // Keep the last contents if the file has become unreadable.
+    if let Ok(bytes) = read_file_bytes(&self.path.borrow()) {
+      *self.out.borrow_mut() = RowDVector::from_vec(bytes);
+    }
+  }
+  fn out(&self) -> Value { Value::MatrixU8(Matrix::RowDVector(self.out.clone())) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoReadFileBytes {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let out = Value::MatrixU8(Matrix::RowDVector(self.out.clone()));
+    let path = Value::String(self.path.clone());
+    compile_value_varop!("IoReadFileBytes", out, [path], ctx, FeatureFlag::Custom(hash_str("io/read-file")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoReadFileBytes",
+    ptr: IoReadFileBytes::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoReadFileBytes",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoReadFile {}
+
+impl NativeFunctionCompiler for IoReadFile {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    match arguments.as_slice() {
+      [path] => {
+        let path = file_path_argument(path, "io/read-file")?;
+        let text = read_file_string(&path)?;
+        Ok(Box::new(IoReadFileString { path: Ref::new(path), out: Ref::new(text) }))
+      }
+      [path, Value::Kind(ValueKind::Matrix(elem, _))] if **elem == ValueKind::U8 => {
+        let path = file_path_argument(path, "io/read-file")?;
+        let bytes = read_file_bytes(&path)?;
+        Ok(Box::new(IoReadFileBytes { path: Ref::new(path), out: Ref::new(RowDVector::from_vec(bytes)) }))
+      }
+      [_, x] => Err(MechError::new(
+          UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: "io/read-file".to_string() },
+          None
+        ).with_compiler_loc()
+      ),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/read-file",
+    ptr: &IoReadFile{},
+  }
+}
+
+// Write File
+// ----------------------------------------------------------------------------
+
+// Writes the contents when compiled and again each time they change, and
+// returns the contents.
+#[derive(Debug)]
+pub struct IoWriteFileContents {
+  pub path: Ref<String>,
+  pub contents: Value,
+  pub written: RefCell<Vec<u8>>,
+}
+impl MechFunctionFactory for IoWriteFileContents {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(_, arguments) => {
+        file_arguments(&arguments, 2)?;
+        let path = file_path_argument(&arguments[0], "io/write-file")?;
+        let contents = file_contents_argument(&arguments[1], "io/write-file")?;
+        let bytes = file_contents_bytes(&contents).unwrap_or_default();
+        write_file_bytes(&path, &bytes)?;
+        Ok(Box::new(Self { path: Ref::new(path), contents, written: RefCell::new(bytes) }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoWriteFileContents {
+  fn solve(&self) {
+    let bytes = file_contents_bytes(&self.contents).unwrap_or_default();
+    if *self.written.borrow() != bytes && write_file_bytes(&self.path.borrow(), &bytes).is_ok() {
+      *self.written.borrow_mut() = bytes;
+    }
+  }
+  fn out(&self) -> Value { self.contents.clone() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoWriteFileContents {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let arguments = [Value::String(self.path.clone()), self.contents.clone()];
+    compile_value_varop!("IoWriteFileContents", self.contents.clone(), arguments, ctx, FeatureFlag::Custom(hash_str("io/write-file")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoWriteFileContents",
+    ptr: IoWriteFileContents::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoWriteFileContents",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoWriteFile {}
+
+impl NativeFunctionCompiler for IoWriteFile {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let (path, contents) = match arguments.as_slice() {
+      [path, contents] => (file_path_argument(path, "io/write-file")?, file_contents_argument(contents, "io/write-file")?),
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let bytes = file_contents_bytes(&contents).unwrap_or_default();
+    write_file_bytes(&path, &bytes)?;
+    Ok(Box::new(IoWriteFileContents { path: Ref::new(path), contents, written: RefCell::new(bytes) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/write-file",
+    ptr: &IoWriteFile{},
+  }
+}
+
+// Append File
+// ----------------------------------------------------------------------------
+
+// Appends the contents when compiled and again each time they change, so a
+// program can keep a log by appending a line that it updates. Returns the
+// contents.
+#[derive(Debug)]
+pub struct IoAppendFileContents {
+  pub path: Ref<String>,
+  pub contents: Value,
+  pub appended: RefCell<Vec<u8>>,
+}
+impl MechFunctionFactory for IoAppendFileContents {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(_, arguments) => {
+        file_arguments(&arguments, 2)?;
+        let path = file_path_argument(&arguments[0], "io/append-file")?;
+        let contents = file_contents_argument(&arguments[1], "io/append-file")?;
+        let bytes = file_contents_bytes(&contents).unwrap_or_default();
+        append_file_bytes(&path, &bytes)?;
+        Ok(Box::new(Self { path: Ref::new(path), contents, appended: RefCell::new(bytes) }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoAppendFileContents {
+  fn solve(&self) {
+    let bytes = file_contents_bytes(&self.contents).unwrap_or_default();
+    if *self.appended.borrow() != bytes && append_file_bytes(&self.path.borrow(), &bytes).is_ok() {
+      *self.appended.borrow_mut() = bytes;
+    }
+  }
+  fn out(&self) -> Value { self.contents.clone() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoAppendFileContents {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let arguments = [Value::String(self.path.clone()), self.contents.clone()];
+    compile_value_varop!("IoAppendFileContents", self.contents.clone(), arguments, ctx, FeatureFlag::Custom(hash_str("io/append-file")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoAppendFileContents",
+    ptr: IoAppendFileContents::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoAppendFileContents",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoAppendFile {}
+
+impl NativeFunctionCompiler for IoAppendFile {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let (path, contents) = match arguments.as_slice() {
+      [path, contents] => (file_path_argument(path, "io/append-file")?, file_contents_argument(contents, "io/append-file")?),
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let bytes = file_contents_bytes(&contents).unwrap_or_default();
+    append_file_bytes(&path, &bytes)?;
+    Ok(Box::new(IoAppendFileContents { path: Ref::new(path), contents, appended: RefCell::new(bytes) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/append-file",
+    ptr: &IoAppendFile{},
+  }
+}
+
+// List Dir
+// ----------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub struct IoListDirNames {
+  pub path: Ref<String>,
+  pub out: Ref<RowDVector<String>>,
+}
+impl MechFunctionFactory for IoListDirNames {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(Value::MatrixString(Matrix::RowDVector(out)), arguments) => {
+        file_arguments(&arguments, 1)?;
+        let path = file_path_argument(&arguments[0], "io/list-dir")?;
+        *out.borrow_mut() = RowDVector::from_vec(list_dir_names(&path)?);
+        Ok(Box::new(Self { path: Ref::new(path), out }))
+      },
+      FunctionArgs::Variadic(out, _) => Err(MechError::new(
+          UnhandledFunctionArgumentKind1 { arg: out.kind(), fxn_name: "io/list-dir".to_string() },
+          None
+        ).with_compiler_loc()
+      ),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoListDirNames {
+  fn solve(&self) {
+    // Keep the last listing if the directory has become unreadable.
+    if let Ok(names) = list_dir_names(&self.path.borrow()) {
+      *self.out.borrow_mut() = RowDVector::from_vec(names);
+    }
+  }
+  fn out(&self) -> Value { Value::MatrixString(Matrix::RowDVector(self.out.clone())) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoListDirNames {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let out = Value::MatrixString(Matrix::RowDVector(self.out.clone()));
+    let path = Value::String(self.path.clone());
+    compile_value_varop!("IoListDirNames", out, [path], ctx, FeatureFlag::Custom(hash_str("io/list-dir")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoListDirNames",
+    ptr: IoListDirNames::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoListDirNames",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoListDir {}
+
+impl NativeFunctionCompiler for IoListDir {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let path = match arguments.as_slice() {
+      [path] => file_path_argument(path, "io/list-dir")?,
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let names = list_dir_names(&path)?;
+    Ok(Box::new(IoListDirNames { path: Ref::new(path), out: Ref::new(RowDVector::from_vec(names)) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/list-dir",
+    ptr: &IoListDir{},
+  }
+}
+
+// Exists
+// ----------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub struct IoPathExists {
+  pub path: Ref<String>,
+  pub out: Ref<bool>,
+}
+impl MechFunctionFactory for IoPathExists {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(out, arguments) => {
+        file_arguments(&arguments, 1)?;
+        let path = file_path_argument(&arguments[0], "io/exists")?;
+        let out: Ref<bool> = unsafe { out.as_unchecked() }.clone();
+        *out.borrow_mut() = std::path::Path::new(&path).exists();
+        Ok(Box::new(Self { path: Ref::new(path), out }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoPathExists {
+  fn solve(&self) {
+    *self.out.borrow_mut() = std::path::Path::new(&*self.path.borrow()).exists();
+  }
+  fn out(&self) -> Value { Value::Bool(self.out.clone()) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoPathExists {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let path = Value::String(self.path.clone());
+    compile_value_varop!("IoPathExists", Value::Bool(self.out.clone()), [path], ctx, FeatureFlag::Custom(hash_str("io/exists")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoPathExists",
+    ptr: IoPathExists::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoPathExists",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoExists {}
+
+impl NativeFunctionCompiler for IoExists {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let path = match arguments.as_slice() {
+      [path] => file_path_argument(path, "io/exists")?,
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let exists = std::path::Path::new(&path).exists();
+    Ok(Box::new(IoPathExists { path: Ref::new(path), out: Ref::new(exists) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/exists",
+    ptr: &IoExists{},
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileReadError {
+  pub file_path: String,
+  pub source: String,
+}
+impl MechErrorKind for FileReadError {
+  fn name(&self) -> &str { "FileRead" }
+  fn message(&self) -> String {
+    format!("Failed to read {}: {}", self.file_path, self.source)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileWriteError {
+  pub file_path: String,
+  pub source: String,
+}
+impl MechErrorKind for FileWriteError {
+  fn name(&self) -> &str { "FileWrite" }
+  fn message(&self) -> String {
+    format!("Failed to write {}: {}", self.file_path, self.source)
+  }
+}
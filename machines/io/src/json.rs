@@ -0,0 +1,936 @@
+use crate::*;
+use mech_core::matrix::Matrix;
+use indexmap::map::IndexMap;
+use serde_json::Value as Json;
+use std::collections::HashMap;
+
+type JsonObject = serde_json::Map<String, Json>;
+
+// JSON
+// ----------------------------------------------------------------------------
+
+// Converts values to and from JSON.
+//
+//   _                 null
+//   bool, string      true, "text"
+//   numbers           numbers; u128/i128 outside the 64-bit range as strings
+//   matrices          arrays of rows; row vectors are flat arrays
+//   records, maps     objects; non-string map keys are written as JSON text
+//   tables            arrays of objects, one per row
+//   tuples            {"$mech": "tuple", "value": [..]}
+//   sets              {"$mech": "set", "value": [..]}
+//   atoms             {"$mech": "atom", "value": "name"}
+//   enums             {"$mech": "enum", "name": "name", "variant": "name", "value": payload}
+//   c64, r64          {"$mech": "complex", "value": [re, im]}, {"$mech": "rational", "value": [numer, denom]}
+//
+// The "$mech" key marks the objects that hold these values. A record or map
+// with other keys, such as {"tuple": [1, 2]}, stays a record.
+//
+// Without a target kind, numbers decode as f64, arrays of scalars of one kind
+// as matrices, arrays of objects with the same keys as tables, and any other
+// array as a tuple. With a target kind, the JSON must have that kind's shape
+// and numbers are read at the kind's width.
+
+pub fn value_to_json(value: &Value) -> MResult<Json> {
+  if let Some(json) = matrix_to_json(value) {
+    return json;
+  }
+  match value {
+    Value::Empty => Ok(Json::Null),
+    Value::Bool(x) => Ok(Json::Bool(*x.borrow())),
+    Value::String(x) => Ok(Json::String(x.borrow().clone())),
+    #[cfg(feature = "u8")]
+    Value::U8(x) => Ok(Json::from(*x.borrow())),
+    #[cfg(feature = "u16")]
+    Value::U16(x) => Ok(Json::from(*x.borrow())),
+    #[cfg(feature = "u32")]
+    Value::U32(x) => Ok(Json::from(*x.borrow())),
+    #[cfg(feature = "u64")]
+    Value::U64(x) => Ok(Json::from(*x.borrow())),
+    #[cfg(feature = "u128")]
+    Value::U128(x) => {
+      let x = *x.borrow();
+      Ok(u64::try_from(x).map(Json::from).unwrap_or_else(|_| Json::String(x.to_string())))
+    },
+    #[cfg(feature = "i8")]
+    Value::I8(x) => Ok(Json::from(*x.borrow())),
+    #[cfg(feature = "i16")]
+    Value::I16(x) => Ok(Json::from(*x.borrow())),
+    #[cfg(feature = "i32")]
+    Value::I32(x) => Ok(Json::from(*x.borrow())),
+    #[cfg(feature = "i64")]
+    Value::I64(x) => Ok(Json::from(*x.borrow())),
+    #[cfg(feature = "i128")]
+    Value::I128(x) => {
+      let x = *x.borrow();
+      Ok(i64::try_from(x).map(Json::from).unwrap_or_else(|_| Json::String(x.to_string())))
+    },
+    #[cfg(feature = "f32")]
+    Value::F32(x) => Ok(float_json(*x.borrow() as f64)),
+    #[cfg(feature = "f64")]
+    Value::F64(x) => Ok(float_json(*x.borrow())),
+    #[cfg(feature = "complex")]
+    Value::C64(x) => {
+      let x = x.borrow();
+      Ok(tagged("complex", Json::Array(vec![float_json(x.0.re), float_json(x.0.im)])))
+    },
+    #[cfg(feature = "rational")]
+    Value::R64(x) => {
+      let x = x.borrow();
+      Ok(tagged("rational", Json::Array(vec![Json::from(*x.numer()), Json::from(*x.denom())])))
+    },
+    Value::Atom(x) => Ok(tagged("atom", Json::String(x.borrow().name()))),
+    Value::Tuple(x) => {
+      let elements = x.borrow().elements.iter().map(|element| value_to_json(element)).collect::<MResult<Vec<_>>>()?;
+      Ok(tagged("tuple", Json::Array(elements)))
+    },
+    Value::Set(x) => {
+      let elements = x.borrow().set.iter().map(value_to_json).collect::<MResult<Vec<_>>>()?;
+      Ok(tagged("set", Json::Array(elements)))
+    },
+    Value::Enum(x) => enum_to_json(&x.borrow()),
+    Value::Record(x) => {
+      let record = x.borrow();
+      let mut object = JsonObject::new();
+      for (id, field) in record.data.iter() {
+        let name = record.field_names.get(id).cloned().unwrap_or_else(|| id.to_string());
+        object.insert(name, value_to_json(field)?);
+      }
+      Ok(Json::Object(object))
+    },
+    Value::Map(x) => {
+      let mut object = JsonObject::new();
+      for (key, value) in x.borrow().map.iter() {
+        let key = match key {
+          Value::String(key) => key.borrow().clone(),
+          key => value_to_json(key)?.to_string(),
+        };
+        object.insert(key, value_to_json(value)?);
+      }
+      Ok(Json::Object(object))
+    },
+    Value::Table(x) => {
+      let table = x.borrow();
+      let mut rows = Vec::with_capacity(table.rows);
+      for ix in 0..table.rows {
+        let mut object = JsonObject::new();
+        for (id, (_, column)) in table.data.iter() {
+          let name = table.col_names.get(id).cloned().unwrap_or_else(|| id.to_string());
+          object.insert(name, value_to_json(&column.index1d(ix + 1))?);
+        }
+        rows.push(Json::Object(object));
+      }
+      Ok(Json::Array(rows))
+    },
+    Value::Typed(value, _) => value_to_json(value),
+    Value::MutableReference(value) => value_to_json(&value.borrow()),
+    x => Err(MechError::new(JsonUnsupportedKindError { kind: x.kind() }, None).with_compiler_loc()),
+  }
+}
+
+pub fn value_to_json_string(value: &Value) -> MResult<String> {
+  Ok(value_to_json(value)?.to_string())
+}
+
+// NaN and infinities have no JSON number, so they are written as null.
+fn float_json(x: f64) -> Json {
+  serde_json::Number::from_f64(x).map(Json::Number).unwrap_or(Json::Null)
+}
+
+const TAG_KEY: &str = "$mech";
+
+fn tagged(tag: &str, value: Json) -> Json {
+  let mut object = JsonObject::new();
+  object.insert(TAG_KEY.to_string(), Json::String(tag.to_string()));
+  object.insert("value".to_string(), value);
+  Json::Object(object)
+}
+
+fn enum_to_json(enm: &MechEnum) -> MResult<Json> {
+  let (variant_id, payload) = match enm.variants.as_slice() {
+    [variant] => variant,
+    _ => return Err(MechError::new(JsonUnsupportedKindError { kind: enm.kind() }, None).with_compiler_loc()),
+  };
+  let variant = enm.names.borrow().get(variant_id)
+    .map(|name| name.rsplit('/').next().unwrap_or(name).to_string())
+    .unwrap_or_else(|| variant_id.to_string());
+  let mut object = JsonObject::new();
+  object.insert(TAG_KEY.to_string(), Json::String("enum".to_string()));
+  object.insert("name".to_string(), Json::String(enm.name()));
+  object.insert("variant".to_string(), Json::String(variant));
+  if let Some(payload) = payload {
+    object.insert("value".to_string(), value_to_json(payload)?);
+  }
+  Ok(Json::Object(object))
+}
+
+macro_rules! matrix_cells {
+  ($value:expr, $($variant:ident, $scalar:ident, $feature:tt);+ $(;)?) => {
+    match $value {
+      $(
+        #[cfg(feature = $feature)]
+        Value::$variant(m) => Some((m.as_vec().into_iter().map(|x| Value::$scalar(Ref::new(x))).collect::<Vec<_>>(), m.rows(), m.cols())),
+      )+
+      Value::MatrixValue(m) => Some((m.as_vec(), m.rows(), m.cols())),
+      _ => None,
+    }
+  };
+}
+
+// Matrices are stored column-major; JSON lists them row by row.
+fn matrix_to_json(value: &Value) -> Option<MResult<Json>> {
+  let (cells, rows, cols) = matrix_cells!(value,
+    MatrixBool, Bool, "bool"; MatrixString, String, "string";
+    MatrixU8, U8, "u8"; MatrixU16, U16, "u16"; MatrixU32, U32, "u32"; MatrixU64, U64, "u64"; MatrixU128, U128, "u128";
+    MatrixI8, I8, "i8"; MatrixI16, I16, "i16"; MatrixI32, I32, "i32"; MatrixI64, I64, "i64"; MatrixI128, I128, "i128";
+    MatrixF32, F32, "f32"; MatrixF64, F64, "f64"; MatrixC64, C64, "complex"; MatrixR64, R64, "rational")?;
+  let cell = |r: usize, c: usize| value_to_json(&cells[c * rows + r]);
+  let json = if rows == 1 {
+    (0..cols).map(|c| cell(0, c)).collect::<MResult<Vec<_>>>().map(Json::Array)
+  } else {
+    (0..rows).map(|r| (0..cols).map(|c| cell(r, c)).collect::<MResult<Vec<_>>>().map(Json::Array))
+      .collect::<MResult<Vec<_>>>().map(Json::Array)
+  };
+  Some(json)
+}
+
+// Decoding
+// ----------------------------------------------------------------------------
+
+pub fn json_string_to_value(source: &str, kind: Option<&ValueKind>) -> MResult<Value> {
+  let json: Json = serde_json::from_str(source).map_err(|err| MechError::new(
+      JsonParseError { source: err.to_string() },
+      None
+    ).with_compiler_loc()
+  )?;
+  match kind {
+    Some(kind) => json_to_kind(&json, kind),
+    None => json_to_value(&json),
+  }
+}
+
+pub fn json_to_value(json: &Json) -> MResult<Value> {
+  match json {
+    Json::Null => Ok(Value::Empty),
+    Json::Bool(x) => Ok(Value::Bool(Ref::new(*x))),
+    Json::Number(x) => Ok(Value::F64(Ref::new(x.as_f64().unwrap_or(f64::NAN)))),
+    Json::String(x) => Ok(Value::String(Ref::new(x.clone()))),
+    Json::Array(items) => array_to_value(items),
+    Json::Object(object) => match tagged_to_value(object)? {
+      Some(value) => Ok(value),
+      None => {
+        let mut fields = Vec::with_capacity(object.len());
+        for (name, field) in object.iter() {
+          fields.push(((hash_str(name), name.clone()), json_to_value(field)?));
+        }
+        Ok(Value::Record(Ref::new(MechRecord::from_vec(fields))))
+      },
+    },
+  }
+}
+
+fn array_to_value(items: &[Json]) -> MResult<Value> {
+  let values = items.iter().map(json_to_value).collect::<MResult<Vec<_>>>()?;
+  if let Some(matrix) = cells_to_matrix(&values, 1, values.len()) {
+    return Ok(matrix);
+  }
+  // Arrays of equal-length rows
+  let row_len = match items.first() {
+    Some(Json::Array(row)) if !row.is_empty() => Some(row.len()),
+    _ => None,
+  };
+  if let Some(cols) = row_len {
+    if items.iter().all(|row| matches!(row, Json::Array(row) if row.len() == cols)) {
+      let cells = items.iter()
+        .flat_map(|row| row.as_array().into_iter().flatten())
+        .map(json_to_value)
+        .collect::<MResult<Vec<_>>>()?;
+      if let Some(matrix) = cells_to_matrix(&cells, items.len(), cols) {
+        return Ok(matrix);
+      }
+    }
+  }
+  if let Some(table) = records_to_table(&values) {
+    return Ok(Value::Table(Ref::new(table)));
+  }
+  Ok(Value::Tuple(Ref::new(MechTuple::from_vec(values))))
+}
+
+// Builds a matrix from row-major cells if they are all bools, all strings or
+// all f64s.
+fn cells_to_matrix(cells: &[Value], rows: usize, cols: usize) -> Option<Value> {
+  let column_major = |cells: &[Value]| -> Vec<Value> {
+    (0..cols).flat_map(|c| (0..rows).map(move |r| c + r * cols)).map(|ix| cells[ix].clone()).collect()
+  };
+  let cells = column_major(cells);
+  if cells.iter().all(|x| matches!(x, Value::F64(_))) {
+    return typed_matrix(&ValueKind::F64, cells, rows, cols);
+  }
+  if cells.iter().all(|x| matches!(x, Value::Bool(_))) {
+    return typed_matrix(&ValueKind::Bool, cells, rows, cols);
+  }
+  if cells.iter().all(|x| matches!(x, Value::String(_))) {
+    return typed_matrix(&ValueKind::String, cells, rows, cols);
+  }
+  None
+}
+
+// Records with the same fields in the same order become the rows of a table.
+// A column takes the kind its cells share, made optional if some are null.
+fn records_to_table(values: &[Value]) -> Option<MechTable> {
+  let records = values.iter().map(|value| match value {
+    Value::Record(record) => Some(record.borrow().clone()),
+    _ => None,
+  }).collect::<Option<Vec<_>>>()?;
+  let first = records.first()?;
+  let ids: Vec<u64> = first.data.keys().cloned().collect();
+  if records.iter().any(|record| !record.data.keys().eq(ids.iter())) {
+    return None;
+  }
+  let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+  let mut col_names: HashMap<u64, String> = HashMap::new();
+  for id in ids {
+    let cells: Vec<Value> = records.iter().map(|record| record.data[&id].clone()).collect();
+    let mut kinds = cells.iter().filter(|cell| !matches!(cell, Value::Empty)).map(|cell| cell.kind());
+    let kind = match kinds.next() {
+      Some(kind) if kinds.all(|other| other == kind) => {
+        if cells.iter().any(|cell| matches!(cell, Value::Empty)) { ValueKind::Option(Box::new(kind)) } else { kind }
+      },
+      _ => ValueKind::Any,
+    };
+    data.insert(id, (kind.clone(), Column::from_values(&kind, cells)));
+    col_names.insert(id, first.field_names.get(&id).cloned().unwrap_or_default());
+  }
+  Some(MechTable::new(records.len(), data.len(), data, col_names))
+}
+
+// Tuples, sets, atoms, enums and numbers that JSON can't hold are written as
+// objects tagged with "$mech"; anything else is a record.
+fn tagged_to_value(object: &JsonObject) -> MResult<Option<Value>> {
+  if let Some((name, variant, payload)) = enum_fields(object) {
+    let payload = payload.map(json_to_value).transpose()?;
+    return Ok(Some(Value::Enum(Ref::new(json_enum(name, variant, payload)))));
+  }
+  let tag = object.get(TAG_KEY).and_then(Json::as_str);
+  let payload = if object.len() == 2 { object.get("value") } else { None };
+  let value = match (tag, payload) {
+    (Some("tuple"), Some(Json::Array(items))) => {
+      let elements = items.iter().map(json_to_value).collect::<MResult<Vec<_>>>()?;
+      Value::Tuple(Ref::new(MechTuple::from_vec(elements)))
+    },
+    (Some("set"), Some(Json::Array(items))) => {
+      let elements = items.iter().map(json_to_value).collect::<MResult<Vec<_>>>()?;
+      Value::Set(Ref::new(MechSet::from_vec(elements)))
+    },
+    (Some("atom"), Some(Json::String(name))) => Value::Atom(Ref::new(MechAtom::from_name(name))),
+    #[cfg(feature = "complex")]
+    (Some("complex"), Some(Json::Array(_))) => json_to_kind(&Json::Object(object.clone()), &ValueKind::C64)?,
+    #[cfg(feature = "rational")]
+    (Some("rational"), Some(Json::Array(_))) => json_to_kind(&Json::Object(object.clone()), &ValueKind::R64)?,
+    _ => return Ok(None),
+  };
+  Ok(Some(value))
+}
+
+// The name, variant and payload of an object tagged as an enum.
+fn enum_fields(object: &JsonObject) -> Option<(&str, &str, Option<&Json>)> {
+  if object.get(TAG_KEY).and_then(Json::as_str) != Some("enum") {
+    return None;
+  }
+  if object.keys().any(|key| !matches!(key.as_str(), TAG_KEY | "name" | "variant" | "value")) {
+    return None;
+  }
+  let name = object.get("name")?.as_str()?;
+  let variant = object.get("variant")?.as_str()?;
+  Some((name, variant, object.get("value")))
+}
+
+fn json_enum(name: &str, variant: &str, payload: Option<Value>) -> MechEnum {
+  let id = hash_str(name);
+  let variant_id = hash_str(variant);
+  let mut names = Dictionary::new();
+  names.insert(id, name.to_string());
+  names.insert(variant_id, variant.to_string());
+  MechEnum { id, variants: vec![(variant_id, payload)], names: Ref::new(names) }
+}
+
+macro_rules! typed_matrix_arms {
+  ($kind:expr, $cells:expr, $rows:expr, $cols:expr, $($variant:ident, $matrix:ident, $feature:tt);+ $(;)?) => {
+    match $kind {
+      $(
+        #[cfg(feature = $feature)]
+        ValueKind::$variant => {
+          let cells = $cells.iter().map(|cell| match cell {
+            Value::$variant(x) => Some(x.borrow().clone()),
+            _ => None,
+          }).collect::<Option<Vec<_>>>()?;
+          Some(Value::$matrix(Matrix::from_vec(cells, $rows, $cols)))
+        },
+      )+
+      _ => Some(Value::MatrixValue(Matrix::from_vec($cells, $rows, $cols))),
+    }
+  };
+}
+
+// Builds the matrix for column-major cells of scalar `kind`. Other kinds give
+// a matrix of values.
+fn typed_matrix(kind: &ValueKind, cells: Vec<Value>, rows: usize, cols: usize) -> Option<Value> {
+  typed_matrix_arms!(kind, cells, rows, cols,
+    Bool, MatrixBool, "bool"; String, MatrixString, "string";
+    U8, MatrixU8, "u8"; U16, MatrixU16, "u16"; U32, MatrixU32, "u32"; U64, MatrixU64, "u64"; U128, MatrixU128, "u128";
+    I8, MatrixI8, "i8"; I16, MatrixI16, "i16"; I32, MatrixI32, "i32"; I64, MatrixI64, "i64"; I128, MatrixI128, "i128";
+    F32, MatrixF32, "f32"; F64, MatrixF64, "f64"; C64, MatrixC64, "complex"; R64, MatrixR64, "rational")
+}
+
+// Integers may be written as whole floats ("3.0") or, past 64 bits, as
+// strings.
+macro_rules! integer_from_json {
+  ($json:expr, $kind:expr, $variant:ident, $ty:ty) => {
+    match $json {
+      Json::Number(x) => x.as_i64().map(|x| x as i128)
+        .or(x.as_u64().map(|x| x as i128))
+        .or(x.as_f64().filter(|x| x.fract() == 0.0 && x.abs() < 2f64.powi(63)).map(|x| x as i128))
+        .and_then(|x| <$ty>::try_from(x).ok()),
+      Json::String(x) => x.parse::<$ty>().ok(),
+      _ => None,
+    }.map(|x| Value::$variant(Ref::new(x))).ok_or_else(|| kind_mismatch($kind, $json))
+  };
+}
+
+pub fn json_to_kind(json: &Json, kind: &ValueKind) -> MResult<Value> {
+  match (kind, json) {
+    (ValueKind::Any, json) => json_to_value(json),
+    (ValueKind::Option(_), Json::Null) => Ok(Value::Empty),
+    (ValueKind::Option(inner), json) => json_to_kind(json, inner),
+    (ValueKind::Empty, Json::Null) => Ok(Value::Empty),
+    (ValueKind::Bool, Json::Bool(x)) => Ok(Value::Bool(Ref::new(*x))),
+    (ValueKind::String, Json::String(x)) => Ok(Value::String(Ref::new(x.clone()))),
+    #[cfg(feature = "u8")]
+    (ValueKind::U8, json) => integer_from_json!(json, kind, U8, u8),
+    #[cfg(feature = "u16")]
+    (ValueKind::U16, json) => integer_from_json!(json, kind, U16, u16),
+    #[cfg(feature = "u32")]
+    (ValueKind::U32, json) => integer_from_json!(json, kind, U32, u32),
+    #[cfg(feature = "u64")]
+    (ValueKind::U64, json) => integer_from_json!(json, kind, U64, u64),
+    #[cfg(feature = "u128")]
+    (ValueKind::U128, json) => integer_from_json!(json, kind, U128, u128),
+    #[cfg(feature = "i8")]
+    (ValueKind::I8, json) => integer_from_json!(json, kind, I8, i8),
+    #[cfg(feature = "i16")]
+    (ValueKind::I16, json) => integer_from_json!(json, kind, I16, i16),
+    #[cfg(feature = "i32")]
+    (ValueKind::I32, json) => integer_from_json!(json, kind, I32, i32),
+    #[cfg(feature = "i64")]
+    (ValueKind::I64, json) => integer_from_json!(json, kind, I64, i64),
+    #[cfg(feature = "i128")]
+    (ValueKind::I128, json) => integer_from_json!(json, kind, I128, i128),
+    #[cfg(feature = "f32")]
+    (ValueKind::F32, Json::Number(x)) => Ok(Value::F32(Ref::new(x.as_f64().unwrap_or(f64::NAN) as f32))),
+    #[cfg(feature = "f32")]
+    (ValueKind::F32, Json::Null) => Ok(Value::F32(Ref::new(f32::NAN))),
+    #[cfg(feature = "f64")]
+    (ValueKind::F64, Json::Number(x)) => Ok(Value::F64(Ref::new(x.as_f64().unwrap_or(f64::NAN)))),
+    #[cfg(feature = "f64")]
+    (ValueKind::F64, Json::Null) => Ok(Value::F64(Ref::new(f64::NAN))),
+    #[cfg(feature = "complex")]
+    (ValueKind::C64, json) => match tag_value(json, "complex").and_then(Json::as_array).map(|x| x.as_slice()) {
+      Some([re, im]) => match (re.as_f64(), im.as_f64()) {
+        (Some(re), Some(im)) => Ok(Value::C64(Ref::new(C64::new(re, im)))),
+        _ => Err(kind_mismatch(kind, json)),
+      },
+      _ => Err(kind_mismatch(kind, json)),
+    },
+    #[cfg(feature = "rational")]
+    (ValueKind::R64, json) => match tag_value(json, "rational").and_then(Json::as_array).map(|x| x.as_slice()) {
+      Some([numer, denom]) => match (numer.as_i64(), denom.as_i64()) {
+        (Some(numer), Some(denom)) if denom != 0 => Ok(Value::R64(Ref::new(R64::new(numer, denom)))),
+        _ => Err(kind_mismatch(kind, json)),
+      },
+      _ => Err(kind_mismatch(kind, json)),
+    },
+    (ValueKind::Matrix(element, dims), Json::Array(items)) => json_to_matrix(items, element, dims, kind, json),
+    (ValueKind::Record(fields), Json::Object(object)) => {
+      let mut values = Vec::with_capacity(fields.len());
+      for (name, field_kind) in fields {
+        values.push(((hash_str(name), name.clone()), json_field(object, name, field_kind, kind)?));
+      }
+      Ok(Value::Record(Ref::new(MechRecord::from_vec(values))))
+    },
+    (ValueKind::Map(key_kind, value_kind), Json::Object(object)) => {
+      let mut map = IndexMap::new();
+      for (key, value) in object.iter() {
+        let key = match key_kind.as_ref() {
+          ValueKind::String => Value::String(Ref::new(key.clone())),
+          key_kind => {
+            let key_json: Json = serde_json::from_str(key).map_err(|_| kind_mismatch(key_kind, &Json::String(key.clone())))?;
+            json_to_kind(&key_json, key_kind)?
+          },
+        };
+        map.insert(key, json_to_kind(value, value_kind)?);
+      }
+      Ok(Value::Map(Ref::new(MechMap {
+        key_kind: key_kind.as_ref().clone(),
+        value_kind: value_kind.as_ref().clone(),
+        num_elements: map.len(),
+        map,
+      })))
+    },
+    (ValueKind::Table(columns, _), Json::Array(rows)) => {
+      let objects = rows.iter().map(|row| row.as_object().ok_or_else(|| kind_mismatch(kind, row))).collect::<MResult<Vec<_>>>()?;
+      let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+      let mut col_names: HashMap<u64, String> = HashMap::new();
+      for (name, column_kind) in columns {
+        let cells = objects.iter().map(|object| json_field(object, name, column_kind, kind)).collect::<MResult<Vec<_>>>()?;
+        let id = hash_str(name);
+        data.insert(id, (column_kind.clone(), Column::from_values(column_kind, cells)));
+        col_names.insert(id, name.clone());
+      }
+      Ok(Value::Table(Ref::new(MechTable::new(objects.len(), data.len(), data, col_names))))
+    },
+    (ValueKind::Tuple(kinds), json) => match tag_value(json, "tuple").or(Some(json)).and_then(Json::as_array) {
+      Some(items) if items.len() == kinds.len() => {
+        let elements = items.iter().zip(kinds).map(|(item, kind)| json_to_kind(item, kind)).collect::<MResult<Vec<_>>>()?;
+        Ok(Value::Tuple(Ref::new(MechTuple::from_vec(elements))))
+      },
+      _ => Err(kind_mismatch(kind, json)),
+    },
+    (ValueKind::Set(element, _), json) => match tag_value(json, "set").or(Some(json)).and_then(Json::as_array) {
+      Some(items) => {
+        let elements = items.iter().map(|item| json_to_kind(item, element)).collect::<MResult<Vec<_>>>()?;
+        let mut set = MechSet::from_vec(elements);
+        set.kind = element.as_ref().clone();
+        Ok(Value::Set(Ref::new(set)))
+      },
+      None => Err(kind_mismatch(kind, json)),
+    },
+    (ValueKind::Atom(id, _), json) => match tag_value(json, "atom") {
+      Some(Json::String(name)) if hash_str(name) == *id => Ok(Value::Atom(Ref::new(MechAtom::from_name(name)))),
+      _ => Err(kind_mismatch(kind, json)),
+    },
+    (ValueKind::Enum(id, _), Json::Object(object)) => match enum_fields(object) {
+      Some((name, variant, payload)) if hash_str(name) == *id => {
+        let payload = payload.map(json_to_value).transpose()?;
+        Ok(Value::Enum(Ref::new(json_enum(name, variant, payload))))
+      },
+      _ => Err(kind_mismatch(kind, json)),
+    },
+    (kind, json) => Err(kind_mismatch(kind, json)),
+  }
+}
+
+// A flat array is a row vector and an array of arrays is a matrix, row by row.
+// A matrix kind with a fixed shape also accepts a flat array of the same size.
+fn json_to_matrix(items: &[Json], element: &ValueKind, dims: &[usize], kind: &ValueKind, json: &Json) -> MResult<Value> {
+  let nested = !items.is_empty() && items.iter().all(|item| item.is_array());
+  let (mut rows, mut cols, row_major) = if nested {
+    let cols = items[0].as_array().map(|row| row.len()).unwrap_or(0);
+    if items.iter().any(|row| row.as_array().map(|row| row.len()) != Some(cols)) {
+      return Err(kind_mismatch(kind, json));
+    }
+    (items.len(), cols, items.iter().flat_map(|row| row.as_array().into_iter().flatten()).collect::<Vec<_>>())
+  } else {
+    (1, items.len(), items.iter().collect::<Vec<_>>())
+  };
+  match dims {
+    [r, c] if *r > 0 && *c > 0 => {
+      if r * c != row_major.len() || (nested && (*r, *c) != (rows, cols)) {
+        return Err(MechError::new(
+            JsonShapeError { expected: dims.to_vec(), found: vec![rows, cols] },
+            None
+          ).with_compiler_loc()
+        );
+      }
+      (rows, cols) = (*r, *c);
+    },
+    _ => (),
+  }
+  let cells = row_major.iter().map(|cell| json_to_kind(cell, element)).collect::<MResult<Vec<_>>>()?;
+  let column_major = (0..cols).flat_map(|c| (0..rows).map(move |r| c + r * cols)).map(|ix| cells[ix].clone()).collect();
+  typed_matrix(element, column_major, rows, cols).ok_or_else(|| kind_mismatch(kind, json))
+}
+
+// A missing field reads as null, so it is only allowed for optional kinds.
+fn json_field(object: &JsonObject, name: &str, field_kind: &ValueKind, kind: &ValueKind) -> MResult<Value> {
+  match object.get(name) {
+    Some(field) => json_to_kind(field, field_kind),
+    None if matches!(field_kind, ValueKind::Option(_)) => Ok(Value::Empty),
+    None => Err(MechError::new(
+        JsonMissingFieldError { field: name.to_string(), kind: kind.clone() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+fn tag_value<'a>(json: &'a Json, tag: &str) -> Option<&'a Json> {
+  match json {
+    Json::Object(object) if object.len() == 2 && object.get(TAG_KEY).and_then(Json::as_str) == Some(tag) => object.get("value"),
+    _ => None,
+  }
+}
+
+fn kind_mismatch(kind: &ValueKind, json: &Json) -> MechError {
+  let mut found = json.to_string();
+  if found.chars().count() > 40 {
+    found = format!("{}...", found.chars().take(40).collect::<String>());
+  }
+  MechError::new(JsonKindMismatchError { kind: kind.clone(), found }, None).with_compiler_loc()
+}
+
+fn json_string_argument(value: &Value, fxn_name: &str) -> MResult<Ref<String>> {
+  match value {
+    Value::String(string) => Ok(string.clone()),
+    Value::MutableReference(value) => json_string_argument(&value.borrow(), fxn_name),
+    x => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+// To JSON
+// ----------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub struct IoToJsonValue {
+  pub source: Ref<Value>,
+  pub out: Ref<String>,
+}
+impl MechFunctionFactory for IoToJsonValue {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Unary(out, source) => {
+        let out: Ref<String> = unsafe { out.as_unchecked() }.clone();
+        Ok(Box::new(Self { source: Ref::new(source), out }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoToJsonValue {
+  fn solve(&self) {
+    if let Ok(json) = value_to_json_string(&self.source.borrow()) {
+      *self.out.borrow_mut() = json;
+    }
+  }
+  fn out(&self) -> Value { Value::String(self.out.clone()) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoToJsonValue {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    compile_unop!("IoToJsonValue", self.out, self.source, ctx, FeatureFlag::Custom(hash_str("io/to-json")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoToJsonValue",
+    ptr: IoToJsonValue::new,
+  }
+}
+
+pub struct IoToJson {}
+
+impl NativeFunctionCompiler for IoToJson {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let source = match arguments.as_slice() {
+      [source] => source.clone(),
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let json = value_to_json_string(&source)?;
+    Ok(Box::new(IoToJsonValue { source: Ref::new(source), out: Ref::new(json) }))
+  }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/to-json",
+    ptr: &IoToJson{},
+  }
+}
+
+// From JSON
+// ----------------------------------------------------------------------------
+
+// Kinds can't be bytecode constants, so a compiled target kind is carried as
+// its serialized form in a string argument.
+fn json_kind_to_string(kind: &ValueKind) -> MResult<String> {
+  serde_json::to_string(kind).map_err(|err| MechError::new(
+      JsonParseError { source: err.to_string() },
+      None
+    ).with_compiler_loc()
+  )
+}
+
+fn json_kind_from_string(kind: &str) -> MResult<ValueKind> {
+  serde_json::from_str(kind).map_err(|err| MechError::new(
+      JsonParseError { source: err.to_string() },
+      None
+    ).with_compiler_loc()
+  )
+}
+
+#[derive(Debug)]
+pub struct IoFromJsonValue {
+  pub source: Ref<String>,
+  pub kind: Option<ValueKind>,
+  pub out: Ref<Value>,
+}
+impl MechFunctionFactory for IoFromJsonValue {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(_, arguments) => {
+        let (source, kind) = match arguments.as_slice() {
+          [source] => (json_string_argument(source, "io/from-json")?, None),
+          [source, kind] => {
+            let kind = json_string_argument(kind, "io/from-json")?;
+            (json_string_argument(source, "io/from-json")?, Some(json_kind_from_string(&kind.borrow())?))
+          },
+          _ => return Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        };
+        let value = json_string_to_value(&source.borrow(), kind.as_ref())?;
+        Ok(Box::new(Self { source, kind, out: Ref::new(value) }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoFromJsonValue {
+  fn solve(&self) {
+    if let Ok(value) = json_string_to_value(&self.source.borrow(), self.kind.as_ref()) {
+      *self.out.borrow_mut() = value;
+    }
+  }
+  fn out(&self) -> Value { self.out.borrow().clone() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoFromJsonValue {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let out = self.out.borrow().clone();
+    let mut arguments = vec![Value::String(self.source.clone())];
+    if let Some(kind) = &self.kind {
+      arguments.push(Value::String(Ref::new(json_kind_to_string(kind)?)));
+    }
+    compile_value_varop!("IoFromJsonValue", out, arguments, ctx, FeatureFlag::Custom(hash_str("io/from-json")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoFromJsonValue",
+    ptr: IoFromJsonValue::new,
+  }
+}
+
+pub struct IoFromJson {}
+
+impl NativeFunctionCompiler for IoFromJson {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let (source, kind) = match arguments.as_slice() {
+      [source] => (json_string_argument(source, "io/from-json")?, None),
+      [source, Value::Kind(kind)] => (json_string_argument(source, "io/from-json")?, Some(kind.clone())),
+      [_, x] => return Err(MechError::new(
+          UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: "io/from-json".to_string() },
+          None
+        ).with_compiler_loc()
+      ),
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let value = json_string_to_value(&source.borrow(), kind.as_ref())?;
+    Ok(Box::new(IoFromJsonValue { source, kind, out: Ref::new(value) }))
+  }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/from-json",
+    ptr: &IoFromJson{},
+  }
+}
+
+// Read JSON
+// ----------------------------------------------------------------------------
+
+pub fn read_json_value(path: &str) -> MResult<Value> {
+  let source = std::fs::read_to_string(path).map_err(|err| MechError::new(
+      JsonReadError { file_path: path.to_string(), source: err.to_string() },
+      None
+    ).with_compiler_loc()
+  )?;
+  json_string_to_value(&source, None)
+}
+
+#[derive(Debug)]
+pub struct IoReadJsonValue {
+  pub path: Ref<String>,
+  pub out: Ref<Value>,
+}
+impl MechFunctionFactory for IoReadJsonValue {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(_, arguments) => {
+        let path = match arguments.as_slice() {
+          [path] => json_string_argument(path, "io/read-json")?,
+          _ => return Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        };
+        let value = read_json_value(&path.borrow())?;
+        Ok(Box::new(Self { path, out: Ref::new(value) }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoReadJsonValue {
+  fn solve(&self) {
+    // Keep the last value if the file has become unreadable.
+    if let Ok(value) = read_json_value(&self.path.borrow()) {
+      *self.out.borrow_mut() = value;
+    }
+  }
+  fn out(&self) -> Value { self.out.borrow().clone() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoReadJsonValue {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let out = self.out.borrow().clone();
+    compile_value_varop!("IoReadJsonValue", out, [Value::String(self.path.clone())], ctx, FeatureFlag::Custom(hash_str("io/read-json")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoReadJsonValue",
+    ptr: IoReadJsonValue::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoReadJsonValue",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoReadJson {}
+
+impl NativeFunctionCompiler for IoReadJson {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let path = match arguments.as_slice() {
+      [path] => json_string_argument(path, "io/read-json")?,
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let value = read_json_value(&path.borrow())?;
+    Ok(Box::new(IoReadJsonValue { path, out: Ref::new(value) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/read-json",
+    ptr: &IoReadJson{},
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonUnsupportedKindError {
+  pub kind: ValueKind,
+}
+impl MechErrorKind for JsonUnsupportedKindError {
+  fn name(&self) -> &str { "JsonUnsupportedKind" }
+  fn message(&self) -> String {
+    format!("Values of kind {} have no JSON representation", self.kind)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonParseError {
+  pub source: String,
+}
+impl MechErrorKind for JsonParseError {
+  fn name(&self) -> &str { "JsonParse" }
+  fn message(&self) -> String {
+    format!("Failed to parse JSON: {}", self.source)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonKindMismatchError {
+  pub kind: ValueKind,
+  pub found: String,
+}
+impl MechErrorKind for JsonKindMismatchError {
+  fn name(&self) -> &str { "JsonKindMismatch" }
+  fn message(&self) -> String {
+    format!("Expected JSON for a value of kind {}, found {}", self.kind, self.found)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonMissingFieldError {
+  pub field: String,
+  pub kind: ValueKind,
+}
+impl MechErrorKind for JsonMissingFieldError {
+  fn name(&self) -> &str { "JsonMissingField" }
+  fn message(&self) -> String {
+    format!("JSON object is missing field \"{}\" required by kind {}", self.field, self.kind)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonShapeError {
+  pub expected: Vec<usize>,
+  pub found: Vec<usize>,
+}
+impl MechErrorKind for JsonShapeError {
+  fn name(&self) -> &str { "JsonShape" }
+  fn message(&self) -> String {
+    format!("Expected a JSON matrix of shape {:?}, found {:?}", self.expected, self.found)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonReadError {
+  pub file_path: String,
+  pub source: String,
+}
+impl MechErrorKind for JsonReadError {
+  fn name(&self) -> &str { "JsonRead" }
+  fn message(&self) -> String {
+    format!("Failed to read JSON file {}: {}", self.file_path, self.source)
+  }
+}
@@ -61,11 +61,35 @@ macro_rules! log {
 pub mod print;
 #[cfg(feature = "println")]
 pub mod println;
+#[cfg(feature = "read_csv")]
+pub mod read_csv;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "file")]
+pub mod file;
+#[cfg(feature = "value_file")]
+pub mod value_file;
 
 #[cfg(feature = "print")]
 pub use self::print::*;
 #[cfg(feature = "println")]
 pub use self::println::*;
+#[cfg(feature = "read_csv")]
+pub use self::read_csv::*;
+#[cfg(feature = "arrow")]
+pub use self::arrow::*;
+#[cfg(feature = "parquet")]
+pub use self::parquet::*;
+#[cfg(feature = "json")]
+pub use self::json::*;
+#[cfg(feature = "file")]
+pub use self::file::*;
+#[cfg(feature = "value_file")]
+pub use self::value_file::*;
 
 #[macro_export]
 macro_rules! register_op {
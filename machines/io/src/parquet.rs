@@ -0,0 +1,258 @@
+use crate::*;
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+// Parquet
+// ----------------------------------------------------------------------------
+
+// Reads and writes tables as Parquet files, going through Arrow record batches
+// (see arrow.rs for how column kinds map to Arrow types). Files are written
+// with Snappy compression, which pyarrow and DuckDB read by default.
+
+pub fn read_parquet_table(path: &str) -> MResult<MechTable> {
+  let read_error = |source: String| MechError::new(
+    ParquetReadError { file_path: path.to_string(), source },
+    None
+  ).with_compiler_loc();
+  let file = std::fs::File::open(path).map_err(|err| read_error(err.to_string()))?;
+  let builder = ParquetRecordBatchReaderBuilder::try_new(file).map_err(|err| read_error(err.to_string()))?;
+  let schema = builder.schema().clone();
+  let reader = builder.build().map_err(|err| read_error(err.to_string()))?;
+  let batches = reader.collect::<Result<Vec<_>, _>>().map_err(|err| read_error(err.to_string()))?;
+  record_batches_to_table(&schema, &batches)
+}
+
+pub fn write_parquet_table(path: &str, table: &MechTable) -> MResult<()> {
+  let write_error = |source: String| MechError::new(
+    ParquetWriteError { file_path: path.to_string(), source },
+    None
+  ).with_compiler_loc();
+  let batch = table_to_record_batch(table)?;
+  let properties = ::parquet::file::properties::WriterProperties::builder()
+    .set_compression(::parquet::basic::Compression::SNAPPY)
+    .build();
+  let file = std::fs::File::create(path).map_err(|err| write_error(err.to_string()))?;
+  let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties)).map_err(|err| write_error(err.to_string()))?;
+  writer.write(&batch).map_err(|err| write_error(err.to_string()))?;
+  writer.close().map_err(|err| write_error(err.to_string()))?;
+  Ok(())
+}
+
+fn parquet_path_argument(value: &Value, fxn_name: &str) -> MResult<String> {
+  match value {
+    Value::String(path) => Ok(path.borrow().clone()),
+    Value::MutableReference(value) => parquet_path_argument(&value.borrow(), fxn_name),
+    x => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+fn parquet_arguments(arguments: &[Value], expected: usize) -> MResult<()> {
+  if arguments.len() != expected {
+    return Err(MechError::new(
+        IncorrectNumberOfArguments { expected, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    );
+  }
+  Ok(())
+}
+
+fn parquet_table_argument(value: &Value) -> MResult<Ref<MechTable>> {
+  match value {
+    Value::Table(table) => Ok(table.clone()),
+    Value::MutableReference(value) => parquet_table_argument(&value.borrow()),
+    x => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: "io/write-parquet".to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+// Read Parquet
+// ----------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub struct IoReadParquetTable {
+  pub path: Ref<String>,
+  pub out: Ref<MechTable>,
+}
+impl MechFunctionFactory for IoReadParquetTable {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(out, arguments) => {
+        parquet_arguments(&arguments, 1)?;
+        let path = parquet_path_argument(&arguments[0], "io/read-parquet")?;
+        let out: Ref<MechTable> = unsafe { out.as_unchecked() }.clone();
+        *out.borrow_mut() = read_parquet_table(&path)?;
+        Ok(Box::new(Self { path: Ref::new(path), out }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoReadParquetTable {
+  fn solve(&self) {
+    // Keep the last table if the file has become unreadable.
+    if let Ok(table) = read_parquet_table(&self.path.borrow()) {
+      *self.out.borrow_mut() = table;
+    }
+  }
+  fn out(&self) -> Value { Value::Table(self.out.clone()) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoReadParquetTable {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let path = Value::String(self.path.clone());
+    compile_value_varop!("IoReadParquetTable", Value::Table(self.out.clone()), [path], ctx, FeatureFlag::Custom(hash_str("io/read-parquet")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoReadParquetTable",
+    ptr: IoReadParquetTable::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoReadParquetTable",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoReadParquet {}
+
+impl NativeFunctionCompiler for IoReadParquet {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    parquet_arguments(arguments, 1)?;
+    let path = parquet_path_argument(&arguments[0], "io/read-parquet")?;
+    let table = read_parquet_table(&path)?;
+    Ok(Box::new(IoReadParquetTable { path: Ref::new(path), out: Ref::new(table) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/read-parquet",
+    ptr: &IoReadParquet{},
+  }
+}
+
+// Write Parquet
+// ----------------------------------------------------------------------------
+
+// Writes the table when compiled and again each time it changes, and returns
+// the table it writes. A failed write when compiling or loading is an error;
+// later failures are ignored and the next change tries again.
+#[derive(Debug)]
+pub struct IoWriteParquetTable {
+  pub path: Ref<String>,
+  pub table: Ref<MechTable>,
+}
+impl IoWriteParquetTable {
+  pub fn write(&self) -> MResult<()> {
+    write_parquet_table(&self.path.borrow(), &self.table.borrow())
+  }
+}
+impl MechFunctionFactory for IoWriteParquetTable {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(_, arguments) => {
+        parquet_arguments(&arguments, 2)?;
+        let fxn = Self {
+          path: Ref::new(parquet_path_argument(&arguments[0], "io/write-parquet")?),
+          table: parquet_table_argument(&arguments[1])?,
+        };
+        fxn.write()?;
+        Ok(Box::new(fxn))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoWriteParquetTable {
+  fn solve(&self) {
+    let _ = self.write();
+  }
+  fn out(&self) -> Value { Value::Table(self.table.clone()) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoWriteParquetTable {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let arguments = [Value::String(self.path.clone()), Value::Table(self.table.clone())];
+    compile_value_varop!("IoWriteParquetTable", Value::Table(self.table.clone()), arguments, ctx, FeatureFlag::Custom(hash_str("io/write-parquet")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoWriteParquetTable",
+    ptr: IoWriteParquetTable::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoWriteParquetTable",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoWriteParquet {}
+
+impl NativeFunctionCompiler for IoWriteParquet {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    parquet_arguments(arguments, 2)?;
+    let fxn = IoWriteParquetTable {
+      path: Ref::new(parquet_path_argument(&arguments[0], "io/write-parquet")?),
+      table: parquet_table_argument(&arguments[1])?,
+    };
+    fxn.write()?;
+    Ok(Box::new(fxn))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/write-parquet",
+    ptr: &IoWriteParquet{},
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParquetReadError {
+  pub file_path: String,
+  pub source: String,
+}
+impl MechErrorKind for ParquetReadError {
+  fn name(&self) -> &str { "ParquetRead" }
+  fn message(&self) -> String {
+    format!("Failed to read Parquet file {}: {}", self.file_path, self.source)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParquetWriteError {
+  pub file_path: String,
+  pub source: String,
+}
+impl MechErrorKind for ParquetWriteError {
+  fn name(&self) -> &str { "ParquetWrite" }
+  fn message(&self) -> String {
+    format!("Failed to write Parquet file {}: {}", self.file_path, self.source)
+  }
+}
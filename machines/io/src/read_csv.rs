@@ -0,0 +1,492 @@
+use crate::*;
+use indexmap::map::IndexMap;
+use std::collections::HashMap;
+
+// Read CSV
+// ----------------------------------------------------------------------------
+
+// Loads a comma (or, for .tsv files, tab) separated file into a table. The
+// first row names the columns. A header cell may fix its column's kind with an
+// annotation such as `age<u8>` or `score<f64?>`; otherwise the kind is inferred
+// from the column's non-empty cells, trying bool, then the narrowest signed
+// integer that holds every value, then f64, and finally string. Columns with
+// empty cells become optional, and their empty cells hold `Value::Empty`.
+
+pub fn csv_delimiter(path: &str) -> u8 {
+  if path.to_lowercase().ends_with(".tsv") { b'\t' } else { b',' }
+}
+
+pub fn read_csv_table(path: &str, schema: Option<&ValueKind>) -> MResult<MechTable> {
+  let file = std::fs::File::open(path).map_err(|err| MechError::new(
+      CsvReadError { file_path: path.to_string(), source: err.to_string() },
+      None
+    ).with_compiler_loc()
+  )?;
+  parse_csv_table(path, file, csv_delimiter(path), schema)
+}
+
+pub fn parse_csv_table<R: std::io::Read>(file_path: &str, reader: R, delimiter: u8, schema: Option<&ValueKind>) -> MResult<MechTable> {
+  let mut rdr = csv::ReaderBuilder::new()
+    .delimiter(delimiter)
+    .trim(csv::Trim::All)
+    .from_reader(reader);
+  let headers = rdr.headers().map_err(|err| csv_read_error(file_path, err))?.clone();
+  let mut names = Vec::new();
+  let mut kinds = Vec::new();
+  for field in headers.iter() {
+    let (name, kind) = parse_csv_header(field)?;
+    if names.contains(&name) {
+      return Err(MechError::new(CsvDuplicateColumnError { column: name }, None).with_compiler_loc());
+    }
+    names.push(name);
+    kinds.push(kind);
+  }
+  match schema {
+    Some(ValueKind::Table(columns, _)) => {
+      for (name, kind) in columns {
+        match names.iter().position(|n| n == name) {
+          Some(ix) => kinds[ix] = Some(kind.clone()),
+          None => return Err(MechError::new(CsvSchemaColumnError { column: name.clone() }, None).with_compiler_loc()),
+        }
+      }
+    }
+    Some(kind) => return Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: kind.clone(), fxn_name: "io/read-csv".to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+    None => (),
+  }
+  let mut cells: Vec<Vec<String>> = vec![Vec::new(); names.len()];
+  for record in rdr.records() {
+    let record = record.map_err(|err| csv_read_error(file_path, err))?;
+    for (col, cell) in record.iter().enumerate() {
+      cells[col].push(cell.to_string());
+    }
+  }
+  let rows = cells.first().map(|col| col.len()).unwrap_or(0);
+  let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+  let mut col_names: HashMap<u64, String> = HashMap::new();
+  for ((name, kind), column) in names.into_iter().zip(kinds.into_iter()).zip(cells.iter()) {
+    let kind = kind.unwrap_or_else(|| infer_csv_kind(column));
+    let mut values = Vec::with_capacity(rows);
+    for (row, text) in column.iter().enumerate() {
+      match parse_csv_cell(text, &kind) {
+        Some(value) => values.push(value),
+        None => return Err(MechError::new(
+            CsvCellKindError { row: row + 1, column: name.clone(), kind: kind.clone(), text: text.clone() },
+            None
+          ).with_compiler_loc()
+        ),
+      }
+    }
+    let id = hash_str(&name);
+    let column = Column::from_values(&kind, values);
+    data.insert(id, (kind, column));
+    col_names.insert(id, name);
+  }
+  Ok(MechTable::new(rows, data.len(), data, col_names))
+}
+
+fn csv_read_error(file_path: &str, err: csv::Error) -> MechError {
+  MechError::new(
+    CsvReadError { file_path: file_path.to_string(), source: err.to_string() },
+    None
+  ).with_compiler_loc()
+}
+
+fn parse_csv_header(field: &str) -> MResult<(String, Option<ValueKind>)> {
+  match (field.find('<'), field.ends_with('>')) {
+    (Some(ix), true) => {
+      let name = field[..ix].trim().to_string();
+      let kind_name = field[ix + 1..field.len() - 1].trim();
+      match csv_kind_from_name(kind_name) {
+        Some(kind) => Ok((name, Some(kind))),
+        None => Err(MechError::new(
+            CsvUnknownKindError { column: name, kind: kind_name.to_string() },
+            None
+          ).with_compiler_loc()
+        ),
+      }
+    }
+    _ => Ok((field.to_string(), None)),
+  }
+}
+
+fn csv_kind_from_name(name: &str) -> Option<ValueKind> {
+  if let Some(inner) = name.strip_suffix('?') {
+    return csv_kind_from_name(inner).map(|kind| ValueKind::Option(Box::new(kind)));
+  }
+  match name {
+    "string" => Some(ValueKind::String),
+    #[cfg(feature = "bool")]
+    "bool" => Some(ValueKind::Bool),
+    #[cfg(feature = "u8")]
+    "u8" => Some(ValueKind::U8),
+    #[cfg(feature = "u16")]
+    "u16" => Some(ValueKind::U16),
+    #[cfg(feature = "u32")]
+    "u32" => Some(ValueKind::U32),
+    #[cfg(feature = "u64")]
+    "u64" => Some(ValueKind::U64),
+    #[cfg(feature = "u128")]
+    "u128" => Some(ValueKind::U128),
+    #[cfg(feature = "i8")]
+    "i8" => Some(ValueKind::I8),
+    #[cfg(feature = "i16")]
+    "i16" => Some(ValueKind::I16),
+    #[cfg(feature = "i32")]
+    "i32" => Some(ValueKind::I32),
+    #[cfg(feature = "i64")]
+    "i64" => Some(ValueKind::I64),
+    #[cfg(feature = "i128")]
+    "i128" => Some(ValueKind::I128),
+    #[cfg(feature = "f32")]
+    "f32" => Some(ValueKind::F32),
+    #[cfg(feature = "f64")]
+    "f64" => Some(ValueKind::F64),
+    _ => None,
+  }
+}
+
+fn csv_kind_name(kind: &ValueKind) -> Option<String> {
+  match kind {
+    ValueKind::Option(inner) => csv_kind_name(inner).map(|name| format!("{}?", name)),
+    ValueKind::String => Some("string".to_string()),
+    #[cfg(feature = "bool")]
+    ValueKind::Bool => Some("bool".to_string()),
+    #[cfg(feature = "u8")]
+    ValueKind::U8 => Some("u8".to_string()),
+    #[cfg(feature = "u16")]
+    ValueKind::U16 => Some("u16".to_string()),
+    #[cfg(feature = "u32")]
+    ValueKind::U32 => Some("u32".to_string()),
+    #[cfg(feature = "u64")]
+    ValueKind::U64 => Some("u64".to_string()),
+    #[cfg(feature = "u128")]
+    ValueKind::U128 => Some("u128".to_string()),
+    #[cfg(feature = "i8")]
+    ValueKind::I8 => Some("i8".to_string()),
+    #[cfg(feature = "i16")]
+    ValueKind::I16 => Some("i16".to_string()),
+    #[cfg(feature = "i32")]
+    ValueKind::I32 => Some("i32".to_string()),
+    #[cfg(feature = "i64")]
+    ValueKind::I64 => Some("i64".to_string()),
+    #[cfg(feature = "i128")]
+    ValueKind::I128 => Some("i128".to_string()),
+    #[cfg(feature = "f32")]
+    ValueKind::F32 => Some("f32".to_string()),
+    #[cfg(feature = "f64")]
+    ValueKind::F64 => Some("f64".to_string()),
+    _ => None,
+  }
+}
+
+// A schema is encoded in compiled programs as an annotated CSV header, such
+// as `age<u8>,score<f64?>`, and decoded with the same parser as file headers.
+#[cfg(feature = "compiler")]
+fn csv_schema_to_header(schema: &ValueKind) -> MResult<String> {
+  let unsupported = |kind: &ValueKind| MechError::new(UnsupportedValueKindError { kind: kind.clone() }, None).with_compiler_loc();
+  let columns = match schema {
+    ValueKind::Table(columns, _) => columns,
+    kind => return Err(unsupported(kind)),
+  };
+  let mut fields = Vec::with_capacity(columns.len());
+  for (name, kind) in columns {
+    let kind_name = csv_kind_name(kind).ok_or_else(|| unsupported(kind))?;
+    fields.push(format!("{}<{}>", name, kind_name));
+  }
+  let mut writer = csv::WriterBuilder::new().terminator(csv::Terminator::Any(b'\n')).from_writer(vec![]);
+  writer.write_record(&fields).map_err(|err| csv_schema_error(err.to_string()))?;
+  let bytes = writer.into_inner().map_err(|err| csv_schema_error(err.to_string()))?;
+  Ok(String::from_utf8_lossy(&bytes).trim_end_matches('\n').to_string())
+}
+
+#[cfg(feature = "compiler")]
+fn csv_schema_error(source: String) -> MechError {
+  MechError::new(
+    CsvReadError { file_path: "<schema>".to_string(), source },
+    None
+  ).with_compiler_loc()
+}
+
+fn csv_schema_from_header(header: &str) -> MResult<ValueKind> {
+  let mut rdr = csv::ReaderBuilder::new()
+    .has_headers(false)
+    .trim(csv::Trim::All)
+    .from_reader(header.as_bytes());
+  let mut columns = Vec::new();
+  if let Some(record) = rdr.records().next() {
+    let record = record.map_err(|err| csv_read_error("<schema>", err))?;
+    for field in record.iter() {
+      match parse_csv_header(field)? {
+        (name, Some(kind)) => columns.push((name, kind)),
+        (name, None) => return Err(MechError::new(
+            CsvUnknownKindError { column: name, kind: String::new() },
+            None
+          ).with_compiler_loc()
+        ),
+      }
+    }
+  }
+  Ok(ValueKind::Table(columns, 0))
+}
+
+fn parse_csv_bool(text: &str) -> Option<bool> {
+  match text.to_lowercase().as_str() {
+    "true" => Some(true),
+    "false" => Some(false),
+    _ => None,
+  }
+}
+
+macro_rules! parse_csv_number {
+  ($text:expr, $kind:expr, $($variant:ident, $ty:ty, $feature:literal);+) => {
+    match $kind {
+      $(
+        #[cfg(feature = $feature)]
+        ValueKind::$variant => $text.parse::<$ty>().ok().map(|x| Value::$variant(Ref::new(x))),
+      )+
+      _ => None,
+    }
+  };
+}
+
+fn parse_csv_cell(text: &str, kind: &ValueKind) -> Option<Value> {
+  match kind {
+    ValueKind::Option(_) if text.is_empty() => Some(Value::Empty),
+    ValueKind::Option(inner) => parse_csv_cell(text, inner),
+    ValueKind::String => Some(Value::String(Ref::new(text.to_string()))),
+    #[cfg(feature = "bool")]
+    ValueKind::Bool => parse_csv_bool(text).map(|x| Value::Bool(Ref::new(x))),
+    kind => parse_csv_number!(text, kind,
+      U8, u8, "u8"; U16, u16, "u16"; U32, u32, "u32"; U64, u64, "u64"; U128, u128, "u128";
+      I8, i8, "i8"; I16, i16, "i16"; I32, i32, "i32"; I64, i64, "i64"; I128, i128, "i128";
+      F32, f32, "f32"; F64, f64, "f64"),
+  }
+}
+
+fn infer_csv_kind(column: &[String]) -> ValueKind {
+  let values: Vec<&str> = column.iter().map(|c| c.as_str()).filter(|c| !c.is_empty()).collect();
+  let kind = infer_csv_value_kind(&values);
+  if values.len() < column.len() {
+    ValueKind::Option(Box::new(kind))
+  } else {
+    kind
+  }
+}
+
+fn infer_csv_value_kind(values: &[&str]) -> ValueKind {
+  if values.is_empty() {
+    return ValueKind::String;
+  }
+  #[cfg(feature = "bool")]
+  if values.iter().all(|v| parse_csv_bool(v).is_some()) {
+    return ValueKind::Bool;
+  }
+  if let Some(kind) = infer_csv_int_kind(values) {
+    return kind;
+  }
+  #[cfg(feature = "f64")]
+  if values.iter().all(|v| v.parse::<f64>().is_ok()) {
+    return ValueKind::F64;
+  }
+  ValueKind::String
+}
+
+fn infer_csv_int_kind(values: &[&str]) -> Option<ValueKind> {
+  let mut min = 0i128;
+  let mut max = 0i128;
+  for v in values {
+    let x = v.parse::<i128>().ok()?;
+    min = min.min(x);
+    max = max.max(x);
+  }
+  #[cfg(feature = "i8")]
+  if min >= i8::MIN as i128 && max <= i8::MAX as i128 {
+    return Some(ValueKind::I8);
+  }
+  #[cfg(feature = "i16")]
+  if min >= i16::MIN as i128 && max <= i16::MAX as i128 {
+    return Some(ValueKind::I16);
+  }
+  #[cfg(feature = "i32")]
+  if min >= i32::MIN as i128 && max <= i32::MAX as i128 {
+    return Some(ValueKind::I32);
+  }
+  #[cfg(feature = "i64")]
+  if min >= i64::MIN as i128 && max <= i64::MAX as i128 {
+    return Some(ValueKind::I64);
+  }
+  #[cfg(feature = "i128")]
+  return Some(ValueKind::I128);
+  None
+}
+
+#[derive(Debug)]
+pub struct IoReadCsvTable {
+  pub path: Ref<String>,
+  pub schema: Option<ValueKind>,
+  pub out: Ref<MechTable>,
+}
+impl MechFunctionFactory for IoReadCsvTable {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(out, arguments) => {
+        let (path, schema) = match arguments.as_slice() {
+          [path] => (csv_path_argument(path)?, None),
+          [path, Value::String(header)] => (csv_path_argument(path)?, Some(csv_schema_from_header(&header.borrow())?)),
+          _ => return Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        };
+        let out: Ref<MechTable> = unsafe { out.as_unchecked() }.clone();
+        *out.borrow_mut() = read_csv_table(&path, schema.as_ref())?;
+        Ok(Box::new(Self { path: Ref::new(path), schema, out }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoReadCsvTable {
+  fn solve(&self) {
+    // Keep the last table if the file has become unreadable.
+    if let Ok(table) = read_csv_table(&self.path.borrow(), self.schema.as_ref()) {
+      *self.out.borrow_mut() = table;
+    }
+  }
+  fn out(&self) -> Value { Value::Table(self.out.clone()) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoReadCsvTable {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let mut arguments = vec![Value::String(self.path.clone())];
+    if let Some(schema) = &self.schema {
+      arguments.push(Value::String(Ref::new(csv_schema_to_header(schema)?)));
+    }
+    compile_value_varop!("IoReadCsvTable", Value::Table(self.out.clone()), arguments, ctx, FeatureFlag::Custom(hash_str("io/read-csv")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoReadCsvTable",
+    ptr: IoReadCsvTable::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoReadCsvTable",
+    capability: Capability::FileSystem,
+  }
+}
+
+fn csv_path_argument(value: &Value) -> MResult<String> {
+  match value {
+    Value::String(path) => Ok(path.borrow().clone()),
+    Value::MutableReference(value) => csv_path_argument(&value.borrow()),
+    x => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: "io/read-csv".to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+pub struct IoReadCsv {}
+
+impl NativeFunctionCompiler for IoReadCsv {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let (path, schema) = match arguments.as_slice() {
+      [path] => (csv_path_argument(path)?, None),
+      [path, Value::Kind(schema)] => (csv_path_argument(path)?, Some(schema.clone())),
+      [_, x] => return Err(MechError::new(
+          UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: "io/read-csv".to_string() },
+          None
+        ).with_compiler_loc()
+      ),
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let table = read_csv_table(&path, schema.as_ref())?;
+    Ok(Box::new(IoReadCsvTable { path: Ref::new(path), schema, out: Ref::new(table) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/read-csv",
+    ptr: &IoReadCsv{},
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvReadError {
+  pub file_path: String,
+  pub source: String,
+}
+impl MechErrorKind for CsvReadError {
+  fn name(&self) -> &str { "CsvRead" }
+  fn message(&self) -> String {
+    format!("Failed to read CSV file {}: {}", self.file_path, self.source)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvUnknownKindError {
+  pub column: String,
+  pub kind: String,
+}
+impl MechErrorKind for CsvUnknownKindError {
+  fn name(&self) -> &str { "CsvUnknownKind" }
+  fn message(&self) -> String {
+    format!("Column \"{}\" is annotated with unknown kind <{}>", self.column, self.kind)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvDuplicateColumnError {
+  pub column: String,
+}
+impl MechErrorKind for CsvDuplicateColumnError {
+  fn name(&self) -> &str { "CsvDuplicateColumn" }
+  fn message(&self) -> String {
+    format!("Column \"{}\" appears more than once in the CSV header", self.column)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvSchemaColumnError {
+  pub column: String,
+}
+impl MechErrorKind for CsvSchemaColumnError {
+  fn name(&self) -> &str { "CsvSchemaColumn" }
+  fn message(&self) -> String {
+    format!("Schema column \"{}\" does not appear in the CSV header", self.column)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvCellKindError {
+  pub row: usize,
+  pub column: String,
+  pub kind: ValueKind,
+  pub text: String,
+}
+impl MechErrorKind for CsvCellKindError {
+  fn name(&self) -> &str { "CsvCellKind" }
+  fn message(&self) -> String {
+    format!("Cell \"{}\" in row {} of column \"{}\" is not a valid {}", self.text, self.row, self.column, self.kind)
+  }
+}
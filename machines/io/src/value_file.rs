@@ -0,0 +1,267 @@
+use crate::*;
+use std::cell::RefCell;
+
+// Value Files
+// ----------------------------------------------------------------------------
+
+// Saves a value to disk and loads it back, so a program can keep its state
+// between runs. The file is a bytecode program holding the value as its only
+// constant, in the same encoding `mech build` uses for constants, so it carries
+// the bytecode header, version and checksum. Scalars, strings, matrices of
+// those, sets, tables and records of scalar fields round-trip; other values are
+// rejected when saved rather than producing a file that can't be loaded.
+
+fn value_file_scalar_kind(kind: &ValueKind) -> bool {
+  match kind {
+    ValueKind::U8 | ValueKind::U16 | ValueKind::U32 | ValueKind::U64 | ValueKind::U128 |
+    ValueKind::I8 | ValueKind::I16 | ValueKind::I32 | ValueKind::I64 | ValueKind::I128 |
+    ValueKind::F32 | ValueKind::F64 | ValueKind::C64 | ValueKind::R64 |
+    ValueKind::String | ValueKind::Bool => true,
+    _ => false,
+  }
+}
+
+pub fn value_file_supported_kind(kind: &ValueKind) -> bool {
+  match kind {
+    ValueKind::Matrix(elem, _) => value_file_scalar_kind(elem),
+    ValueKind::Set(elem, _) => value_file_scalar_kind(elem),
+    #[cfg(feature = "table")]
+    ValueKind::Table(columns, _) => columns.iter().all(|(_, kind)| match kind {
+      ValueKind::Option(inner) => value_file_scalar_kind(inner),
+      kind => value_file_scalar_kind(kind),
+    }),
+    #[cfg(feature = "record")]
+    ValueKind::Record(fields) => fields.iter().all(|(_, kind)| value_file_scalar_kind(kind)),
+    kind => value_file_scalar_kind(kind),
+  }
+}
+
+pub fn encode_value(value: &Value) -> MResult<Vec<u8>> {
+  let value = match value {
+    Value::MutableReference(value) => value.borrow().clone(),
+    value => value.clone(),
+  };
+  if !value_file_supported_kind(&value.kind()) {
+    return Err(MechError::new(
+        ValueFileUnsupportedKindError { kind: value.kind() },
+        None
+      ).with_compiler_loc()
+    );
+  }
+  let mut ctx = CompileCtx::new();
+  value.compile_const(&mut ctx)?;
+  ctx.compile()
+}
+
+pub fn decode_value(file_path: &str, bytes: &[u8]) -> MResult<Value> {
+  let program = load_program_from_bytes(bytes)?;
+  program.validate()?;
+  let mut values = program.decode_const_entries()?;
+  match (values.pop(), values.is_empty()) {
+    (Some(value), true) => Ok(value),
+    _ => Err(MechError::new(
+        ValueFileFormatError { file_path: file_path.to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+pub fn write_value_file(path: &str, value: &Value) -> MResult<()> {
+  let bytes = encode_value(value)?;
+  write_file_bytes(path, &bytes)
+}
+
+pub fn read_value_file(path: &str) -> MResult<Value> {
+  let bytes = read_file_bytes(path)?;
+  decode_value(path, &bytes)
+}
+
+// Write Value
+// ----------------------------------------------------------------------------
+
+// Saves the value when compiled and again each time it changes, and returns
+// the value.
+#[derive(Debug)]
+pub struct IoWriteValueFile {
+  pub path: Ref<String>,
+  pub value: Value,
+  pub written: RefCell<Vec<u8>>,
+}
+impl MechFunctionFactory for IoWriteValueFile {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(_, arguments) => {
+        file_arguments(&arguments, 2)?;
+        let path = file_path_argument(&arguments[0], "io/write-value")?;
+        let value = arguments[1].clone();
+        let bytes = encode_value(&value)?;
+        write_file_bytes(&path, &bytes)?;
+        Ok(Box::new(Self { path: Ref::new(path), value, written: RefCell::new(bytes) }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoWriteValueFile {
+  fn solve(&self) {
+    if let Ok(bytes) = encode_value(&self.value) {
+      if *self.written.borrow() != bytes && write_file_bytes(&self.path.borrow(), &bytes).is_ok() {
+        *self.written.borrow_mut() = bytes;
+      }
+    }
+  }
+  fn out(&self) -> Value { self.value.clone() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoWriteValueFile {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let arguments = [Value::String(self.path.clone()), self.value.clone()];
+    compile_value_varop!("IoWriteValueFile", self.value.clone(), arguments, ctx, FeatureFlag::Custom(hash_str("io/write-value")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoWriteValueFile",
+    ptr: IoWriteValueFile::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoWriteValueFile",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoWriteValue {}
+
+impl NativeFunctionCompiler for IoWriteValue {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let (path, value) = match arguments.as_slice() {
+      [path, value] => (file_path_argument(path, "io/write-value")?, value.clone()),
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let bytes = encode_value(&value)?;
+    write_file_bytes(&path, &bytes)?;
+    Ok(Box::new(IoWriteValueFile { path: Ref::new(path), value, written: RefCell::new(bytes) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/write-value",
+    ptr: &IoWriteValue{},
+  }
+}
+
+// Read Value
+// ----------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub struct IoReadValueFile {
+  pub path: Ref<String>,
+  pub out: Ref<Value>,
+}
+impl MechFunctionFactory for IoReadValueFile {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(_, arguments) => {
+        file_arguments(&arguments, 1)?;
+        let path = file_path_argument(&arguments[0], "io/read-value")?;
+        let value = read_value_file(&path)?;
+        Ok(Box::new(Self { path: Ref::new(path), out: Ref::new(value) }))
+      },
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl MechFunctionImpl for IoReadValueFile {
+  fn solve(&self) {
+    // Keep the last value if the file has become unreadable.
+    if let Ok(value) = read_value_file(&self.path.borrow()) {
+      *self.out.borrow_mut() = value;
+    }
+  }
+  fn out(&self) -> Value { self.out.borrow().clone() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for IoReadValueFile {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let out = self.out.borrow().clone();
+    compile_value_varop!("IoReadValueFile", out, [Value::String(self.path.clone())], ctx, FeatureFlag::Custom(hash_str("io/read-value")));
+  }
+}
+register_descriptor! {
+  FunctionDescriptor {
+    name: "IoReadValueFile",
+    ptr: IoReadValueFile::new,
+  }
+}
+register_descriptor! {
+  FunctionCapabilityDescriptor {
+    name: "IoReadValueFile",
+    capability: Capability::FileSystem,
+  }
+}
+
+pub struct IoReadValue {}
+
+impl NativeFunctionCompiler for IoReadValue {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    let path = match arguments.as_slice() {
+      [path] => file_path_argument(path, "io/read-value")?,
+      _ => return Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let value = read_value_file(&path)?;
+    Ok(Box::new(IoReadValueFile { path: Ref::new(path), out: Ref::new(value) }))
+  }
+  fn capability(&self) -> Option<Capability> { Some(Capability::FileSystem) }
+}
+
+register_descriptor!{
+  FunctionCompilerDescriptor {
+    name: "io/read-value",
+    ptr: &IoReadValue{},
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ValueFileUnsupportedKindError {
+  pub kind: ValueKind,
+}
+impl MechErrorKind for ValueFileUnsupportedKindError {
+  fn name(&self) -> &str { "ValueFileUnsupportedKind" }
+  fn message(&self) -> String {
+    format!("Values of kind {} can't be saved to a file", self.kind)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ValueFileFormatError {
+  pub file_path: String,
+}
+impl MechErrorKind for ValueFileFormatError {
+  fn name(&self) -> &str { "ValueFileFormat" }
+  fn message(&self) -> String {
+    format!("{} does not hold a single saved value", self.file_path)
+  }
+}
@@ -0,0 +1,20 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Cholesky Decomposition -------------------------------------------------------
+
+// Returns {l} with A = l * l', where l is lower triangular.
+pub fn linalg_cholesky<T: LinalgElement>(a: &DMatrix<T>) -> Vec<DMatrix<T>> {
+  match a.clone().cholesky() {
+    Some(chol) => vec![chol.l()],
+    None => vec![DMatrix::from_element(a.nrows(), a.ncols(), linalg_nan())],
+  }
+}
+
+impl_linalg_square_shapes!(impl_linalg_structs!(record ["l"], Cholesky, linalg_cholesky, "matrix/cholesky"));
+
+impl_linalg_dispatch!(square, impl_cholesky_fxn, MatrixCholesky, linalg_check_positive_definite, "matrix/cholesky");
+
+impl_linalg_compiler!(MatrixCholesky, impl_cholesky_fxn, "matrix/cholesky");
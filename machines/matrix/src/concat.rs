@@ -0,0 +1,52 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Concat -----------------------------------------------------------------------
+
+// matrix/concat(1, A, B, ...) stacks the matrices vertically, and
+// matrix/concat(2, A, B, ...) places them side by side.
+pub fn concat_plan(args: &[Value]) -> MResult<ShapePlan> {
+  shape_check_args(args, 2, usize::MAX)?;
+  let dim = shape_dim("matrix/concat", &args[0])?;
+  for arg in &args[1..] {
+    shape_check_matrix("matrix/concat", arg)?;
+  }
+  let shapes: Vec<(usize, usize)> = args[1..].iter().map(shape_of).collect();
+  let (rows, cols) = shapes[0];
+  for (r, c) in &shapes[1..] {
+    let (expected, found) = if dim == 1 { (cols, *c) } else { (rows, *r) };
+    if expected != found {
+      return Err(MechError::new(
+          MatrixConcatShapeError { fxn_name: "matrix/concat".to_string(), dim, expected, found },
+          None
+        ).with_compiler_loc()
+      );
+    }
+  }
+  let inputs = (1..args.len()).collect();
+  if dim == 1 {
+    let out_rows = shapes.iter().map(|(r, _)| r).sum();
+    let mut map = Vec::new();
+    for col in 0..cols {
+      for (input, (r, _)) in shapes.iter().enumerate() {
+        map.extend((0..*r).map(|row| Some((input, row + col * r))));
+      }
+    }
+    Ok(ShapePlan { rows: out_rows, cols, inputs, map })
+  } else {
+    let out_cols = shapes.iter().map(|(_, c)| c).sum();
+    let mut map = Vec::new();
+    for (input, (r, c)) in shapes.iter().enumerate() {
+      map.extend((0..r * c).map(|ix| Some((input, ix))));
+    }
+    Ok(ShapePlan { rows, cols: out_cols, inputs, map })
+  }
+}
+
+impl_shape_fxn!(MatrixConcatGather, concat_plan, "matrix/concat");
+
+impl_shape_dispatch!(matrix_concat, MatrixConcatGather, concat_plan, "matrix/concat");
+
+impl_shape_compiler!(MatrixConcat, matrix_concat, "matrix/concat", [0]);
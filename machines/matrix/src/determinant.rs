@@ -0,0 +1,16 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Determinant ------------------------------------------------------------------
+
+pub fn linalg_determinant<T: LinalgElement>(a: &DMatrix<T>) -> T {
+  a.determinant()
+}
+
+impl_linalg_square_shapes!(impl_linalg_structs!(scalar, Determinant, linalg_determinant, "matrix/determinant"));
+
+impl_linalg_dispatch!(square, impl_determinant_fxn, MatrixDeterminant, linalg_check_square, "matrix/determinant");
+
+impl_linalg_compiler!(MatrixDeterminant, impl_determinant_fxn, "matrix/determinant");
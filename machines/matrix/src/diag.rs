@@ -0,0 +1,29 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Diag -------------------------------------------------------------------------
+
+// A vector becomes the diagonal of a square matrix; a matrix gives its
+// diagonal as a column.
+pub fn diag_plan(args: &[Value]) -> MResult<ShapePlan> {
+  shape_check_args(args, 1, 1)?;
+  shape_check_matrix("matrix/diag", &args[0])?;
+  let (rows, cols) = shape_of(&args[0]);
+  if rows == 1 || cols == 1 {
+    let n = rows * cols;
+    let map = (0..n * n).map(|ix| if ix % n == ix / n { Some((0, ix % n)) } else { None }).collect();
+    Ok(ShapePlan { rows: n, cols: n, inputs: vec![0], map })
+  } else {
+    let n = rows.min(cols);
+    let map = (0..n).map(|ix| Some((0, ix + ix * rows))).collect();
+    Ok(ShapePlan { rows: n, cols: 1, inputs: vec![0], map })
+  }
+}
+
+impl_shape_fxn!(MatrixDiagGather, diag_plan, "matrix/diag");
+
+impl_shape_dispatch!(matrix_diag, MatrixDiagGather, diag_plan, "matrix/diag");
+
+impl_shape_compiler!(MatrixDiag, matrix_diag, "matrix/diag", []);
@@ -0,0 +1,24 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Symmetric Eigendecomposition -------------------------------------------------
+
+// Returns {values, vectors} for a symmetric matrix. The eigenvalues are a
+// column in ascending order, and column i of vectors belongs to value i.
+pub fn linalg_eig<T: LinalgElement>(a: &DMatrix<T>) -> Vec<DMatrix<T>> {
+  let n = a.nrows();
+  let eig = a.clone().symmetric_eigen();
+  let mut order: Vec<usize> = (0..n).collect();
+  order.sort_by(|i, j| eig.eigenvalues[*i].partial_cmp(&eig.eigenvalues[*j]).unwrap_or(std::cmp::Ordering::Equal));
+  let values = DMatrix::from_iterator(n, 1, order.iter().map(|i| eig.eigenvalues[*i]));
+  let vectors = DMatrix::from_fn(n, n, |row, col| eig.eigenvectors[(row, order[col])]);
+  vec![values, vectors]
+}
+
+impl_linalg_square_shapes!(impl_linalg_structs!(record ["values", "vectors"], Eig, linalg_eig, "matrix/eig"));
+
+impl_linalg_dispatch!(square, impl_eig_fxn, MatrixEig, linalg_check_symmetric, "matrix/eig");
+
+impl_linalg_compiler!(MatrixEig, impl_eig_fxn, "matrix/eig");
@@ -0,0 +1,32 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Flip -------------------------------------------------------------------------
+
+// matrix/flip(A, 1) reverses the rows, matrix/flip(A, 2) the columns. Without
+// a dimension the first one longer than 1 is flipped.
+pub fn flip_plan(args: &[Value]) -> MResult<ShapePlan> {
+  shape_check_args(args, 1, 2)?;
+  shape_check_matrix("matrix/flip", &args[0])?;
+  let (rows, cols) = shape_of(&args[0]);
+  let dim = match args.get(1) {
+    Some(dim) => shape_dim("matrix/flip", dim)?,
+    None => if rows > 1 { 1 } else { 2 },
+  };
+  let map = (0..rows * cols).map(|ix| {
+    let (row, col) = (ix % rows, ix / rows);
+    match dim {
+      1 => Some((0, (rows - 1 - row) + col * rows)),
+      _ => Some((0, row + (cols - 1 - col) * rows)),
+    }
+  }).collect();
+  Ok(ShapePlan { rows, cols, inputs: vec![0], map })
+}
+
+impl_shape_fxn!(MatrixFlipGather, flip_plan, "matrix/flip");
+
+impl_shape_dispatch!(matrix_flip, MatrixFlipGather, flip_plan, "matrix/flip");
+
+impl_shape_compiler!(MatrixFlip, matrix_flip, "matrix/flip", []);
@@ -0,0 +1,18 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Identity ---------------------------------------------------------------------
+
+pub fn identity_plan(args: &[Value]) -> MResult<(usize, usize)> {
+  shape_fill_size("matrix/identity", args)
+}
+
+pub fn identity_kernel(_params: &[f64], rows: usize, cols: usize) -> Vec<f64> {
+  (0..rows * cols).map(|ix| if ix % rows == ix / rows { 1.0 } else { 0.0 }).collect()
+}
+
+impl_fill_fxn!(MatrixIdentityFill, matrix_identity, identity_plan, identity_kernel, "matrix/identity");
+
+impl_shape_compiler!(MatrixIdentity, matrix_identity, "matrix/identity", [0, 1]);
@@ -0,0 +1,16 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Inverse ----------------------------------------------------------------------
+
+pub fn linalg_inverse<T: LinalgElement>(a: &DMatrix<T>) -> DMatrix<T> {
+  a.clone().try_inverse().unwrap_or_else(|| DMatrix::from_element(a.nrows(), a.ncols(), linalg_nan()))
+}
+
+impl_linalg_square_shapes!(impl_linalg_structs!(same, Inverse, linalg_inverse, "matrix/inverse"));
+
+impl_linalg_dispatch!(square, impl_inverse_fxn, MatrixInverse, linalg_check_invertible, "matrix/inverse");
+
+impl_linalg_compiler!(MatrixInverse, impl_inverse_fxn, "matrix/inverse");
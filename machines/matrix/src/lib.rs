@@ -43,6 +43,8 @@ use nalgebra::Matrix2x3;
 use nalgebra::Matrix3x2;
 #[cfg(feature = "matrix2")]
 use nalgebra::Matrix2;
+#[cfg(feature = "linalg")]
+use nalgebra::RealField;
 
 use std::ops::*;
 #[cfg(any(feature = "transpose", feature = "matmul"))]
@@ -61,6 +63,50 @@ pub mod transpose;
 pub mod dot;
 #[cfg(feature = "solve")]
 pub mod solve;
+#[cfg(feature = "inverse")]
+pub mod inverse;
+#[cfg(feature = "determinant")]
+pub mod determinant;
+#[cfg(feature = "trace")]
+pub mod trace;
+#[cfg(feature = "rank")]
+pub mod rank;
+#[cfg(feature = "norm")]
+pub mod norm;
+#[cfg(feature = "pinv")]
+pub mod pinv;
+#[cfg(feature = "lu")]
+pub mod lu;
+#[cfg(feature = "qr")]
+pub mod qr;
+#[cfg(feature = "cholesky")]
+pub mod cholesky;
+#[cfg(feature = "svd")]
+pub mod svd;
+#[cfg(feature = "eig")]
+pub mod eig;
+#[cfg(feature = "identity")]
+pub mod identity;
+#[cfg(feature = "zeros")]
+pub mod zeros;
+#[cfg(feature = "ones")]
+pub mod ones;
+#[cfg(feature = "linspace")]
+pub mod linspace;
+#[cfg(feature = "diag")]
+pub mod diag;
+#[cfg(feature = "meshgrid")]
+pub mod meshgrid;
+#[cfg(feature = "repeat")]
+pub mod repeat;
+#[cfg(feature = "reshape")]
+pub mod reshape;
+#[cfg(feature = "flip")]
+pub mod flip;
+#[cfg(feature = "rotate")]
+pub mod rotate;
+#[cfg(feature = "concat")]
+pub mod concat;
 //pub mod cross;
 
 #[cfg(feature = "matmul")]
@@ -71,8 +117,991 @@ pub use self::transpose::*;
 pub use self::dot::*;
 #[cfg(feature = "solve")]
 pub use self::solve::*;
+#[cfg(feature = "inverse")]
+pub use self::inverse::*;
+#[cfg(feature = "determinant")]
+pub use self::determinant::*;
+#[cfg(feature = "trace")]
+pub use self::trace::*;
+#[cfg(feature = "rank")]
+pub use self::rank::*;
+#[cfg(feature = "norm")]
+pub use self::norm::*;
+#[cfg(feature = "pinv")]
+pub use self::pinv::*;
+#[cfg(feature = "lu")]
+pub use self::lu::*;
+#[cfg(feature = "qr")]
+pub use self::qr::*;
+#[cfg(feature = "cholesky")]
+pub use self::cholesky::*;
+#[cfg(feature = "svd")]
+pub use self::svd::*;
+#[cfg(feature = "eig")]
+pub use self::eig::*;
+#[cfg(feature = "identity")]
+pub use self::identity::*;
+#[cfg(feature = "zeros")]
+pub use self::zeros::*;
+#[cfg(feature = "ones")]
+pub use self::ones::*;
+#[cfg(feature = "linspace")]
+pub use self::linspace::*;
+#[cfg(feature = "diag")]
+pub use self::diag::*;
+#[cfg(feature = "meshgrid")]
+pub use self::meshgrid::*;
+#[cfg(feature = "repeat")]
+pub use self::repeat::*;
+#[cfg(feature = "reshape")]
+pub use self::reshape::*;
+#[cfg(feature = "flip")]
+pub use self::flip::*;
+#[cfg(feature = "rotate")]
+pub use self::rotate::*;
+#[cfg(feature = "concat")]
+pub use self::concat::*;
 //pub use self::cross::*;
 
 // ----------------------------------------------------------------------------
 // Matrix Library
-// ----------------------------------------------------------------------------
+// ----------------------------------------------------------------------------
+
+// Linear Algebra -------------------------------------------------------------
+
+// The decompositions and the other linear algebra functions are computed by
+// nalgebra on a dynamic copy of the argument, so one kernel serves every
+// matrix shape. They are implemented for the real kinds f32 and f64.
+
+#[cfg(feature = "linalg")]
+pub trait LinalgElement: RealField + Copy + Debug + Display + Default + Sync + Send + 'static {}
+#[cfg(all(feature = "linalg", feature = "f32"))]
+impl LinalgElement for f32 {}
+#[cfg(all(feature = "linalg", feature = "f64"))]
+impl LinalgElement for f64 {}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_dmatrix<T, R, C, S>(m: &na::Matrix<T, R, C, S>) -> DMatrix<T>
+where
+  T: LinalgElement,
+  R: na::Dim,
+  C: na::Dim,
+  S: na::RawStorage<T, R, C>,
+{
+  DMatrix::from_iterator(m.nrows(), m.ncols(), m.iter().cloned())
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_nan<T: LinalgElement>() -> T {
+  T::from_subset(&f64::NAN)
+}
+
+// Singular values below this are treated as zero by rank and pinv, the same
+// cutoff numpy uses.
+#[cfg(feature = "linalg")]
+pub fn linalg_tolerance<T: LinalgElement>(a: &DMatrix<T>, singular_values: &[T]) -> T {
+  let largest = singular_values.iter().fold(T::zero(), |acc, x| if *x > acc { *x } else { acc });
+  let dim = a.nrows().max(a.ncols()) as f64;
+  T::default_epsilon() * T::from_subset(&dim) * largest
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_is_symmetric<T: LinalgElement>(a: &DMatrix<T>) -> bool {
+  if !a.is_square() {
+    return false;
+  }
+  let scale = a.iter().fold(T::one(), |acc, x| if x.abs() > acc { x.abs() } else { acc });
+  let tol = T::default_epsilon().sqrt() * scale;
+  (0..a.nrows()).all(|i| (0..i).all(|j| (a[(i, j)] - a[(j, i)]).abs() <= tol))
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_check_square<T: LinalgElement>(fxn_name: &str, a: &DMatrix<T>) -> MResult<()> {
+  if a.is_square() {
+    Ok(())
+  } else {
+    Err(MechError::new(MatrixNotSquareError { fxn_name: fxn_name.to_string(), rows: a.nrows(), cols: a.ncols() }, None).with_compiler_loc())
+  }
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_check_any<T: LinalgElement>(_fxn_name: &str, _a: &DMatrix<T>) -> MResult<()> {
+  Ok(())
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_check_invertible<T: LinalgElement>(fxn_name: &str, a: &DMatrix<T>) -> MResult<()> {
+  linalg_check_square(fxn_name, a)?;
+  if a.clone().try_inverse().is_some() {
+    Ok(())
+  } else {
+    Err(MechError::new(MatrixSingularError { fxn_name: fxn_name.to_string() }, None).with_compiler_loc())
+  }
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_check_symmetric<T: LinalgElement>(fxn_name: &str, a: &DMatrix<T>) -> MResult<()> {
+  linalg_check_square(fxn_name, a)?;
+  if linalg_is_symmetric(a) {
+    Ok(())
+  } else {
+    Err(MechError::new(MatrixNotSymmetricError { fxn_name: fxn_name.to_string() }, None).with_compiler_loc())
+  }
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_check_positive_definite<T: LinalgElement>(fxn_name: &str, a: &DMatrix<T>) -> MResult<()> {
+  linalg_check_symmetric(fxn_name, a)?;
+  if a.clone().cholesky().is_some() {
+    Ok(())
+  } else {
+    Err(MechError::new(MatrixNotPositiveDefiniteError { fxn_name: fxn_name.to_string() }, None).with_compiler_loc())
+  }
+}
+
+// Builds the record returned by a decomposition, one dynamic matrix field per
+// factor. The field refs are returned too so the function can update them in
+// place.
+#[cfg(all(feature = "linalg", feature = "record"))]
+pub fn linalg_record<T>(names: &[&str], factors: Vec<DMatrix<T>>) -> (Ref<MechRecord>, Vec<Ref<DMatrix<T>>>)
+where
+  T: LinalgElement,
+  Ref<DMatrix<T>>: ToValue,
+{
+  let fields: Vec<Ref<DMatrix<T>>> = factors.into_iter().map(Ref::new).collect();
+  let record = MechRecord::new(names.iter().zip(fields.iter()).map(|(name, field)| (*name, field.to_value())).collect());
+  (Ref::new(record), fields)
+}
+
+// One row per matrix shape: suffix, shape, transposed shape, [features].
+#[macro_export]
+macro_rules! impl_linalg_shapes {
+  ($callback:ident!($($args:tt)*)) => {
+    $callback!($($args)*;
+      M1,   Matrix1,    Matrix1,    ["matrix1"];
+      M2,   Matrix2,    Matrix2,    ["matrix2"];
+      M3,   Matrix3,    Matrix3,    ["matrix3"];
+      M4,   Matrix4,    Matrix4,    ["matrix4"];
+      M2x3, Matrix2x3,  Matrix3x2,  ["matrix2x3", "matrix3x2"];
+      M3x2, Matrix3x2,  Matrix2x3,  ["matrix3x2", "matrix2x3"];
+      MD,   DMatrix,    DMatrix,    ["matrixd"];
+      V2,   Vector2,    RowVector2, ["vector2", "row_vector2"];
+      V3,   Vector3,    RowVector3, ["vector3", "row_vector3"];
+      V4,   Vector4,    RowVector4, ["vector4", "row_vector4"];
+      VD,   DVector,    RowDVector, ["vectord", "row_vectord"];
+      R2,   RowVector2, Vector2,    ["row_vector2", "vector2"];
+      R3,   RowVector3, Vector3,    ["row_vector3", "vector3"];
+      R4,   RowVector4, Vector4,    ["row_vector4", "vector4"];
+      RD,   RowDVector, DVector,    ["row_vectord", "vectord"];
+    );
+  };
+}
+
+// The shapes which can hold a square matrix. Dynamic matrices are checked
+// when the function is built.
+#[macro_export]
+macro_rules! impl_linalg_square_shapes {
+  ($callback:ident!($($args:tt)*)) => {
+    $callback!($($args)*;
+      M1,   Matrix1,    Matrix1,    ["matrix1"];
+      M2,   Matrix2,    Matrix2,    ["matrix2"];
+      M3,   Matrix3,    Matrix3,    ["matrix3"];
+      M4,   Matrix4,    Matrix4,    ["matrix4"];
+      MD,   DMatrix,    DMatrix,    ["matrixd"];
+    );
+  };
+}
+
+#[macro_export]
+macro_rules! linalg_init {
+  (same, $arg:expr) => { Ref::new($arg.borrow().clone()) };
+  (transposed, $arg:expr) => { Ref::new($arg.borrow().transpose()) };
+  (scalar, $arg:expr) => { Ref::new(T::zero()) };
+  (count, $arg:expr) => { Ref::new(0u64) };
+}
+
+#[macro_export]
+macro_rules! linalg_matrix_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let result = $kernel(&linalg_dmatrix(&*$arg));
+      (*$out).copy_from_slice(result.as_slice());
+    }
+  };}
+
+#[macro_export]
+macro_rules! linalg_scalar_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe { *$out = $kernel(&linalg_dmatrix(&*$arg)); }
+  };}
+
+#[macro_export]
+macro_rules! impl_linalg_fxn {
+  ($struct_name:ident, $arg_type:ty, $out_type:ty, $init:ident, $op:ident, $kernel:ident, $feature:tt) => {
+    #[derive(Debug)]
+    struct $struct_name<T> {
+      arg: Ref<$arg_type>,
+      out: Ref<$out_type>,
+    }
+    impl<T> $struct_name<T>
+    where
+      T: LinalgElement,
+      Ref<$out_type>: ToValue
+    {
+      fn from_arg(arg: Ref<$arg_type>) -> Self {
+        let out = linalg_init!($init, arg);
+        let fxn = Self { arg, out };
+        fxn.solve();
+        fxn
+      }
+    }
+    impl<T> MechFunctionFactory for $struct_name<T>
+    where
+      T: LinalgElement + CompileConst + ConstElem + AsValueKind,
+      Ref<$out_type>: ToValue
+    {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Unary(out, arg) => {
+            let arg = unsafe{ arg.as_unchecked().clone() };
+            let out = unsafe{ out.as_unchecked().clone() };
+            Ok(Box::new(Self { arg, out }))
+          }
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 1, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl<T> MechFunctionImpl for $struct_name<T>
+    where
+      T: LinalgElement,
+      Ref<$out_type>: ToValue
+    {
+      fn solve(&self) {
+        let arg_ptr = self.arg.as_ptr();
+        let out_ptr = self.out.as_mut_ptr();
+        $op!(arg_ptr, out_ptr, $kernel);
+      }
+      fn out(&self) -> Value { self.out.to_value() }
+      fn to_string(&self) -> String { format!("{:#?}", self) }
+    }
+    #[cfg(feature = "compiler")]
+    impl<T> MechFunctionCompiler for $struct_name<T>
+    where
+      T: CompileConst + ConstElem + AsValueKind,
+    {
+      fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        let name = format!("{}<{}>", stringify!($struct_name), T::as_value_kind());
+        compile_unop!(name, self.out, self.arg, ctx, FeatureFlag::Custom(hash_str($feature)));
+      }
+    }
+    register_fxn_descriptor!($struct_name, f32, "f32", f64, "f64");
+  };}
+
+// A decomposition returns a record of factors. The kernel returns the factors
+// in the same order as the field names.
+#[macro_export]
+macro_rules! impl_linalg_record_fxn {
+  ($struct_name:ident, $arg_type:ty, $fields:tt, $kernel:ident, $feature:tt) => {
+    #[derive(Debug)]
+    struct $struct_name<T> {
+      arg: Ref<$arg_type>,
+      fields: Vec<Ref<DMatrix<T>>>,
+      out: Ref<MechRecord>,
+    }
+    impl<T> $struct_name<T>
+    where
+      T: LinalgElement,
+      Ref<DMatrix<T>>: ToValue
+    {
+      fn from_arg(arg: Ref<$arg_type>) -> Self {
+        let (out, fields) = linalg_record(&$fields, $kernel(&linalg_dmatrix(&*arg.borrow())));
+        Self { arg, fields, out }
+      }
+    }
+    impl<T> MechFunctionFactory for $struct_name<T>
+    where
+      T: LinalgElement + CompileConst + ConstElem + AsValueKind,
+      Ref<DMatrix<T>>: ToValue
+    {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Unary(Value::Record(out), arg) => {
+            let arg = unsafe{ arg.as_unchecked().clone() };
+            let fields = out.borrow().data.values().map(|field| unsafe{ field.as_unchecked().clone() }).collect();
+            Ok(Box::new(Self { arg, fields, out }))
+          }
+          FunctionArgs::Unary(out, _) => Err(MechError::new(
+              UnhandledFunctionArgumentKind1 { arg: out.kind(), fxn_name: stringify!($struct_name).to_string() },
+              None
+            ).with_compiler_loc()
+          ),
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 1, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl<T> MechFunctionImpl for $struct_name<T>
+    where
+      T: LinalgElement,
+      Ref<DMatrix<T>>: ToValue
+    {
+      fn solve(&self) {
+        let factors = $kernel(&linalg_dmatrix(&*self.arg.borrow()));
+        for (field, factor) in self.fields.iter().zip(factors) {
+          *field.borrow_mut() = factor;
+        }
+      }
+      fn out(&self) -> Value { Value::Record(self.out.clone()) }
+      fn to_string(&self) -> String { format!("{:#?}", self) }
+    }
+    #[cfg(feature = "compiler")]
+    impl<T> MechFunctionCompiler for $struct_name<T>
+    where
+      T: CompileConst + ConstElem + AsValueKind,
+    {
+      fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        let name = format!("{}<{}>", stringify!($struct_name), T::as_value_kind());
+        compile_unop!(name, self.out, self.arg, ctx, FeatureFlag::Custom(hash_str($feature)));
+      }
+    }
+    register_fxn_descriptor!($struct_name, f32, "f32", f64, "f64");
+  };}
+
+// The per shape structs of one function. The mode picks the output: `same`
+// and `transposed` matrices, a `scalar` of the element kind, a `count` (u64),
+// or a `record` of factors.
+#[macro_export]
+macro_rules! impl_linalg_structs {
+  (same, $name:ident, $kernel:ident, $feature:tt; $($suffix:ident, $in:ident, $tr:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_linalg_fxn!([<Matrix $name $suffix>], $in<T>, $in<T>, same, linalg_matrix_op, $kernel, $feature);
+      )+
+    }
+  };
+  (transposed, $name:ident, $kernel:ident, $feature:tt; $($suffix:ident, $in:ident, $tr:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_linalg_fxn!([<Matrix $name $suffix>], $in<T>, $tr<T>, transposed, linalg_matrix_op, $kernel, $feature);
+      )+
+    }
+  };
+  (scalar, $name:ident, $kernel:ident, $feature:tt; $($suffix:ident, $in:ident, $tr:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_linalg_fxn!([<Matrix $name $suffix>], $in<T>, T, scalar, linalg_scalar_op, $kernel, $feature);
+      )+
+    }
+  };
+  (count, $name:ident, $kernel:ident, $feature:tt; $($suffix:ident, $in:ident, $tr:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_linalg_fxn!([<Matrix $name $suffix>], $in<T>, u64, count, linalg_scalar_op, $kernel, $feature);
+      )+
+    }
+  };
+  (record $fields:tt, $name:ident, $kernel:ident, $feature:tt; $($suffix:ident, $in:ident, $tr:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_linalg_record_fxn!([<Matrix $name $suffix>], $in<T>, $fields, $kernel, $feature);
+      )+
+    }
+  };
+}
+
+#[macro_export]
+macro_rules! impl_linalg_arms {
+  ($value:ident, $prefix:ident, $check:ident, $fxn_string:tt, $value_kind:ident, $target_type:ty, $value_string:tt; $($suffix:ident, $in:ident, $tr:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all(feature = $value_string, $(feature = $shape_feature),+))]
+        if let Value::[<Matrix $value_kind>](Matrix::<$target_type>::$in(arg)) = &$value {
+          $check($fxn_string, &linalg_dmatrix(&*arg.borrow()))?;
+          return Ok(Box::new([<$prefix $suffix>]::<$target_type>::from_arg(arg.clone())));
+        }
+      )+
+    }
+  };
+}
+
+// A constructor for the function `$prefix`, trying f32 and f64 in turn. A
+// real matrix of another shape is reported as not square for the square-only
+// functions.
+#[macro_export]
+macro_rules! impl_linalg_dispatch {
+  (square, $fxn:ident, $prefix:ident, $check:ident, $fxn_string:tt) => {
+    fn $fxn(value: Value) -> MResult<Box<dyn MechFunction>> {
+      impl_linalg_square_shapes!(impl_linalg_arms!(value, $prefix, $check, $fxn_string, F32, f32, "f32"));
+      impl_linalg_square_shapes!(impl_linalg_arms!(value, $prefix, $check, $fxn_string, F64, f64, "f64"));
+      match &value {
+        #[cfg(feature = "f32")]
+        Value::MatrixF32(_) => linalg_not_square($fxn_string, &value),
+        #[cfg(feature = "f64")]
+        Value::MatrixF64(_) => linalg_not_square($fxn_string, &value),
+        _ => linalg_unhandled($fxn_string, &value),
+      }
+    }
+  };
+  (any, $fxn:ident, $prefix:ident, $check:ident, $fxn_string:tt) => {
+    fn $fxn(value: Value) -> MResult<Box<dyn MechFunction>> {
+      impl_linalg_shapes!(impl_linalg_arms!(value, $prefix, $check, $fxn_string, F32, f32, "f32"));
+      impl_linalg_shapes!(impl_linalg_arms!(value, $prefix, $check, $fxn_string, F64, f64, "f64"));
+      linalg_unhandled($fxn_string, &value)
+    }
+  };
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_not_square(fxn_name: &str, value: &Value) -> MResult<Box<dyn MechFunction>> {
+  let shape = value.shape();
+  Err(MechError::new(
+      MatrixNotSquareError { fxn_name: fxn_name.to_string(), rows: shape[0], cols: shape[1] },
+      None
+    ).with_compiler_loc()
+  )
+}
+
+#[cfg(feature = "linalg")]
+pub fn linalg_unhandled(fxn_name: &str, value: &Value) -> MResult<Box<dyn MechFunction>> {
+  Err(MechError::new(
+      UnhandledFunctionArgumentKind1 { arg: value.kind(), fxn_name: fxn_name.to_string() },
+      None
+    ).with_compiler_loc()
+  )
+}
+
+// Like impl_mech_urnop_fxn, but the errors of the constructor (a singular or
+// non-square argument) are passed on to the caller.
+#[macro_export]
+macro_rules! impl_linalg_compiler {
+  ($fxn_name:ident, $gen_fxn:tt, $fxn_string:tt) => {
+    pub struct $fxn_name {}
+    impl NativeFunctionCompiler for $fxn_name {
+      fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        if arguments.len() != 1 {
+          return Err(MechError::new(IncorrectNumberOfArguments { expected: 1, found: arguments.len() }, None).with_compiler_loc());
+        }
+        match &arguments[0] {
+          Value::MutableReference(input) => $gen_fxn(input.borrow().clone()),
+          input => $gen_fxn(input.clone()),
+        }
+      }
+    }
+    register_descriptor! {
+      FunctionCompilerDescriptor {
+        name: $fxn_string,
+        ptr: &$fxn_name{},
+      }
+    }
+  };
+}
+
+// Construction and Reshaping -------------------------------------------------
+
+// Every reshaping function is a gather: each element of the output, in
+// column-major order, is copied from an element of one of the input matrices,
+// or left as the default value of its kind. The plan is worked out from the
+// argument shapes when the function is built, and the output is created with
+// Matrix::from_vec, so it takes a fixed nalgebra type when one of that size is
+// enabled, and a dynamic one otherwise.
+
+#[cfg(feature = "shape")]
+pub trait ShapeElement: Clone + Debug + Default + PartialEq + Sync + Send + 'static + AsValueKind {
+  fn matrix(value: &Value) -> Option<Matrix<Self>>;
+}
+
+#[cfg(feature = "shape")]
+macro_rules! impl_shape_element {
+  ($($t:ty, $kind:ident, $value_string:tt);+ $(;)?) => {
+    $(
+      #[cfg(feature = $value_string)]
+      impl ShapeElement for $t {
+        fn matrix(value: &Value) -> Option<Matrix<Self>> {
+          match value {
+            Value::$kind(m) => Some(m.clone()),
+            Value::MutableReference(value) => Self::matrix(&value.borrow()),
+            _ => None,
+          }
+        }
+      }
+    )+
+  };
+}
+
+#[cfg(feature = "shape")]
+impl_shape_element!(
+  bool, MatrixBool, "bool";
+  String, MatrixString, "string";
+  u8, MatrixU8, "u8";
+  u16, MatrixU16, "u16";
+  u32, MatrixU32, "u32";
+  u64, MatrixU64, "u64";
+  u128, MatrixU128, "u128";
+  i8, MatrixI8, "i8";
+  i16, MatrixI16, "i16";
+  i32, MatrixI32, "i32";
+  i64, MatrixI64, "i64";
+  i128, MatrixI128, "i128";
+  f32, MatrixF32, "f32";
+  f64, MatrixF64, "f64";
+);
+
+#[cfg(feature = "shape")]
+#[derive(Debug, Clone)]
+pub struct ShapePlan {
+  pub rows: usize,
+  pub cols: usize,
+  // The arguments the elements are gathered from.
+  pub inputs: Vec<usize>,
+  // For each output element, the input and the element within it.
+  pub map: Vec<Option<(usize, usize)>>,
+}
+
+#[cfg(feature = "shape")]
+pub fn shape_of(value: &Value) -> (usize, usize) {
+  let shape = value.shape();
+  (shape[0], shape[1])
+}
+
+// A size or count argument, which must be a positive whole number.
+#[cfg(feature = "shape")]
+pub fn shape_size(fxn_name: &str, value: &Value) -> MResult<usize> {
+  let size = value.as_f64().map(|x| *x.borrow());
+  match size {
+    Ok(size) if size >= 1.0 && size.fract() == 0.0 => Ok(size as usize),
+    _ => Err(shape_argument_error(fxn_name, value, "a positive whole number")),
+  }
+}
+
+// A dimension argument: 1 for rows, 2 for columns.
+#[cfg(feature = "shape")]
+pub fn shape_dim(fxn_name: &str, value: &Value) -> MResult<usize> {
+  match value.as_f64().map(|x| *x.borrow()) {
+    Ok(dim) if dim == 1.0 || dim == 2.0 => Ok(dim as usize),
+    _ => Err(shape_argument_error(fxn_name, value, "1 or 2")),
+  }
+}
+
+// A number of quarter turns, which may be negative.
+#[cfg(feature = "shape")]
+pub fn shape_turns(fxn_name: &str, value: &Value) -> MResult<usize> {
+  match value.as_f64().map(|x| *x.borrow()) {
+    Ok(turns) if turns.fract() == 0.0 => Ok((turns as i64).rem_euclid(4) as usize),
+    _ => Err(shape_argument_error(fxn_name, value, "a whole number")),
+  }
+}
+
+#[cfg(feature = "shape")]
+pub fn shape_argument_error(fxn_name: &str, value: &Value, expected: &str) -> MechError {
+  let found = match value.as_f64() {
+    Ok(x) => format!("{}", x.borrow()),
+    Err(_) => format!("{}", value.kind()),
+  };
+  MechError::new(
+    MatrixArgumentError { fxn_name: fxn_name.to_string(), expected: expected.to_string(), found },
+    None
+  ).with_compiler_loc()
+}
+
+#[cfg(feature = "shape")]
+pub fn shape_check_args(args: &[Value], min: usize, max: usize) -> MResult<()> {
+  if args.len() < min || args.len() > max {
+    let expected = if args.len() < min { min } else { max };
+    return Err(MechError::new(IncorrectNumberOfArguments { expected, found: args.len() }, None).with_compiler_loc());
+  }
+  Ok(())
+}
+
+#[cfg(feature = "shape")]
+pub fn shape_check_matrix(fxn_name: &str, value: &Value) -> MResult<()> {
+  if value.is_matrix() {
+    Ok(())
+  } else {
+    Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: value.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    )
+  }
+}
+
+// The size of a construction function: (n) is n x n, (r, c) is r x c.
+#[cfg(feature = "shape")]
+pub fn shape_fill_size(fxn_name: &str, args: &[Value]) -> MResult<(usize, usize)> {
+  shape_check_args(args, 1, 2)?;
+  let rows = shape_size(fxn_name, &args[0])?;
+  let cols = match args.get(1) {
+    Some(cols) => shape_size(fxn_name, cols)?,
+    None => rows,
+  };
+  Ok((rows, cols))
+}
+
+// A new output of the given size. It has a fixed nalgebra type when the size
+// is a constant of the program, and is a matrixd when it may change.
+#[cfg(feature = "shape")]
+pub fn shape_out<T: ShapeElement>(rows: usize, cols: usize, dynamic: bool) -> Matrix<T> {
+  let elements = vec![T::default(); rows * cols];
+  if dynamic {
+    Matrix::DMatrix(Ref::new(DMatrix::from_vec(rows, cols, elements)))
+  } else {
+    Matrix::from_vec(elements, rows, cols)
+  }
+}
+
+// Writes a result into the output. A matrixd takes on the new size; any other
+// output keeps its last value when the size no longer fits.
+#[cfg(feature = "shape")]
+pub fn shape_write<T: ShapeElement>(out: &Matrix<T>, elements: Vec<T>, rows: usize, cols: usize) {
+  match out {
+    Matrix::DMatrix(m) => {
+      let mut m = m.borrow_mut();
+      if m.shape() == (rows, cols) {
+        m.copy_from_slice(&elements);
+      } else {
+        *m = DMatrix::from_vec(rows, cols, elements);
+      }
+    }
+    out if out.shape() == vec![rows, cols] => out.set(elements),
+    _ => (),
+  }
+}
+
+#[cfg(feature = "shape")]
+pub fn shape_deref(args: &[Value]) -> Vec<Value> {
+  args.iter().map(|arg| match arg {
+    Value::MutableReference(value) => value.borrow().clone(),
+    value => value.clone(),
+  }).collect()
+}
+
+#[macro_export]
+macro_rules! impl_shape_fxn {
+  ($struct_name:ident, $plan:ident, $feature:tt) => {
+    #[derive(Debug)]
+    struct $struct_name<T> {
+      args: Vec<Value>,
+      inputs: Vec<Matrix<T>>,
+      out: Matrix<T>,
+    }
+    impl<T> $struct_name<T>
+    where
+      T: ShapeElement,
+      Matrix<T>: ToValue
+    {
+      fn build(args: Vec<Value>, plan: ShapePlan, out: Option<Matrix<T>>, dynamic: bool) -> MResult<Self> {
+        let mut inputs = Vec::new();
+        for ix in &plan.inputs {
+          match T::matrix(&args[*ix]) {
+            Some(input) => inputs.push(input),
+            None => return Err(MechError::new(
+                UnhandledFunctionArgumentKindVarg { arg: args.iter().map(|x| x.kind()).collect(), fxn_name: $feature.to_string() },
+                Some("All matrix arguments must have the same kind".to_string())
+              ).with_compiler_loc()
+            ),
+          }
+        }
+        let out = match out {
+          Some(out) => out,
+          None => shape_out(plan.rows, plan.cols, dynamic),
+        };
+        let fxn = Self { args, inputs, out };
+        fxn.solve();
+        Ok(fxn)
+      }
+    }
+    impl<T> MechFunctionFactory for $struct_name<T>
+    where
+      T: ShapeElement,
+      Matrix<T>: ToValue
+    {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Variadic(out, args) => {
+            let plan = $plan(&args)?;
+            Ok(Box::new(Self::build(args, plan, T::matrix(&out), false)?))
+          }
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 1, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl<T> MechFunctionImpl for $struct_name<T>
+    where
+      T: ShapeElement,
+      Matrix<T>: ToValue
+    {
+      // The plan is made again from the current arguments, since a size, a
+      // dimension or a number of turns may have changed since the last solve.
+      fn solve(&self) {
+        let plan = match $plan(&self.args) {
+          Ok(plan) => plan,
+          Err(_) => return,
+        };
+        let inputs: Vec<Vec<T>> = self.inputs.iter().map(|input| input.as_vec()).collect();
+        let elements = plan.map.iter().map(|ix| match ix {
+          Some((input, element)) => inputs[*input][*element].clone(),
+          None => T::default(),
+        }).collect();
+        shape_write(&self.out, elements, plan.rows, plan.cols);
+      }
+      fn out(&self) -> Value { self.out.to_value() }
+      fn to_string(&self) -> String { format!("{:#?}", self) }
+    }
+    #[cfg(feature = "compiler")]
+    impl<T> MechFunctionCompiler for $struct_name<T>
+    where
+      T: ShapeElement,
+      Matrix<T>: ToValue
+    {
+      fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        let name = format!("{}<{}>", stringify!($struct_name), T::as_value_kind());
+        let out = compile_register!(self.out.to_value(), ctx);
+        let mut args = Vec::new();
+        for arg in &self.args {
+          args.push(compile_register!(arg, ctx));
+        }
+        ctx.features.insert(FeatureFlag::Custom(hash_str($feature)));
+        ctx.emit_varop(hash_str(&name), out, args);
+        Ok(out)
+      }
+    }
+    register_fxn_descriptor!($struct_name, bool, "bool", String, "string", u8, "u8", u16, "u16", u32, "u32", u64, "u64", u128, "u128", i8, "i8", i16, "i16", i32, "i32", i64, "i64", i128, "i128", f32, "f32", f64, "f64");
+  };}
+
+// Builds the gather for the kind of the first input.
+#[macro_export]
+macro_rules! impl_shape_dispatch {
+  ($fxn:ident, $struct_name:ident, $plan:ident, $fxn_string:tt) => {
+    fn $fxn(args: Vec<Value>, dynamic: bool) -> MResult<Box<dyn MechFunction>> {
+      let plan = $plan(&args)?;
+      let first = &args[plan.inputs[0]];
+      impl_shape_dispatch!(@kinds first, args, plan, dynamic, $struct_name,
+        bool, "bool"; String, "string";
+        u8, "u8"; u16, "u16"; u32, "u32"; u64, "u64"; u128, "u128";
+        i8, "i8"; i16, "i16"; i32, "i32"; i64, "i64"; i128, "i128";
+        f32, "f32"; f64, "f64");
+      Err(MechError::new(
+          UnhandledFunctionArgumentKindVarg { arg: args.iter().map(|x| x.kind()).collect(), fxn_name: $fxn_string.to_string() },
+          None
+        ).with_compiler_loc()
+      )
+    }
+  };
+  (@kinds $first:ident, $args:ident, $plan:ident, $dynamic:ident, $struct_name:ident, $($t:ty, $value_string:tt);+) => {
+    $(
+      #[cfg(feature = $value_string)]
+      if <$t as ShapeElement>::matrix($first).is_some() {
+        return Ok(Box::new($struct_name::<$t>::build($args, $plan, None, $dynamic)?));
+      }
+    )+
+  };
+}
+
+// Construction functions fill an f64 matrix from their scalar arguments. Both
+// the size and the values follow the arguments, though only a matrixd output
+// can change its size.
+#[macro_export]
+macro_rules! impl_fill_fxn {
+  ($struct_name:ident, $gen_fxn:ident, $plan:ident, $kernel:ident, $feature:tt) => {
+    #[derive(Debug)]
+    struct $struct_name {
+      args: Vec<Value>,
+      params: Vec<Ref<f64>>,
+      out: Matrix<f64>,
+    }
+    impl $struct_name {
+      fn build(args: Vec<Value>, out: Option<Matrix<f64>>, dynamic: bool) -> MResult<Self> {
+        let (rows, cols) = $plan(&args)?;
+        let mut params = Vec::new();
+        for arg in &args {
+          params.push(arg.as_f64()?);
+        }
+        let out = match out {
+          Some(out) => out,
+          None => shape_out(rows, cols, dynamic),
+        };
+        let fxn = Self { args, params, out };
+        fxn.solve();
+        Ok(fxn)
+      }
+    }
+    impl MechFunctionFactory for $struct_name {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Variadic(out, args) => Ok(Box::new(Self::build(args, f64::matrix(&out), false)?)),
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 1, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl MechFunctionImpl for $struct_name {
+      fn solve(&self) {
+        let (rows, cols) = match $plan(&self.args) {
+          Ok(size) => size,
+          Err(_) => return,
+        };
+        let params: Vec<f64> = self.params.iter().map(|x| *x.borrow()).collect();
+        shape_write(&self.out, $kernel(&params, rows, cols), rows, cols);
+      }
+      fn out(&self) -> Value { self.out.to_value() }
+      fn to_string(&self) -> String { format!("{:#?}", self) }
+    }
+    #[cfg(feature = "compiler")]
+    impl MechFunctionCompiler for $struct_name {
+      fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        let name = format!("{}<f64>", stringify!($struct_name));
+        let out = compile_register!(self.out.to_value(), ctx);
+        let mut args = Vec::new();
+        for arg in &self.args {
+          args.push(compile_register!(arg, ctx));
+        }
+        ctx.features.insert(FeatureFlag::Custom(hash_str($feature)));
+        ctx.emit_varop(hash_str(&name), out, args);
+        Ok(out)
+      }
+    }
+    register_fxn_descriptor_inner_logic!($struct_name, f64, "f64");
+    fn $gen_fxn(args: Vec<Value>, dynamic: bool) -> MResult<Box<dyn MechFunction>> {
+      Ok(Box::new($struct_name::build(args, None, dynamic)?))
+    }
+  };}
+
+// Like impl_mech_urnop_fxn for any number of arguments, passing the errors of
+// the constructor on to the caller. The sizes are the positions of the
+// arguments that decide the size of the output; the output is a matrixd when
+// any of them is not a constant of the program.
+#[macro_export]
+macro_rules! impl_shape_compiler {
+  ($fxn_name:ident, $gen_fxn:tt, $fxn_string:tt, [$($size:expr),*]) => {
+    pub struct $fxn_name {}
+    impl NativeFunctionCompiler for $fxn_name {
+      // Without the interpreter to say which arguments are constants, only a
+      // size read from a variable is known to change.
+      fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        let dynamic = [$($size),*].iter().any(|ix: &usize| matches!(arguments.get(*ix), Some(Value::MutableReference(_))));
+        $gen_fxn(shape_deref(arguments), dynamic)
+      }
+      fn compile_with_constants(&self, arguments: &Vec<Value>, constants: &[bool], _resolve: &mut CallbackResolver) -> MResult<Box<dyn MechFunction>> {
+        let dynamic = [$($size),*].iter().any(|ix: &usize| constants.get(*ix) == Some(&false));
+        $gen_fxn(shape_deref(arguments), dynamic)
+      }
+    }
+    register_descriptor! {
+      FunctionCompilerDescriptor {
+        name: $fxn_string,
+        ptr: &$fxn_name{},
+      }
+    }
+  };
+}
+
+// ----------------------------------------------------------------------------
+// Errors
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct MatrixNotSquareError {
+  pub fxn_name: String,
+  pub rows: usize,
+  pub cols: usize,
+}
+impl MechErrorKind for MatrixNotSquareError {
+  fn name(&self) -> &str { "MatrixNotSquare" }
+  fn message(&self) -> String {
+    format!("{} requires a square matrix, found {}x{}", self.fxn_name, self.rows, self.cols)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatrixSingularError {
+  pub fxn_name: String,
+}
+impl MechErrorKind for MatrixSingularError {
+  fn name(&self) -> &str { "MatrixSingular" }
+  fn message(&self) -> String {
+    format!("{} requires an invertible matrix, found a singular one", self.fxn_name)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatrixNotSymmetricError {
+  pub fxn_name: String,
+}
+impl MechErrorKind for MatrixNotSymmetricError {
+  fn name(&self) -> &str { "MatrixNotSymmetric" }
+  fn message(&self) -> String {
+    format!("{} requires a symmetric matrix", self.fxn_name)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatrixNotPositiveDefiniteError {
+  pub fxn_name: String,
+}
+impl MechErrorKind for MatrixNotPositiveDefiniteError {
+  fn name(&self) -> &str { "MatrixNotPositiveDefinite" }
+  fn message(&self) -> String {
+    format!("{} requires a symmetric positive definite matrix", self.fxn_name)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatrixArgumentError {
+  pub fxn_name: String,
+  pub expected: String,
+  pub found: String,
+}
+impl MechErrorKind for MatrixArgumentError {
+  fn name(&self) -> &str { "MatrixArgument" }
+  fn message(&self) -> String {
+    format!("{} expected {}, found {}", self.fxn_name, self.expected, self.found)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatrixReshapeError {
+  pub fxn_name: String,
+  pub elements: usize,
+  pub rows: usize,
+  pub cols: usize,
+}
+impl MechErrorKind for MatrixReshapeError {
+  fn name(&self) -> &str { "MatrixReshape" }
+  fn message(&self) -> String {
+    format!("{} cannot arrange {} elements into a {}x{} matrix", self.fxn_name, self.elements, self.rows, self.cols)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatrixConcatShapeError {
+  pub fxn_name: String,
+  pub dim: usize,
+  pub expected: usize,
+  pub found: usize,
+}
+impl MechErrorKind for MatrixConcatShapeError {
+  fn name(&self) -> &str { "MatrixConcatShape" }
+  fn message(&self) -> String {
+    let along = if self.dim == 1 { "columns" } else { "rows" };
+    format!("{} requires every matrix to have {} {}, found {}", self.fxn_name, self.expected, along, self.found)
+  }
+}
@@ -0,0 +1,25 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Linspace ---------------------------------------------------------------------
+
+// matrix/linspace(a, b, n) is a row of n evenly spaced values from a to b.
+pub fn linspace_plan(args: &[Value]) -> MResult<(usize, usize)> {
+  shape_check_args(args, 3, 3)?;
+  Ok((1, shape_size("matrix/linspace", &args[2])?))
+}
+
+pub fn linspace_kernel(params: &[f64], _rows: usize, cols: usize) -> Vec<f64> {
+  let (start, stop) = (params[0], params[1]);
+  if cols == 1 {
+    return vec![stop];
+  }
+  let step = (stop - start) / (cols - 1) as f64;
+  (0..cols).map(|ix| if ix == cols - 1 { stop } else { start + step * ix as f64 }).collect()
+}
+
+impl_fill_fxn!(MatrixLinspaceFill, matrix_linspace, linspace_plan, linspace_kernel, "matrix/linspace");
+
+impl_shape_compiler!(MatrixLinspace, matrix_linspace, "matrix/linspace", [2]);
@@ -0,0 +1,22 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// LU Decomposition -------------------------------------------------------------
+
+// Returns {l, u, p} with p * A = l * u, where l is unit lower triangular and
+// p is a permutation matrix.
+pub fn linalg_lu<T: LinalgElement>(a: &DMatrix<T>) -> Vec<DMatrix<T>> {
+  let n = a.nrows();
+  let (p, l, u) = a.clone().lu().unpack();
+  let mut pm = DMatrix::identity(n, n);
+  p.permute_rows(&mut pm);
+  vec![l, u, pm]
+}
+
+impl_linalg_square_shapes!(impl_linalg_structs!(record ["l", "u", "p"], Lu, linalg_lu, "matrix/lu"));
+
+impl_linalg_dispatch!(square, impl_lu_fxn, MatrixLu, linalg_check_square, "matrix/lu");
+
+impl_linalg_compiler!(MatrixLu, impl_lu_fxn, "matrix/lu");
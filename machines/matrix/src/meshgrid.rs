@@ -0,0 +1,121 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Meshgrid ---------------------------------------------------------------------
+
+// matrix/meshgrid(x, y) is the record {x, y} of two len(y) x len(x) grids:
+// x repeated down the rows and y repeated across the columns.
+pub fn meshgrid_plan(args: &[Value]) -> MResult<ShapePlan> {
+  shape_check_args(args, 2, 2)?;
+  for arg in args {
+    shape_check_matrix("matrix/meshgrid", arg)?;
+  }
+  let (x_rows, x_cols) = shape_of(&args[0]);
+  let (y_rows, y_cols) = shape_of(&args[1]);
+  Ok(ShapePlan { rows: y_rows * y_cols, cols: x_rows * x_cols, inputs: vec![0, 1], map: vec![] })
+}
+
+#[derive(Debug)]
+struct MatrixMeshgridGrid<T> {
+  args: Vec<Value>,
+  x: Matrix<T>,
+  y: Matrix<T>,
+  grid_x: Matrix<T>,
+  grid_y: Matrix<T>,
+  out: Ref<MechRecord>,
+}
+impl<T> MatrixMeshgridGrid<T>
+where
+  T: ShapeElement,
+  Matrix<T>: ToValue
+{
+  fn build(args: Vec<Value>, plan: ShapePlan, out: Option<Ref<MechRecord>>, dynamic: bool) -> MResult<Self> {
+    let (x, y) = match (T::matrix(&args[0]), T::matrix(&args[1])) {
+      (Some(x), Some(y)) => (x, y),
+      _ => return Err(MechError::new(
+          UnhandledFunctionArgumentKindVarg { arg: args.iter().map(|x| x.kind()).collect(), fxn_name: "matrix/meshgrid".to_string() },
+          Some("Both arguments must have the same kind".to_string())
+        ).with_compiler_loc()
+      ),
+    };
+    let (grid_x, grid_y, out) = match out {
+      Some(out) => {
+        let grids = out.borrow().data.values().filter_map(T::matrix).collect::<Vec<_>>();
+        (grids[0].clone(), grids[1].clone(), out)
+      }
+      None => {
+        let grid_x = shape_out(plan.rows, plan.cols, dynamic);
+        let grid_y = shape_out(plan.rows, plan.cols, dynamic);
+        let record = MechRecord::new(vec![("x", grid_x.to_value()), ("y", grid_y.to_value())]);
+        (grid_x, grid_y, Ref::new(record))
+      }
+    };
+    let fxn = Self { args, x, y, grid_x, grid_y, out };
+    fxn.solve();
+    Ok(fxn)
+  }
+}
+impl<T> MechFunctionFactory for MatrixMeshgridGrid<T>
+where
+  T: ShapeElement,
+  Matrix<T>: ToValue
+{
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(Value::Record(out), args) => {
+        let plan = meshgrid_plan(&args)?;
+        Ok(Box::new(Self::build(args, plan, Some(out), false)?))
+      }
+      FunctionArgs::Variadic(out, _) => Err(MechError::new(
+          UnhandledFunctionArgumentKind1 { arg: out.kind(), fxn_name: "MatrixMeshgridGrid".to_string() },
+          None
+        ).with_compiler_loc()
+      ),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 2, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+impl<T> MechFunctionImpl for MatrixMeshgridGrid<T>
+where
+  T: ShapeElement,
+  Matrix<T>: ToValue
+{
+  fn solve(&self) {
+    let (xs, ys) = (self.x.as_vec(), self.y.as_vec());
+    let rows = ys.len();
+    let cells = 0..xs.len() * ys.len();
+    self.grid_x.set(cells.clone().map(|ix| xs[ix / rows].clone()).collect());
+    self.grid_y.set(cells.map(|ix| ys[ix % rows].clone()).collect());
+  }
+  fn out(&self) -> Value { Value::Record(self.out.clone()) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+#[cfg(feature = "compiler")]
+impl<T> MechFunctionCompiler for MatrixMeshgridGrid<T>
+where
+  T: ShapeElement,
+  Matrix<T>: ToValue
+{
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let name = format!("MatrixMeshgridGrid<{}>", T::as_value_kind());
+    let out = compile_register!(Value::Record(self.out.clone()), ctx);
+    let mut args = Vec::new();
+    for arg in &self.args {
+      args.push(compile_register!(arg, ctx));
+    }
+    ctx.features.insert(FeatureFlag::Custom(hash_str("matrix/meshgrid")));
+    ctx.emit_varop(hash_str(&name), out, args);
+    Ok(out)
+  }
+}
+register_fxn_descriptor!(MatrixMeshgridGrid, bool, "bool", String, "string", u8, "u8", u16, "u16", u32, "u32", u64, "u64", u128, "u128", i8, "i8", i16, "i16", i32, "i32", i64, "i64", i128, "i128", f32, "f32", f64, "f64");
+
+impl_shape_dispatch!(matrix_meshgrid, MatrixMeshgridGrid, meshgrid_plan, "matrix/meshgrid");
+
+impl_shape_compiler!(MatrixMeshgrid, matrix_meshgrid, "matrix/meshgrid", []);
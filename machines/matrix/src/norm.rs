@@ -0,0 +1,17 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Norm -------------------------------------------------------------------------
+
+// The Frobenius norm, which is the Euclidean norm for vectors.
+pub fn linalg_norm<T: LinalgElement>(a: &DMatrix<T>) -> T {
+  a.norm()
+}
+
+impl_linalg_shapes!(impl_linalg_structs!(scalar, Norm, linalg_norm, "matrix/norm"));
+
+impl_linalg_dispatch!(any, impl_norm_fxn, MatrixNorm, linalg_check_any, "matrix/norm");
+
+impl_linalg_compiler!(MatrixNorm, impl_norm_fxn, "matrix/norm");
@@ -0,0 +1,18 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Ones -------------------------------------------------------------------------
+
+pub fn ones_plan(args: &[Value]) -> MResult<(usize, usize)> {
+  shape_fill_size("matrix/ones", args)
+}
+
+pub fn ones_kernel(_params: &[f64], rows: usize, cols: usize) -> Vec<f64> {
+  vec![1.0; rows * cols]
+}
+
+impl_fill_fxn!(MatrixOnesFill, matrix_ones, ones_plan, ones_kernel, "matrix/ones");
+
+impl_shape_compiler!(MatrixOnes, matrix_ones, "matrix/ones", [0, 1]);
@@ -0,0 +1,19 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Pseudo-inverse ---------------------------------------------------------------
+
+// The Moore-Penrose pseudo-inverse, computed from the SVD.
+pub fn linalg_pinv<T: LinalgElement>(a: &DMatrix<T>) -> DMatrix<T> {
+  let svd = a.clone().svd(true, true);
+  let tol = linalg_tolerance(a, svd.singular_values.as_slice());
+  svd.pseudo_inverse(tol).unwrap_or_else(|_| DMatrix::from_element(a.ncols(), a.nrows(), linalg_nan()))
+}
+
+impl_linalg_shapes!(impl_linalg_structs!(transposed, Pinv, linalg_pinv, "matrix/pinv"));
+
+impl_linalg_dispatch!(any, impl_pinv_fxn, MatrixPinv, linalg_check_any, "matrix/pinv");
+
+impl_linalg_compiler!(MatrixPinv, impl_pinv_fxn, "matrix/pinv");
@@ -0,0 +1,19 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// QR Decomposition -------------------------------------------------------------
+
+// Returns {q, r} with A = q * r, where q has orthonormal columns and r is
+// upper triangular.
+pub fn linalg_qr<T: LinalgElement>(a: &DMatrix<T>) -> Vec<DMatrix<T>> {
+  let qr = a.clone().qr();
+  vec![qr.q(), qr.r()]
+}
+
+impl_linalg_shapes!(impl_linalg_structs!(record ["q", "r"], Qr, linalg_qr, "matrix/qr"));
+
+impl_linalg_dispatch!(any, impl_qr_fxn, MatrixQr, linalg_check_any, "matrix/qr");
+
+impl_linalg_compiler!(MatrixQr, impl_qr_fxn, "matrix/qr");
@@ -0,0 +1,19 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Rank -------------------------------------------------------------------------
+
+// The number of singular values above the tolerance.
+pub fn linalg_rank<T: LinalgElement>(a: &DMatrix<T>) -> u64 {
+  let svd = a.clone().svd(false, false);
+  let tol = linalg_tolerance(a, svd.singular_values.as_slice());
+  svd.singular_values.iter().filter(|s| **s > tol).count() as u64
+}
+
+impl_linalg_shapes!(impl_linalg_structs!(count, Rank, linalg_rank, "matrix/rank"));
+
+impl_linalg_dispatch!(any, impl_rank_fxn, MatrixRank, linalg_check_any, "matrix/rank");
+
+impl_linalg_compiler!(MatrixRank, impl_rank_fxn, "matrix/rank");
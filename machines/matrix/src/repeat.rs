@@ -0,0 +1,26 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Repeat -----------------------------------------------------------------------
+
+// matrix/repeat(A, m, n) tiles A m times down and n times across.
+pub fn repeat_plan(args: &[Value]) -> MResult<ShapePlan> {
+  shape_check_args(args, 3, 3)?;
+  shape_check_matrix("matrix/repeat", &args[0])?;
+  let (rows, cols) = shape_of(&args[0]);
+  let out_rows = rows * shape_size("matrix/repeat", &args[1])?;
+  let out_cols = cols * shape_size("matrix/repeat", &args[2])?;
+  let map = (0..out_rows * out_cols).map(|ix| {
+    let (row, col) = (ix % out_rows, ix / out_rows);
+    Some((0, row % rows + (col % cols) * rows))
+  }).collect();
+  Ok(ShapePlan { rows: out_rows, cols: out_cols, inputs: vec![0], map })
+}
+
+impl_shape_fxn!(MatrixRepeatGather, repeat_plan, "matrix/repeat");
+
+impl_shape_dispatch!(matrix_repeat, MatrixRepeatGather, repeat_plan, "matrix/repeat");
+
+impl_shape_compiler!(MatrixRepeat, matrix_repeat, "matrix/repeat", [1, 2]);
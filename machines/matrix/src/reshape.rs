@@ -0,0 +1,30 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Reshape ----------------------------------------------------------------------
+
+// matrix/reshape(A, r, c) keeps the elements in column-major order.
+pub fn reshape_plan(args: &[Value]) -> MResult<ShapePlan> {
+  shape_check_args(args, 3, 3)?;
+  shape_check_matrix("matrix/reshape", &args[0])?;
+  let (rows, cols) = shape_of(&args[0]);
+  let out_rows = shape_size("matrix/reshape", &args[1])?;
+  let out_cols = shape_size("matrix/reshape", &args[2])?;
+  if out_rows * out_cols != rows * cols {
+    return Err(MechError::new(
+        MatrixReshapeError { fxn_name: "matrix/reshape".to_string(), elements: rows * cols, rows: out_rows, cols: out_cols },
+        None
+      ).with_compiler_loc()
+    );
+  }
+  let map = (0..rows * cols).map(|ix| Some((0, ix))).collect();
+  Ok(ShapePlan { rows: out_rows, cols: out_cols, inputs: vec![0], map })
+}
+
+impl_shape_fxn!(MatrixReshapeGather, reshape_plan, "matrix/reshape");
+
+impl_shape_dispatch!(matrix_reshape, MatrixReshapeGather, reshape_plan, "matrix/reshape");
+
+impl_shape_compiler!(MatrixReshape, matrix_reshape, "matrix/reshape", [1, 2]);
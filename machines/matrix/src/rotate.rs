@@ -0,0 +1,35 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Rotate -----------------------------------------------------------------------
+
+// matrix/rotate(A, k) turns A counterclockwise by k quarter turns; k defaults
+// to 1 and may be negative.
+pub fn rotate_plan(args: &[Value]) -> MResult<ShapePlan> {
+  shape_check_args(args, 1, 2)?;
+  shape_check_matrix("matrix/rotate", &args[0])?;
+  let turns = match args.get(1) {
+    Some(turns) => shape_turns("matrix/rotate", turns)?,
+    None => 1,
+  };
+  let (mut rows, mut cols) = shape_of(&args[0]);
+  let mut source: Vec<usize> = (0..rows * cols).collect();
+  for _ in 0..turns {
+    // After a quarter turn, element (row, col) comes from (col, cols - 1 - row).
+    source = (0..rows * cols).map(|ix| {
+      let (row, col) = (ix % cols, ix / cols);
+      source[col + (cols - 1 - row) * rows]
+    }).collect();
+    std::mem::swap(&mut rows, &mut cols);
+  }
+  let map = source.into_iter().map(|ix| Some((0, ix))).collect();
+  Ok(ShapePlan { rows, cols, inputs: vec![0], map })
+}
+
+impl_shape_fxn!(MatrixRotateGather, rotate_plan, "matrix/rotate");
+
+impl_shape_dispatch!(matrix_rotate, MatrixRotateGather, rotate_plan, "matrix/rotate");
+
+impl_shape_compiler!(MatrixRotate, matrix_rotate, "matrix/rotate", [1]);
@@ -0,0 +1,22 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Singular Value Decomposition -------------------------------------------------
+
+// Returns {u, s, v} with A = u * diag(s) * v'. The singular values s are a
+// column in descending order.
+pub fn linalg_svd<T: LinalgElement>(a: &DMatrix<T>) -> Vec<DMatrix<T>> {
+  let svd = a.clone().svd(true, true);
+  let s = DMatrix::from_column_slice(svd.singular_values.len(), 1, svd.singular_values.as_slice());
+  let u = svd.u.unwrap();
+  let v = svd.v_t.unwrap().transpose();
+  vec![u, s, v]
+}
+
+impl_linalg_shapes!(impl_linalg_structs!(record ["u", "s", "v"], Svd, linalg_svd, "matrix/svd"));
+
+impl_linalg_dispatch!(any, impl_svd_fxn, MatrixSvd, linalg_check_any, "matrix/svd");
+
+impl_linalg_compiler!(MatrixSvd, impl_svd_fxn, "matrix/svd");
@@ -0,0 +1,16 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Trace ------------------------------------------------------------------------
+
+pub fn linalg_trace<T: LinalgElement>(a: &DMatrix<T>) -> T {
+  a.trace()
+}
+
+impl_linalg_square_shapes!(impl_linalg_structs!(scalar, Trace, linalg_trace, "matrix/trace"));
+
+impl_linalg_dispatch!(square, impl_trace_fxn, MatrixTrace, linalg_check_square, "matrix/trace");
+
+impl_linalg_compiler!(MatrixTrace, impl_trace_fxn, "matrix/trace");
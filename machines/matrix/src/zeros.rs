@@ -0,0 +1,18 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Zeros ------------------------------------------------------------------------
+
+pub fn zeros_plan(args: &[Value]) -> MResult<(usize, usize)> {
+  shape_fill_size("matrix/zeros", args)
+}
+
+pub fn zeros_kernel(_params: &[f64], rows: usize, cols: usize) -> Vec<f64> {
+  vec![0.0; rows * cols]
+}
+
+impl_fill_fxn!(MatrixZerosFill, matrix_zeros, zeros_plan, zeros_kernel, "matrix/zeros");
+
+impl_shape_compiler!(MatrixZeros, matrix_zeros, "matrix/zeros", [0, 1]);
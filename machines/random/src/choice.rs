@@ -0,0 +1,22 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Choice -----------------------------------------------------------------------
+
+// random/choice(seed, A) picks one element of A, and
+// random/choice(seed, A, rows, cols) a matrix of them, with replacement.
+pub fn choice_size(fxn_name: &str, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+  random_size(fxn_name, args, 2)
+}
+
+pub fn choice_kernel<T: Clone>(rng: &mut ChaCha8Rng, elements: Vec<T>, count: usize) -> Vec<T> {
+  (0..count).map(|_| elements[rng.random_range(0..elements.len())].clone()).collect()
+}
+
+impl_random_pick_fxn!(RandomChoicePick, choice_size, choice_kernel, "random/choice");
+
+impl_random_pick_dispatch!(random_choice, RandomChoicePick, "random/choice");
+
+impl_random_compiler!(RandomChoice, random_choice, "random/choice");
@@ -0,0 +1,24 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Integer ----------------------------------------------------------------------
+
+// random/integer(seed, lo, hi) draws a whole number from lo to hi, inclusive,
+// of the kind of the bounds.
+pub fn integer_kernel<T: RandomNumber>(rng: &mut ChaCha8Rng, params: &[Value]) -> T {
+  T::whole_between(rng, &params[0], &params[1])
+}
+
+impl_random_draw_fxn!(RandomIntegerDraw, integer_kernel, "random/integer",
+  u8, "u8", u16, "u16", u32, "u32", u64, "u64", u128, "u128",
+  i8, "i8", i16, "i16", i32, "i32", i64, "i64", i128, "i128",
+  f32, "f32", f64, "f64");
+
+impl_random_draw_dispatch!(random_integer, RandomIntegerDraw, "random/integer",
+  u8, "u8", u16, "u16", u32, "u32", u64, "u64", u128, "u128",
+  i8, "i8", i16, "i16", i32, "i32", i64, "i64", i128, "i128",
+  f32, "f32", f64, "f64");
+
+impl_random_compiler!(RandomInteger, random_integer, "random/integer");
@@ -0,0 +1,546 @@
+#![no_main]
+#![allow(warnings)]
+#[macro_use]
+extern crate mech_core;
+#[cfg(feature = "matrix")]
+extern crate nalgebra as na;
+extern crate paste;
+
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+#[cfg(feature = "random")]
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "random")]
+use rand::seq::SliceRandom;
+#[cfg(feature = "random")]
+use rand_chacha::ChaCha8Rng;
+
+use std::cell::{RefCell, RefMut};
+use std::fmt::Debug;
+use paste::paste;
+
+#[cfg(feature = "uniform")]
+pub mod uniform;
+#[cfg(feature = "normal")]
+pub mod normal;
+#[cfg(feature = "integer")]
+pub mod integer;
+#[cfg(feature = "choice")]
+pub mod choice;
+#[cfg(feature = "shuffle")]
+pub mod shuffle;
+
+#[cfg(feature = "uniform")]
+pub use self::uniform::*;
+#[cfg(feature = "normal")]
+pub use self::normal::*;
+#[cfg(feature = "integer")]
+pub use self::integer::*;
+#[cfg(feature = "choice")]
+pub use self::choice::*;
+#[cfg(feature = "shuffle")]
+pub use self::shuffle::*;
+
+// ----------------------------------------------------------------------------
+// Random Library
+// ----------------------------------------------------------------------------
+
+// Every function owns a generator seeded from its first argument, so a program
+// draws the same numbers each time it runs. Each solve continues the stream
+// where the last one stopped, and a changed seed starts it over. Solving again
+// with the same inputs gives new numbers, so the functions report that they
+// are not deterministic.
+
+#[cfg(feature = "random")]
+#[derive(Debug)]
+pub struct RandomStream {
+  seed: Value,
+  current: RefCell<u64>,
+  rng: RefCell<ChaCha8Rng>,
+}
+
+#[cfg(feature = "random")]
+impl RandomStream {
+  pub fn new(fxn_name: &str, seed: &Value) -> MResult<Self> {
+    let current = random_seed(seed).ok_or_else(|| random_argument_error(fxn_name, seed, "a whole number seed"))?;
+    Ok(Self { seed: seed.clone(), current: RefCell::new(current), rng: RefCell::new(ChaCha8Rng::seed_from_u64(current)) })
+  }
+
+  // The generator, started over if the seed has changed since the last draw.
+  pub fn rng(&self) -> RefMut<'_, ChaCha8Rng> {
+    if let Some(seed) = random_seed(&self.seed) {
+      if seed != *self.current.borrow() {
+        *self.current.borrow_mut() = seed;
+        *self.rng.borrow_mut() = ChaCha8Rng::seed_from_u64(seed);
+      }
+    }
+    self.rng.borrow_mut()
+  }
+}
+
+#[cfg(feature = "random")]
+pub fn random_seed(value: &Value) -> Option<u64> {
+  match value.as_f64().map(|x| *x.borrow()) {
+    Ok(seed) if seed.fract() == 0.0 => Some(seed as i64 as u64),
+    _ => None,
+  }
+}
+
+// A numeric parameter, read each time the function solves.
+#[cfg(feature = "random")]
+pub fn random_param(value: &Value) -> f64 {
+  value.as_f64().map(|x| *x.borrow()).unwrap_or(f64::NAN)
+}
+
+#[cfg(feature = "random")]
+pub fn random_deref(value: &Value) -> Value {
+  match value {
+    Value::MutableReference(value) => value.borrow().clone(),
+    value => value.clone(),
+  }
+}
+
+// The optional trailing rows and columns, starting at `position`. Without them
+// the function returns a scalar.
+#[cfg(feature = "random")]
+pub fn random_size(fxn_name: &str, args: &[Value], position: usize) -> MResult<Option<(usize, usize)>> {
+  if args.len() == position {
+    return Ok(None);
+  }
+  if args.len() != position + 2 {
+    return Err(MechError::new(IncorrectNumberOfArguments { expected: position + 2, found: args.len() }, None).with_compiler_loc());
+  }
+  let mut size = Vec::new();
+  for arg in &args[position..] {
+    match arg.as_f64().map(|x| *x.borrow()) {
+      Ok(n) if n >= 1.0 && n.fract() == 0.0 => size.push(n as usize),
+      _ => return Err(random_argument_error(fxn_name, arg, "a positive whole number size")),
+    }
+  }
+  Ok(Some((size[0], size[1])))
+}
+
+// The kind shared by the two bounds of a draw.
+#[cfg(feature = "random")]
+pub fn random_bounds_kind(fxn_name: &str, args: &[Value]) -> MResult<ValueKind> {
+  if args.len() < 3 {
+    return Err(MechError::new(IncorrectNumberOfArguments { expected: 3, found: args.len() }, None).with_compiler_loc());
+  }
+  let (lo, hi) = (random_deref(&args[1]).kind(), random_deref(&args[2]).kind());
+  if lo != hi {
+    return Err(MechError::new(
+        UnhandledFunctionArgumentKindVarg { arg: args.iter().map(|x| x.kind()).collect(), fxn_name: fxn_name.to_string() },
+        Some("Both bounds must have the same kind".to_string())
+      ).with_compiler_loc()
+    );
+  }
+  Ok(lo)
+}
+
+#[cfg(feature = "random")]
+pub fn random_argument_error(fxn_name: &str, value: &Value, expected: &str) -> MechError {
+  let found = match value.as_f64() {
+    Ok(x) => format!("{}", x.borrow()),
+    Err(_) => format!("{}", value.kind()),
+  };
+  MechError::new(
+    RandomArgumentError { fxn_name: fxn_name.to_string(), expected: expected.to_string(), found },
+    None
+  ).with_compiler_loc()
+}
+
+// Elements ---------------------------------------------------------------------
+
+pub trait RandomItem: Clone + Debug + Default + PartialEq + Sync + Send + 'static + AsValueKind {
+  fn scalar(value: &Value) -> Option<Ref<Self>>;
+  #[cfg(feature = "matrix")]
+  fn matrix(value: &Value) -> Option<Matrix<Self>>;
+}
+
+// The kinds numbers can be drawn as. Draws from continuous distributions are
+// made in f64 and converted.
+pub trait RandomNumber: RandomItem + Copy {
+  fn from_f64(x: f64) -> Self;
+  // A whole number from lo to hi, inclusive. Integer kinds are drawn at their
+  // own width, so bounds past 2^53 keep every bit.
+  #[cfg(feature = "random")]
+  fn whole_between(rng: &mut ChaCha8Rng, lo: &Value, hi: &Value) -> Self;
+}
+
+macro_rules! impl_random_item {
+  ($($t:ty, $scalar:ident, $matrix:ident, $value_string:tt);+ $(;)?) => {
+    $(
+      #[cfg(feature = $value_string)]
+      impl RandomItem for $t {
+        fn scalar(value: &Value) -> Option<Ref<Self>> {
+          match value {
+            Value::$scalar(x) => Some(x.clone()),
+            Value::MutableReference(value) => Self::scalar(&value.borrow()),
+            _ => None,
+          }
+        }
+        #[cfg(feature = "matrix")]
+        fn matrix(value: &Value) -> Option<Matrix<Self>> {
+          match value {
+            Value::$matrix(m) => Some(m.clone()),
+            Value::MutableReference(value) => Self::matrix(&value.borrow()),
+            _ => None,
+          }
+        }
+      }
+    )+
+  };
+}
+
+macro_rules! impl_random_number {
+  (integer $($t:ty, $value_string:tt);+ $(;)?) => {
+    $(
+      #[cfg(feature = $value_string)]
+      impl RandomNumber for $t {
+        fn from_f64(x: f64) -> Self { x as $t }
+        #[cfg(feature = "random")]
+        fn whole_between(rng: &mut ChaCha8Rng, lo: &Value, hi: &Value) -> Self {
+          match (Self::scalar(lo), Self::scalar(hi)) {
+            (Some(lo), Some(hi)) => {
+              let (lo, hi) = (*lo.borrow(), *hi.borrow());
+              rng.random_range(lo.min(hi)..=lo.max(hi))
+            },
+            _ => Self::default(),
+          }
+        }
+      }
+    )+
+  };
+  (float $($t:ty, $value_string:tt);+ $(;)?) => {
+    $(
+      #[cfg(feature = $value_string)]
+      impl RandomNumber for $t {
+        fn from_f64(x: f64) -> Self { x as $t }
+        #[cfg(feature = "random")]
+        fn whole_between(rng: &mut ChaCha8Rng, lo: &Value, hi: &Value) -> Self {
+          let (lo, hi) = (random_param(lo), random_param(hi));
+          let (lo, hi) = (lo.min(hi).ceil(), lo.max(hi).floor());
+          if !(lo <= hi) {
+            return <$t>::NAN;
+          }
+          rng.random_range(lo as i128..=hi as i128) as $t
+        }
+      }
+    )+
+  };
+}
+
+impl_random_item!(
+  bool, Bool, MatrixBool, "bool";
+  String, String, MatrixString, "string";
+  u8, U8, MatrixU8, "u8";
+  u16, U16, MatrixU16, "u16";
+  u32, U32, MatrixU32, "u32";
+  u64, U64, MatrixU64, "u64";
+  u128, U128, MatrixU128, "u128";
+  i8, I8, MatrixI8, "i8";
+  i16, I16, MatrixI16, "i16";
+  i32, I32, MatrixI32, "i32";
+  i64, I64, MatrixI64, "i64";
+  i128, I128, MatrixI128, "i128";
+  f32, F32, MatrixF32, "f32";
+  f64, F64, MatrixF64, "f64";
+);
+
+impl_random_number!(integer
+  u8, "u8"; u16, "u16"; u32, "u32"; u64, "u64"; u128, "u128";
+  i8, "i8"; i16, "i16"; i32, "i32"; i64, "i64"; i128, "i128";
+);
+
+impl_random_number!(float f32, "f32"; f64, "f64");
+
+// A random result is a scalar, or a matrix when a size is given.
+#[cfg(feature = "random")]
+#[derive(Debug)]
+pub enum RandomOut<T> {
+  Scalar(Ref<T>),
+  Matrix(Matrix<T>),
+}
+
+#[cfg(feature = "random")]
+impl<T> RandomOut<T>
+where
+  T: RandomItem,
+  Ref<T>: ToValue,
+  Matrix<T>: ToValue,
+{
+  pub fn new(size: Option<(usize, usize)>) -> Self {
+    match size {
+      Some((rows, cols)) => RandomOut::Matrix(Matrix::from_vec(vec![T::default(); rows * cols], rows, cols)),
+      None => RandomOut::Scalar(Ref::new(T::default())),
+    }
+  }
+
+  pub fn from_value(value: &Value) -> Option<Self> {
+    T::matrix(value).map(RandomOut::Matrix).or_else(|| T::scalar(value).map(RandomOut::Scalar))
+  }
+
+  pub fn len(&self) -> usize {
+    match self {
+      RandomOut::Scalar(_) => 1,
+      RandomOut::Matrix(m) => m.shape().iter().product(),
+    }
+  }
+
+  pub fn set(&self, mut elements: Vec<T>) {
+    match self {
+      RandomOut::Scalar(x) => *x.borrow_mut() = elements.remove(0),
+      RandomOut::Matrix(m) => m.set(elements),
+    }
+  }
+
+  pub fn to_value(&self) -> Value {
+    match self {
+      RandomOut::Scalar(x) => x.to_value(),
+      RandomOut::Matrix(m) => m.to_value(),
+    }
+  }
+}
+
+// Functions --------------------------------------------------------------------
+
+// A draw of numbers from a distribution with two parameters, as
+// fxn(seed, a, b) or fxn(seed, a, b, rows, cols). The kernel turns the
+// parameters into one number of the kind being drawn.
+#[macro_export]
+macro_rules! impl_random_draw_fxn {
+  ($struct_name:ident, $kernel:ident, $fxn_string:tt, $($t:ty, $value_string:tt),+) => {
+    #[derive(Debug)]
+    pub struct $struct_name<T> {
+      args: Vec<Value>,
+      stream: RandomStream,
+      out: RandomOut<T>,
+    }
+    impl<T> $struct_name<T>
+    where
+      T: RandomNumber,
+      Ref<T>: ToValue,
+      Matrix<T>: ToValue,
+    {
+      fn build(args: Vec<Value>, out: Option<RandomOut<T>>) -> MResult<Self> {
+        let stream = RandomStream::new($fxn_string, &args[0])?;
+        let out = match out {
+          Some(out) => out,
+          None => RandomOut::new(random_size($fxn_string, &args, 3)?),
+        };
+        Ok(Self { args, stream, out })
+      }
+    }
+    impl<T> MechFunctionFactory for $struct_name<T>
+    where
+      T: RandomNumber,
+      Ref<T>: ToValue,
+      Matrix<T>: ToValue,
+    {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Variadic(out, args) => Ok(Box::new(Self::build(args, RandomOut::from_value(&out))?)),
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 3, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl<T> MechFunctionImpl for $struct_name<T>
+    where
+      T: RandomNumber,
+      Ref<T>: ToValue,
+      Matrix<T>: ToValue,
+    {
+      fn solve(&self) {
+        let mut rng = self.stream.rng();
+        let elements = (0..self.out.len()).map(|_| $kernel::<T>(&mut rng, &self.args[1..3])).collect();
+        self.out.set(elements);
+      }
+      fn out(&self) -> Value { self.out.to_value() }
+      fn to_string(&self) -> String { format!("{:#?}", self) }
+      fn deterministic(&self) -> bool { false }
+    }
+    #[cfg(feature = "compiler")]
+    impl<T> MechFunctionCompiler for $struct_name<T>
+    where
+      T: RandomNumber,
+      Ref<T>: ToValue,
+      Matrix<T>: ToValue,
+    {
+      fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        let name = format!("{}<{}>", stringify!($struct_name), T::as_value_kind());
+        compile_value_varop!(name, self.out.to_value(), self.args, ctx, FeatureFlag::Custom(hash_str($fxn_string)));
+      }
+    }
+    register_fxn_descriptor!($struct_name, $($t, $value_string),+);
+  };
+}
+
+// Builds a draw for the kind of its bounds.
+#[macro_export]
+macro_rules! impl_random_draw_dispatch {
+  ($fxn:ident, $struct_name:ident, $fxn_string:tt, $($t:ty, $value_string:tt),+) => {
+    fn $fxn(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+      let kind = random_bounds_kind($fxn_string, &args)?;
+      $(
+        #[cfg(feature = $value_string)]
+        if kind == <$t as AsValueKind>::as_value_kind() {
+          return Ok(Box::new($struct_name::<$t>::build(args, None)?));
+        }
+      )+
+      Err(MechError::new(
+          UnhandledFunctionArgumentKindVarg { arg: args.iter().map(|x| x.kind()).collect(), fxn_name: $fxn_string.to_string() },
+          None
+        ).with_compiler_loc()
+      )
+    }
+  };
+}
+
+// A pick from the elements of a matrix, as fxn(seed, A, ...). The size
+// function gives the shape of the result, and the kernel makes the picks.
+#[macro_export]
+macro_rules! impl_random_pick_fxn {
+  ($struct_name:ident, $size:ident, $kernel:ident, $fxn_string:tt) => {
+    #[derive(Debug)]
+    pub struct $struct_name<T> {
+      args: Vec<Value>,
+      stream: RandomStream,
+      source: Matrix<T>,
+      out: RandomOut<T>,
+    }
+    impl<T> $struct_name<T>
+    where
+      T: RandomItem,
+      Ref<T>: ToValue,
+      Matrix<T>: ToValue,
+    {
+      fn build(args: Vec<Value>, out: Option<RandomOut<T>>) -> MResult<Self> {
+        let stream = RandomStream::new($fxn_string, &args[0])?;
+        let source = match T::matrix(&args[1]) {
+          Some(source) if source.shape().iter().product::<usize>() > 0 => source,
+          _ => return Err(random_argument_error($fxn_string, &args[1], "a non-empty matrix")),
+        };
+        let out = match out {
+          Some(out) => out,
+          None => RandomOut::new($size($fxn_string, &args)?),
+        };
+        Ok(Self { args, stream, source, out })
+      }
+    }
+    impl<T> MechFunctionFactory for $struct_name<T>
+    where
+      T: RandomItem,
+      Ref<T>: ToValue,
+      Matrix<T>: ToValue,
+    {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Variadic(out, args) => Ok(Box::new(Self::build(args, RandomOut::from_value(&out))?)),
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 2, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl<T> MechFunctionImpl for $struct_name<T>
+    where
+      T: RandomItem,
+      Ref<T>: ToValue,
+      Matrix<T>: ToValue,
+    {
+      fn solve(&self) {
+        let mut rng = self.stream.rng();
+        self.out.set($kernel(&mut rng, self.source.as_vec(), self.out.len()));
+      }
+      fn out(&self) -> Value { self.out.to_value() }
+      fn to_string(&self) -> String { format!("{:#?}", self) }
+      fn deterministic(&self) -> bool { false }
+    }
+    #[cfg(feature = "compiler")]
+    impl<T> MechFunctionCompiler for $struct_name<T>
+    where
+      T: RandomItem,
+      Ref<T>: ToValue,
+      Matrix<T>: ToValue,
+    {
+      fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        let name = format!("{}<{}>", stringify!($struct_name), T::as_value_kind());
+        compile_value_varop!(name, self.out.to_value(), self.args, ctx, FeatureFlag::Custom(hash_str($fxn_string)));
+      }
+    }
+    register_fxn_descriptor!($struct_name, bool, "bool", String, "string", u8, "u8", u16, "u16", u32, "u32", u64, "u64", u128, "u128", i8, "i8", i16, "i16", i32, "i32", i64, "i64", i128, "i128", f32, "f32", f64, "f64");
+  };
+}
+
+// Builds a pick for the kind of the matrix it picks from.
+#[macro_export]
+macro_rules! impl_random_pick_dispatch {
+  ($fxn:ident, $struct_name:ident, $fxn_string:tt) => {
+    fn $fxn(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+      if args.len() < 2 {
+        return Err(MechError::new(IncorrectNumberOfArguments { expected: 2, found: args.len() }, None).with_compiler_loc());
+      }
+      impl_random_pick_dispatch!(@kinds args, $struct_name,
+        bool, "bool"; String, "string";
+        u8, "u8"; u16, "u16"; u32, "u32"; u64, "u64"; u128, "u128";
+        i8, "i8"; i16, "i16"; i32, "i32"; i64, "i64"; i128, "i128";
+        f32, "f32"; f64, "f64");
+      Err(MechError::new(
+          UnhandledFunctionArgumentKindVarg { arg: args.iter().map(|x| x.kind()).collect(), fxn_name: $fxn_string.to_string() },
+          None
+        ).with_compiler_loc()
+      )
+    }
+  };
+  (@kinds $args:ident, $struct_name:ident, $($t:ty, $value_string:tt);+) => {
+    $(
+      #[cfg(feature = $value_string)]
+      if <$t as RandomItem>::matrix(&$args[1]).is_some() {
+        return Ok(Box::new($struct_name::<$t>::build($args, None)?));
+      }
+    )+
+  };
+}
+
+#[macro_export]
+macro_rules! impl_random_compiler {
+  ($fxn_name:ident, $gen_fxn:tt, $fxn_string:tt) => {
+    pub struct $fxn_name {}
+    impl NativeFunctionCompiler for $fxn_name {
+      fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        $gen_fxn(arguments.clone())
+      }
+    }
+    register_descriptor! {
+      FunctionCompilerDescriptor {
+        name: $fxn_string,
+        ptr: &$fxn_name{},
+      }
+    }
+  };
+}
+
+
+// ----------------------------------------------------------------------------
+// Errors
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct RandomArgumentError {
+  pub fxn_name: String,
+  pub expected: String,
+  pub found: String,
+}
+impl MechErrorKind for RandomArgumentError {
+  fn name(&self) -> &str { "RandomArgument" }
+  fn message(&self) -> String {
+    format!("{} expected {}, found {}", self.fxn_name, self.expected, self.found)
+  }
+}
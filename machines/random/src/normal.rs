@@ -0,0 +1,21 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Normal -----------------------------------------------------------------------
+
+// random/normal(seed, mean, std) draws from a normal distribution, using the
+// Box-Muller transform.
+pub fn normal_kernel<T: RandomNumber>(rng: &mut ChaCha8Rng, params: &[Value]) -> T {
+  let (mean, std) = (random_param(&params[0]), random_param(&params[1]));
+  let u1 = 1.0 - rng.random::<f64>();
+  let u2 = rng.random::<f64>();
+  T::from_f64(mean + std * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos())
+}
+
+impl_random_draw_fxn!(RandomNormalDraw, normal_kernel, "random/normal", f32, "f32", f64, "f64");
+
+impl_random_draw_dispatch!(random_normal, RandomNormalDraw, "random/normal", f32, "f32", f64, "f64");
+
+impl_random_compiler!(RandomNormal, random_normal, "random/normal");
@@ -0,0 +1,27 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Shuffle ----------------------------------------------------------------------
+
+// random/shuffle(seed, A) is a random permutation of the elements of A, with
+// the shape of A.
+pub fn shuffle_size(_fxn_name: &str, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+  if args.len() != 2 {
+    return Err(MechError::new(IncorrectNumberOfArguments { expected: 2, found: args.len() }, None).with_compiler_loc());
+  }
+  let shape = random_deref(&args[1]).shape();
+  Ok(Some((shape[0], shape[1])))
+}
+
+pub fn shuffle_kernel<T: Clone>(rng: &mut ChaCha8Rng, mut elements: Vec<T>, _count: usize) -> Vec<T> {
+  elements.shuffle(rng);
+  elements
+}
+
+impl_random_pick_fxn!(RandomShufflePick, shuffle_size, shuffle_kernel, "random/shuffle");
+
+impl_random_pick_dispatch!(random_shuffle, RandomShufflePick, "random/shuffle");
+
+impl_random_compiler!(RandomShuffle, random_shuffle, "random/shuffle");
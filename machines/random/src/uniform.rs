@@ -0,0 +1,18 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Uniform ----------------------------------------------------------------------
+
+// random/uniform(seed, lo, hi) draws from [lo, hi).
+pub fn uniform_kernel<T: RandomNumber>(rng: &mut ChaCha8Rng, params: &[Value]) -> T {
+  let (lo, hi) = (random_param(&params[0]), random_param(&params[1]));
+  T::from_f64(lo + (hi - lo) * rng.random::<f64>())
+}
+
+impl_random_draw_fxn!(RandomUniformDraw, uniform_kernel, "random/uniform", f32, "f32", f64, "f64");
+
+impl_random_draw_dispatch!(random_uniform, RandomUniformDraw, "random/uniform", f32, "f32", f64, "f64");
+
+impl_random_compiler!(RandomUniform, random_uniform, "random/uniform");
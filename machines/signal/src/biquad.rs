@@ -0,0 +1,75 @@
+use crate::*;
+use mech_core::*;
+
+// Biquad ----------------------------------------------------------------------
+
+// signal/biquad(x, b, a) runs x through the second order IIR filter with
+// numerator [b0 b1 b2] and denominator [a0 a1 a2]:
+//
+//   a0 * y[n] = b0 * x[n] + b1 * x[n-1] + b2 * x[n-2] - a1 * y[n-1] - a2 * y[n-2]
+//
+// The filter keeps its state from one solve to the next, so each step of the
+// interpreter feeds it the next sample or block of samples and the output
+// carries on where the last one stopped. Because of that, solving again with
+// the same input gives a different output, and the function reports that it
+// is not deterministic.
+
+// Normalized coefficients [b0 b1 b2 a1 a2], with a0 divided out.
+pub fn biquad_coefficients(b: &Value, a: &Value) -> Option<[f64; 5]> {
+  match (signal_samples(b)?.as_slice(), signal_samples(a)?.as_slice()) {
+    (&[b0, b1, b2], &[a0, a1, a2]) if a0 != 0.0 => Some([b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0]),
+    _ => None,
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct BiquadOp {
+  // The two delay registers of the transposed direct form II.
+  state: RefCell<[f64; 2]>,
+}
+
+impl BiquadOp {
+  pub fn filter(&self, x: &[f64], [b0, b1, b2, a1, a2]: [f64; 5]) -> Vec<f64> {
+    let mut z = self.state.borrow_mut();
+    x.iter().map(|xi| {
+      let y = b0 * xi + z[0];
+      z[0] = b1 * xi - a1 * y + z[1];
+      z[1] = b2 * xi - a2 * y;
+      y
+    }).collect()
+  }
+}
+
+impl SignalOp for BiquadOp {
+  type Out = f64;
+  const NAME: &'static str = "signal/biquad";
+  const DESCRIPTOR: &'static str = "SignalBiquadFxn<f64>";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    signal_check_args(args, 3, 3)?;
+    let shape = signal_shape(Self::NAME, &args[0])?;
+    signal_real_argument(Self::NAME, &args[0], "a non-empty [f64] signal")?;
+    for arg in &args[1..] {
+      match signal_samples(arg) {
+        Some(coefficients) if coefficients.len() == 3 => (),
+        _ => return Err(signal_argument_error(Self::NAME, arg, "three [f64] coefficients")),
+      }
+    }
+    biquad_coefficients(&args[1], &args[2]).ok_or_else(|| signal_argument_error(Self::NAME, &args[2], "a non-zero a0"))?;
+    Ok(shape)
+  }
+  fn apply(&self, args: &[Value]) -> Option<Vec<f64>> {
+    let coefficients = biquad_coefficients(&args[1], &args[2])?;
+    Some(self.filter(&signal_samples(&args[0])?, coefficients))
+  }
+  fn deterministic(&self) -> bool { false }
+}
+
+pub type SignalBiquadFxn = SignalFxn<BiquadOp>;
+
+register_fxn_descriptor_inner_logic!(SignalBiquadFxn, f64, "f64");
+
+fn signal_biquad(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(SignalBiquadFxn::build(args, None)?))
+}
+
+impl_signal_compiler!(SignalBiquad, signal_biquad, "signal/biquad");
@@ -0,0 +1,89 @@
+use crate::*;
+use mech_core::*;
+
+// Convolve --------------------------------------------------------------------
+
+// signal/convolve(x, h) gives the full convolution of two signals, with
+// length(x) + length(h) - 1 samples.
+
+pub fn convolve(x: &[f64], h: &[f64]) -> Vec<f64> {
+  let mut y = vec![0.0; x.len() + h.len() - 1];
+  for (i, xi) in x.iter().enumerate() {
+    for (j, hj) in h.iter().enumerate() {
+      y[i + j] += xi * hj;
+    }
+  }
+  y
+}
+
+// The result of two signals, shaped like the first, with one sample for each
+// way they can overlap.
+pub fn overlap_shape(fxn_name: &str, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+  signal_check_args(args, 2, 2)?;
+  let shape = signal_shape(fxn_name, &args[0])?;
+  signal_shape(fxn_name, &args[1])?;
+  let x = signal_real_argument(fxn_name, &args[0], "a non-empty [f64] signal")?;
+  let h = signal_real_argument(fxn_name, &args[1], "a non-empty [f64] signal")?;
+  Ok(signal_resize(shape, x.len() + h.len() - 1))
+}
+
+#[derive(Debug, Default)]
+pub struct ConvolveOp {}
+
+impl SignalOp for ConvolveOp {
+  type Out = f64;
+  const NAME: &'static str = "signal/convolve";
+  const DESCRIPTOR: &'static str = "SignalConvolveFxn<f64>";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> { overlap_shape(Self::NAME, args) }
+  fn apply(&self, args: &[Value]) -> Option<Vec<f64>> {
+    let (x, h) = (signal_samples(&args[0])?, signal_samples(&args[1])?);
+    if x.is_empty() || h.is_empty() {
+      return None;
+    }
+    Some(convolve(&x, &h))
+  }
+}
+
+pub type SignalConvolveFxn = SignalFxn<ConvolveOp>;
+
+register_fxn_descriptor_inner_logic!(SignalConvolveFxn, f64, "f64");
+
+fn signal_convolve(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(SignalConvolveFxn::build(args, None)?))
+}
+
+impl_signal_compiler!(SignalConvolve, signal_convolve, "signal/convolve");
+
+// Correlate -------------------------------------------------------------------
+
+// signal/correlate(x, y) gives the full cross-correlation of two signals. The
+// samples run over the lags of y against x, from -(length(y) - 1) to
+// length(x) - 1, so the zero lag is at index length(y).
+
+#[derive(Debug, Default)]
+pub struct CorrelateOp {}
+
+impl SignalOp for CorrelateOp {
+  type Out = f64;
+  const NAME: &'static str = "signal/correlate";
+  const DESCRIPTOR: &'static str = "SignalCorrelateFxn<f64>";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> { overlap_shape(Self::NAME, args) }
+  fn apply(&self, args: &[Value]) -> Option<Vec<f64>> {
+    let (x, mut y) = (signal_samples(&args[0])?, signal_samples(&args[1])?);
+    if x.is_empty() || y.is_empty() {
+      return None;
+    }
+    y.reverse();
+    Some(convolve(&x, &y))
+  }
+}
+
+pub type SignalCorrelateFxn = SignalFxn<CorrelateOp>;
+
+register_fxn_descriptor_inner_logic!(SignalCorrelateFxn, f64, "f64");
+
+fn signal_correlate(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(SignalCorrelateFxn::build(args, None)?))
+}
+
+impl_signal_compiler!(SignalCorrelate, signal_correlate, "signal/correlate");
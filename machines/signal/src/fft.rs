@@ -0,0 +1,67 @@
+use crate::*;
+use mech_core::*;
+
+// FFT -------------------------------------------------------------------------
+
+// signal/fft(x) gives the discrete Fourier transform of a [f64] or [c64]
+// signal as [c64], and signal/ifft(X) transforms it back. Any length works;
+// powers of two are fastest.
+
+pub fn fft_shape(fxn_name: &str, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+  signal_check_args(args, 1, 1)?;
+  let shape = signal_shape(fxn_name, &args[0])?;
+  if signal_complex_samples(&args[0]).is_none() {
+    return Err(signal_argument_error(fxn_name, &args[0], "a [f64] or [c64] signal"));
+  }
+  Ok(shape)
+}
+
+pub fn fft_apply(args: &[Value], inverse: bool) -> Option<Vec<C64>> {
+  let mut samples = signal_complex_samples(&args[0])?;
+  signal_fft(&mut samples, inverse);
+  Some(samples.into_iter().map(C64).collect())
+}
+
+#[derive(Debug, Default)]
+pub struct FftOp {}
+
+impl SignalOp for FftOp {
+  type Out = C64;
+  const NAME: &'static str = "signal/fft";
+  const DESCRIPTOR: &'static str = "SignalFftFxn<c64>";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> { fft_shape(Self::NAME, args) }
+  fn apply(&self, args: &[Value]) -> Option<Vec<C64>> { fft_apply(args, false) }
+}
+
+pub type SignalFftFxn = SignalFxn<FftOp>;
+
+register_fxn_descriptor_inner_logic!(SignalFftFxn, C64, "c64");
+
+fn signal_fft_fxn(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(SignalFftFxn::build(args, None)?))
+}
+
+impl_signal_compiler!(SignalFft, signal_fft_fxn, "signal/fft");
+
+// Inverse FFT -----------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct IfftOp {}
+
+impl SignalOp for IfftOp {
+  type Out = C64;
+  const NAME: &'static str = "signal/ifft";
+  const DESCRIPTOR: &'static str = "SignalIfftFxn<c64>";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> { fft_shape(Self::NAME, args) }
+  fn apply(&self, args: &[Value]) -> Option<Vec<C64>> { fft_apply(args, true) }
+}
+
+pub type SignalIfftFxn = SignalFxn<IfftOp>;
+
+register_fxn_descriptor_inner_logic!(SignalIfftFxn, C64, "c64");
+
+fn signal_ifft_fxn(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(SignalIfftFxn::build(args, None)?))
+}
+
+impl_signal_compiler!(SignalIfft, signal_ifft_fxn, "signal/ifft");
@@ -0,0 +1,44 @@
+use crate::*;
+use mech_core::*;
+
+// FIR -------------------------------------------------------------------------
+
+// signal/fir(x, b) filters x with the taps b, so each output sample is
+// b[1] * x[n] + b[2] * x[n - 1] + ... Samples before the start of x count as
+// zero, and the result has as many samples as x.
+
+pub fn fir(x: &[f64], b: &[f64]) -> Vec<f64> {
+  (0..x.len()).map(|n| {
+    b.iter().take(n + 1).enumerate().map(|(k, bk)| bk * x[n - k]).sum()
+  }).collect()
+}
+
+#[derive(Debug, Default)]
+pub struct FirOp {}
+
+impl SignalOp for FirOp {
+  type Out = f64;
+  const NAME: &'static str = "signal/fir";
+  const DESCRIPTOR: &'static str = "SignalFirFxn<f64>";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    signal_check_args(args, 2, 2)?;
+    let shape = signal_shape(Self::NAME, &args[0])?;
+    signal_shape(Self::NAME, &args[1])?;
+    signal_real_argument(Self::NAME, &args[0], "a non-empty [f64] signal")?;
+    signal_real_argument(Self::NAME, &args[1], "non-empty [f64] filter taps")?;
+    Ok(shape)
+  }
+  fn apply(&self, args: &[Value]) -> Option<Vec<f64>> {
+    Some(fir(&signal_samples(&args[0])?, &signal_samples(&args[1])?))
+  }
+}
+
+pub type SignalFirFxn = SignalFxn<FirOp>;
+
+register_fxn_descriptor_inner_logic!(SignalFirFxn, f64, "f64");
+
+fn signal_fir(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(SignalFirFxn::build(args, None)?))
+}
+
+impl_signal_compiler!(SignalFir, signal_fir, "signal/fir");
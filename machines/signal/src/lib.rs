@@ -0,0 +1,359 @@
+#![no_main]
+#![allow(warnings)]
+#[macro_use]
+extern crate mech_core;
+#[cfg(feature = "matrix")]
+extern crate nalgebra as na;
+extern crate paste;
+
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+#[cfg(feature = "fft")]
+use rustfft::{FftPlanner, num_complex::Complex};
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use paste::paste;
+
+#[cfg(feature = "fft")]
+pub mod fft;
+#[cfg(feature = "convolve")]
+pub mod convolve;
+#[cfg(feature = "smooth")]
+pub mod smooth;
+#[cfg(feature = "fir")]
+pub mod fir;
+#[cfg(feature = "biquad")]
+pub mod biquad;
+
+#[cfg(feature = "fft")]
+pub use self::fft::*;
+#[cfg(feature = "convolve")]
+pub use self::convolve::*;
+#[cfg(feature = "smooth")]
+pub use self::smooth::*;
+#[cfg(feature = "fir")]
+pub use self::fir::*;
+#[cfg(feature = "biquad")]
+pub use self::biquad::*;
+
+// ----------------------------------------------------------------------------
+// Signal Library
+// ----------------------------------------------------------------------------
+
+// A signal is a scalar sample or a row or column vector of samples, and a
+// result has the orientation of the signal it was computed from. Real signals
+// are [f64]; the transforms also take and give [c64]. Arguments are kept as
+// values, so a signal held in a mutable variable is read again on every solve.
+
+pub fn signal_deref(value: &Value) -> Value {
+  match value {
+    Value::MutableReference(value) => signal_deref(&value.borrow()),
+    value => value.clone(),
+  }
+}
+
+// The samples of a real signal, read each time the function solves.
+pub fn signal_samples(value: &Value) -> Option<Vec<f64>> {
+  match signal_deref(value) {
+    Value::F64(x) => Some(vec![*x.borrow()]),
+    Value::MatrixF64(m) => Some(m.as_vec()),
+    _ => None,
+  }
+}
+
+// A numeric parameter, read each time the function solves.
+pub fn signal_param(value: &Value) -> f64 {
+  value.as_f64().map(|x| *x.borrow()).unwrap_or(f64::NAN)
+}
+
+// None for a scalar sample, or the rows and columns of a vector.
+pub fn signal_shape(fxn_name: &str, value: &Value) -> MResult<Option<(usize, usize)>> {
+  let (rows, cols) = match signal_deref(value) {
+    Value::F64(_) => return Ok(None),
+    Value::MatrixF64(m) => (m.rows(), m.cols()),
+    #[cfg(feature = "c64")]
+    Value::C64(_) => return Ok(None),
+    #[cfg(feature = "c64")]
+    Value::MatrixC64(m) => (m.rows(), m.cols()),
+    _ => return Err(signal_argument_error(fxn_name, value, "a scalar or vector signal")),
+  };
+  if rows != 1 && cols != 1 {
+    return Err(signal_argument_error(fxn_name, value, "a scalar or vector signal"));
+  }
+  Ok(Some((rows, cols)))
+}
+
+// The shape of a result of `len` samples computed from a signal of `shape`.
+pub fn signal_resize(shape: Option<(usize, usize)>, len: usize) -> Option<(usize, usize)> {
+  match shape {
+    Some((_, 1)) if len != 1 => Some((len, 1)),
+    None if len == 1 => None,
+    _ => Some((1, len)),
+  }
+}
+
+// Checks that an argument is a real signal with at least one sample.
+pub fn signal_real_argument(fxn_name: &str, value: &Value, expected: &str) -> MResult<Vec<f64>> {
+  match signal_samples(value) {
+    Some(samples) if !samples.is_empty() => Ok(samples),
+    _ => Err(signal_argument_error(fxn_name, value, expected)),
+  }
+}
+
+pub fn signal_check_args(args: &[Value], min: usize, max: usize) -> MResult<()> {
+  if args.len() < min || args.len() > max {
+    let expected = if args.len() < min { min } else { max };
+    return Err(MechError::new(IncorrectNumberOfArguments { expected, found: args.len() }, None).with_compiler_loc());
+  }
+  Ok(())
+}
+
+pub fn signal_argument_error(fxn_name: &str, value: &Value, expected: &str) -> MechError {
+  let found = match value.as_f64() {
+    Ok(x) => format!("{}", x.borrow()),
+    Err(_) => format!("{}", value.kind()),
+  };
+  MechError::new(
+    SignalArgumentError { fxn_name: fxn_name.to_string(), expected: expected.to_string(), found },
+    None
+  ).with_compiler_loc()
+}
+
+#[macro_export]
+macro_rules! impl_signal_compiler {
+  ($fxn_name:ident, $gen_fxn:tt, $fxn_string:tt) => {
+    pub struct $fxn_name {}
+    impl NativeFunctionCompiler for $fxn_name {
+      fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        $gen_fxn(arguments.clone())
+      }
+    }
+    register_descriptor! {
+      FunctionCompilerDescriptor {
+        name: $fxn_string,
+        ptr: &$fxn_name{},
+      }
+    }
+  };
+}
+
+// Transforms ------------------------------------------------------------------
+
+// The samples of a real or complex signal as complex numbers.
+#[cfg(feature = "fft")]
+pub fn signal_complex_samples(value: &Value) -> Option<Vec<Complex<f64>>> {
+  match signal_deref(value) {
+    Value::C64(x) => Some(vec![x.borrow().0]),
+    Value::MatrixC64(m) => Some(m.as_vec().into_iter().map(|x| x.0).collect()),
+    value => signal_samples(&value).map(|samples| samples.into_iter().map(|x| Complex::new(x, 0.0)).collect()),
+  }
+}
+
+// The discrete Fourier transform of the samples, or its inverse scaled by
+// 1/n so that a signal survives a round trip.
+#[cfg(feature = "fft")]
+pub fn signal_fft(samples: &mut [Complex<f64>], inverse: bool) {
+  let n = samples.len();
+  if n == 0 {
+    return;
+  }
+  let mut planner = FftPlanner::new();
+  if inverse {
+    planner.plan_fft_inverse(n).process(samples);
+    let scale = 1.0 / n as f64;
+    samples.iter_mut().for_each(|x| *x *= scale);
+  } else {
+    planner.plan_fft_forward(n).process(samples);
+  }
+}
+
+// Functions -------------------------------------------------------------------
+
+#[cfg(feature = "matrix")]
+pub trait SignalItem: Clone + Debug + Default + PartialEq + 'static {
+  fn scalar(value: &Value) -> Option<Ref<Self>>;
+  fn matrix(value: &Value) -> Option<Matrix<Self>>;
+}
+
+macro_rules! impl_signal_item {
+  ($($t:ty, $scalar:ident, $matrix:ident, $value_string:tt);+ $(;)?) => {
+    $(
+      #[cfg(all(feature = "matrix", feature = $value_string))]
+      impl SignalItem for $t {
+        fn scalar(value: &Value) -> Option<Ref<Self>> {
+          match value {
+            Value::$scalar(x) => Some(x.clone()),
+            Value::MutableReference(value) => Self::scalar(&value.borrow()),
+            _ => None,
+          }
+        }
+        fn matrix(value: &Value) -> Option<Matrix<Self>> {
+          match value {
+            Value::$matrix(m) => Some(m.clone()),
+            Value::MutableReference(value) => Self::matrix(&value.borrow()),
+            _ => None,
+          }
+        }
+      }
+    )+
+  };
+}
+
+impl_signal_item!(
+  f64, F64, MatrixF64, "f64";
+  C64, C64, MatrixC64, "c64";
+);
+
+#[cfg(feature = "matrix")]
+#[derive(Debug)]
+pub enum SignalCells<T> {
+  Scalar(Ref<T>),
+  Matrix(Matrix<T>),
+}
+
+#[cfg(feature = "matrix")]
+impl<T> SignalCells<T>
+where
+  T: SignalItem,
+  Ref<T>: ToValue,
+  Matrix<T>: ToValue,
+{
+  pub fn new(shape: Option<(usize, usize)>) -> Self {
+    match shape {
+      Some((rows, cols)) => SignalCells::Matrix(Matrix::from_vec(vec![T::default(); rows * cols], rows, cols)),
+      None => SignalCells::Scalar(Ref::new(T::default())),
+    }
+  }
+
+  pub fn from_value(value: &Value) -> Option<Self> {
+    T::matrix(value).map(SignalCells::Matrix).or_else(|| T::scalar(value).map(SignalCells::Scalar))
+  }
+
+  pub fn len(&self) -> usize {
+    match self {
+      SignalCells::Scalar(_) => 1,
+      SignalCells::Matrix(m) => m.shape().iter().product(),
+    }
+  }
+
+  pub fn set(&self, mut cells: Vec<T>) {
+    match self {
+      SignalCells::Scalar(x) => *x.borrow_mut() = cells.remove(0),
+      SignalCells::Matrix(m) => m.set(cells),
+    }
+  }
+
+  pub fn to_value(&self) -> Value {
+    match self {
+      SignalCells::Scalar(x) => x.to_value(),
+      SignalCells::Matrix(m) => m.to_value(),
+    }
+  }
+}
+
+#[cfg(feature = "matrix")]
+pub trait SignalOp: Debug + Default + 'static {
+  type Out: SignalItem;
+  // The Mech name, and the name the function is registered under.
+  const NAME: &'static str;
+  const DESCRIPTOR: &'static str;
+  // Checks the arguments when the function is built, and gives the shape of
+  // the result.
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>>;
+  // The result for the current arguments, or None when they no longer fit the
+  // result, in which case the last result is kept.
+  fn apply(&self, args: &[Value]) -> Option<Vec<Self::Out>>;
+  // False for filters that carry state from one solve to the next.
+  fn deterministic(&self) -> bool { true }
+}
+
+#[cfg(feature = "matrix")]
+#[derive(Debug)]
+pub struct SignalFxn<O: SignalOp> {
+  op: O,
+  args: Vec<Value>,
+  out: SignalCells<O::Out>,
+}
+
+#[cfg(feature = "matrix")]
+impl<O> SignalFxn<O>
+where
+  O: SignalOp,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  pub fn build(args: Vec<Value>, out: Option<SignalCells<O::Out>>) -> MResult<Self> {
+    let op = O::default();
+    let shape = op.shape(&args)?;
+    let out = out.unwrap_or_else(|| SignalCells::new(shape));
+    Ok(Self { op, args, out })
+  }
+}
+
+#[cfg(feature = "matrix")]
+impl<O> MechFunctionFactory for SignalFxn<O>
+where
+  O: SignalOp,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(out, args) if !args.is_empty() => Ok(Box::new(Self::build(args, SignalCells::from_value(&out))?)),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+
+#[cfg(feature = "matrix")]
+impl<O> MechFunctionImpl for SignalFxn<O>
+where
+  O: SignalOp,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  fn solve(&self) {
+    match self.op.apply(&self.args) {
+      Some(cells) if cells.len() == self.out.len() => self.out.set(cells),
+      _ => (),
+    }
+  }
+  fn out(&self) -> Value { self.out.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+  fn deterministic(&self) -> bool { self.op.deterministic() }
+}
+
+#[cfg(all(feature = "matrix", feature = "compiler"))]
+impl<O> MechFunctionCompiler for SignalFxn<O>
+where
+  O: SignalOp,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    compile_value_varop!(O::DESCRIPTOR, self.out.to_value(), self.args, ctx, FeatureFlag::Custom(hash_str(O::NAME)));
+  }
+}
+
+// ----------------------------------------------------------------------------
+// Errors
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct SignalArgumentError {
+  pub fxn_name: String,
+  pub expected: String,
+  pub found: String,
+}
+impl MechErrorKind for SignalArgumentError {
+  fn name(&self) -> &str { "SignalArgument" }
+  fn message(&self) -> String {
+    format!("{} expected {}, found {}", self.fxn_name, self.expected, self.found)
+  }
+}
@@ -0,0 +1,100 @@
+use crate::*;
+use mech_core::*;
+
+// Moving Average --------------------------------------------------------------
+
+// signal/moving-average(x, n) averages each sample with the n - 1 samples
+// before it. The first samples average over as many as there are.
+
+pub fn moving_average_window(value: &Value) -> Option<usize> {
+  let n = signal_param(value);
+  if n >= 1.0 && n.fract() == 0.0 { Some(n as usize) } else { None }
+}
+
+pub fn moving_average(x: &[f64], n: usize) -> Vec<f64> {
+  let mut sum = 0.0;
+  x.iter().enumerate().map(|(i, xi)| {
+    sum += xi;
+    if i >= n {
+      sum -= x[i - n];
+    }
+    sum / (i + 1).min(n) as f64
+  }).collect()
+}
+
+#[derive(Debug, Default)]
+pub struct MovingAverageOp {}
+
+impl SignalOp for MovingAverageOp {
+  type Out = f64;
+  const NAME: &'static str = "signal/moving-average";
+  const DESCRIPTOR: &'static str = "SignalMovingAverageFxn<f64>";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    signal_check_args(args, 2, 2)?;
+    let shape = signal_shape(Self::NAME, &args[0])?;
+    signal_real_argument(Self::NAME, &args[0], "a non-empty [f64] signal")?;
+    moving_average_window(&args[1]).ok_or_else(|| signal_argument_error(Self::NAME, &args[1], "a positive whole number window"))?;
+    Ok(shape)
+  }
+  fn apply(&self, args: &[Value]) -> Option<Vec<f64>> {
+    Some(moving_average(&signal_samples(&args[0])?, moving_average_window(&args[1])?))
+  }
+}
+
+pub type SignalMovingAverageFxn = SignalFxn<MovingAverageOp>;
+
+register_fxn_descriptor_inner_logic!(SignalMovingAverageFxn, f64, "f64");
+
+fn signal_moving_average(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(SignalMovingAverageFxn::build(args, None)?))
+}
+
+impl_signal_compiler!(SignalMovingAverage, signal_moving_average, "signal/moving-average");
+
+// Exponential Smoothing -------------------------------------------------------
+
+// signal/exponential-smoothing(x, alpha) starts at the first sample and then
+// moves a fraction alpha of the way to each new one. An alpha near 1 follows
+// the signal closely; near 0 it smooths heavily.
+
+pub fn smoothing_factor(value: &Value) -> Option<f64> {
+  let alpha = signal_param(value);
+  if alpha > 0.0 && alpha <= 1.0 { Some(alpha) } else { None }
+}
+
+pub fn exponential_smoothing(x: &[f64], alpha: f64) -> Vec<f64> {
+  let mut level = x.first().copied().unwrap_or(0.0);
+  x.iter().map(|xi| {
+    level += alpha * (xi - level);
+    level
+  }).collect()
+}
+
+#[derive(Debug, Default)]
+pub struct ExponentialSmoothingOp {}
+
+impl SignalOp for ExponentialSmoothingOp {
+  type Out = f64;
+  const NAME: &'static str = "signal/exponential-smoothing";
+  const DESCRIPTOR: &'static str = "SignalExponentialSmoothingFxn<f64>";
+  fn shape(&self, args: &[Value]) -> MResult<Option<(usize, usize)>> {
+    signal_check_args(args, 2, 2)?;
+    let shape = signal_shape(Self::NAME, &args[0])?;
+    signal_real_argument(Self::NAME, &args[0], "a non-empty [f64] signal")?;
+    smoothing_factor(&args[1]).ok_or_else(|| signal_argument_error(Self::NAME, &args[1], "a smoothing factor in (0, 1]"))?;
+    Ok(shape)
+  }
+  fn apply(&self, args: &[Value]) -> Option<Vec<f64>> {
+    Some(exponential_smoothing(&signal_samples(&args[0])?, smoothing_factor(&args[1])?))
+  }
+}
+
+pub type SignalExponentialSmoothingFxn = SignalFxn<ExponentialSmoothingOp>;
+
+register_fxn_descriptor_inner_logic!(SignalExponentialSmoothingFxn, f64, "f64");
+
+fn signal_exponential_smoothing(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(SignalExponentialSmoothingFxn::build(args, None)?))
+}
+
+impl_signal_compiler!(SignalExponentialSmoothing, signal_exponential_smoothing, "signal/exponential-smoothing");
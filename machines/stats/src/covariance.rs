@@ -0,0 +1,38 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Stats Covariance and Correlation --------------------------------------------
+
+// The sample covariance (normalized by n - 1) of two equally long samples.
+pub fn stats_cov<T: StatsElement>(xs: &[T], ys: &[T]) -> f64 {
+  let n = xs.len();
+  if n < 2 {
+    return if n == 1 { 0.0 } else { f64::NAN };
+  }
+  let (x_mean, y_mean) = (stats_mean(xs), stats_mean(ys));
+  xs.iter().zip(ys.iter()).map(|(x, y)| (x.to_f64() - x_mean) * (y.to_f64() - y_mean)).sum::<f64>() / (n - 1) as f64
+}
+
+// The Pearson correlation; NaN when either sample is constant.
+pub fn stats_corr<T: StatsElement>(xs: &[T], ys: &[T]) -> f64 {
+  stats_cov(xs, ys) / (stats_cov(xs, xs) * stats_cov(ys, ys)).sqrt()
+}
+
+impl_stats_shapes!(impl_stats_structs!(covariance, Cov, stats_cov, "stats/cov"));
+impl_stats_shapes!(impl_stats_structs!(covariance, Corr, stats_corr, "stats/corr"));
+
+impl_stats_dispatch!(pair, impl_stats_cov_fxn, StatsCov, whole);
+impl_stats_dispatch!(unary, impl_stats_cov_row_fxn, StatsCovRow, cov_row);
+impl_stats_dispatch!(unary, impl_stats_cov_column_fxn, StatsCovColumn, cov_column);
+impl_stats_dispatch!(pair, impl_stats_corr_fxn, StatsCorr, whole);
+impl_stats_dispatch!(unary, impl_stats_corr_row_fxn, StatsCorrRow, cov_row);
+impl_stats_dispatch!(unary, impl_stats_corr_column_fxn, StatsCorrColumn, cov_column);
+
+impl_stats_binop_compiler!(StatsCov, impl_stats_cov_fxn, "stats/cov");
+impl_mech_urnop_fxn!(StatsCovRow, impl_stats_cov_row_fxn, "stats/cov/row");
+impl_mech_urnop_fxn!(StatsCovColumn, impl_stats_cov_column_fxn, "stats/cov/column");
+impl_stats_binop_compiler!(StatsCorr, impl_stats_corr_fxn, "stats/corr");
+impl_mech_urnop_fxn!(StatsCorrRow, impl_stats_corr_row_fxn, "stats/corr/row");
+impl_mech_urnop_fxn!(StatsCorrColumn, impl_stats_corr_column_fxn, "stats/corr/column");
@@ -0,0 +1,31 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Stats Cumulative Sum and Product --------------------------------------------
+
+pub fn stats_add<T: StatsElement>(acc: T, x: T) -> T {
+  acc + x
+}
+
+pub fn stats_mul<T: StatsElement>(acc: T, x: T) -> T {
+  acc * x
+}
+
+impl_stats_shapes!(impl_stats_structs!(cumulative, Cumsum, stats_add, "stats/cumsum"));
+impl_stats_shapes!(impl_stats_structs!(cumulative, Cumprod, stats_mul, "stats/cumprod"));
+
+impl_stats_dispatch!(unary, impl_stats_cumsum_fxn, StatsCumsum, same);
+impl_stats_dispatch!(unary, impl_stats_cumsum_row_fxn, StatsCumsumRow, same);
+impl_stats_dispatch!(unary, impl_stats_cumsum_column_fxn, StatsCumsumColumn, same);
+impl_stats_dispatch!(unary, impl_stats_cumprod_fxn, StatsCumprod, same);
+impl_stats_dispatch!(unary, impl_stats_cumprod_row_fxn, StatsCumprodRow, same);
+impl_stats_dispatch!(unary, impl_stats_cumprod_column_fxn, StatsCumprodColumn, same);
+
+impl_mech_urnop_fxn!(StatsCumsum, impl_stats_cumsum_fxn, "stats/cumsum");
+impl_mech_urnop_fxn!(StatsCumsumRow, impl_stats_cumsum_row_fxn, "stats/cumsum/row");
+impl_mech_urnop_fxn!(StatsCumsumColumn, impl_stats_cumsum_column_fxn, "stats/cumsum/column");
+impl_mech_urnop_fxn!(StatsCumprod, impl_stats_cumprod_fxn, "stats/cumprod");
+impl_mech_urnop_fxn!(StatsCumprodRow, impl_stats_cumprod_row_fxn, "stats/cumprod/row");
+impl_mech_urnop_fxn!(StatsCumprodColumn, impl_stats_cumprod_column_fxn, "stats/cumprod/column");
@@ -0,0 +1,75 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Stats Min, Max, Argmin and Argmax -------------------------------------------
+
+// The 1-based index of the first element `better` prefers over all others,
+// skipping NaNs; 0 for an empty matrix.
+fn stats_arg_best<T: StatsElement>(xs: &[T], better: fn(&T, &T) -> bool) -> usize {
+  let mut best: Option<usize> = None;
+  for (ix, x) in xs.iter().enumerate() {
+    if x.partial_cmp(x).is_none() {
+      continue;
+    }
+    match best {
+      Some(b) if !better(x, &xs[b]) => (),
+      _ => best = Some(ix),
+    }
+  }
+  best.map(|ix| ix + 1).unwrap_or(0)
+}
+
+pub fn stats_argmin<T: StatsElement>(xs: &[T]) -> u64 {
+  stats_arg_best(xs, |a, b| a < b) as u64
+}
+
+pub fn stats_argmax<T: StatsElement>(xs: &[T]) -> u64 {
+  stats_arg_best(xs, |a, b| a > b) as u64
+}
+
+pub fn stats_min<T: StatsElement>(xs: &[T]) -> T {
+  match stats_argmin(xs) {
+    0 => xs.first().copied().unwrap_or_default(),
+    ix => xs[ix as usize - 1],
+  }
+}
+
+pub fn stats_max<T: StatsElement>(xs: &[T]) -> T {
+  match stats_argmax(xs) {
+    0 => xs.first().copied().unwrap_or_default(),
+    ix => xs[ix as usize - 1],
+  }
+}
+
+impl_stats_shapes!(impl_stats_structs!(reduce, Min, stats_min, T, "stats/min"));
+impl_stats_shapes!(impl_stats_structs!(reduce, Max, stats_max, T, "stats/max"));
+impl_stats_shapes!(impl_stats_structs!(reduce, Argmin, stats_argmin, u64, "stats/argmin"));
+impl_stats_shapes!(impl_stats_structs!(reduce, Argmax, stats_argmax, u64, "stats/argmax"));
+
+impl_stats_dispatch!(unary, impl_stats_min_fxn, StatsMin, whole);
+impl_stats_dispatch!(unary, impl_stats_min_row_fxn, StatsMinRow, row);
+impl_stats_dispatch!(unary, impl_stats_min_column_fxn, StatsMinColumn, column);
+impl_stats_dispatch!(unary, impl_stats_max_fxn, StatsMax, whole);
+impl_stats_dispatch!(unary, impl_stats_max_row_fxn, StatsMaxRow, row);
+impl_stats_dispatch!(unary, impl_stats_max_column_fxn, StatsMaxColumn, column);
+impl_stats_dispatch!(unary, impl_stats_argmin_fxn, StatsArgmin, whole);
+impl_stats_dispatch!(unary, impl_stats_argmin_row_fxn, StatsArgminRow, row);
+impl_stats_dispatch!(unary, impl_stats_argmin_column_fxn, StatsArgminColumn, column);
+impl_stats_dispatch!(unary, impl_stats_argmax_fxn, StatsArgmax, whole);
+impl_stats_dispatch!(unary, impl_stats_argmax_row_fxn, StatsArgmaxRow, row);
+impl_stats_dispatch!(unary, impl_stats_argmax_column_fxn, StatsArgmaxColumn, column);
+
+impl_mech_urnop_fxn!(StatsMin, impl_stats_min_fxn, "stats/min");
+impl_mech_urnop_fxn!(StatsMinRow, impl_stats_min_row_fxn, "stats/min/row");
+impl_mech_urnop_fxn!(StatsMinColumn, impl_stats_min_column_fxn, "stats/min/column");
+impl_mech_urnop_fxn!(StatsMax, impl_stats_max_fxn, "stats/max");
+impl_mech_urnop_fxn!(StatsMaxRow, impl_stats_max_row_fxn, "stats/max/row");
+impl_mech_urnop_fxn!(StatsMaxColumn, impl_stats_max_column_fxn, "stats/max/column");
+impl_mech_urnop_fxn!(StatsArgmin, impl_stats_argmin_fxn, "stats/argmin");
+impl_mech_urnop_fxn!(StatsArgminRow, impl_stats_argmin_row_fxn, "stats/argmin/row");
+impl_mech_urnop_fxn!(StatsArgminColumn, impl_stats_argmin_column_fxn, "stats/argmin/column");
+impl_mech_urnop_fxn!(StatsArgmax, impl_stats_argmax_fxn, "stats/argmax");
+impl_mech_urnop_fxn!(StatsArgmaxRow, impl_stats_argmax_row_fxn, "stats/argmax/row");
+impl_mech_urnop_fxn!(StatsArgmaxColumn, impl_stats_argmax_column_fxn, "stats/argmax/column");
@@ -0,0 +1,128 @@
+use crate::*;
+use mech_core::*;
+use indexmap::map::IndexMap;
+use std::collections::HashMap;
+
+// Stats Histogram -------------------------------------------------------------
+
+// Counts the elements of a matrix into `bins` equal-width bins spanning its
+// smallest to largest element. The result is a table with one row per bin and
+// the columns `lower`, `upper` (the bin edges) and `count`. Each bin holds the
+// elements from its lower edge up to but excluding its upper edge, except the
+// last, which also holds the largest element. NaNs are not counted.
+#[derive(Debug)]
+pub struct StatsHistogramFxn {
+  arg: Value,
+  bins: usize,
+  out: Ref<MechTable>,
+}
+
+impl MechFunctionImpl for StatsHistogramFxn {
+  fn solve(&self) {
+    if let Some(values) = stats_histogram_values(&self.arg) {
+      unsafe {
+        *self.out.as_mut_ptr() = stats_histogram_table(&values, self.bins);
+      }
+    }
+  }
+  fn out(&self) -> Value { Value::Table(self.out.clone()) }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for StatsHistogramFxn {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let name = format!("StatsHistogramFxn<{}>", self.bins);
+    let out = compile_register_brrw!(self.out, ctx);
+    let arg = compile_register!(self.arg, ctx);
+    ctx.features.insert(FeatureFlag::Custom(hash_str("stats/histogram")));
+    ctx.emit_unop(hash_str(&name), out, arg);
+    Ok(out)
+  }
+}
+
+macro_rules! stats_histogram_values {
+  ($value:expr, $($kind:ident, $value_string:tt);+ $(;)?) => {
+    match $value {
+      $(
+        #[cfg(feature = $value_string)]
+        Value::$kind(matrix) => Some(matrix.as_vec().into_iter().map(|x| x.to_f64()).collect::<Vec<f64>>()),
+      )+
+      _ => None,
+    }
+  };
+}
+
+fn stats_histogram_values(value: &Value) -> Option<Vec<f64>> {
+  stats_histogram_values!(value,
+    MatrixU8, "u8"; MatrixU16, "u16"; MatrixU32, "u32"; MatrixU64, "u64"; MatrixU128, "u128";
+    MatrixI8, "i8"; MatrixI16, "i16"; MatrixI32, "i32"; MatrixI64, "i64"; MatrixI128, "i128";
+    MatrixF32, "f32"; MatrixF64, "f64";
+  )
+}
+
+pub fn stats_histogram_table(values: &[f64], bins: usize) -> MechTable {
+  let values = values.iter().copied().filter(|x| !x.is_nan()).collect::<Vec<f64>>();
+  let (min, max) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), x| (min.min(*x), max.max(*x)));
+  let (min, max) = if values.is_empty() { (0.0, 0.0) } else { (min, max) };
+  let width = (max - min) / bins as f64;
+  let mut counts = vec![0u64; bins];
+  for x in values.iter() {
+    let bin = if width > 0.0 { ((x - min) / width) as usize } else { 0 };
+    counts[bin.min(bins - 1)] += 1;
+  }
+  let edge = |ix: usize| if ix == bins { max } else { min + width * ix as f64 };
+  let columns = vec![
+    ("lower", ValueKind::F64, (0..bins).map(|ix| Value::F64(Ref::new(edge(ix)))).collect::<Vec<Value>>()),
+    ("upper", ValueKind::F64, (0..bins).map(|ix| Value::F64(Ref::new(edge(ix + 1)))).collect()),
+    ("count", ValueKind::U64, counts.iter().map(|count| Value::U64(Ref::new(*count))).collect()),
+  ];
+  let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+  let mut col_names: HashMap<u64, String> = HashMap::new();
+  for (name, kind, values) in columns {
+    let id = hash_str(name);
+    data.insert(id, (kind.clone(), Column::from_values(&kind, values)));
+    col_names.insert(id, name.to_string());
+  }
+  MechTable::new(bins, data.len(), data, col_names)
+}
+
+pub struct StatsHistogram {}
+impl NativeFunctionCompiler for StatsHistogram {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    if arguments.len() != 2 {
+      return Err(MechError::new(IncorrectNumberOfArguments { expected: 2, found: arguments.len() }, None).with_compiler_loc());
+    }
+    let arg = stats_deref(&arguments[0]);
+    let values = match stats_histogram_values(&arg) {
+      Some(values) => values,
+      None => return Err(MechError::new(
+          UnhandledFunctionArgumentKind1 { arg: arg.kind(), fxn_name: "stats/histogram".to_string() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    let bins = arguments[1].as_usize()?;
+    if bins == 0 {
+      return Err(MechError::new(StatsHistogramBinsError {}, None).with_compiler_loc());
+    }
+    let out = Ref::new(stats_histogram_table(&values, bins));
+    Ok(Box::new(StatsHistogramFxn { arg, bins, out }))
+  }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "stats/histogram",
+    ptr: &StatsHistogram{},
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatsHistogramBinsError {}
+impl MechErrorKind for StatsHistogramBinsError {
+  fn name(&self) -> &str { "StatsHistogramBins" }
+  fn message(&self) -> String {
+    "A histogram needs at least one bin".to_string()
+  }
+}
@@ -48,10 +48,39 @@ pub mod sum_column;
 #[cfg(feature = "sum")]
 pub mod sum_row;
 
+#[cfg(feature = "mean")]
+pub mod mean;
+#[cfg(feature = "variance")]
+pub mod variance;
+#[cfg(feature = "median")]
+pub mod median;
+#[cfg(feature = "extrema")]
+pub mod extrema;
+#[cfg(feature = "cumulative")]
+pub mod cumulative;
+#[cfg(feature = "covariance")]
+pub mod covariance;
+#[cfg(feature = "histogram")]
+pub mod histogram;
+
 #[cfg(feature = "sum")]
 pub use self::sum_column::*;
 #[cfg(feature = "sum")]
 pub use self::sum_row::*;
+#[cfg(feature = "mean")]
+pub use self::mean::*;
+#[cfg(feature = "variance")]
+pub use self::variance::*;
+#[cfg(feature = "median")]
+pub use self::median::*;
+#[cfg(feature = "extrema")]
+pub use self::extrema::*;
+#[cfg(feature = "cumulative")]
+pub use self::cumulative::*;
+#[cfg(feature = "covariance")]
+pub use self::covariance::*;
+#[cfg(feature = "histogram")]
+pub use self::histogram::*;
 
 #[macro_export]  
 macro_rules! impl_stats_unop {
@@ -119,3 +148,570 @@ macro_rules! impls_stas {
     register_fxn_descriptor!($name, u8, "u8", u16, "u16", u32, "u32", u64, "u64", u128, "u128", i8, "i8", i16, "i16", i32, "i32", i64, "i64", i128, "i128", f32, "f32", f64, "f64", C64, "complex", R64, "rational");
   };
 }
+
+// Descriptive Statistics
+// ----------------------------------------------------------------------------
+
+// The statistics below read every kind they support as f64, except where the
+// element kind is kept (min, max, cumsum, cumprod).
+pub trait StatsElement: Copy + Debug + Clone + Sync + Send + 'static + Default + PartialOrd + Add<Output = Self> + Mul<Output = Self> {
+  fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_stats_element {
+  ($($type:ty),+) => {
+    $(
+      impl StatsElement for $type {
+        fn to_f64(self) -> f64 { self as f64 }
+      }
+    )+
+  };
+}
+
+impl_stats_element!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+// NaN for an empty matrix.
+pub fn stats_mean<T: StatsElement>(xs: &[T]) -> f64 {
+  xs.iter().map(|x| x.to_f64()).sum::<f64>() / xs.len() as f64
+}
+
+// A rows x cols matrix of defaults, for the fixed and dynamic shapes alike.
+#[cfg(feature = "matrix")]
+pub fn stats_filled<N, R, C>(rows: usize, cols: usize) -> nalgebra::OMatrix<N, R, C>
+where
+  N: nalgebra::Scalar + Default,
+  R: nalgebra::Dim,
+  C: nalgebra::Dim,
+  nalgebra::DefaultAllocator: nalgebra::allocator::Allocator<R, C>,
+{
+  nalgebra::OMatrix::from_element_generic(R::from_usize(rows), C::from_usize(cols), N::default())
+}
+
+// Like impl_stats_unop, for statistics over the real kinds that may produce
+// another element kind than they consume. The op gets `$kernel` as well.
+#[macro_export]
+macro_rules! impl_stats_fxn {
+  ($struct_name:ident, $arg_type:ty, $out_type:ty, $op:ident, $kernel:ident, $feature:tt) => {
+    #[derive(Debug)]
+    struct $struct_name<T> {
+      arg: Ref<$arg_type>,
+      out: Ref<$out_type>,
+    }
+    impl<T> MechFunctionFactory for $struct_name<T>
+    where
+      T: StatsElement + CompileConst + ConstElem + AsValueKind,
+      Ref<$out_type>: ToValue
+    {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Unary(out, arg) => {
+            let arg = unsafe{ arg.as_unchecked().clone() };
+            let out = unsafe{ out.as_unchecked().clone() };
+            Ok(Box::new(Self { arg, out }))
+          }
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 1, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl<T> MechFunctionImpl for $struct_name<T>
+    where
+      T: StatsElement,
+      Ref<$out_type>: ToValue
+    {
+      fn solve(&self) {
+        let arg_ptr = self.arg.as_ptr();
+        let out_ptr = self.out.as_mut_ptr();
+        $op!(arg_ptr, out_ptr, $kernel);
+      }
+      fn out(&self) -> Value { self.out.to_value() }
+      fn to_string(&self) -> String { format!("{:#?}", self) }
+    }
+    #[cfg(feature = "compiler")]
+    impl<T> MechFunctionCompiler for $struct_name<T>
+    where
+      T: CompileConst + ConstElem + AsValueKind,
+    {
+      fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        let name = format!("{}<{}>", stringify!($struct_name), T::as_value_kind());
+        compile_unop!(name, self.out, self.arg, ctx, FeatureFlag::Custom(hash_str($feature)));
+      }
+    }};}
+
+// The two argument form of impl_stats_fxn, for quantile (a matrix and q) and
+// cov/corr (two matrices of the same shape).
+#[macro_export]
+macro_rules! impl_stats_binop_fxn {
+  ($struct_name:ident, $arg1_type:ty, $arg2_type:ty, $out_type:ty, $op:ident, $kernel:ident, $feature:tt) => {
+    #[derive(Debug)]
+    struct $struct_name<T> {
+      lhs: Ref<$arg1_type>,
+      rhs: Ref<$arg2_type>,
+      out: Ref<$out_type>,
+    }
+    impl<T> MechFunctionFactory for $struct_name<T>
+    where
+      T: StatsElement + CompileConst + ConstElem + AsValueKind,
+      Ref<$out_type>: ToValue
+    {
+      fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+          FunctionArgs::Binary(out, arg1, arg2) => {
+            let lhs = unsafe{ arg1.as_unchecked().clone() };
+            let rhs = unsafe{ arg2.as_unchecked().clone() };
+            let out = unsafe{ out.as_unchecked().clone() };
+            Ok(Box::new(Self { lhs, rhs, out }))
+          }
+          _ => Err(MechError::new(
+              IncorrectNumberOfArguments { expected: 2, found: args.len() },
+              None
+            ).with_compiler_loc()
+          ),
+        }
+      }
+    }
+    impl<T> MechFunctionImpl for $struct_name<T>
+    where
+      T: StatsElement,
+      Ref<$out_type>: ToValue
+    {
+      fn solve(&self) {
+        let lhs_ptr = self.lhs.as_ptr();
+        let rhs_ptr = self.rhs.as_ptr();
+        let out_ptr = self.out.as_mut_ptr();
+        $op!(lhs_ptr, rhs_ptr, out_ptr, $kernel);
+      }
+      fn out(&self) -> Value { self.out.to_value() }
+      fn to_string(&self) -> String { format!("{:#?}", self) }
+    }
+    #[cfg(feature = "compiler")]
+    impl<T> MechFunctionCompiler for $struct_name<T>
+    where
+      T: CompileConst + ConstElem + AsValueKind,
+    {
+      fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        let name = format!("{}<{}>", stringify!($struct_name), T::as_value_kind());
+        compile_binop!(name, self.out, self.lhs, self.rhs, ctx, FeatureFlag::Custom(hash_str($feature)));
+      }
+    }};}
+
+#[macro_export]
+macro_rules! impls_stats_real {
+  ($name:ident) => {
+    register_fxn_descriptor!($name, u8, "u8", u16, "u16", u32, "u32", u64, "u64", u128, "u128", i8, "i8", i16, "i16", i32, "i32", i64, "i64", i128, "i128", f32, "f32", f64, "f64");
+  };
+}
+
+// The shapes every statistic is instantiated for. Each line holds the struct
+// suffix, the input shape, the outputs of the /row and /column variants, the
+// square outputs of cov/row and cov/column, and the features they all need.
+// Dynamic inputs keep to dynamic outputs, so they work without matrix1.
+#[macro_export]
+macro_rules! impl_stats_shapes {
+  ($callback:ident!($($args:tt)*)) => {
+    $callback!($($args)*;
+      M1,   Matrix1,    Matrix1,    Matrix1, Matrix1, Matrix1, ["matrix1"];
+      M2,   Matrix2,    RowVector2, Vector2, Matrix2, Matrix2, ["matrix2", "row_vector2", "vector2"];
+      M3,   Matrix3,    RowVector3, Vector3, Matrix3, Matrix3, ["matrix3", "row_vector3", "vector3"];
+      M4,   Matrix4,    RowVector4, Vector4, Matrix4, Matrix4, ["matrix4", "row_vector4", "vector4"];
+      M2x3, Matrix2x3,  RowVector3, Vector2, Matrix3, Matrix2, ["matrix2x3", "row_vector3", "vector2", "matrix3", "matrix2"];
+      M3x2, Matrix3x2,  RowVector2, Vector3, Matrix2, Matrix3, ["matrix3x2", "row_vector2", "vector3", "matrix2", "matrix3"];
+      MD,   DMatrix,    RowDVector, DVector, DMatrix, DMatrix, ["matrixd", "row_vectord", "vectord"];
+      V2,   Vector2,    Matrix1,    Vector2, Matrix1, Matrix2, ["vector2", "matrix1", "matrix2"];
+      V3,   Vector3,    Matrix1,    Vector3, Matrix1, Matrix3, ["vector3", "matrix1", "matrix3"];
+      V4,   Vector4,    Matrix1,    Vector4, Matrix1, Matrix4, ["vector4", "matrix1", "matrix4"];
+      VD,   DVector,    RowDVector, DVector, DMatrix, DMatrix, ["vectord", "row_vectord", "matrixd"];
+      R2,   RowVector2, RowVector2, Matrix1, Matrix2, Matrix1, ["row_vector2", "matrix1", "matrix2"];
+      R3,   RowVector3, RowVector3, Matrix1, Matrix3, Matrix1, ["row_vector3", "matrix1", "matrix3"];
+      R4,   RowVector4, RowVector4, Matrix1, Matrix4, Matrix1, ["row_vector4", "matrix1", "matrix4"];
+      RD,   RowDVector, RowDVector, DVector, DMatrix, DMatrix, ["row_vectord", "vectord", "matrixd"];
+    );
+  };
+}
+
+// The whole-matrix, /row and /column structs of one statistic, per shape.
+#[macro_export]
+macro_rules! impl_stats_structs {
+  (reduce, $name:ident, $kernel:ident, $out:ty, $feature:tt; $($suffix:ident, $in:ident, $row:ident, $col:ident, $cov_row:ident, $cov_col:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_fxn!([<Stats $name $suffix>], $in<T>, $out, stats_whole_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name $suffix>]);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_fxn!([<Stats $name Row $suffix>], $in<T>, $row<$out>, stats_row_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name Row $suffix>]);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_fxn!([<Stats $name Column $suffix>], $in<T>, $col<$out>, stats_column_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name Column $suffix>]);
+      )+
+    }
+  };
+  (quantile, $name:ident, $kernel:ident, $feature:tt; $($suffix:ident, $in:ident, $row:ident, $col:ident, $cov_row:ident, $cov_col:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_binop_fxn!([<Stats $name $suffix>], $in<T>, f64, f64, stats_quantile_whole_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name $suffix>]);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_binop_fxn!([<Stats $name Row $suffix>], $in<T>, f64, $row<f64>, stats_quantile_row_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name Row $suffix>]);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_binop_fxn!([<Stats $name Column $suffix>], $in<T>, f64, $col<f64>, stats_quantile_column_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name Column $suffix>]);
+      )+
+    }
+  };
+  (cumulative, $name:ident, $kernel:ident, $feature:tt; $($suffix:ident, $in:ident, $row:ident, $col:ident, $cov_row:ident, $cov_col:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_fxn!([<Stats $name $suffix>], $in<T>, $in<T>, stats_cumulative_whole_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name $suffix>]);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_fxn!([<Stats $name Row $suffix>], $in<T>, $in<T>, stats_cumulative_row_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name Row $suffix>]);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_fxn!([<Stats $name Column $suffix>], $in<T>, $in<T>, stats_cumulative_column_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name Column $suffix>]);
+      )+
+    }
+  };
+  (covariance, $name:ident, $kernel:ident, $feature:tt; $($suffix:ident, $in:ident, $row:ident, $col:ident, $cov_row:ident, $cov_col:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_binop_fxn!([<Stats $name $suffix>], $in<T>, $in<T>, f64, stats_pair_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name $suffix>]);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_fxn!([<Stats $name Row $suffix>], $in<T>, $cov_row<f64>, stats_pair_row_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name Row $suffix>]);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impl_stats_fxn!([<Stats $name Column $suffix>], $in<T>, $cov_col<f64>, stats_pair_column_op, $kernel, $feature);
+        #[cfg(all($(feature = $shape_feature),+))]
+        impls_stats_real!([<Stats $name Column $suffix>]);
+      )+
+    }
+  };
+}
+
+// The output a statistic starts from, sized after its (first) argument.
+#[macro_export]
+macro_rules! stats_out {
+  (whole, $arg:ident) => { Ref::new(Default::default()) };
+  (row, $arg:ident) => {{ let (_, cols) = $arg.borrow().shape(); Ref::new(stats_filled(1, cols)) }};
+  (column, $arg:ident) => {{ let (rows, _) = $arg.borrow().shape(); Ref::new(stats_filled(rows, 1)) }};
+  (same, $arg:ident) => { Ref::new($arg.borrow().clone()) };
+  (cov_row, $arg:ident) => {{ let (_, cols) = $arg.borrow().shape(); Ref::new(stats_filled(cols, cols)) }};
+  (cov_column, $arg:ident) => {{ let (rows, _) = $arg.borrow().shape(); Ref::new(stats_filled(rows, rows)) }};
+}
+
+// Match arms for one element kind over every shape, as early returns.
+#[macro_export]
+macro_rules! impl_stats_arms {
+  (unary, $value:ident, $prefix:ident, $mode:ident, $value_kind:ident, $target_type:ty, $value_string:tt; $($suffix:ident, $in:ident, $row:ident, $col:ident, $cov_row:ident, $cov_col:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all(feature = $value_string, $(feature = $shape_feature),+))]
+        if let Value::[<Matrix $value_kind>](Matrix::<$target_type>::$in(arg)) = &$value {
+          return Ok(Box::new([<$prefix $suffix>]::<$target_type>{ arg: arg.clone(), out: stats_out!($mode, arg) }));
+        }
+      )+
+    }
+  };
+  (quantile, $lhs:ident, $rhs:ident, $prefix:ident, $mode:ident, $value_kind:ident, $target_type:ty, $value_string:tt; $($suffix:ident, $in:ident, $row:ident, $col:ident, $cov_row:ident, $cov_col:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all(feature = $value_string, $(feature = $shape_feature),+))]
+        if let (Value::[<Matrix $value_kind>](Matrix::<$target_type>::$in(arg)), Value::F64(q)) = (&$lhs, &$rhs) {
+          stats_check_quantile(*q.borrow())?;
+          return Ok(Box::new([<$prefix $suffix>]::<$target_type>{ lhs: arg.clone(), rhs: q.clone(), out: stats_out!($mode, arg) }));
+        }
+      )+
+    }
+  };
+  (pair, $lhs:ident, $rhs:ident, $prefix:ident, $mode:ident, $value_kind:ident, $target_type:ty, $value_string:tt; $($suffix:ident, $in:ident, $row:ident, $col:ident, $cov_row:ident, $cov_col:ident, [$($shape_feature:tt),+]);+ $(;)?) => {
+    paste!{
+      $(
+        #[cfg(all(feature = $value_string, $(feature = $shape_feature),+))]
+        if let (Value::[<Matrix $value_kind>](Matrix::<$target_type>::$in(arg)), Value::[<Matrix $value_kind>](Matrix::<$target_type>::$in(other))) = (&$lhs, &$rhs) {
+          stats_check_shapes(stringify!($prefix), arg.borrow().shape(), other.borrow().shape())?;
+          return Ok(Box::new([<$prefix $suffix>]::<$target_type>{ lhs: arg.clone(), rhs: other.clone(), out: stats_out!($mode, arg) }));
+        }
+      )+
+    }
+  };
+}
+
+// A constructor for the statistic `$prefix`, trying every real kind in turn.
+#[macro_export]
+macro_rules! impl_stats_dispatch {
+  (unary, $fxn:ident, $prefix:ident, $mode:ident) => {
+    fn $fxn(value: Value) -> MResult<Box<dyn MechFunction>> {
+      impl_stats_kinds!(unary, value, $prefix, $mode);
+      Err(MechError::new(
+          UnhandledFunctionArgumentKind1 { arg: value.kind(), fxn_name: stringify!($prefix).to_string() },
+          None
+        ).with_compiler_loc()
+      )
+    }
+  };
+  ($arms:ident, $fxn:ident, $prefix:ident, $mode:ident) => {
+    fn $fxn(lhs: Value, rhs: Value) -> MResult<Box<dyn MechFunction>> {
+      impl_stats_kinds!($arms, lhs, rhs, $prefix, $mode);
+      Err(MechError::new(
+          UnhandledFunctionArgumentKind2 { arg: (lhs.kind(), rhs.kind()), fxn_name: stringify!($prefix).to_string() },
+          None
+        ).with_compiler_loc()
+      )
+    }
+  };
+}
+
+#[macro_export]
+macro_rules! impl_stats_kinds {
+  ($($args:tt)*) => {
+    impl_stats_shapes!(impl_stats_arms!($($args)*, I8, i8, "i8"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, I16, i16, "i16"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, I32, i32, "i32"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, I64, i64, "i64"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, I128, i128, "i128"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, U8, u8, "u8"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, U16, u16, "u16"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, U32, u32, "u32"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, U64, u64, "u64"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, U128, u128, "u128"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, F32, f32, "f32"));
+    impl_stats_shapes!(impl_stats_arms!($($args)*, F64, f64, "f64"));
+  };
+}
+
+// Reduces the whole matrix, each column (/row) or each row (/column).
+#[macro_export]
+macro_rules! stats_whole_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      *$out = $kernel(&(*$arg).iter().copied().collect::<Vec<T>>());
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_row_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      for (ix, column) in (*$arg).column_iter().enumerate() {
+        out[ix] = $kernel(&column.iter().copied().collect::<Vec<T>>());
+      }
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_column_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      for (ix, row) in (*$arg).row_iter().enumerate() {
+        out[ix] = $kernel(&row.iter().copied().collect::<Vec<T>>());
+      }
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_quantile_whole_op {
+  ($arg:expr, $q:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      *$out = $kernel(&(*$arg).iter().copied().collect::<Vec<T>>(), *$q);
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_quantile_row_op {
+  ($arg:expr, $q:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      for (ix, column) in (*$arg).column_iter().enumerate() {
+        out[ix] = $kernel(&column.iter().copied().collect::<Vec<T>>(), *$q);
+      }
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_quantile_column_op {
+  ($arg:expr, $q:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      for (ix, row) in (*$arg).row_iter().enumerate() {
+        out[ix] = $kernel(&row.iter().copied().collect::<Vec<T>>(), *$q);
+      }
+    }
+  };}
+
+// Accumulates in linear (column-major) order, down each column (/row) or
+// along each row (/column).
+#[macro_export]
+macro_rules! stats_cumulative_whole_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      *out = (*$arg).clone();
+      for ix in 1..out.len() {
+        out[ix] = $kernel(out[ix - 1], out[ix]);
+      }
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_cumulative_row_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      *out = (*$arg).clone();
+      for col in 0..out.ncols() {
+        for row in 1..out.nrows() {
+          out[(row, col)] = $kernel(out[(row - 1, col)], out[(row, col)]);
+        }
+      }
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_cumulative_column_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      *out = (*$arg).clone();
+      for row in 0..out.nrows() {
+        for col in 1..out.ncols() {
+          out[(row, col)] = $kernel(out[(row, col - 1)], out[(row, col)]);
+        }
+      }
+    }
+  };}
+
+// Pairs up two matrices, the columns of one (/row) or the rows of one (/column).
+#[macro_export]
+macro_rules! stats_pair_op {
+  ($lhs:expr, $rhs:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let lhs = (*$lhs).iter().copied().collect::<Vec<T>>();
+      let rhs = (*$rhs).iter().copied().collect::<Vec<T>>();
+      *$out = $kernel(&lhs, &rhs);
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_pair_row_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      let columns = (*$arg).column_iter().map(|column| column.iter().copied().collect::<Vec<T>>()).collect::<Vec<_>>();
+      for i in 0..columns.len() {
+        for j in 0..columns.len() {
+          out[(i, j)] = $kernel(&columns[i], &columns[j]);
+        }
+      }
+    }
+  };}
+
+#[macro_export]
+macro_rules! stats_pair_column_op {
+  ($arg:expr, $out:expr, $kernel:ident) => {
+    unsafe {
+      let out = &mut *$out;
+      let rows = (*$arg).row_iter().map(|row| row.iter().copied().collect::<Vec<T>>()).collect::<Vec<_>>();
+      for i in 0..rows.len() {
+        for j in 0..rows.len() {
+          out[(i, j)] = $kernel(&rows[i], &rows[j]);
+        }
+      }
+    }
+  };}
+
+// Like impl_mech_binop_fxn, but without kind conversion, so that errors
+// about the arguments' values reach the user.
+#[macro_export]
+macro_rules! impl_stats_binop_compiler {
+  ($fxn_name:ident, $gen_fxn:tt, $fxn_string:tt) => {
+    pub struct $fxn_name {}
+    impl NativeFunctionCompiler for $fxn_name {
+      fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        if arguments.len() != 2 {
+          return Err(MechError::new(IncorrectNumberOfArguments { expected: 2, found: arguments.len() }, None).with_compiler_loc());
+        }
+        $gen_fxn(stats_deref(&arguments[0]), stats_deref(&arguments[1]))
+      }
+    }
+    register_descriptor! {
+      FunctionCompilerDescriptor {
+        name: $fxn_string,
+        ptr: &$fxn_name{},
+      }
+    }
+  };
+}
+
+pub fn stats_deref(value: &Value) -> Value {
+  match value {
+    Value::MutableReference(value) => value.borrow().clone(),
+    value => value.clone(),
+  }
+}
+
+pub fn stats_check_quantile(q: f64) -> MResult<()> {
+  if (0.0..=1.0).contains(&q) {
+    Ok(())
+  } else {
+    Err(MechError::new(StatsQuantileRangeError { q }, None).with_compiler_loc())
+  }
+}
+
+pub fn stats_check_shapes(fxn_name: &str, lhs: (usize, usize), rhs: (usize, usize)) -> MResult<()> {
+  if lhs == rhs {
+    Ok(())
+  } else {
+    Err(MechError::new(StatsShapeMismatchError { fxn_name: fxn_name.to_string(), lhs, rhs }, None).with_compiler_loc())
+  }
+}
+
+// ----------------------------------------------------------------------------
+// Errors
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct StatsQuantileRangeError {
+  pub q: f64,
+}
+impl MechErrorKind for StatsQuantileRangeError {
+  fn name(&self) -> &str { "StatsQuantileRange" }
+  fn message(&self) -> String {
+    format!("Quantile must be between 0 and 1, found {}", self.q)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatsShapeMismatchError {
+  pub fxn_name: String,
+  pub lhs: (usize, usize),
+  pub rhs: (usize, usize),
+}
+impl MechErrorKind for StatsShapeMismatchError {
+  fn name(&self) -> &str { "StatsShapeMismatch" }
+  fn message(&self) -> String {
+    format!("Arguments to {} must have the same shape, found {}x{} and {}x{}", self.fxn_name, self.lhs.0, self.lhs.1, self.rhs.0, self.rhs.1)
+  }
+}
@@ -0,0 +1,16 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Stats Mean ------------------------------------------------------------------
+
+impl_stats_shapes!(impl_stats_structs!(reduce, Mean, stats_mean, f64, "stats/mean"));
+
+impl_stats_dispatch!(unary, impl_stats_mean_fxn, StatsMean, whole);
+impl_stats_dispatch!(unary, impl_stats_mean_row_fxn, StatsMeanRow, row);
+impl_stats_dispatch!(unary, impl_stats_mean_column_fxn, StatsMeanColumn, column);
+
+impl_mech_urnop_fxn!(StatsMean, impl_stats_mean_fxn, "stats/mean");
+impl_mech_urnop_fxn!(StatsMeanRow, impl_stats_mean_row_fxn, "stats/mean/row");
+impl_mech_urnop_fxn!(StatsMeanColumn, impl_stats_mean_column_fxn, "stats/mean/column");
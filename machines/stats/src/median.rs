@@ -0,0 +1,40 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Stats Median and Quantile ---------------------------------------------------
+
+// Linear interpolation between the closest ranks, NaN for an empty matrix.
+pub fn stats_quantile<T: StatsElement>(xs: &[T], q: f64) -> f64 {
+  if xs.is_empty() {
+    return f64::NAN;
+  }
+  let mut sorted = xs.iter().map(|x| x.to_f64()).collect::<Vec<f64>>();
+  sorted.sort_by(|a, b| a.total_cmp(b));
+  let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+  let lower = position.floor() as usize;
+  let upper = position.ceil() as usize;
+  sorted[lower] + (position - lower as f64) * (sorted[upper] - sorted[lower])
+}
+
+pub fn stats_median<T: StatsElement>(xs: &[T]) -> f64 {
+  stats_quantile(xs, 0.5)
+}
+
+impl_stats_shapes!(impl_stats_structs!(reduce, Median, stats_median, f64, "stats/median"));
+impl_stats_shapes!(impl_stats_structs!(quantile, Quantile, stats_quantile, "stats/quantile"));
+
+impl_stats_dispatch!(unary, impl_stats_median_fxn, StatsMedian, whole);
+impl_stats_dispatch!(unary, impl_stats_median_row_fxn, StatsMedianRow, row);
+impl_stats_dispatch!(unary, impl_stats_median_column_fxn, StatsMedianColumn, column);
+impl_stats_dispatch!(quantile, impl_stats_quantile_fxn, StatsQuantile, whole);
+impl_stats_dispatch!(quantile, impl_stats_quantile_row_fxn, StatsQuantileRow, row);
+impl_stats_dispatch!(quantile, impl_stats_quantile_column_fxn, StatsQuantileColumn, column);
+
+impl_mech_urnop_fxn!(StatsMedian, impl_stats_median_fxn, "stats/median");
+impl_mech_urnop_fxn!(StatsMedianRow, impl_stats_median_row_fxn, "stats/median/row");
+impl_mech_urnop_fxn!(StatsMedianColumn, impl_stats_median_column_fxn, "stats/median/column");
+impl_stats_binop_compiler!(StatsQuantile, impl_stats_quantile_fxn, "stats/quantile");
+impl_stats_binop_compiler!(StatsQuantileRow, impl_stats_quantile_row_fxn, "stats/quantile/row");
+impl_stats_binop_compiler!(StatsQuantileColumn, impl_stats_quantile_column_fxn, "stats/quantile/column");
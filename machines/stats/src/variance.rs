@@ -0,0 +1,37 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Stats Variance --------------------------------------------------------------
+
+// The sample variance (normalized by n - 1); zero for a single element.
+pub fn stats_variance<T: StatsElement>(xs: &[T]) -> f64 {
+  let n = xs.len();
+  if n < 2 {
+    return if n == 1 { 0.0 } else { f64::NAN };
+  }
+  let mean = stats_mean(xs);
+  xs.iter().map(|x| (x.to_f64() - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+}
+
+pub fn stats_std<T: StatsElement>(xs: &[T]) -> f64 {
+  stats_variance(xs).sqrt()
+}
+
+impl_stats_shapes!(impl_stats_structs!(reduce, Var, stats_variance, f64, "stats/var"));
+impl_stats_shapes!(impl_stats_structs!(reduce, Std, stats_std, f64, "stats/std"));
+
+impl_stats_dispatch!(unary, impl_stats_var_fxn, StatsVar, whole);
+impl_stats_dispatch!(unary, impl_stats_var_row_fxn, StatsVarRow, row);
+impl_stats_dispatch!(unary, impl_stats_var_column_fxn, StatsVarColumn, column);
+impl_stats_dispatch!(unary, impl_stats_std_fxn, StatsStd, whole);
+impl_stats_dispatch!(unary, impl_stats_std_row_fxn, StatsStdRow, row);
+impl_stats_dispatch!(unary, impl_stats_std_column_fxn, StatsStdColumn, column);
+
+impl_mech_urnop_fxn!(StatsVar, impl_stats_var_fxn, "stats/var");
+impl_mech_urnop_fxn!(StatsVarRow, impl_stats_var_row_fxn, "stats/var/row");
+impl_mech_urnop_fxn!(StatsVarColumn, impl_stats_var_column_fxn, "stats/var/column");
+impl_mech_urnop_fxn!(StatsStd, impl_stats_std_fxn, "stats/std");
+impl_mech_urnop_fxn!(StatsStdRow, impl_stats_std_row_fxn, "stats/std/row");
+impl_mech_urnop_fxn!(StatsStdColumn, impl_stats_std_column_fxn, "stats/std/column");
@@ -0,0 +1,48 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Case ------------------------------------------------------------------------
+
+// Unicode case mappings, which may change a string's length ("ß" uppercases
+// to "SS").
+
+#[derive(Debug)]
+pub enum CaseOp {
+  Upper,
+  Lower,
+}
+
+impl StringMapOp for CaseOp {
+  type Out = String;
+  fn name(&self) -> &'static str {
+    match self {
+      CaseOp::Upper => "string/to-uppercase",
+      CaseOp::Lower => "string/to-lowercase",
+    }
+  }
+  fn apply(&self, cells: &[&str]) -> MResult<String> {
+    match self {
+      CaseOp::Upper => Ok(cells[0].to_uppercase()),
+      CaseOp::Lower => Ok(cells[0].to_lowercase()),
+    }
+  }
+}
+
+fn build_case(op: CaseOp, arguments: &Vec<Value>) -> MResult<StringMapFxn<CaseOp>> {
+  match arguments.as_slice() {
+    [text] => {
+      let text = string_argument(text, op.name())?;
+      StringMapFxn::new(op, vec![text], arguments)
+    }
+    _ => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+register_string_map!(StringToUppercase, new_string_to_uppercase, "string/to-uppercase", |arguments: &Vec<Value>| build_case(CaseOp::Upper, arguments));
+register_string_map!(StringToLowercase, new_string_to_lowercase, "string/to-lowercase", |arguments: &Vec<Value>| build_case(CaseOp::Lower, arguments));
@@ -0,0 +1,84 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Format ----------------------------------------------------------------------
+
+// string/format(template, a, b, ...) fills the placeholders {1}, {2}, ... with
+// the arguments after the template; {{ and }} write literal braces. Arguments
+// of any kind are accepted and written the way they print inline, and any of
+// them (the template included) may be a matrix to format element-wise.
+
+#[derive(Debug)]
+pub struct FormatOp {}
+
+impl StringMapOp for FormatOp {
+  type Out = String;
+  fn name(&self) -> &'static str { "string/format" }
+  fn apply(&self, cells: &[&str]) -> MResult<String> {
+    format_template(cells[0], &cells[1..])
+  }
+}
+
+pub fn format_template(template: &str, args: &[&str]) -> MResult<String> {
+  let error = |reason: String| MechError::new(
+    StringFormatError { template: template.to_string(), reason },
+    None
+  ).with_compiler_loc();
+  let mut out = String::with_capacity(template.len());
+  let mut chars = template.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '{' if chars.peek() == Some(&'{') => { chars.next(); out.push('{'); }
+      '}' if chars.peek() == Some(&'}') => { chars.next(); out.push('}'); }
+      '{' => {
+        let mut index = String::new();
+        loop {
+          match chars.next() {
+            Some('}') => break,
+            Some(c) => index.push(c),
+            None => return Err(error("unclosed placeholder".to_string())),
+          }
+        }
+        match index.trim().parse::<usize>() {
+          Ok(ix) if ix >= 1 && ix <= args.len() => out.push_str(args[ix - 1]),
+          Ok(ix) => return Err(error(format!("placeholder {{{}}} has no argument, {} given", ix, args.len()))),
+          Err(_) => return Err(error(format!("placeholder {{{}}} is not a positive index", index))),
+        }
+      }
+      '}' => return Err(error("unmatched }".to_string())),
+      c => out.push(c),
+    }
+  }
+  Ok(out)
+}
+
+fn build_format(arguments: &Vec<Value>) -> MResult<StringMapFxn<FormatOp>> {
+  match arguments.split_first() {
+    Some((template, rest)) => {
+      let mut args = vec![string_argument(template, "string/format")?];
+      args.extend(rest.iter().map(deref_argument));
+      StringMapFxn::new(FormatOp {}, args, arguments)
+    }
+    None => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 1, found: 0 },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+register_string_map!(StringFormat, new_string_format, "string/format", build_format);
+
+#[derive(Debug, Clone)]
+pub struct StringFormatError {
+  pub template: String,
+  pub reason: String,
+}
+impl MechErrorKind for StringFormatError {
+  fn name(&self) -> &str { "StringFormat" }
+  fn message(&self) -> String {
+    format!("Invalid format template {:?}: {}", self.template, self.reason)
+  }
+}
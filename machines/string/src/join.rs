@@ -0,0 +1,107 @@
+use crate::*;
+use mech_core::*;
+use mech_core::matrix::Matrix;
+
+// Join ------------------------------------------------------------------------
+
+// string/join(strings, separator) joins a vector of strings into one string.
+// A matrix with several rows and columns joins each row, giving a column
+// vector. The separator defaults to "".
+
+#[derive(Debug)]
+pub struct StringJoinFxn {
+  pub strings: Value,
+  pub separator: Value,
+  pub out: StringCells<String>,
+}
+
+fn join_cells(strings: &Value, separator: &Value) -> (Vec<String>, Option<(usize, usize)>) {
+  let separator = argument_texts(separator).remove(0);
+  let cells = argument_texts(strings);
+  let (rows, cols) = match strings.is_matrix() {
+    true => (strings.shape()[0], strings.shape()[1]),
+    false => (1, 1),
+  };
+  if rows == 1 || cols == 1 {
+    return (vec![cells.join(&separator)], None);
+  }
+  let joined = (0..rows)
+    .map(|r| (0..cols).map(|c| cells[c * rows + r].as_str()).collect::<Vec<&str>>().join(&separator))
+    .collect();
+  (joined, Some((rows, 1)))
+}
+
+impl MechFunctionImpl for StringJoinFxn {
+  fn solve(&self) {
+    let (cells, _) = join_cells(&self.strings, &self.separator);
+    self.out.set(cells);
+  }
+  fn out(&self) -> Value { self.out.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for StringJoinFxn {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let args = vec![self.strings.clone(), self.separator.clone()];
+    compile_value_varop!("StringJoinFxn", self.out.to_value(), args, ctx, FeatureFlag::Custom(hash_str("string/join")));
+  }
+}
+
+fn build_join(arguments: &Vec<Value>) -> MResult<StringJoinFxn> {
+  let fxn_name = "string/join";
+  let (strings, separator) = match arguments.as_slice() {
+    [strings] => (strings, Value::String(Ref::new(String::new()))),
+    [strings, separator] => {
+      scalar_string_argument(separator, fxn_name)?;
+      (strings, deref_argument(separator))
+    }
+    _ => return Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  };
+  let strings = string_argument(strings, fxn_name)?;
+  let (cells, shape) = join_cells(&strings, &separator);
+  Ok(StringJoinFxn { strings, separator, out: StringCells::new(cells, shape) })
+}
+
+pub struct StringJoin {}
+
+impl NativeFunctionCompiler for StringJoin {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    Ok(Box::new(build_join(arguments)?))
+  }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "string/join",
+    ptr: &StringJoin{},
+  }
+}
+
+// A compiled join is built again from its strings and separator.
+fn new_string_join(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+  match args {
+    FunctionArgs::Variadic(out, arguments) => {
+      let mut fxn = build_join(&arguments)?;
+      fxn.out = unsafe { StringCells::bind(&out) };
+      fxn.solve();
+      Ok(Box::new(fxn))
+    }
+    _ => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 2, found: args.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+register_descriptor! {
+  FunctionDescriptor {
+    name: "StringJoinFxn",
+    ptr: new_string_join,
+  }
+}
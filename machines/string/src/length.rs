@@ -0,0 +1,32 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Length ----------------------------------------------------------------------
+
+// Counts graphemes, so "née" and "👩‍🚀!" have lengths 3 and 2.
+
+#[derive(Debug)]
+pub struct LengthOp {}
+
+impl StringMapOp for LengthOp {
+  type Out = u64;
+  fn name(&self) -> &'static str { "string/length" }
+  fn apply(&self, cells: &[&str]) -> MResult<u64> {
+    Ok(grapheme_count(cells[0]) as u64)
+  }
+}
+
+fn build_length(arguments: &Vec<Value>) -> MResult<StringMapFxn<LengthOp>> {
+  match arguments.as_slice() {
+    [text] => StringMapFxn::new(LengthOp {}, vec![string_argument(text, "string/length")?], arguments),
+    _ => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+register_string_map!(StringLength, new_string_length, "string/length", build_length);
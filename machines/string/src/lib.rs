@@ -5,6 +5,8 @@ extern crate mech_core;
 extern crate paste;
 
 use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
 
 #[cfg(feature = "vector3")]
 use nalgebra::Vector3;
@@ -43,9 +45,45 @@ use paste::paste;
 
 #[cfg(feature = "concat")]
 pub mod concat;
+#[cfg(feature = "length")]
+pub mod length;
+#[cfg(feature = "split")]
+pub mod split;
+#[cfg(feature = "join")]
+pub mod join;
+#[cfg(feature = "search")]
+pub mod search;
+#[cfg(feature = "replace")]
+pub mod replace;
+#[cfg(feature = "case")]
+pub mod case;
+#[cfg(feature = "trim")]
+pub mod trim;
+#[cfg(feature = "pad")]
+pub mod pad;
+#[cfg(feature = "format")]
+pub mod format;
 
 #[cfg(feature = "concat")]
 pub use self::concat::*;
+#[cfg(feature = "length")]
+pub use self::length::*;
+#[cfg(feature = "split")]
+pub use self::split::*;
+#[cfg(feature = "join")]
+pub use self::join::*;
+#[cfg(feature = "search")]
+pub use self::search::*;
+#[cfg(feature = "replace")]
+pub use self::replace::*;
+#[cfg(feature = "case")]
+pub use self::case::*;
+#[cfg(feature = "trim")]
+pub use self::trim::*;
+#[cfg(feature = "pad")]
+pub use self::pad::*;
+#[cfg(feature = "format")]
+pub use self::format::*;
 
 // ----------------------------------------------------------------------------
 // String Library
@@ -126,4 +164,473 @@ macro_rules! impl_string_fxns {
   ($lib:ident) => {
     impl_fxns!($lib,T,T,impl_string_binop);
   }
-}
\ No newline at end of file
+}
+
+// ----------------------------------------------------------------------------
+// Graphemes
+// ----------------------------------------------------------------------------
+
+// Lengths, widths and positions count extended grapheme clusters, the same
+// units the parser reads source text in.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn graphemes(text: &str) -> Vec<&str> {
+  UnicodeSegmentation::graphemes(text, true).collect::<Vec<&str>>()
+}
+
+pub fn grapheme_count(text: &str) -> usize {
+  UnicodeSegmentation::graphemes(text, true).count()
+}
+
+// The 1-based grapheme position of a byte offset into `text`.
+pub fn grapheme_position(text: &str, byte_offset: usize) -> usize {
+  grapheme_count(&text[..byte_offset]) + 1
+}
+
+// ----------------------------------------------------------------------------
+// Broadcasting
+// ----------------------------------------------------------------------------
+
+// Element-wise string functions take scalars or matrices. A matrix argument
+// supplies one cell per element, a scalar is repeated for every element, and
+// all matrix arguments have to share a shape. The result takes that shape, or
+// is a scalar when every argument is one.
+
+#[cfg(feature = "matrix")]
+pub trait StringMapOp: std::fmt::Debug + 'static {
+  type Out: std::fmt::Debug + Clone + PartialEq + 'static;
+  fn name(&self) -> &'static str;
+  fn apply(&self, cells: &[&str]) -> MResult<Self::Out>;
+}
+
+#[cfg(feature = "matrix")]
+#[derive(Debug)]
+pub enum StringCells<T: std::fmt::Debug + Clone + PartialEq + 'static> {
+  Scalar(Ref<T>),
+  Matrix(Matrix<T>),
+}
+
+#[cfg(feature = "matrix")]
+impl<T> StringCells<T>
+where
+  T: std::fmt::Debug + Clone + PartialEq + 'static,
+  Ref<T>: ToValue,
+  Matrix<T>: ToValue,
+{
+  pub fn new(cells: Vec<T>, shape: Option<(usize, usize)>) -> Self {
+    match shape {
+      Some((rows, cols)) => StringCells::Matrix(Matrix::from_vec(cells, rows, cols)),
+      None => StringCells::Scalar(Ref::new(cells[0].clone())),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    match self {
+      StringCells::Scalar(_) => 1,
+      StringCells::Matrix(m) => m.rows() * m.cols(),
+    }
+  }
+
+  pub fn set(&self, cells: Vec<T>) {
+    if cells.len() != self.len() {
+      return;
+    }
+    match self {
+      StringCells::Scalar(x) => *x.borrow_mut() = cells[0].clone(),
+      StringCells::Matrix(m) => m.set(cells),
+    }
+  }
+
+  pub fn to_value(&self) -> Value {
+    match self {
+      StringCells::Scalar(x) => x.to_value(),
+      StringCells::Matrix(m) => m.to_value(),
+    }
+  }
+
+  // Cells that write to `out`, the output register of a loaded program.
+  pub unsafe fn bind(out: &Value) -> Self {
+    match out {
+      #[cfg(feature = "string")]
+      Value::MatrixString(m) => StringCells::Matrix((*(m as *const Matrix<String> as *const Matrix<T>)).clone()),
+      #[cfg(feature = "bool")]
+      Value::MatrixBool(m) => StringCells::Matrix((*(m as *const Matrix<bool> as *const Matrix<T>)).clone()),
+      #[cfg(feature = "u64")]
+      Value::MatrixU64(m) => StringCells::Matrix((*(m as *const Matrix<u64> as *const Matrix<T>)).clone()),
+      out => StringCells::Scalar(out.as_unchecked::<T>().clone()),
+    }
+  }
+}
+
+#[cfg(feature = "matrix")]
+#[derive(Debug)]
+pub struct StringMapFxn<O: StringMapOp> {
+  pub op: O,
+  pub args: Vec<Value>,
+  // Everything the function was called with, options included, so that a
+  // compiled program can build it again.
+  pub arguments: Vec<Value>,
+  pub out: StringCells<O::Out>,
+}
+
+#[cfg(feature = "matrix")]
+impl<O> StringMapFxn<O>
+where
+  O: StringMapOp,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  pub fn new(op: O, args: Vec<Value>, arguments: &[Value]) -> MResult<Self> {
+    let shape = broadcast_shape(&args, op.name())?;
+    let len = shape.map_or(1, |(rows, cols)| rows * cols);
+    let cells = map_cells(&op, &args, len)?;
+    let arguments = arguments.iter().map(deref_argument).collect();
+    Ok(StringMapFxn { out: StringCells::new(cells, shape), op, args, arguments })
+  }
+}
+
+// Loads a function of a compiled program, building it from its arguments
+// the way its compiler did and writing to the program's output register.
+#[cfg(feature = "matrix")]
+pub fn new_string_map<O, F>(args: FunctionArgs, build: F) -> MResult<Box<dyn MechFunction>>
+where
+  O: StringMapOp,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+  F: Fn(&Vec<Value>) -> MResult<StringMapFxn<O>>,
+{
+  match args {
+    FunctionArgs::Variadic(out, arguments) => {
+      let mut fxn = build(&arguments)?;
+      fxn.out = unsafe { StringCells::bind(&out) };
+      fxn.solve();
+      Ok(Box::new(fxn))
+    }
+    _ => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 1, found: args.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+// Registers a broadcasting string function by name, along with the
+// descriptor its compiled form is loaded by.
+#[macro_export]
+macro_rules! register_string_map {
+  ($struct_name:ident, $factory:ident, $fxn_name:tt, $build:expr) => {
+    pub struct $struct_name {}
+
+    impl NativeFunctionCompiler for $struct_name {
+      fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        Ok(Box::new(($build)(arguments)?))
+      }
+    }
+
+    register_descriptor! {
+      FunctionCompilerDescriptor {
+        name: $fxn_name,
+        ptr: &$struct_name{},
+      }
+    }
+
+    fn $factory(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+      new_string_map(args, $build)
+    }
+
+    register_descriptor! {
+      FunctionDescriptor {
+        name: concat!("StringMapFxn<", $fxn_name, ">"),
+        ptr: $factory,
+      }
+    }
+  };
+}
+
+#[cfg(feature = "matrix")]
+impl<O> MechFunctionImpl for StringMapFxn<O>
+where
+  O: StringMapOp,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  fn solve(&self) {
+    if let Ok(cells) = map_cells(&self.op, &self.args, self.out.len()) {
+      self.out.set(cells);
+    }
+  }
+  fn out(&self) -> Value { self.out.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+
+#[cfg(all(feature = "matrix", feature = "compiler"))]
+impl<O> MechFunctionCompiler for StringMapFxn<O>
+where
+  O: StringMapOp,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    let name = format!("StringMapFxn<{}>", self.op.name());
+    compile_value_varop!(name, self.out.to_value(), self.arguments, ctx, FeatureFlag::Custom(hash_str(self.op.name())));
+  }
+}
+
+// Applies `op` to every element, reading each argument's current cells.
+#[cfg(feature = "matrix")]
+pub fn map_cells<O: StringMapOp>(op: &O, args: &[Value], len: usize) -> MResult<Vec<O::Out>> {
+  let columns = args.iter().map(argument_texts).collect::<Vec<_>>();
+  if let Some(column) = columns.iter().find(|column| column.len() != 1 && column.len() != len) {
+    return Err(MechError::new(
+        StringShapeMismatchError { fxn_name: op.name().to_string(), expected: len, found: column.len() },
+        None
+      ).with_compiler_loc()
+    );
+  }
+  (0..len).map(|ix| {
+    let cells = columns.iter().map(|column| if column.len() == 1 { column[0].as_str() } else { column[ix].as_str() }).collect::<Vec<&str>>();
+    op.apply(&cells)
+  }).collect()
+}
+
+// The shape matrix arguments broadcast to, or None when all are scalars.
+#[cfg(feature = "matrix")]
+pub fn broadcast_shape(args: &[Value], fxn_name: &str) -> MResult<Option<(usize, usize)>> {
+  let mut shape: Option<(usize, usize)> = None;
+  for arg in args.iter().filter(|arg| arg.is_matrix()) {
+    let arg_shape = (arg.shape()[0], arg.shape()[1]);
+    match shape {
+      Some(shape) if shape != arg_shape => {
+        return Err(MechError::new(
+            StringShapeMismatchError { fxn_name: fxn_name.to_string(), expected: shape.0 * shape.1, found: arg_shape.0 * arg_shape.1 },
+            None
+          ).with_compiler_loc()
+        );
+      }
+      _ => shape = Some(arg_shape),
+    }
+  }
+  Ok(shape)
+}
+
+macro_rules! matrix_texts {
+  ($value:expr, $($variant:ident, $feature:tt);+ $(;)?) => {
+    match $value {
+      $(
+        #[cfg(feature = $feature)]
+        Value::$variant(m) => Some(m.as_vec().iter().map(|x| x.to_string()).collect::<Vec<String>>()),
+      )+
+      _ => None,
+    }
+  };
+}
+
+// The cells of an argument as text, in column-major order. Strings are taken
+// as they are; other values are written the way they print inline.
+#[cfg(feature = "matrix")]
+pub fn argument_texts(value: &Value) -> Vec<String> {
+  match value {
+    Value::String(x) => vec![x.borrow().clone()],
+    Value::MatrixString(m) => m.as_vec(),
+    Value::MutableReference(x) => argument_texts(&x.borrow()),
+    value => matrix_texts!(value,
+        MatrixBool, "bool"; MatrixU8, "u8"; MatrixU16, "u16"; MatrixU32, "u32"; MatrixU64, "u64"; MatrixU128, "u128";
+        MatrixI8, "i8"; MatrixI16, "i16"; MatrixI32, "i32"; MatrixI64, "i64"; MatrixI128, "i128";
+        MatrixF32, "f32"; MatrixF64, "f64"; MatrixC64, "complex"; MatrixR64, "rational")
+      .unwrap_or_else(|| vec![value.format_value_inline()]),
+  }
+}
+
+pub fn deref_argument(value: &Value) -> Value {
+  match value {
+    Value::MutableReference(value) => deref_argument(&value.borrow()),
+    value => value.clone(),
+  }
+}
+
+// A string or [string] argument that broadcasts.
+#[cfg(feature = "matrix")]
+pub fn string_argument(value: &Value, fxn_name: &str) -> MResult<Value> {
+  match deref_argument(value) {
+    value @ (Value::String(_) | Value::MatrixString(_)) => Ok(value),
+    x => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+// A scalar option, read once when the function is compiled.
+pub fn scalar_string_argument(value: &Value, fxn_name: &str) -> MResult<String> {
+  match deref_argument(value) {
+    Value::String(x) => Ok(x.borrow().clone()),
+    x => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+pub fn flag_argument(value: &Value, fxn_name: &str) -> MResult<bool> {
+  match deref_argument(value) {
+    #[cfg(feature = "bool")]
+    Value::Bool(x) => Ok(*x.borrow()),
+    x => Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+pub fn count_argument(value: &Value, fxn_name: &str) -> MResult<usize> {
+  let value = deref_argument(value);
+  value.as_usize().map_err(|_| MechError::new(
+      UnhandledFunctionArgumentKind1 { arg: value.kind(), fxn_name: fxn_name.to_string() },
+      None
+    ).with_compiler_loc()
+  )
+}
+
+// ----------------------------------------------------------------------------
+// Matching
+// ----------------------------------------------------------------------------
+
+// Matches a literal pattern, or a regular expression in regex mode. Compiled
+// expressions are cached by pattern, so a constant pattern compiles once.
+
+const MATCHER_CACHE_SIZE: usize = 64;
+
+#[derive(Debug, Default)]
+pub struct Matcher {
+  pub regex: bool,
+  #[cfg(feature = "regex")]
+  cache: std::cell::RefCell<std::collections::HashMap<String, regex::Regex>>,
+}
+
+impl Matcher {
+
+  pub fn new(regex: bool) -> MResult<Matcher> {
+    if regex && cfg!(not(feature = "regex")) {
+      return Err(MechError::new(StringRegexUnavailableError {}, None).with_compiler_loc());
+    }
+    Ok(Matcher { regex, ..Default::default() })
+  }
+
+  #[cfg(feature = "regex")]
+  fn compiled(&self, pattern: &str) -> MResult<regex::Regex> {
+    let mut cache = self.cache.borrow_mut();
+    if let Some(regex) = cache.get(pattern) {
+      return Ok(regex.clone());
+    }
+    let regex = regex::Regex::new(pattern).map_err(|err| MechError::new(
+        StringRegexError { pattern: pattern.to_string(), source: err.to_string() },
+        None
+      ).with_compiler_loc()
+    )?;
+    if cache.len() >= MATCHER_CACHE_SIZE {
+      cache.clear();
+    }
+    cache.insert(pattern.to_string(), regex.clone());
+    Ok(regex)
+  }
+
+  // Byte range of the first match.
+  pub fn find(&self, text: &str, pattern: &str) -> MResult<Option<(usize, usize)>> {
+    #[cfg(feature = "regex")]
+    if self.regex {
+      return Ok(self.compiled(pattern)?.find(text).map(|m| (m.start(), m.end())));
+    }
+    Ok(text.find(pattern).map(|start| (start, start + pattern.len())))
+  }
+
+  pub fn starts_with(&self, text: &str, pattern: &str) -> MResult<bool> {
+    #[cfg(feature = "regex")]
+    if self.regex {
+      self.compiled(pattern)?;
+      return Ok(self.compiled(&format!(r"\A(?:{})", pattern))?.is_match(text));
+    }
+    Ok(text.starts_with(pattern))
+  }
+
+  pub fn ends_with(&self, text: &str, pattern: &str) -> MResult<bool> {
+    #[cfg(feature = "regex")]
+    if self.regex {
+      self.compiled(pattern)?;
+      return Ok(self.compiled(&format!(r"(?:{})\z", pattern))?.is_match(text));
+    }
+    Ok(text.ends_with(pattern))
+  }
+
+  // Replaces every match. In regex mode the replacement may refer to capture
+  // groups as `$1` or `${name}`.
+  pub fn replace(&self, text: &str, pattern: &str, replacement: &str) -> MResult<String> {
+    #[cfg(feature = "regex")]
+    if self.regex {
+      return Ok(self.compiled(pattern)?.replace_all(text, replacement).into_owned());
+    }
+    Ok(text.replace(pattern, replacement))
+  }
+
+  // An empty literal separator splits text into graphemes.
+  pub fn split(&self, text: &str, pattern: &str) -> MResult<Vec<String>> {
+    #[cfg(feature = "regex")]
+    if self.regex {
+      return Ok(self.compiled(pattern)?.split(text).map(|part| part.to_string()).collect());
+    }
+    if pattern.is_empty() {
+      return Ok(graphemes(text).into_iter().map(|part| part.to_string()).collect());
+    }
+    Ok(text.split(pattern).map(|part| part.to_string()).collect())
+  }
+
+}
+
+// Reads the optional trailing regex flag of a matching function.
+pub fn matcher_argument(arguments: &[Value], position: usize, fxn_name: &str) -> MResult<Matcher> {
+  match arguments.get(position) {
+    Some(flag) => Matcher::new(flag_argument(flag, fxn_name)?),
+    None => Matcher::new(false),
+  }
+}
+
+// ----------------------------------------------------------------------------
+// Errors
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct StringShapeMismatchError {
+  pub fxn_name: String,
+  pub expected: usize,
+  pub found: usize,
+}
+impl MechErrorKind for StringShapeMismatchError {
+  fn name(&self) -> &str { "StringShapeMismatch" }
+  fn message(&self) -> String {
+    format!("Matrix arguments to {} must have the same shape, found {} and {} elements", self.fxn_name, self.expected, self.found)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct StringRegexError {
+  pub pattern: String,
+  pub source: String,
+}
+impl MechErrorKind for StringRegexError {
+  fn name(&self) -> &str { "StringRegex" }
+  fn message(&self) -> String {
+    format!("Invalid regular expression {:?}: {}", self.pattern, self.source)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct StringRegexUnavailableError {}
+impl MechErrorKind for StringRegexUnavailableError {
+  fn name(&self) -> &str { "StringRegexUnavailable" }
+  fn message(&self) -> String {
+    "Regex mode requires the string machine's \"regex\" feature".to_string()
+  }
+}
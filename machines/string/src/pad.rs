@@ -0,0 +1,64 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Pad -------------------------------------------------------------------------
+
+// Pads to a width in graphemes with a one-grapheme fill, a space by default.
+// Strings already at least that wide are left as they are.
+
+#[derive(Debug)]
+pub struct PadOp {
+  pub left: bool,
+  pub width: usize,
+  pub fill: String,
+}
+
+impl StringMapOp for PadOp {
+  type Out = String;
+  fn name(&self) -> &'static str {
+    if self.left { "string/pad-left" } else { "string/pad-right" }
+  }
+  fn apply(&self, cells: &[&str]) -> MResult<String> {
+    let text = cells[0];
+    let padding = self.fill.repeat(self.width.saturating_sub(grapheme_count(text)));
+    if self.left {
+      Ok(format!("{}{}", padding, text))
+    } else {
+      Ok(format!("{}{}", text, padding))
+    }
+  }
+}
+
+fn build_pad(left: bool, arguments: &Vec<Value>) -> MResult<StringMapFxn<PadOp>> {
+  let fxn_name = if left { "string/pad-left" } else { "string/pad-right" };
+  let (text, width, fill) = match arguments.as_slice() {
+    [text, width] => (text, width, " ".to_string()),
+    [text, width, fill] => (text, width, scalar_string_argument(fill, fxn_name)?),
+    _ => return Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  };
+  if grapheme_count(&fill) != 1 {
+    return Err(MechError::new(StringPadFillError { fill }, None).with_compiler_loc());
+  }
+  let op = PadOp { left, width: count_argument(width, fxn_name)?, fill };
+  StringMapFxn::new(op, vec![string_argument(text, fxn_name)?], arguments)
+}
+
+register_string_map!(StringPadLeft, new_string_pad_left, "string/pad-left", |arguments: &Vec<Value>| build_pad(true, arguments));
+register_string_map!(StringPadRight, new_string_pad_right, "string/pad-right", |arguments: &Vec<Value>| build_pad(false, arguments));
+
+#[derive(Debug, Clone)]
+pub struct StringPadFillError {
+  pub fill: String,
+}
+impl MechErrorKind for StringPadFillError {
+  fn name(&self) -> &str { "StringPadFill" }
+  fn message(&self) -> String {
+    format!("Padding fill must be a single grapheme, found {:?}", self.fill)
+  }
+}
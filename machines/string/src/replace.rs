@@ -0,0 +1,43 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Replace ---------------------------------------------------------------------
+
+// Replaces every match of a pattern. With a trailing `true` the pattern is a
+// regular expression and the replacement can use its groups as `$1`.
+
+#[derive(Debug)]
+pub struct ReplaceOp {
+  pub matcher: Matcher,
+}
+
+impl StringMapOp for ReplaceOp {
+  type Out = String;
+  fn name(&self) -> &'static str { "string/replace" }
+  fn apply(&self, cells: &[&str]) -> MResult<String> {
+    self.matcher.replace(cells[0], cells[1], cells[2])
+  }
+}
+
+fn build_replace(arguments: &Vec<Value>) -> MResult<StringMapFxn<ReplaceOp>> {
+  let fxn_name = "string/replace";
+  match arguments.as_slice() {
+    [text, pattern, replacement] | [text, pattern, replacement, _] => {
+      let args = vec![
+        string_argument(text, fxn_name)?,
+        string_argument(pattern, fxn_name)?,
+        string_argument(replacement, fxn_name)?,
+      ];
+      StringMapFxn::new(ReplaceOp { matcher: matcher_argument(arguments, 3, fxn_name)? }, args, arguments)
+    }
+    _ => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 3, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+register_string_map!(StringReplace, new_string_replace, "string/replace", build_replace);
@@ -0,0 +1,92 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Search ----------------------------------------------------------------------
+
+// string/contains, string/starts-with and string/ends-with test each string
+// against a pattern; string/find gives the 1-based grapheme position of the
+// first match, or 0 when there is none. A trailing `true` switches the
+// pattern to a regular expression.
+
+#[derive(Debug)]
+pub enum SearchKind {
+  Contains,
+  StartsWith,
+  EndsWith,
+}
+
+impl SearchKind {
+  pub fn name(&self) -> &'static str {
+    match self {
+      SearchKind::Contains => "string/contains",
+      SearchKind::StartsWith => "string/starts-with",
+      SearchKind::EndsWith => "string/ends-with",
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct SearchOp {
+  pub kind: SearchKind,
+  pub matcher: Matcher,
+}
+
+impl StringMapOp for SearchOp {
+  type Out = bool;
+  fn name(&self) -> &'static str { self.kind.name() }
+  fn apply(&self, cells: &[&str]) -> MResult<bool> {
+    let (text, pattern) = (cells[0], cells[1]);
+    match self.kind {
+      SearchKind::Contains => Ok(self.matcher.find(text, pattern)?.is_some()),
+      SearchKind::StartsWith => self.matcher.starts_with(text, pattern),
+      SearchKind::EndsWith => self.matcher.ends_with(text, pattern),
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct FindOp {
+  pub matcher: Matcher,
+}
+
+impl StringMapOp for FindOp {
+  type Out = u64;
+  fn name(&self) -> &'static str { "string/find" }
+  fn apply(&self, cells: &[&str]) -> MResult<u64> {
+    let text = cells[0];
+    let position = self.matcher.find(text, cells[1])?.map_or(0, |(start, _)| grapheme_position(text, start));
+    Ok(position as u64)
+  }
+}
+
+// (text, pattern) or (text, pattern, regex)
+fn search_arguments(arguments: &Vec<Value>, fxn_name: &str) -> MResult<(Vec<Value>, Matcher)> {
+  match arguments.as_slice() {
+    [text, pattern] | [text, pattern, _] => {
+      let args = vec![string_argument(text, fxn_name)?, string_argument(pattern, fxn_name)?];
+      Ok((args, matcher_argument(arguments, 2, fxn_name)?))
+    }
+    _ => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+fn build_search(kind: SearchKind, arguments: &Vec<Value>) -> MResult<StringMapFxn<SearchOp>> {
+  let (args, matcher) = search_arguments(arguments, kind.name())?;
+  StringMapFxn::new(SearchOp { kind, matcher }, args, arguments)
+}
+
+fn build_find(arguments: &Vec<Value>) -> MResult<StringMapFxn<FindOp>> {
+  let (args, matcher) = search_arguments(arguments, "string/find")?;
+  StringMapFxn::new(FindOp { matcher }, args, arguments)
+}
+
+register_string_map!(StringContains, new_string_contains, "string/contains", |arguments: &Vec<Value>| build_search(SearchKind::Contains, arguments));
+register_string_map!(StringStartsWith, new_string_starts_with, "string/starts-with", |arguments: &Vec<Value>| build_search(SearchKind::StartsWith, arguments));
+register_string_map!(StringEndsWith, new_string_ends_with, "string/ends-with", |arguments: &Vec<Value>| build_search(SearchKind::EndsWith, arguments));
+register_string_map!(StringFind, new_string_find, "string/find", build_find);
@@ -0,0 +1,135 @@
+use crate::*;
+use mech_core::*;
+use mech_core::matrix::Matrix;
+
+// Split -----------------------------------------------------------------------
+
+// string/split(text, separator) splits a string into a row vector of parts.
+// Given a vector of strings it gives a matrix with one row per string, so
+// every string has to split into the same number of parts. An empty separator
+// splits into graphemes, and a trailing `true` makes the separator a regular
+// expression.
+
+#[derive(Debug)]
+pub struct StringSplitFxn {
+  pub text: Value,
+  pub separator: Value,
+  pub matcher: Matcher,
+  pub arguments: Vec<Value>,
+  pub out: StringCells<String>,
+}
+
+fn split_parts(text: &Value, separator: &Value, matcher: &Matcher) -> MResult<Vec<Vec<String>>> {
+  let separator = argument_texts(separator).remove(0);
+  let parts = argument_texts(text).iter().map(|text| matcher.split(text, &separator)).collect::<MResult<Vec<_>>>()?;
+  if let Some(row) = parts.iter().find(|row| row.len() != parts[0].len()) {
+    return Err(MechError::new(
+        StringSplitShapeError { expected: parts[0].len(), found: row.len() },
+        None
+      ).with_compiler_loc()
+    );
+  }
+  Ok(parts)
+}
+
+// The parts as column-major cells of a matrix with a row per string.
+fn split_cells(parts: Vec<Vec<String>>) -> (Vec<String>, (usize, usize)) {
+  let (rows, cols) = (parts.len(), parts.first().map_or(0, |row| row.len()));
+  let cells = (0..cols).flat_map(|c| parts.iter().map(move |row| row[c].clone())).collect();
+  (cells, (rows, cols))
+}
+
+// The output keeps its shape, so a change in the number of parts leaves it
+// as it was.
+impl MechFunctionImpl for StringSplitFxn {
+  fn solve(&self) {
+    if let Ok(parts) = split_parts(&self.text, &self.separator, &self.matcher) {
+      self.out.set(split_cells(parts).0);
+    }
+  }
+  fn out(&self) -> Value { self.out.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for StringSplitFxn {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    compile_value_varop!("StringSplitFxn", self.out.to_value(), self.arguments, ctx, FeatureFlag::Custom(hash_str("string/split")));
+  }
+}
+
+fn build_split(arguments: &Vec<Value>) -> MResult<StringSplitFxn> {
+  let fxn_name = "string/split";
+  let (text, separator) = match arguments.as_slice() {
+    [text, separator] | [text, separator, _] => (string_argument(text, fxn_name)?, deref_argument(separator)),
+    _ => return Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 2, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  };
+  if let Value::MatrixString(_) = separator {
+    return Err(MechError::new(
+        UnhandledFunctionArgumentKind1 { arg: separator.kind(), fxn_name: fxn_name.to_string() },
+        None
+      ).with_compiler_loc()
+    );
+  }
+  scalar_string_argument(&separator, fxn_name)?;
+  let matcher = matcher_argument(arguments, 2, fxn_name)?;
+  let (cells, shape) = split_cells(split_parts(&text, &separator, &matcher)?);
+  let out = StringCells::new(cells, Some(shape));
+  let arguments = arguments.iter().map(deref_argument).collect();
+  Ok(StringSplitFxn { text, separator, matcher, arguments, out })
+}
+
+pub struct StringSplit {}
+
+impl NativeFunctionCompiler for StringSplit {
+  fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+    Ok(Box::new(build_split(arguments)?))
+  }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "string/split",
+    ptr: &StringSplit{},
+  }
+}
+
+// A compiled split is built again from its arguments, regex flag included.
+fn new_string_split(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+  match args {
+    FunctionArgs::Variadic(out, arguments) => {
+      let mut fxn = build_split(&arguments)?;
+      fxn.out = unsafe { StringCells::bind(&out) };
+      fxn.solve();
+      Ok(Box::new(fxn))
+    }
+    _ => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 2, found: args.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+register_descriptor! {
+  FunctionDescriptor {
+    name: "StringSplitFxn",
+    ptr: new_string_split,
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct StringSplitShapeError {
+  pub expected: usize,
+  pub found: usize,
+}
+impl MechErrorKind for StringSplitShapeError {
+  fn name(&self) -> &str { "StringSplitShape" }
+  fn message(&self) -> String {
+    format!("Every string must split into the same number of parts, found {} and {}", self.expected, self.found)
+  }
+}
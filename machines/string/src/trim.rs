@@ -0,0 +1,52 @@
+use crate::*;
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+
+// Trim ------------------------------------------------------------------------
+
+// Removes leading and/or trailing Unicode whitespace.
+
+#[derive(Debug)]
+pub enum TrimOp {
+  Both,
+  Start,
+  End,
+}
+
+impl StringMapOp for TrimOp {
+  type Out = String;
+  fn name(&self) -> &'static str {
+    match self {
+      TrimOp::Both => "string/trim",
+      TrimOp::Start => "string/trim-start",
+      TrimOp::End => "string/trim-end",
+    }
+  }
+  fn apply(&self, cells: &[&str]) -> MResult<String> {
+    let trimmed = match self {
+      TrimOp::Both => cells[0].trim(),
+      TrimOp::Start => cells[0].trim_start(),
+      TrimOp::End => cells[0].trim_end(),
+    };
+    Ok(trimmed.to_string())
+  }
+}
+
+fn build_trim(op: TrimOp, arguments: &Vec<Value>) -> MResult<StringMapFxn<TrimOp>> {
+  match arguments.as_slice() {
+    [text] => {
+      let text = string_argument(text, op.name())?;
+      StringMapFxn::new(op, vec![text], arguments)
+    }
+    _ => Err(MechError::new(
+        IncorrectNumberOfArguments { expected: 1, found: arguments.len() },
+        None
+      ).with_compiler_loc()
+    ),
+  }
+}
+
+register_string_map!(StringTrim, new_string_trim, "string/trim", |arguments: &Vec<Value>| build_trim(TrimOp::Both, arguments));
+register_string_map!(StringTrimStart, new_string_trim_start, "string/trim-start", |arguments: &Vec<Value>| build_trim(TrimOp::Start, arguments));
+register_string_map!(StringTrimEnd, new_string_trim_end, "string/trim-end", |arguments: &Vec<Value>| build_trim(TrimOp::End, arguments));
@@ -0,0 +1,121 @@
+use crate::*;
+use mech_core::*;
+
+// Duration --------------------------------------------------------------------
+
+// time/format-duration(d) writes seconds as days, hours, minutes and seconds,
+// such as "1d 2h 30m 15.5s", to the millisecond. time/parse-duration(text)
+// reads that form back, also accepting "ms" for milliseconds, "min" for
+// minutes, and a plain number of seconds.
+
+pub fn format_duration(seconds: f64) -> Option<String> {
+  if !seconds.is_finite() {
+    return None;
+  }
+  let total = (seconds.abs() * 1000.0).round() as u128;
+  let (days, ms) = (total / 86_400_000, total % 86_400_000);
+  let (hours, ms) = (ms / 3_600_000, ms % 3_600_000);
+  let (minutes, ms) = (ms / 60_000, ms % 60_000);
+  let mut parts = Vec::new();
+  for (count, unit) in [(days, "d"), (hours, "h"), (minutes, "m")] {
+    if count > 0 {
+      parts.push(format!("{}{}", count, unit));
+    }
+  }
+  if ms > 0 || parts.is_empty() {
+    let secs = format!("{}.{:03}", ms / 1000, ms % 1000);
+    parts.push(format!("{}s", secs.trim_end_matches('0').trim_end_matches('.')));
+  }
+  let sign = if seconds < 0.0 && total > 0 { "-" } else { "" };
+  Some(format!("{}{}", sign, parts.join(" ")))
+}
+
+pub fn parse_duration(text: &str) -> Option<f64> {
+  let text = text.trim();
+  let (sign, mut rest) = match text.strip_prefix('-') {
+    Some(rest) => (-1.0, rest.trim_start()),
+    None => (1.0, text),
+  };
+  if let Ok(seconds) = rest.parse::<f64>() {
+    return if seconds.is_finite() { Some(sign * seconds) } else { None };
+  }
+  if rest.is_empty() {
+    return None;
+  }
+  let mut total = 0.0;
+  while !rest.is_empty() {
+    let digits = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+    let count: f64 = rest[..digits].parse().ok()?;
+    rest = &rest[digits..];
+    let letters = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+    let scale = match &rest[..letters] {
+      "d" => 86_400.0,
+      "h" => 3_600.0,
+      "m" | "min" => 60.0,
+      "s" => 1.0,
+      "ms" => 0.001,
+      _ => return None,
+    };
+    total += count * scale;
+    rest = rest[letters..].trim_start();
+  }
+  Some(sign * total)
+}
+
+#[derive(Debug, Default)]
+pub struct FormatDurationOp {}
+
+impl TimeMapOp for FormatDurationOp {
+  type In = f64;
+  type Out = String;
+  const NAME: &'static str = "time/format-duration";
+  const DESCRIPTOR: &'static str = "TimeFormatDurationFxn<string>";
+  const INPUT: &'static str = "a duration or [f64] of durations";
+  fn apply(&self, seconds: &f64, _params: &[Value]) -> Option<String> {
+    format_duration(*seconds)
+  }
+  fn error(&self, seconds: &f64, _params: &[Value]) -> MechError {
+    time_argument_error(Self::NAME, &Value::F64(Ref::new(*seconds)), "a finite duration")
+  }
+  fn fallback(&self) -> String { String::new() }
+}
+
+#[derive(Debug, Default)]
+pub struct ParseDurationOp {}
+
+impl TimeMapOp for ParseDurationOp {
+  type In = String;
+  type Out = f64;
+  const NAME: &'static str = "time/parse-duration";
+  const DESCRIPTOR: &'static str = "TimeParseDurationFxn<f64>";
+  const INPUT: &'static str = "a string or [string] of durations";
+  fn apply(&self, text: &String, _params: &[Value]) -> Option<f64> {
+    parse_duration(text)
+  }
+  fn error(&self, text: &String, _params: &[Value]) -> MechError {
+    MechError::new(
+      TimeParseError { fxn_name: Self::NAME.to_string(), text: text.clone(), format: None },
+      None
+    ).with_compiler_loc()
+  }
+  fn fallback(&self) -> f64 { f64::NAN }
+}
+
+pub type TimeFormatDurationFxn = TimeMapFxn<FormatDurationOp>;
+pub type TimeParseDurationFxn = TimeMapFxn<ParseDurationOp>;
+
+register_fxn_descriptor_inner_logic!(TimeFormatDurationFxn, String, "string");
+register_fxn_descriptor_inner_logic!(TimeParseDurationFxn, f64, "f64");
+
+fn time_format_duration(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  time_check_args(&args, 1, 1)?;
+  Ok(Box::new(TimeFormatDurationFxn::build(args, None)?))
+}
+
+fn time_parse_duration(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  time_check_args(&args, 1, 1)?;
+  Ok(Box::new(TimeParseDurationFxn::build(args, None)?))
+}
+
+impl_time_compiler!(TimeFormatDuration, time_format_duration, "time/format-duration");
+impl_time_compiler!(TimeParseDuration, time_parse_duration, "time/parse-duration");
@@ -0,0 +1,69 @@
+use crate::*;
+use mech_core::*;
+
+// Elapsed ---------------------------------------------------------------------
+
+// time/elapsed() is the seconds since the program started, and
+// time/elapsed(t) the seconds since the date t.
+
+#[derive(Debug)]
+pub struct TimeElapsedFxn {
+  args: Vec<Value>,
+  start: Instant,
+  out: Ref<f64>,
+}
+
+impl TimeElapsedFxn {
+  fn build(args: Vec<Value>, out: Ref<f64>) -> MResult<Self> {
+    time_check_args(&args, 0, 1)?;
+    if let Some(since) = args.first() {
+      if since.as_f64().is_err() {
+        return Err(time_argument_error("time/elapsed", since, "a date"));
+      }
+    }
+    let fxn = Self { args, start: Instant::now(), out };
+    fxn.solve();
+    Ok(fxn)
+  }
+}
+
+impl MechFunctionFactory for TimeElapsedFxn {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Nullary(Value::F64(out)) => Ok(Box::new(Self::build(vec![], out)?)),
+      FunctionArgs::Variadic(Value::F64(out), args) => Ok(Box::new(Self::build(args, out)?)),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+
+impl MechFunctionImpl for TimeElapsedFxn {
+  fn solve(&self) {
+    *self.out.borrow_mut() = match self.args.first() {
+      Some(since) => unix_now() - time_param(since),
+      None => self.start.elapsed().as_secs_f64(),
+    };
+  }
+  fn out(&self) -> Value { self.out.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+  fn deterministic(&self) -> bool { false }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for TimeElapsedFxn {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    compile_value_varop!("TimeElapsedFxn<f64>", self.out.to_value(), self.args, ctx, FeatureFlag::Custom(hash_str("time/elapsed")));
+  }
+}
+
+register_fxn_descriptor_inner_logic!(TimeElapsedFxn, f64, "f64");
+
+fn time_elapsed(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(TimeElapsedFxn::build(args, Ref::new(0.0))?))
+}
+
+impl_time_compiler!(TimeElapsed, time_elapsed, "time/elapsed");
@@ -0,0 +1,67 @@
+use crate::*;
+use mech_core::*;
+use chrono::format::{Item, StrftimeItems};
+use std::fmt::Write;
+
+// Format ----------------------------------------------------------------------
+
+// time/format(t) writes a date as ISO 8601 text, and time/format(t, fmt) with
+// a strftime format such as "%Y-%m-%d %H:%M".
+
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.fZ";
+
+pub fn date_format(params: &[Value]) -> Option<String> {
+  match params.first() {
+    Some(format) => time_text(format),
+    None => Some(DEFAULT_DATE_FORMAT.to_string()),
+  }
+}
+
+pub fn valid_date_format(format: &str) -> bool {
+  !StrftimeItems::new(format).any(|item| item == Item::Error)
+}
+
+pub fn format_date(t: f64, format: &str) -> Option<String> {
+  if !valid_date_format(format) {
+    return None;
+  }
+  let mut text = String::new();
+  write!(text, "{}", date_from_seconds(t)?.format(format)).ok()?;
+  Some(text)
+}
+
+#[derive(Debug, Default)]
+pub struct FormatOp {}
+
+impl TimeMapOp for FormatOp {
+  type In = f64;
+  type Out = String;
+  const NAME: &'static str = "time/format";
+  const DESCRIPTOR: &'static str = "TimeFormatFxn<string>";
+  const INPUT: &'static str = "a date or [f64] of dates";
+  fn apply(&self, t: &f64, params: &[Value]) -> Option<String> {
+    format_date(*t, &date_format(params)?)
+  }
+  fn error(&self, t: &f64, params: &[Value]) -> MechError {
+    match date_format(params) {
+      Some(format) if !valid_date_format(&format) => MechError::new(
+          TimeFormatError { fxn_name: Self::NAME.to_string(), format },
+          None
+        ).with_compiler_loc(),
+      Some(_) => time_argument_error(Self::NAME, &Value::F64(Ref::new(*t)), "a date within the supported range"),
+      None => time_argument_error(Self::NAME, &params[0], "a format string"),
+    }
+  }
+  fn fallback(&self) -> String { String::new() }
+}
+
+pub type TimeFormatFxn = TimeMapFxn<FormatOp>;
+
+register_fxn_descriptor_inner_logic!(TimeFormatFxn, String, "string");
+
+fn time_format(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  time_check_args(&args, 1, 2)?;
+  Ok(Box::new(TimeFormatFxn::build(args, None)?))
+}
+
+impl_time_compiler!(TimeFormat, time_format, "time/format");
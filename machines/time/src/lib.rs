@@ -0,0 +1,369 @@
+#![no_main]
+#![allow(warnings)]
+#[macro_use]
+extern crate mech_core;
+#[cfg(feature = "matrix")]
+extern crate nalgebra as na;
+extern crate paste;
+
+use mech_core::*;
+#[cfg(feature = "matrix")]
+use mech_core::matrix::Matrix;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use paste::paste;
+
+#[cfg(feature = "now")]
+pub mod now;
+#[cfg(feature = "elapsed")]
+pub mod elapsed;
+#[cfg(feature = "timer")]
+pub mod timer;
+#[cfg(feature = "format")]
+pub mod format;
+#[cfg(feature = "parse")]
+pub mod parse;
+#[cfg(feature = "duration")]
+pub mod duration;
+
+#[cfg(feature = "now")]
+pub use self::now::*;
+#[cfg(feature = "elapsed")]
+pub use self::elapsed::*;
+#[cfg(feature = "timer")]
+pub use self::timer::*;
+#[cfg(feature = "format")]
+pub use self::format::*;
+#[cfg(feature = "parse")]
+pub use self::parse::*;
+#[cfg(feature = "duration")]
+pub use self::duration::*;
+
+// ----------------------------------------------------------------------------
+// Time Library
+// ----------------------------------------------------------------------------
+
+// Dates are f64 seconds since the Unix epoch (1970-01-01T00:00:00Z) and
+// durations are f64 seconds, so both work with ordinary arithmetic: the
+// difference of two dates is a duration, and a date plus a duration is a
+// date. Dates are read and written in UTC.
+
+pub fn unix_now() -> f64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0)
+}
+
+#[cfg(feature = "chrono")]
+pub fn date_from_seconds(t: f64) -> Option<DateTime<Utc>> {
+  if !t.is_finite() {
+    return None;
+  }
+  let secs = t.floor();
+  let nanos = (((t - secs) * 1e9).round() as u32).min(999_999_999);
+  DateTime::from_timestamp(secs as i64, nanos)
+}
+
+#[cfg(feature = "chrono")]
+pub fn date_to_seconds(date: DateTime<Utc>) -> f64 {
+  date.timestamp() as f64 + date.timestamp_subsec_nanos() as f64 / 1e9
+}
+
+pub fn time_deref(value: &Value) -> Value {
+  match value {
+    Value::MutableReference(value) => time_deref(&value.borrow()),
+    value => value.clone(),
+  }
+}
+
+// A numeric parameter, read each time the function solves.
+pub fn time_param(value: &Value) -> f64 {
+  value.as_f64().map(|x| *x.borrow()).unwrap_or(f64::NAN)
+}
+
+// A string parameter, read each time the function solves.
+pub fn time_text(value: &Value) -> Option<String> {
+  match time_deref(value) {
+    Value::String(x) => Some(x.borrow().clone()),
+    _ => None,
+  }
+}
+
+pub fn time_check_args(args: &[Value], min: usize, max: usize) -> MResult<()> {
+  if args.len() < min || args.len() > max {
+    let expected = if args.len() < min { min } else { max };
+    return Err(MechError::new(IncorrectNumberOfArguments { expected, found: args.len() }, None).with_compiler_loc());
+  }
+  Ok(())
+}
+
+pub fn time_argument_error(fxn_name: &str, value: &Value, expected: &str) -> MechError {
+  let found = match value.as_f64() {
+    Ok(x) => format!("{}", x.borrow()),
+    Err(_) => format!("{}", value.kind()),
+  };
+  MechError::new(
+    TimeArgumentError { fxn_name: fxn_name.to_string(), expected: expected.to_string(), found },
+    None
+  ).with_compiler_loc()
+}
+
+#[macro_export]
+macro_rules! impl_time_compiler {
+  ($fxn_name:ident, $gen_fxn:tt, $fxn_string:tt) => {
+    pub struct $fxn_name {}
+    impl NativeFunctionCompiler for $fxn_name {
+      fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        $gen_fxn(arguments.clone())
+      }
+    }
+    register_descriptor! {
+      FunctionCompilerDescriptor {
+        name: $fxn_string,
+        ptr: &$fxn_name{},
+      }
+    }
+  };
+}
+
+// Conversions -----------------------------------------------------------------
+
+// Parsing and formatting work element-wise on a scalar or a matrix, and the
+// result has the shape of the input.
+
+#[cfg(feature = "matrix")]
+pub trait TimeItem: Clone + Debug + Default + PartialEq + 'static {
+  fn scalar(value: &Value) -> Option<Ref<Self>>;
+  fn matrix(value: &Value) -> Option<Matrix<Self>>;
+}
+
+macro_rules! impl_time_item {
+  ($($t:ty, $scalar:ident, $matrix:ident, $value_string:tt);+ $(;)?) => {
+    $(
+      #[cfg(all(feature = "matrix", feature = $value_string))]
+      impl TimeItem for $t {
+        fn scalar(value: &Value) -> Option<Ref<Self>> {
+          match value {
+            Value::$scalar(x) => Some(x.clone()),
+            Value::MutableReference(value) => Self::scalar(&value.borrow()),
+            _ => None,
+          }
+        }
+        fn matrix(value: &Value) -> Option<Matrix<Self>> {
+          match value {
+            Value::$matrix(m) => Some(m.clone()),
+            Value::MutableReference(value) => Self::matrix(&value.borrow()),
+            _ => None,
+          }
+        }
+      }
+    )+
+  };
+}
+
+impl_time_item!(
+  f64, F64, MatrixF64, "f64";
+  String, String, MatrixString, "string";
+);
+
+#[cfg(feature = "matrix")]
+#[derive(Debug)]
+pub enum TimeCells<T> {
+  Scalar(Ref<T>),
+  Matrix(Matrix<T>),
+}
+
+#[cfg(feature = "matrix")]
+impl<T> TimeCells<T>
+where
+  T: TimeItem,
+  Ref<T>: ToValue,
+  Matrix<T>: ToValue,
+{
+  pub fn new(shape: Option<(usize, usize)>) -> Self {
+    match shape {
+      Some((rows, cols)) => TimeCells::Matrix(Matrix::from_vec(vec![T::default(); rows * cols], rows, cols)),
+      None => TimeCells::Scalar(Ref::new(T::default())),
+    }
+  }
+
+  pub fn from_value(value: &Value) -> Option<Self> {
+    T::matrix(value).map(TimeCells::Matrix).or_else(|| T::scalar(value).map(TimeCells::Scalar))
+  }
+
+  pub fn shape(&self) -> Option<(usize, usize)> {
+    match self {
+      TimeCells::Scalar(_) => None,
+      TimeCells::Matrix(m) => Some((m.rows(), m.cols())),
+    }
+  }
+
+  pub fn values(&self) -> Vec<T> {
+    match self {
+      TimeCells::Scalar(x) => vec![x.borrow().clone()],
+      TimeCells::Matrix(m) => m.as_vec(),
+    }
+  }
+
+  pub fn set(&self, mut cells: Vec<T>) {
+    match self {
+      TimeCells::Scalar(x) => *x.borrow_mut() = cells.remove(0),
+      TimeCells::Matrix(m) => m.set(cells),
+    }
+  }
+
+  pub fn to_value(&self) -> Value {
+    match self {
+      TimeCells::Scalar(x) => x.to_value(),
+      TimeCells::Matrix(m) => m.to_value(),
+    }
+  }
+}
+
+#[cfg(feature = "matrix")]
+pub trait TimeMapOp: Debug + Default + 'static {
+  type In: TimeItem;
+  type Out: TimeItem;
+  // The Mech name, and the name the function is registered under.
+  const NAME: &'static str;
+  const DESCRIPTOR: &'static str;
+  // What the first argument has to be, for error messages.
+  const INPUT: &'static str;
+  // None when an element can't be converted with these parameters.
+  fn apply(&self, x: &Self::In, params: &[Value]) -> Option<Self::Out>;
+  // The error for an element that can't be converted when the function is
+  // built. Later failures give the fallback value instead.
+  fn error(&self, x: &Self::In, params: &[Value]) -> MechError;
+  fn fallback(&self) -> Self::Out;
+}
+
+#[cfg(feature = "matrix")]
+#[derive(Debug)]
+pub struct TimeMapFxn<O: TimeMapOp> {
+  op: O,
+  args: Vec<Value>,
+  input: TimeCells<O::In>,
+  out: TimeCells<O::Out>,
+}
+
+#[cfg(feature = "matrix")]
+impl<O> TimeMapFxn<O>
+where
+  O: TimeMapOp,
+  Ref<O::In>: ToValue,
+  Matrix<O::In>: ToValue,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  pub fn build(args: Vec<Value>, out: Option<TimeCells<O::Out>>) -> MResult<Self> {
+    let op = O::default();
+    let input = TimeCells::<O::In>::from_value(&args[0]).ok_or_else(|| time_argument_error(O::NAME, &args[0], O::INPUT))?;
+    if let Some(x) = input.values().iter().find(|x| op.apply(x, &args[1..]).is_none()) {
+      return Err(op.error(x, &args[1..]));
+    }
+    let out = out.unwrap_or_else(|| TimeCells::new(input.shape()));
+    let fxn = Self { op, args, input, out };
+    fxn.solve();
+    Ok(fxn)
+  }
+}
+
+#[cfg(feature = "matrix")]
+impl<O> MechFunctionFactory for TimeMapFxn<O>
+where
+  O: TimeMapOp,
+  Ref<O::In>: ToValue,
+  Matrix<O::In>: ToValue,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(out, args) if !args.is_empty() => Ok(Box::new(Self::build(args, TimeCells::from_value(&out))?)),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+
+#[cfg(feature = "matrix")]
+impl<O> MechFunctionImpl for TimeMapFxn<O>
+where
+  O: TimeMapOp,
+  Ref<O::In>: ToValue,
+  Matrix<O::In>: ToValue,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  fn solve(&self) {
+    let params = &self.args[1..];
+    let cells = self.input.values().iter().map(|x| self.op.apply(x, params).unwrap_or_else(|| self.op.fallback())).collect();
+    self.out.set(cells);
+  }
+  fn out(&self) -> Value { self.out.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+}
+
+#[cfg(all(feature = "matrix", feature = "compiler"))]
+impl<O> MechFunctionCompiler for TimeMapFxn<O>
+where
+  O: TimeMapOp,
+  Ref<O::In>: ToValue,
+  Matrix<O::In>: ToValue,
+  Ref<O::Out>: ToValue,
+  Matrix<O::Out>: ToValue,
+{
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    compile_value_varop!(O::DESCRIPTOR, self.out.to_value(), self.args, ctx, FeatureFlag::Custom(hash_str(O::NAME)));
+  }
+}
+
+// ----------------------------------------------------------------------------
+// Errors
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct TimeArgumentError {
+  pub fxn_name: String,
+  pub expected: String,
+  pub found: String,
+}
+impl MechErrorKind for TimeArgumentError {
+  fn name(&self) -> &str { "TimeArgument" }
+  fn message(&self) -> String {
+    format!("{} expected {}, found {}", self.fxn_name, self.expected, self.found)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeParseError {
+  pub fxn_name: String,
+  pub text: String,
+  pub format: Option<String>,
+}
+impl MechErrorKind for TimeParseError {
+  fn name(&self) -> &str { "TimeParse" }
+  fn message(&self) -> String {
+    match &self.format {
+      Some(format) => format!("{} could not read \"{}\" with the format \"{}\"", self.fxn_name, self.text, format),
+      None => format!("{} could not read \"{}\"", self.fxn_name, self.text),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeFormatError {
+  pub fxn_name: String,
+  pub format: String,
+}
+impl MechErrorKind for TimeFormatError {
+  fn name(&self) -> &str { "TimeFormat" }
+  fn message(&self) -> String {
+    format!("{} has an invalid format \"{}\"", self.fxn_name, self.format)
+  }
+}
@@ -0,0 +1,59 @@
+use crate::*;
+use mech_core::*;
+
+// Now -------------------------------------------------------------------------
+
+// time/now() is the current date, read from the system clock each time the
+// plan solves.
+
+#[derive(Debug)]
+pub struct TimeNowFxn {
+  out: Ref<f64>,
+}
+
+impl TimeNowFxn {
+  fn build(out: Ref<f64>) -> Self {
+    let fxn = Self { out };
+    fxn.solve();
+    fxn
+  }
+}
+
+impl MechFunctionFactory for TimeNowFxn {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Nullary(Value::F64(out)) => Ok(Box::new(Self::build(out))),
+      FunctionArgs::Variadic(Value::F64(out), args) if args.is_empty() => Ok(Box::new(Self::build(out))),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 0, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+
+impl MechFunctionImpl for TimeNowFxn {
+  fn solve(&self) {
+    *self.out.borrow_mut() = unix_now();
+  }
+  fn out(&self) -> Value { self.out.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+  fn deterministic(&self) -> bool { false }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for TimeNowFxn {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    compile_value_varop!("TimeNowFxn<f64>", self.out.to_value(), Vec::<Value>::new(), ctx, FeatureFlag::Custom(hash_str("time/now")));
+  }
+}
+
+register_fxn_descriptor_inner_logic!(TimeNowFxn, f64, "f64");
+
+fn time_now(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  time_check_args(&args, 0, 0)?;
+  Ok(Box::new(TimeNowFxn::build(Ref::new(0.0))))
+}
+
+impl_time_compiler!(TimeNow, time_now, "time/now");
@@ -0,0 +1,62 @@
+use crate::*;
+use mech_core::*;
+
+// Parse -----------------------------------------------------------------------
+
+// time/parse(text) reads an ISO 8601 date, and time/parse(text, fmt) a date
+// written with a strftime format. Text without a UTC offset is read as UTC,
+// and a date without a time of day as midnight.
+
+pub const DATE_TIME_FORMATS: [&str; 4] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"];
+
+pub fn parse_date(text: &str, format: Option<&str>) -> Option<f64> {
+  let text = text.trim();
+  let midnight = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|date| date.and_utc());
+  let date = match format {
+    Some(format) => DateTime::parse_from_str(text, format).map(|date| date.with_timezone(&Utc)).ok()
+      .or_else(|| NaiveDateTime::parse_from_str(text, format).ok().map(|date| date.and_utc()))
+      .or_else(|| NaiveDate::parse_from_str(text, format).ok().and_then(midnight)),
+    None => DateTime::parse_from_rfc3339(text).map(|date| date.with_timezone(&Utc)).ok()
+      .or_else(|| DATE_TIME_FORMATS.iter().find_map(|format| NaiveDateTime::parse_from_str(text, format).ok()).map(|date| date.and_utc()))
+      .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().and_then(midnight)),
+  };
+  date.map(date_to_seconds)
+}
+
+#[derive(Debug, Default)]
+pub struct ParseOp {}
+
+impl TimeMapOp for ParseOp {
+  type In = String;
+  type Out = f64;
+  const NAME: &'static str = "time/parse";
+  const DESCRIPTOR: &'static str = "TimeParseFxn<f64>";
+  const INPUT: &'static str = "a string or [string] of dates";
+  fn apply(&self, text: &String, params: &[Value]) -> Option<f64> {
+    match params.first() {
+      Some(format) => parse_date(text, Some(&time_text(format)?)),
+      None => parse_date(text, None),
+    }
+  }
+  fn error(&self, text: &String, params: &[Value]) -> MechError {
+    match params.first() {
+      Some(format) if time_text(format).is_none() => time_argument_error(Self::NAME, format, "a format string"),
+      format => MechError::new(
+          TimeParseError { fxn_name: Self::NAME.to_string(), text: text.clone(), format: format.and_then(time_text) },
+          None
+        ).with_compiler_loc(),
+    }
+  }
+  fn fallback(&self) -> f64 { f64::NAN }
+}
+
+pub type TimeParseFxn = TimeMapFxn<ParseOp>;
+
+register_fxn_descriptor_inner_logic!(TimeParseFxn, f64, "f64");
+
+fn time_parse(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  time_check_args(&args, 1, 2)?;
+  Ok(Box::new(TimeParseFxn::build(args, None)?))
+}
+
+impl_time_compiler!(TimeParse, time_parse, "time/parse");
@@ -0,0 +1,81 @@
+use crate::*;
+use mech_core::*;
+
+// Timer -----------------------------------------------------------------------
+
+// time/timer(period) counts the periods of `period` seconds that have passed
+// since the program started. The count changes on its own, so the timer
+// reports when it next ticks, and a runner steps the plan then to recompute
+// everything that depends on it. A changed period applies from the next tick.
+
+#[derive(Debug)]
+pub struct TimeTimerFxn {
+  args: Vec<Value>,
+  next: RefCell<Instant>,
+  ticks: Ref<u64>,
+}
+
+pub fn timer_period(value: &Value) -> Option<f64> {
+  let period = time_param(value);
+  if period > 0.0 && period.is_finite() { Some(period) } else { None }
+}
+
+impl TimeTimerFxn {
+  fn build(args: Vec<Value>, ticks: Ref<u64>) -> MResult<Self> {
+    time_check_args(&args, 1, 1)?;
+    let period = timer_period(&args[0]).ok_or_else(|| time_argument_error("time/timer", &args[0], "a positive period in seconds"))?;
+    let next = RefCell::new(Instant::now() + Duration::from_secs_f64(period));
+    Ok(Self { args, next, ticks })
+  }
+}
+
+impl MechFunctionFactory for TimeTimerFxn {
+  fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+    match args {
+      FunctionArgs::Variadic(Value::U64(out), args) => Ok(Box::new(Self::build(args, out)?)),
+      _ => Err(MechError::new(
+          IncorrectNumberOfArguments { expected: 1, found: args.len() },
+          None
+        ).with_compiler_loc()
+      ),
+    }
+  }
+}
+
+impl MechFunctionImpl for TimeTimerFxn {
+  fn solve(&self) {
+    let period = match timer_period(&self.args[0]) {
+      Some(period) => period,
+      None => return,
+    };
+    let now = Instant::now();
+    let mut next = self.next.borrow_mut();
+    if now >= *next {
+      // Catch up on every period that passed, however long since the last solve.
+      let ticks = ((now - *next).as_secs_f64() / period).floor() as u64 + 1;
+      *self.ticks.borrow_mut() += ticks;
+      *next += Duration::from_secs_f64(period * ticks as f64);
+    }
+  }
+  fn out(&self) -> Value { self.ticks.to_value() }
+  fn to_string(&self) -> String { format!("{:#?}", self) }
+  fn deterministic(&self) -> bool { false }
+  fn next_tick(&self) -> Option<Duration> {
+    Some(self.next.borrow().saturating_duration_since(Instant::now()))
+  }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for TimeTimerFxn {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    compile_value_varop!("TimeTimerFxn<u64>", self.ticks.to_value(), self.args, ctx, FeatureFlag::Custom(hash_str("time/timer")));
+  }
+}
+
+register_fxn_descriptor_inner_logic!(TimeTimerFxn, u64, "u64");
+
+fn time_timer(args: Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+  Ok(Box::new(TimeTimerFxn::build(args, Ref::new(0))?))
+}
+
+impl_time_compiler!(TimeTimer, time_timer, "time/timer");
@@ -230,6 +230,32 @@ async fn main() -> Result<(), MechError> {
         .long("address")
         .value_name("ADDRESS")
         .help("Sets the address of the server (127.0.0.1)")))
+    .subcommand(Command::new("run")
+      .about("Run Mech program, optionally persisting its state across restarts.")
+      .arg(Arg::new("mech_run_file_paths")
+        .help("Source .mec and .mecb files")
+        .required(false)
+        .action(ArgAction::Append))
+      .arg(Arg::new("persist")
+        .long("persist")
+        .value_name("DIR")
+        .help("Persist mutable symbols to DIR and restore them on start"))
+      .arg(Arg::new("persist_symbols")
+        .long("persist-symbols")
+        .value_name("SYMBOLS")
+        .num_args(1..)
+        .action(ArgAction::Append)
+        .help("Persist only these mutable symbols"))
+      .arg(Arg::new("run_for")
+        .long("for")
+        .value_name("SECONDS")
+        .value_parser(value_parser!(f64))
+        .help("Keep running for SECONDS, stepping the program each time a timer ticks"))
+      .arg(Arg::new("repl")
+        .short('r')
+        .long("repl")
+        .help("Start REPL")
+        .action(ArgAction::SetTrue)))
     .arg(Arg::new("tree")
         .short('e')
         .long("tree")
@@ -244,6 +270,11 @@ async fn main() -> Result<(), MechError> {
         .long("trace")
         .help("Print trace output for state-machine arms and function calls")
         .action(ArgAction::SetTrue))
+    .arg(Arg::new("allow_fs")
+        .long("allow-fs")
+        .help("Let programs read and write files, including .csv, .tsv, .parquet and .json files given as sources")
+        .global(true)
+        .action(ArgAction::SetTrue))
     .arg(Arg::new("repl")
         .short('r')
         .long("repl")
@@ -256,6 +287,7 @@ async fn main() -> Result<(), MechError> {
   let mut repl_flag = matches.get_flag("repl");
   let time_flag = matches.get_flag("time");
   let trace_flag = matches.get_flag("trace");
+  let allow_fs_flag = matches.get_flag("allow_fs");
 
   let shim_backup_url = "https://raw.githubusercontent.com/mech-lang/mech/refs/heads/main/include/shim.html".to_string();
   let stylesheet_backup_url = "https://raw.githubusercontent.com/mech-lang/mech/refs/heads/main/include/style.css".to_string();
@@ -352,6 +384,9 @@ async fn main() -> Result<(), MechError> {
 
     let uuid = generate_uuid();
     let mut intrp = Interpreter::new(uuid);
+    if allow_fs_flag {
+      intrp.grant_capability(Capability::FileSystem);
+    }
 
     let result = run_mech_code(&mut intrp, &mechfs, tree_flag, debug_flag, time_flag, trace_flag); 
 
@@ -497,9 +532,22 @@ async fn main() -> Result<(), MechError> {
   let mut caught_inturrupts = Arc::new(Mutex::new(0));
   let uuid = generate_uuid();
   let mut intrp = Interpreter::new(uuid);
+  if allow_fs_flag {
+    intrp.grant_capability(Capability::FileSystem);
+  }
+  #[cfg(feature = "persist")]
+  let mut persister: Option<Persister> = None;
   #[cfg(feature = "run")]
   {
-    let mut paths = if let Some(m) = matches.get_many::<String>("mech_paths") {
+    let run_matches = matches.subcommand_matches("run");
+    if let Some(m) = run_matches {
+      repl_flag |= m.get_flag("repl");
+    }
+    let path_args = match run_matches {
+      Some(m) => m.get_many::<String>("mech_run_file_paths"),
+      None => matches.get_many::<String>("mech_paths"),
+    };
+    let mut paths = if let Some(m) = path_args {
       m.map(|s| s.to_string()).collect()
     } else { repl_flag = true; vec![] };
 
@@ -562,7 +610,33 @@ async fn main() -> Result<(), MechError> {
       }
     }
 
-    let result = run_mech_code(&mut intrp, &mechfs, tree_flag, debug_flag, time_flag, trace_flag); 
+    let mut result = run_mech_code(&mut intrp, &mechfs, tree_flag, debug_flag, time_flag, trace_flag);
+    #[cfg(feature = "persist")]
+    if let Some(dir) = run_matches.and_then(|m| m.get_one::<String>("persist")) {
+      let symbols = run_matches.and_then(|m| m.get_many::<String>("persist_symbols")).map(|s| s.cloned().collect());
+      match persist_mech_code(&mut intrp, dir, symbols) {
+        Ok((p, restored_result)) => {
+          persister = Some(p);
+          if let Some(value) = restored_result {
+            result = Ok(value);
+          }
+        }
+        Err(err) => {
+          print_mech_error(&err);
+          std::process::exit(1);
+        }
+      }
+    }
+    if let (Ok(_), Some(seconds)) = (&result, run_matches.and_then(|m| m.get_one::<f64>("run_for"))) {
+      let duration = Duration::try_from_secs_f64(seconds.max(0.0)).unwrap_or(Duration::MAX);
+      #[cfg(feature = "persist")]
+      let last = run_timers(&mut intrp, duration, |intrp| record_step(&mut persister, Some(intrp)));
+      #[cfg(not(feature = "persist"))]
+      let last = run_timers(&mut intrp, duration, |_| ());
+      if let Some(last) = last {
+        result = last;
+      }
+    }
     if !repl_flag {
       match &result {
         Ok(r) => {
@@ -620,6 +694,8 @@ async fn main() -> Result<(), MechError> {
   // --------------------------------------------------------------------------
   #[cfg(feature = "repl")]
   let mut repl = MechRepl::from(intrp);
+  #[cfg(all(feature = "repl", feature = "persist"))]
+  let persist_id = repl.active;
   #[cfg(feature = "repl")]
   'REPL: loop {
     {
@@ -643,6 +719,8 @@ async fn main() -> Result<(), MechError> {
               println!("!{:?}", err);
             }
           }
+          #[cfg(feature = "persist")]
+          record_step(&mut persister, repl.interpreters.get(&persist_id));
         }
         Err(x) => {
           println!("{} Unrecognized command: {}", "[Error]".truecolor(246,98,78), x);
@@ -660,12 +738,48 @@ async fn main() -> Result<(), MechError> {
           println!("(x)> {:#?}", err);
         }
       }
+      #[cfg(feature = "persist")]
+      record_step(&mut persister, repl.interpreters.get(&persist_id));
     }
   }
   
   Ok(())
 }
 
+// Steps the program each time a timer in its plan ticks, until `duration` has
+// passed or nothing in the plan is driven by a clock, calling `on_step` after
+// each step. Returns the result of the last step, if there was one.
+// `--for inf` runs until interrupted.
+fn run_timers(intrp: &mut Interpreter, duration: Duration, mut on_step: impl FnMut(&Interpreter)) -> Option<MResult<Value>> {
+  let deadline = Instant::now().checked_add(duration);
+  let mut result = None;
+  while let Some(wait) = intrp.next_tick() {
+    if deadline.is_some_and(|deadline| Instant::now() + wait > deadline) {
+      break;
+    }
+    thread::sleep(wait);
+    let step = intrp.step(0, 1);
+    on_step(intrp);
+    let failed = step.is_err();
+    result = Some(step);
+    if failed {
+      break;
+    }
+  }
+  result
+}
+
+// Writes the persisted symbols after a step. In the REPL these are the
+// symbols of the interpreter the session started with.
+#[cfg(feature = "persist")]
+fn record_step(persister: &mut Option<Persister>, intrp: Option<&Interpreter>) {
+  if let (Some(persister), Some(intrp)) = (persister.as_mut(), intrp) {
+    if let Err(err) = record_mech_code(persister, intrp) {
+      print_mech_error(&err);
+    }
+  }
+}
+
 #[cfg(feature = "async")]
 pub async fn load_resource(resource_path: &str) -> String {
   if resource_path.starts_with("http") {
@@ -740,7 +854,7 @@ fn is_intended_path(s: &str) -> bool {
       // Mech specific
       "mec" | "🤖" | "mecb" | "mdoc" | "mpkg" => true,
       // Data/Standard formats
-      "m" | "csv" | "tsv" | "txt" | "md" | "json" | "toml" | "yaml" => true,
+      "m" | "csv" | "tsv" | "parquet" | "txt" | "md" | "json" | "toml" | "yaml" => true,
       // Web
       "html" | "htm" | "css" | "js" | "wasm" => true,
       // Images
@@ -21,7 +21,7 @@ use std::fmt;
 pub type FunctionsRef = Ref<Functions>;
 pub type FunctionTable = HashMap<u64, fn(FunctionArgs) -> MResult<Box<dyn MechFunction>>>;
 pub type FunctionCompilerTable = HashMap<u64, &'static dyn NativeFunctionCompiler>;
-pub type UserFunctionTable = HashMap<u64, FunctionDefinition>;
+pub type UserFunctionTable = HashMap<u64, Vec<FunctionDefinition>>;
 
 #[derive(Clone,Debug)]
 pub enum FunctionArgs {
@@ -83,6 +83,14 @@ pub trait MechFunctionImpl {
   fn solve(&self);
   fn out(&self) -> Value;
   fn to_string(&self) -> String;
+  // False for functions that can give a different output from the same inputs,
+  // such as random draws and clocks. Their results must not be folded into
+  // constants or reused from a cache.
+  fn deterministic(&self) -> bool { true }
+  // For functions driven by a clock, such as timers, how long until their
+  // output next changes on its own. A runner can sleep that long and then
+  // step the plan so dependents see the new value.
+  fn next_tick(&self) -> Option<std::time::Duration> { None }
 }
 
 #[cfg(feature = "compiler")]
@@ -102,12 +110,90 @@ impl<T> MechFunction for T where T: MechFunctionImpl {}
 
 pub trait NativeFunctionCompiler {
   fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>>;
+  // The capability a function needs before the interpreter will compile it,
+  // for functions that reach outside the program, such as file access.
+  fn capability(&self) -> Option<Capability> { None }
+  // The names of the function's parameters, in order, for functions that take
+  // named arguments such as `table/group-by(T, by: "k")`. Functions without
+  // names ignore the names written at a call.
+  fn parameters(&self) -> &'static [&'static str] { &[] }
+  // Compiles a function that takes Mech functions as arguments, such as the
+  // integrand of calculus/integrate. `resolve` turns such an argument, a
+  // lambda or the name of a function, into a callback into the interpreter.
+  fn compile_with_callbacks(&self, arguments: &Vec<Value>, resolve: &mut CallbackResolver) -> MResult<Box<dyn MechFunction>> {
+    self.compile(arguments)
+  }
+  // Compiles a function whose result depends on which arguments are constants
+  // of the program, such as the size given to matrix/zeros. `constants` holds
+  // one flag per argument; an argument that reads a variable or a
+  // nondeterministic function is not a constant.
+  fn compile_with_constants(&self, arguments: &Vec<Value>, constants: &[bool], resolve: &mut CallbackResolver) -> MResult<Box<dyn MechFunction>> {
+    self.compile_with_callbacks(arguments, resolve)
+  }
+}
+
+// Callbacks ------------------------------------------------------------------
+
+pub type MechCallbackCall = dyn FnMut(Vec<Value>) -> MResult<Value>;
+
+// Resolves a function argument to a callback, or fails if the value doesn't
+// name a function in scope.
+pub type CallbackResolver<'a> = dyn FnMut(&Value) -> MResult<MechCallback> + 'a;
+
+// A lambda or user function that a native function calls each time it solves.
+// The interpreter builds it, so the native function doesn't need to know how
+// Mech functions run.
+pub struct MechCallback {
+  pub name: String,
+  call: RefCell<Box<MechCallbackCall>>,
+}
+
+impl Debug for MechCallback {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "MechCallback({})", self.name)
+  }
+}
+
+impl MechCallback {
+  pub fn new(name: String, call: Box<MechCallbackCall>) -> Self {
+    Self { name, call: RefCell::new(call) }
+  }
+
+  pub fn call(&self, args: Vec<Value>) -> MResult<Value> {
+    (self.call.borrow_mut())(args)
+  }
+}
+
+// Capabilities ---------------------------------------------------------------
+
+// Permissions an embedder grants an interpreter. Programs start with none, so
+// running untrusted code can't touch the host unless it is explicitly allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+  FileSystem,
+}
+
+impl fmt::Display for Capability {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Capability::FileSystem => write!(f, "filesystem"),
+    }
+  }
+}
+
+// Names a function descriptor that needs a capability. Compiled programs call
+// functions by descriptor, so loading one checks every call against these
+// rather than trusting the program's feature list.
+pub struct FunctionCapabilityDescriptor {
+  pub name: &'static str,
+  pub capability: Capability,
 }
 
 #[derive(Clone)]
 pub struct Functions {
   pub functions: FunctionTable,
   pub function_compilers: FunctionCompilerTable,
+  pub capabilities: HashMap<u64, Capability>,
   pub user_functions: UserFunctionTable,
   pub dictionary: Ref<Dictionary>,
 }
@@ -117,6 +203,7 @@ impl Functions {
     Self {
       functions: HashMap::new(),
       function_compilers: HashMap::new(),
+      capabilities: HashMap::new(),
       user_functions: HashMap::new(),
       dictionary: Ref::new(Dictionary::new()),
     }
@@ -134,7 +221,7 @@ impl Functions {
     output.push_str("\nFunctions:\n");
     // print number of functions loaded:
     output.push_str(&format!("Total Functions: {}\n", self.functions.len()));
-    output.push_str(&format!("User Functions: {}\n", self.user_functions.len()));
+    output.push_str(&format!("User Functions: {}\n", self.user_functions.values().map(|overloads| overloads.len()).sum::<usize>()));
     //for (id, fxn_ptr) in &self.functions {
     //  let dict_brrw = self.dictionary.borrow();
     //  let name = dict_brrw.get(id).unwrap();
@@ -242,6 +329,41 @@ impl MechFunctionCompiler for UserFunction {
   }
 }
 
+// Lambda ---------------------------------------------------------------------
+
+// An anonymous function value. The environment is captured when the lambda
+// expression is evaluated, so the body sees the bindings that were in scope
+// at its definition site rather than at its call site.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MechLambda {
+  pub code: Lambda,
+  pub input: Vec<ValueKind>,
+  pub captures: HashMap<u64, Value>,
+}
+
+impl MechLambda {
+
+  pub fn new(code: Lambda, input: Vec<ValueKind>, captures: HashMap<u64, Value>) -> Self {
+    Self { code, input, captures }
+  }
+
+  pub fn kind(&self) -> ValueKind {
+    ValueKind::Lambda(self.input.clone())
+  }
+
+  pub fn to_string(&self) -> String {
+    let params = self.code.params.iter()
+      .zip(self.input.iter())
+      .map(|(param, kind)| match kind {
+        ValueKind::Any => param.name.to_string(),
+        kind => format!("{}<{}>", param.name.to_string(), kind),
+      })
+      .collect::<Vec<String>>()
+      .join(", ");
+    format!("λ({})", params)
+  }
+}
+
 // Plan
 // ----------------------------------------------------------------------------
 
@@ -348,6 +470,29 @@ pub struct FunctionRegistry {
   pub registry: RefCell<HashMap<u64, Box<dyn MechFunctionImpl>>>,
 }
 
+#[derive(Debug, Clone)]
+pub struct MissingCapabilityError {
+  pub fxn_name: String,
+  pub capability: Capability,
+}
+impl MechErrorKind for MissingCapabilityError {
+  fn name(&self) -> &str { "MissingCapability" }
+  fn message(&self) -> String {
+    format!("Function '{}' needs the {} capability, which this interpreter has not been granted", self.fxn_name, self.capability)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CallbackNotCompilableError {
+  pub fxn_name: String,
+}
+impl MechErrorKind for CallbackNotCompilableError {
+  fn name(&self) -> &str { "CallbackNotCompilable" }
+  fn message(&self) -> String {
+    format!("Function '{}' calls a Mech function, which cannot be compiled yet", self.fxn_name)
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct UnhandledFunctionArgumentKind1 {
   pub arg: ValueKind,
@@ -132,6 +132,9 @@ inventory::collect!(FunctionDescriptor);
 #[cfg(feature = "functions")]
 inventory::collect!(FunctionCompilerDescriptor);
 
+#[cfg(feature = "functions")]
+inventory::collect!(FunctionCapabilityDescriptor);
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MechSourceCode {
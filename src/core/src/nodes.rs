@@ -98,7 +98,7 @@ pub enum TokenKind {
   Caret, CarriageReturn, CarriageReturnNewLine, Colon, CodeBlock, Comma,
   Dash, DefineOperator, Digit, Dollar,
   Emoji, EmphasisSigil, Empty, Equal, EquationSigil, Error, ErrorSigil, EscapedChar, Exclamation, 
-  False, FloatLeft, FloatRight, FootnotePrefix,
+  False, FlattenOperator, FloatLeft, FloatRight, FootnotePrefix,
   GenOperator, GeneratorArrow, Grave, GraveCodeBlockSigil,
   HashTag, HighlightSigil, HttpPrefix,
   IdeaSigil, Identifier, ImgPrefix, InfoSigil, InlineCode, 
@@ -111,8 +111,8 @@ pub enum TokenKind {
   Percent, Period, Plus, PromptSigil,
   Question, QuestionSigil, Quote, QuoteSigil,
   RightAngle, RightBrace, RightBracket, RightParenthesis,
-  SectionSigil, Semicolon, Space, SpreadOperator, Slash, String, StrikeSigil, StrongSigil, SuccessSigil, SynthOperator,
-  Tab, Text, Tilde, TildeCodeBlockSigil, Title, TransitionOperator, True,
+  SectionSigil, Semicolon, Space, SplitOperator, SpreadOperator, Slash, String, StrikeSigil, StrongSigil, SuccessSigil, SynthOperator,
+  Tab, TableOperator, Text, Tilde, TildeCodeBlockSigil, Title, TransitionOperator, True,
   UnderlineSigil, Underscore,
   WarningSigil,
 }
@@ -505,6 +505,8 @@ pub struct BlockConfig {
   pub disabled: bool,
   pub hidden: bool,
   pub output: bool,
+  // Rows per page when the block's output is a table.
+  pub page_size: Option<usize>,
 }
 
 pub type Footnote = (Token, Vec<Paragraph>);
@@ -840,6 +842,7 @@ pub struct FunctionDefine {
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct FunctionMatchArm {
   pub pattern: Pattern,
+  pub guards: Vec<Expression>,
   pub expression: Expression,
 }
 
@@ -971,6 +974,10 @@ pub enum Pattern {
   TupleStruct(PatternTupleStruct),
   Tuple(PatternTuple),
   Array(PatternArray),
+  String(PatternString),
+  Map(PatternMap),
+  Set(PatternSet),
+  Table(PatternTable),
   Wildcard,
 }
 
@@ -981,6 +988,10 @@ impl Pattern {
       Pattern::TupleStruct(ts) => ts.tokens(),
       Pattern::Tuple(t) => t.tokens(),
       Pattern::Array(a) => a.tokens(),
+      Pattern::String(s) => s.tokens(),
+      Pattern::Map(m) => m.tokens(),
+      Pattern::Set(s) => s.tokens(),
+      Pattern::Table(t) => t.tokens(),
       Pattern::Wildcard => vec![],
     }
   }
@@ -1057,6 +1068,108 @@ impl PatternTuple {
   }
 }
 
+// Matches strings by a literal prefix and/or suffix, binding whatever lies
+// between them.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct PatternString {
+  pub prefix: Option<MechString>,
+  pub binding: Option<Box<Pattern>>,
+  pub suffix: Option<MechString>,
+}
+
+impl PatternString {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = vec![];
+    if let Some(prefix) = &self.prefix {
+      tokens.push(prefix.text.clone());
+    }
+    if let Some(binding) = &self.binding {
+      tokens.append(&mut binding.tokens());
+    }
+    if let Some(suffix) = &self.suffix {
+      tokens.push(suffix.text.clone());
+    }
+    tokens
+  }
+}
+
+// Matches maps that contain each key, with the key's value matching the
+// entry's pattern. Without a rest marker the map must have no other keys.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct PatternMap {
+  pub entries: Vec<PatternMapEntry>,
+  pub rest: bool,
+}
+
+impl PatternMap {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = vec![];
+    for entry in &self.entries {
+      tokens.append(&mut entry.key.tokens());
+      tokens.append(&mut entry.value.tokens());
+    }
+    tokens
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct PatternMapEntry {
+  pub key: Expression,
+  pub value: Pattern,
+}
+
+// Matches sets that contain each element. Without a rest marker the set must
+// have no other elements.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct PatternSet {
+  pub elements: Vec<Expression>,
+  pub rest: bool,
+}
+
+impl PatternSet {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = vec![];
+    for element in &self.elements {
+      tokens.append(&mut element.tokens());
+    }
+    tokens
+  }
+}
+
+// Destructures tables by column name. Without a rest marker the table must
+// have no other columns.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct PatternTable {
+  pub columns: Vec<PatternTableColumn>,
+  pub rest: bool,
+}
+
+impl PatternTable {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = vec![];
+    for column in &self.columns {
+      tokens.append(&mut column.name.tokens());
+      if let Some(pattern) = &column.pattern {
+        tokens.append(&mut pattern.tokens());
+      }
+    }
+    tokens
+  }
+}
+
+// A column with no pattern binds the column to a variable of the same name.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct PatternTableColumn {
+  pub name: Identifier,
+  pub pattern: Option<Pattern>,
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct FsmSpecification {
@@ -1111,8 +1224,8 @@ pub enum Statement {
   VariableAssign(VariableAssign),
   VariableDefine(VariableDefine),
   TupleDestructure(TupleDestructure),
-  SplitTable,     // todo
-  FlattenTable,   // todo
+  SplitTable(SplitTable),
+  FlattenTable(FlattenTable),
 }
 
 impl Statement {
@@ -1125,9 +1238,47 @@ impl Statement {
       Statement::VariableAssign(x) => x.tokens(),
       Statement::VariableDefine(x) => x.tokens(),
       Statement::TupleDestructure(x) => x.tokens(),
-      Statement::SplitTable => vec![], // todo
-      Statement::FlattenTable => vec![], // todo
+      Statement::SplitTable(x) => x.tokens(),
+      Statement::FlattenTable(x) => x.tokens(),
+    }
+  }
+}
+
+// `rows >- table` splits a table into a vector of its records, and
+// `groups >- table by key` into one table per value of the key column.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SplitTable {
+  pub name: Identifier,
+  pub source: Expression,
+  pub by: Option<Identifier>,
+}
+
+impl SplitTable {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = self.name.tokens();
+    tokens.append(&mut self.source.tokens());
+    if let Some(by) = &self.by {
+      tokens.append(&mut by.tokens());
     }
+    tokens
+  }
+}
+
+// `table -< source` flattens records, tables, vectors of either, and nested
+// record or table columns into a single table.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FlattenTable {
+  pub name: Identifier,
+  pub source: Expression,
+}
+
+impl FlattenTable {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = self.name.tokens();
+    tokens.append(&mut self.source.tokens());
+    tokens
   }
 }
 
@@ -1717,6 +1868,7 @@ pub enum Expression {
   Formula(Factor),
   FunctionCall(FunctionCall),
   FsmPipe(FsmPipe),
+  Lambda(Box<Lambda>),
   Literal(Literal),
   Match(Box<MatchExpression>),
   Range(Box<RangeExpression>),
@@ -1724,6 +1876,8 @@ pub enum Expression {
   Structure(Structure),
   SetComprehension(Box<SetComprehension>),
   MatrixComprehension(Box<MatrixComprehension>),
+  TableComprehension(Box<TableComprehension>),
+  RecordComprehension(Box<RecordComprehension>),
   Var(Var),
 }
 
@@ -1740,8 +1894,29 @@ impl Expression {
       Expression::SetComprehension(sc) => sc.tokens(),
       Expression::FsmPipe(pipe) => pipe.tokens(),
       Expression::MatrixComprehension(mc) => mc.tokens(),
+      Expression::TableComprehension(tc) => tc.tokens(),
+      Expression::RecordComprehension(rc) => rc.tokens(),
       Expression::FunctionCall(func_call) => func_call.tokens(),
+      Expression::Lambda(lambda) => lambda.tokens(),
+    }
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Lambda {
+  pub params: Vec<Var>,
+  pub body: Expression,
+}
+
+impl Lambda {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = vec![];
+    for param in &self.params {
+      tokens.append(&mut param.tokens());
     }
+    tokens.append(&mut self.body.tokens());
+    tokens
   }
 }
 
@@ -1800,6 +1975,40 @@ impl MatrixComprehension {
   }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct TableComprehension {
+  pub record: Record,
+  pub qualifiers: Vec<ComprehensionQualifier>,
+}
+
+impl TableComprehension {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = self.record.tokens();
+    for qualifier in &self.qualifiers {
+      tokens.append(&mut qualifier.tokens());
+    }
+    tokens
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RecordComprehension {
+  pub record: Record,
+  pub qualifiers: Vec<ComprehensionQualifier>,
+}
+
+impl RecordComprehension {
+  pub fn tokens(&self) -> Vec<Token> {
+    let mut tokens = self.record.tokens();
+    for qualifier in &self.qualifiers {
+      tokens.append(&mut qualifier.tokens());
+    }
+    tokens
+  }
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum ComprehensionQualifier {
@@ -2374,6 +2583,16 @@ pub enum LogicOp {
   Xor,
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum BitsOp {
+  And,
+  Or,
+  Xor,
+  ShiftLeft,
+  ShiftRight,
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum FormulaOperator {
@@ -2381,6 +2600,7 @@ pub enum FormulaOperator {
   Comparison(ComparisonOp),
   Power(PowerOp),
   Logic(LogicOp),
+  Bits(BitsOp),
   MulDiv(MulDivOp),
   Vec(VecOp),
   Table(TableOp),
@@ -2396,6 +2616,8 @@ pub enum TableOp {
   FullOuterJoin	,
   LeftSemiJoin,  
   LeftAntiJoin,
+  SortBy,
+  Filter,
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -99,7 +99,7 @@ impl CompileConst for Value {
       },
       Value::EmptyKind(k) => ctx.compile_const(&[], k.clone())?,
       Value::Empty => ctx.compile_const(&[], ValueKind::Empty)?,
-      x => todo!("CompileConst not implemented for {:?}", x),
+      x => return Err(MechError::new(UnsupportedValueKindError { kind: x.kind() }, None).with_compiler_loc()),
     };
     Ok(reg)
   }
@@ -356,27 +356,67 @@ where
 impl CompileConst for MechRecord {
   fn compile_const(&self, ctx: &mut CompileCtx) -> MResult<u32> {
     let mut payload = Vec::<u8>::new();
+    self.write_le(&mut payload);
+    ctx.compile_const(&payload, self.kind())
+  }
+}
 
+#[cfg(feature = "record")]
+impl ConstElem for MechRecord {
+  fn write_le(&self, out: &mut Vec<u8>) {
     // write the number of columns
-    payload.write_u32::<LittleEndian>(self.cols as u32)?;
+    out.write_u32::<LittleEndian>(self.cols as u32).expect("write record cols");
 
     // write each column: (name hash, value kind, data)
     for (col_id, value) in self.data.iter() {
       // column name hash
-      payload.write_u64::<LittleEndian>(*col_id)?;
+      out.write_u64::<LittleEndian>(*col_id).expect("write record column id");
       // value kind
       let value_kind = value.kind();
-      value_kind.write_le(&mut payload);
+      value_kind.write_le(out);
       // value data
-      value.write_le(&mut payload);
+      value.write_le(out);
     }
 
     // Write the field name strings into the payload
     for (_col_id, col_name) in self.field_names.iter() {
-      col_name.write_le(&mut payload);
+      col_name.write_le(out);
     }
-    ctx.compile_const(&payload, self.kind())
   }
+  fn from_le(data: &[u8]) -> Self {
+    let mut cursor = Cursor::new(data);
+    let cols = cursor.read_u32::<LittleEndian>().expect("read record cols") as usize;
+    let mut fields: Vec<(u64, Value)> = Vec::with_capacity(cols);
+    for _ in 0..cols {
+      let col_id = cursor.read_u64::<LittleEndian>().expect("read record column id");
+      // skip the kind, the value repeats it
+      let kind = ValueKind::from_le(&data[cursor.position() as usize..]);
+      let mut tmp = Vec::new();
+      kind.write_le(&mut tmp);
+      cursor.set_position(cursor.position() + tmp.len() as u64);
+      let pos = cursor.position() as usize;
+      let value = Value::from_le(&data[pos..]);
+      let mut tmp = Vec::new();
+      value.write_le(&mut tmp);
+      cursor.set_position(pos as u64 + tmp.len() as u64);
+      fields.push((col_id, value));
+    }
+    // The names follow in no particular order, so match them up by hash
+    let mut names: HashMap<u64, String> = HashMap::new();
+    for _ in 0..cols {
+      let name = String::from_le(&data[cursor.position() as usize..]);
+      let mut tmp = Vec::new();
+      name.write_le(&mut tmp);
+      cursor.set_position(cursor.position() + tmp.len() as u64);
+      names.insert(hash_str(&name), name);
+    }
+    MechRecord::from_vec(fields.into_iter().map(|(id, value)| {
+      let name = names.remove(&id).unwrap_or_default();
+      ((id, name), value)
+    }).collect())
+  }
+  fn value_kind(&self) -> ValueKind { self.kind() }
+  fn align() -> u8 { 8 }
 }
 
 #[cfg(feature = "enum")]
@@ -1265,7 +1305,7 @@ impl ConstElem for MechTable {
       // Value kind
       vk.write_le(out);
       // Column data matrix
-      col_data.write_le(out);
+      col_data.to_matrix().write_le(out);
       // Column name
       if let Some(name) = self.col_names.get(col_id) {
         name.write_le(out);
@@ -1287,7 +1327,7 @@ impl ConstElem for MechTable {
     let rows = cursor.read_u32::<LittleEndian>().expect("read rows") as usize;
     let cols = cursor.read_u32::<LittleEndian>().expect("read cols") as usize;
 
-    let mut data_map: IndexMap<u64, (ValueKind, Matrix<Value>)> = IndexMap::new();
+    let mut data_map: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
     let mut col_names: HashMap<u64, String> = HashMap::new();
 
     // Decode each column
@@ -1312,7 +1352,8 @@ impl ConstElem for MechTable {
       name.write_le(&mut tmp);
       cursor.set_position(cursor.position() + tmp.len() as u64);
 
-      data_map.insert(col_id, (kind, matrix));
+      let column = Column::from_values(&kind, matrix.as_vec());
+      data_map.insert(col_id, (kind, column));
       col_names.insert(col_id, name);
     }
 
@@ -1332,7 +1373,7 @@ impl CompileConst for MechTable {
     for (col_id, (vk, col_data)) in &self.data {
       payload.write_u64::<LittleEndian>(*col_id)?;
       vk.write_le(&mut payload);
-      col_data.write_le(&mut payload);
+      col_data.to_matrix().write_le(&mut payload);
 
       if let Some(name) = self.col_names.get(col_id) {
         name.write_le(&mut payload);
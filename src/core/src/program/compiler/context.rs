@@ -96,7 +96,7 @@ impl CompileCtx {
   }
   
   pub fn compile_const(&mut self, bytes: &[u8], value_kind: ValueKind) -> MResult<u32> {
-    let type_id = self.types.get_or_intern(&value_kind);
+    let type_id = self.types.get_or_intern(&value_kind)?;
     let align = value_kind.align();
     let next_blob_len = self.const_blob.len() as u64;
     let padded_off = align_up(next_blob_len, align as u64);
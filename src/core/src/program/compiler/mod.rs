@@ -16,11 +16,11 @@ pub use self::context::*;
 
 pub type Register = u32;
 
-pub fn encode_value_kind(ts: &mut TypeSection, vk: &ValueKind) -> (TypeTag, Vec<u8>) {
+pub fn encode_value_kind(ts: &mut TypeSection, vk: &ValueKind) -> MResult<(TypeTag, Vec<u8>)> {
   let mut b = Vec::new();
   let tag = match vk {
     ValueKind::Kind(kind) => {
-      let kind_id = ts.get_or_intern(kind);
+      let kind_id = ts.get_or_intern(kind)?;
       b.write_u32::<LittleEndian>(kind_id).unwrap();
       TypeTag::Kind
     },
@@ -40,7 +40,7 @@ pub fn encode_value_kind(ts: &mut TypeSection, vk: &ValueKind) -> (TypeTag, Vec<
     ValueKind::None => TypeTag::None,
 
     ValueKind::Matrix(elem, dims) => {
-      let elem_id = ts.get_or_intern(elem);
+      let elem_id = ts.get_or_intern(elem)?;
       b.write_u32::<LittleEndian>(elem_id).unwrap();
       b.write_u32::<LittleEndian>(dims.len() as u32).unwrap();
       for &d in dims { b.write_u32::<LittleEndian>(d as u32).unwrap(); }
@@ -80,15 +80,15 @@ pub fn encode_value_kind(ts: &mut TypeSection, vk: &ValueKind) -> (TypeTag, Vec<
         let name_bytes = name.as_bytes();
         b.write_u32::<LittleEndian>(name_bytes.len() as u32).unwrap();
         b.extend_from_slice(name_bytes);
-        let tid = ts.get_or_intern(ty);
+        let tid = ts.get_or_intern(ty)?;
         b.write_u32::<LittleEndian>(tid).unwrap();
       }
       TypeTag::Record
     }
 
     ValueKind::Map(k,v) => {
-      let kid = ts.get_or_intern(k);
-      let vid = ts.get_or_intern(v);
+      let kid = ts.get_or_intern(k)?;
+      let vid = ts.get_or_intern(v)?;
       b.write_u32::<LittleEndian>(kid).unwrap();
       b.write_u32::<LittleEndian>(vid).unwrap();
       TypeTag::Map
@@ -108,7 +108,7 @@ pub fn encode_value_kind(ts: &mut TypeSection, vk: &ValueKind) -> (TypeTag, Vec<
         let name_b = name.as_bytes();
         b.write_u32::<LittleEndian>(name_b.len() as u32).unwrap();
         b.extend_from_slice(name_b);
-        let tid = ts.get_or_intern(ty);
+        let tid = ts.get_or_intern(ty)?;
         b.write_u32::<LittleEndian>(tid).unwrap();
       }
       b.write_u32::<LittleEndian>(*pk_col as u32).unwrap();
@@ -118,20 +118,20 @@ pub fn encode_value_kind(ts: &mut TypeSection, vk: &ValueKind) -> (TypeTag, Vec<
     ValueKind::Tuple(elems) => {
       b.write_u32::<LittleEndian>(elems.len() as u32).unwrap();
       for t in elems {
-        let tid = ts.get_or_intern(t);
+        let tid = ts.get_or_intern(t)?;
         b.write_u32::<LittleEndian>(tid).unwrap();
       }
       TypeTag::Tuple
     }
 
     ValueKind::Reference(inner) => {
-      let id = ts.get_or_intern(inner);
+      let id = ts.get_or_intern(inner)?;
       b.write_u32::<LittleEndian>(id).unwrap();
       TypeTag::Reference
     }
 
     ValueKind::Set(elem, max) => {
-      let id = ts.get_or_intern(elem);
+      let id = ts.get_or_intern(elem)?;
       b.write_u32::<LittleEndian>(id).unwrap();
       match max {
         Some(m) => { b.push(1); use byteorder::WriteBytesExt; b.write_u32::<LittleEndian>(*m as u32).unwrap(); }
@@ -141,10 +141,31 @@ pub fn encode_value_kind(ts: &mut TypeSection, vk: &ValueKind) -> (TypeTag, Vec<
     }
 
     ValueKind::Option(inner) => {
-      let id = ts.get_or_intern(inner);
+      let id = ts.get_or_intern(inner)?;
       b.write_u32::<LittleEndian>(id).unwrap();
       TypeTag::OptionT
     }
+
+    // Lambdas carry code and captured bindings, which the bytecode format
+    // does not have a representation for yet.
+    ValueKind::Lambda(_) => {
+      return Err(MechError::new(UnsupportedValueKindError { kind: vk.clone() }, None).with_compiler_loc());
+    }
   };
-  (tag, b)
+  Ok((tag, b))
+}
+
+#[derive(Debug, Clone)]
+pub struct UnsupportedValueKindError {
+  pub kind: ValueKind,
+}
+
+impl MechErrorKind for UnsupportedValueKindError {
+  fn name(&self) -> &str { "UnsupportedValueKind" }
+  fn message(&self) -> String {
+    match self.kind {
+      ValueKind::Lambda(_) => "Lambdas cannot be compiled yet".to_string(),
+      _ => format!("Values of kind {} cannot be compiled yet", self.kind),
+    }
+  }
 }
\ No newline at end of file
@@ -232,6 +232,13 @@ pub enum FeatureKind {
   Concat,
 
   Functions, Formulas,
+
+  TableComprehensions, RecordComprehensions,
+
+  BitAnd, BitOr, BitXor, BitNot,
+  ShiftLeft, ShiftRight, RotateLeft, RotateRight,
+  PopCount, LeadingZeros, TrailingZeros,
+  BitGet, BitSet, BitClear,
   Custom = 0xFFFF,
 }
 
@@ -337,6 +344,22 @@ impl FeatureKind {
       FeatureKind::Functions => "functions".to_string(),
       FeatureKind::Formulas => "formulas".to_string(),
       FeatureKind::Concat => "string_concat".to_string(),
+      FeatureKind::TableComprehensions => "table_comprehensions".to_string(),
+      FeatureKind::RecordComprehensions => "record_comprehensions".to_string(),
+      FeatureKind::BitAnd => "bits_and".to_string(),
+      FeatureKind::BitOr => "bits_or".to_string(),
+      FeatureKind::BitXor => "bits_xor".to_string(),
+      FeatureKind::BitNot => "bits_not".to_string(),
+      FeatureKind::ShiftLeft => "bits_shift_left".to_string(),
+      FeatureKind::ShiftRight => "bits_shift_right".to_string(),
+      FeatureKind::RotateLeft => "bits_rotate_left".to_string(),
+      FeatureKind::RotateRight => "bits_rotate_right".to_string(),
+      FeatureKind::PopCount => "bits_popcount".to_string(),
+      FeatureKind::LeadingZeros => "bits_leading_zeros".to_string(),
+      FeatureKind::TrailingZeros => "bits_trailing_zeros".to_string(),
+      FeatureKind::BitGet => "bits_get".to_string(),
+      FeatureKind::BitSet => "bits_set".to_string(),
+      FeatureKind::BitClear => "bits_clear".to_string(),
       FeatureKind::Custom => "custom".to_string(),
     }
   }
@@ -429,14 +452,14 @@ impl TypeSection {
     Self { interner: HashMap::new(), entries: Vec::new() }
   }
 
-  pub fn get_or_intern(&mut self, vk: &ValueKind) -> TypeId {
-    if let Some(id) = self.interner.get(vk) { return *id; }
+  pub fn get_or_intern(&mut self, vk: &ValueKind) -> MResult<TypeId> {
+    if let Some(id) = self.interner.get(vk) { return Ok(*id); }
     // recursively intern children and build payload
-    let (tag, mut bytes) = encode_value_kind(self, vk);
+    let (tag, mut bytes) = encode_value_kind(self, vk)?;
     let id = self.entries.len() as u32;
     self.entries.push(TypeEntry { tag, bytes });
     self.interner.insert(vk.clone(), id);
-    id
+    Ok(id)
   }
 
   pub fn write_to(&self, w: &mut impl Write) -> MResult<()> {
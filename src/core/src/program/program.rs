@@ -460,6 +460,14 @@ impl ParsedProgram {
           let table = MechTable::from_le(&data);
           Value::Table(Ref::new(table))
         }
+        #[cfg(feature = "record")]
+        TypeTag::Record => {
+          if data.len() < 4 {
+            return Err(MechError::new(ConstantTooShortError { type_name: "record" }, None).with_compiler_loc());
+          }
+          let record = MechRecord::from_le(&data);
+          Value::Record(Ref::new(record))
+        }
         _ => {
           return Err(
             MechError::new(
@@ -749,6 +757,18 @@ impl DecodedInstr {
       _ => None,
     }
   }
+
+  pub fn fxn_id(&self) -> Option<u64> {
+    match self {
+      DecodedInstr::NullOp { fxn_id, .. } |
+      DecodedInstr::UnOp { fxn_id, .. } |
+      DecodedInstr::BinOp { fxn_id, .. } |
+      DecodedInstr::TernOp { fxn_id, .. } |
+      DecodedInstr::QuadOp { fxn_id, .. } |
+      DecodedInstr::VarArg { fxn_id, .. } => Some(*fxn_id),
+      _ => None,
+    }
+  }
 }
 
 fn decode_instructions(mut cur: Cursor<&[u8]>) -> MResult<Vec<DecodedInstr>> {
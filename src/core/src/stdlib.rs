@@ -30,7 +30,7 @@ macro_rules! compile_register_brrw {
       let addr = $reg.addr();
       let reg = $ctx.alloc_register_for_ptr(addr);
       let borrow = $reg.borrow();
-      let const_id = borrow.compile_const($ctx).unwrap();
+      let const_id = borrow.compile_const($ctx)?;
       $ctx.emit_const_load(reg, const_id);
       reg
     }
@@ -43,7 +43,7 @@ macro_rules! compile_register {
     {
       let addr = $reg.addr();
       let reg = $ctx.alloc_register_for_ptr(addr);
-      let const_id = $reg.compile_const($ctx).unwrap();
+      let const_id = $reg.compile_const($ctx)?;
       $ctx.emit_const_load(reg, const_id);
       reg
     }
@@ -56,7 +56,7 @@ macro_rules! compile_register_mat {
     {
       let addr = $reg.addr();
       let reg = $ctx.alloc_register_for_ptr(addr);
-      let const_id = $reg.compile_const_mat($ctx).unwrap();
+      let const_id = $reg.compile_const_mat($ctx)?;
       $ctx.emit_const_load(reg, const_id);
       reg
     }
@@ -197,6 +197,27 @@ macro_rules! compile_varop {
   };
 }
 
+// Machine functions that keep their output and arguments as values, rather
+// than typed references, allocate their registers from those values. An
+// argument held in a mutable variable is then read again on every solve.
+#[macro_export]
+macro_rules! compile_value_varop {
+  ($name:expr, $out:expr, $args:expr, $ctx:ident, $feature_flag:expr) => {
+    let out = $out;
+    let mut registers = vec![compile_register!(out, $ctx)];
+    for arg in $args.iter() {
+      registers.push(compile_register!(arg, $ctx));
+    }
+    $ctx.features.insert($feature_flag);
+    $ctx.emit_varop(
+      hash_str(&$name),
+      registers[0],
+      (&registers[1..]).to_vec(),
+    );
+    return Ok(registers[0])
+  };
+}
+
 #[macro_export]
 macro_rules! register_fxn_descriptor_inner_logic {
   // single type
@@ -4,14 +4,31 @@ use indexmap::map::*;
 // Record ------------------------------------------------------------------
 
 #[cfg(feature = "record")]
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct MechRecord {
   pub cols: usize,
   pub kinds: Vec<ValueKind>,
   pub data: IndexMap<u64,Value>,
   pub field_names: HashMap<u64,String>,
+  // The table and row this record was read from, so field updates can be
+  // written back to the table's columns.
+  #[cfg(feature = "table")]
+  pub table_row: Option<(Ref<MechTable>,Ref<usize>)>,
 }
 
+#[cfg(feature = "record")]
+impl PartialEq for MechRecord {
+  fn eq(&self, other: &Self) -> bool {
+    self.cols == other.cols &&
+    self.kinds == other.kinds &&
+    self.data == other.data &&
+    self.field_names == other.field_names
+  }
+}
+
+#[cfg(feature = "record")]
+impl Eq for MechRecord {}
+
 #[cfg(feature = "record")]
 impl MechRecord {
 
@@ -112,6 +129,8 @@ impl MechRecord {
       kinds,
       data,
       field_names,
+      #[cfg(feature = "table")]
+      table_row: None,
     }
   }
 
@@ -123,7 +142,7 @@ impl MechRecord {
       data.insert(k,v);
     }
     let kinds = data.iter().map(|(_,v)| v.kind()).collect();
-    MechRecord{cols: data.len(), kinds, data, field_names}
+    MechRecord{cols: data.len(), kinds, data, field_names, #[cfg(feature = "table")] table_row: None}
   }
 
   pub fn from_kind(fields: &Vec<(String,ValueKind)>) -> MResult<MechRecord> {
@@ -135,7 +154,7 @@ impl MechRecord {
       data.insert(col_id, Value::from_kind(knd));
     }
     let kinds = data.iter().map(|(_,v)| v.kind()).collect();
-    Ok(MechRecord{cols: data.len(), kinds, data, field_names})
+    Ok(MechRecord{cols: data.len(), kinds, data, field_names, #[cfg(feature = "table")] table_row: None})
   }
 
   pub fn insert_field(&mut self, key: u64, value: Value) {
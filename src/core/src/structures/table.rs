@@ -6,6 +6,355 @@ use std::cmp::Ordering;
 use nalgebra::{DMatrix, DVector, RowDVector};
 use std::collections::{HashMap, HashSet};
 
+// Tables with more rows than this are shown as their first and last
+// TABLE_PREVIEW_ROWS rows.
+pub const TABLE_MAX_ROWS: usize = 20;
+pub const TABLE_PREVIEW_ROWS: usize = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnSummary {
+  pub name: String,
+  pub kind: ValueKind,
+  pub min: Option<Value>,
+  pub max: Option<Value>,
+  pub nulls: usize,
+}
+
+fn summary_order(a: &Value, b: &Value) -> Option<Ordering> {
+  match (a, b) {
+    #[cfg(feature = "string")]
+    (Value::String(a), Value::String(b)) => Some(a.borrow().cmp(&b.borrow())),
+    #[cfg(feature = "bool")]
+    (Value::Bool(a), Value::Bool(b)) => Some(a.borrow().cmp(&b.borrow())),
+    (a, b) => match (a.as_f64(), b.as_f64()) {
+      (Ok(a), Ok(b)) => a.borrow().partial_cmp(&b.borrow()),
+      _ => None,
+    },
+  }
+}
+
+// Column ------------------------------------------------------------------
+
+// A table column stores its cells unboxed in a typed matrix whenever the
+// column kind is a scalar kind. Columns of any other kind (options, records,
+// `*`, ...) fall back to a matrix of values. Columns are always DVectors.
+
+macro_rules! impl_table_column {
+  ($($variant:ident, $ty:ty, $feature:tt, $hash_key:path);+ $(;)?) => {
+    paste!{
+
+      #[cfg(feature = "table")]
+      #[derive(Clone, Debug)]
+      pub enum Column {
+        $(
+          #[cfg(feature = $feature)]
+          $variant(Matrix<$ty>),
+        )+
+        Value(Matrix<Value>),
+      }
+
+      #[cfg(feature = "table")]
+      impl Column {
+
+        pub fn from_values(kind: &ValueKind, values: Vec<Value>) -> Column {
+          match kind {
+            $(
+              #[cfg(feature = $feature)]
+              ValueKind::$variant => {
+                let cells: Option<Vec<$ty>> = values.iter().map(|v| match v {
+                  Value::$variant(x) => Some(x.borrow().clone()),
+                  _ => None,
+                }).collect();
+                if let Some(cells) = cells {
+                  return Column::$variant(Matrix::DVector(Ref::new(DVector::from_vec(cells))));
+                }
+              }
+            )+
+            _ => (),
+          }
+          Column::Value(Matrix::DVector(Ref::new(DVector::from_vec(values))))
+        }
+
+        pub fn empty(kind: &ValueKind, rows: usize) -> Column {
+          match kind {
+            $(
+              #[cfg(feature = $feature)]
+              ValueKind::$variant => Column::$variant(Matrix::DVector(Ref::new(DVector::from_element(rows, <$ty>::default())))),
+            )+
+            _ => Column::Value(Matrix::DVector(Ref::new(DVector::from_element(rows, Value::Empty)))),
+          }
+        }
+
+        pub fn cell_kind(&self) -> ValueKind {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(_) => ValueKind::$variant,
+            )+
+            Column::Value(_) => ValueKind::Any,
+          }
+        }
+
+        pub fn len(&self) -> usize {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(m) => m.rows(),
+            )+
+            Column::Value(m) => m.rows(),
+          }
+        }
+
+        pub fn is_empty(&self) -> bool {
+          self.len() == 0
+        }
+
+        // 1-based, like Matrix::index1d. Boxes the cell.
+        pub fn index1d(&self, ix: usize) -> Value {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(m) => Value::$variant(Ref::new(m.index1d(ix))),
+            )+
+            Column::Value(m) => m.index1d(ix),
+          }
+        }
+
+        // 0-based, like Matrix::set_index1d.
+        pub fn set_index1d(&self, ix: usize, value: &Value) -> MResult<()> {
+          match (self, value) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(m), Value::$variant(x)) => m.set_index1d(ix, x.borrow().clone()),
+            )+
+            (Column::Value(m), value) => m.set_index1d(ix, value.clone()),
+            (column, value) => return Err(MechError::new(
+              ColumnCellKindMismatchError { expected_kind: column.cell_kind(), actual_kind: value.kind() },
+              None
+            ).with_compiler_loc()),
+          }
+          Ok(())
+        }
+
+        // Overwrites the contents of `dest` with the cell at row `ix` (1-based)
+        // without replacing its Ref. Returns false when `dest` is not a scalar
+        // of the column's kind.
+        pub fn copy_cell_into(&self, ix: usize, dest: &Value) -> bool {
+          match (self, dest) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(m), Value::$variant(x)) => {
+                *x.borrow_mut() = m.index1d(ix);
+                true
+              }
+            )+
+            _ => false,
+          }
+        }
+
+        // Compares row `ix` of this column with row `jx` of `other` (both 1-based).
+        pub fn cell_eq(&self, ix: usize, other: &Column, jx: usize) -> bool {
+          match (self, other) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(Matrix::DVector(a)), Column::$variant(Matrix::DVector(b))) => a.borrow()[ix - 1] == b.borrow()[jx - 1],
+            )+
+            (lhs, rhs) => lhs.index1d(ix) == rhs.index1d(jx),
+          }
+        }
+
+        // Orders row `ix` of this column against row `jx` of `other` (both
+        // 1-based). Cells of different kinds are unordered.
+        pub fn cell_cmp(&self, ix: usize, other: &Column, jx: usize) -> Option<Ordering> {
+          match (self, other) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(Matrix::DVector(a)), Column::$variant(Matrix::DVector(b))) => a.borrow()[ix - 1].partial_cmp(&b.borrow()[jx - 1]),
+            )+
+            _ => None,
+          }
+        }
+
+        // Hashes row `ix` (1-based) consistently with the hash of its boxed value.
+        pub fn hash_cell<H: Hasher>(&self, ix: usize, state: &mut H) {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(m) => $hash_key(m.index1d(ix)).hash(state),
+            )+
+            Column::Value(m) => m.index1d(ix).hash(state),
+          }
+        }
+
+        // Copies row `jx` of `src` into row `ix` of this column (both 1-based).
+        pub fn copy_cell(&self, ix: usize, src: &Column, jx: usize) -> MResult<()> {
+          match (self, src) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(out), Column::$variant(src)) => {
+                out.set_index1d(ix - 1, src.index1d(jx));
+                Ok(())
+              }
+            )+
+            _ => self.set_index1d(ix - 1, &src.index1d(jx)),
+          }
+        }
+
+        // Gathers the given rows (1-based) of this column into `out`, resizing
+        // it in place so anything holding its matrix sees the new contents.
+        pub fn copy_rows(&self, rows: &[usize], out: &mut Column) -> MResult<()> {
+          out.resize(rows.len())?;
+          match (self, &*out) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(Matrix::DVector(src)), Column::$variant(Matrix::DVector(dst))) => {
+                let src = src.borrow();
+                let mut dst = dst.borrow_mut();
+                for (i, row) in rows.iter().enumerate() {
+                  dst[i] = src[row - 1].clone();
+                }
+              }
+            )+
+            _ => {
+              for (i, row) in rows.iter().enumerate() {
+                out.copy_cell(i + 1, self, *row)?;
+              }
+            }
+          }
+          Ok(())
+        }
+
+        pub fn resize(&mut self, rows: usize) -> MResult<()> {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(m) => m.resize_vertically(rows, <$ty>::default()),
+            )+
+            Column::Value(m) => m.resize_vertically(rows, Value::Empty),
+          }
+        }
+
+        pub fn push(&mut self, value: &Value) -> MResult<()> {
+          match (self, value) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(m), Value::$variant(x)) => m.push(x.borrow().clone()),
+            )+
+            (Column::Value(m), value) => m.push(value.clone()),
+            (column, value) => Err(MechError::new(
+              ColumnCellKindMismatchError { expected_kind: column.cell_kind(), actual_kind: value.kind() },
+              None
+            ).with_compiler_loc()),
+          }
+        }
+
+        pub fn append(&mut self, other: &Column) -> MResult<()> {
+          if other.is_empty() {
+            return Ok(());
+          }
+          match (self, other) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(lhs), Column::$variant(rhs)) => lhs.append(rhs),
+            )+
+            (Column::Value(lhs), rhs) => lhs.append(&rhs.to_matrix()),
+            (lhs, rhs) => {
+              for ix in 1..=rhs.len() {
+                lhs.push(&rhs.index1d(ix))?;
+              }
+              Ok(())
+            }
+          }
+        }
+
+        pub fn to_value(&self) -> Value {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(m) => Value::[<Matrix $variant>](m.clone()),
+            )+
+            Column::Value(m) => Value::MatrixValue(m.clone()),
+          }
+        }
+
+        // Boxes every cell. Used at the edges (printing, bytecode).
+        pub fn as_vec(&self) -> Vec<Value> {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(m) => m.as_vec().into_iter().map(|x| Value::$variant(Ref::new(x))).collect(),
+            )+
+            Column::Value(m) => m.as_vec(),
+          }
+        }
+
+        pub fn to_matrix(&self) -> Matrix<Value> {
+          match self {
+            Column::Value(m) => m.clone(),
+            column => Matrix::DVector(Ref::new(DVector::from_vec(column.as_vec()))),
+          }
+        }
+
+        pub fn size_of(&self) -> usize {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(m) => m.size_of(),
+            )+
+            Column::Value(m) => m.size_of(),
+          }
+        }
+      }
+
+      #[cfg(feature = "table")]
+      impl PartialEq for Column {
+        fn eq(&self, other: &Self) -> bool {
+          match (self, other) {
+            $(
+              #[cfg(feature = $feature)]
+              (Column::$variant(lhs), Column::$variant(rhs)) => lhs == rhs,
+            )+
+            (Column::Value(lhs), Column::Value(rhs)) => lhs == rhs,
+            (lhs, rhs) => lhs.len() == rhs.len() && (1..=lhs.len()).all(|ix| lhs.index1d(ix) == rhs.index1d(ix)),
+          }
+        }
+      }
+
+      #[cfg(feature = "table")]
+      impl Hash for Column {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+          match self {
+            $(
+              #[cfg(feature = $feature)]
+              Column::$variant(m) => m.as_vec().into_iter().for_each(|x| $hash_key(x).hash(state)),
+            )+
+            Column::Value(m) => m.hash(state),
+          }
+        }
+      }
+    }
+  }
+}
+
+impl_table_column!(
+  Bool, bool, "bool", std::convert::identity;
+  U8, u8, "u8", std::convert::identity;
+  U16, u16, "u16", std::convert::identity;
+  U32, u32, "u32", std::convert::identity;
+  U64, u64, "u64", std::convert::identity;
+  U128, u128, "u128", std::convert::identity;
+  I8, i8, "i8", std::convert::identity;
+  I16, i16, "i16", std::convert::identity;
+  I32, i32, "i32", std::convert::identity;
+  I64, i64, "i64", std::convert::identity;
+  I128, i128, "i128", std::convert::identity;
+  F32, f32, "f32", f32::to_bits;
+  F64, f64, "f64", f64::to_bits;
+  String, String, "string", std::convert::identity;
+  C64, C64, "complex", std::convert::identity;
+  R64, R64, "rational", std::convert::identity;
+);
+
 // Table ------------------------------------------------------------------
 
 #[cfg(feature = "table")]
@@ -13,7 +362,7 @@ use std::collections::{HashMap, HashSet};
 pub struct MechTable {
   pub rows: usize,
   pub cols: usize,
-  pub data: IndexMap<u64,(ValueKind,Matrix<Value>)>,
+  pub data: IndexMap<u64,(ValueKind,Column)>,
   pub col_names: HashMap<u64,String>,
 }
 
@@ -44,7 +393,7 @@ impl PartialEq for MechTable {
       if self_kind != other_kind {
         return false;
       }
-      // Compare Matrix element-wise
+      // Compare columns element-wise
       if self_column != other_column {
         return false;
       }
@@ -93,12 +442,12 @@ impl MechTable {
       }
     }
 
-    let data: IndexMap<u64, (ValueKind, Matrix<Value>)> = col_data
+    let data: IndexMap<u64, (ValueKind, Column)> = col_data
       .into_iter()
       .map(|(col_id, values)| {
         let kind = kinds[&col_id].clone();
-        let matrix = Matrix::DVector(Ref::new(DVector::from_vec(values)));
-        (col_id, (kind, matrix))
+        let column = Column::from_values(&kind, values);
+        (col_id, (kind, column))
       })
       .collect();
 
@@ -111,9 +460,9 @@ impl MechTable {
         let mut data = IndexMap::new();
         let mut col_names = HashMap::new();
         for (col_id, col_kind) in &tbl {
-          let matrix = Matrix::DVector(Ref::new(DVector::from_vec(vec![Value::Empty; sze])));
+          let column = Column::empty(col_kind, sze);
           col_names.insert(hash_str(col_id), col_id.clone());
-          data.insert(hash_str(&col_id), (col_kind.clone(), matrix));
+          data.insert(hash_str(&col_id), (col_kind.clone(), column));
         }
         Ok(MechTable { rows: sze, cols: tbl.len(), data, col_names })
       }
@@ -131,11 +480,8 @@ impl MechTable {
   pub fn empty_table(&self, rows: usize) -> MechTable {
     let mut data = IndexMap::new();
     for col in self.data.iter() {
-      let (key, (kind, matrix)) = col;
-      // make a new vector the length of ix with values Value::Empty
-      let elements = vec![Value::Empty; rows];
-      let new_matrix = Matrix::DVector(Ref::new(DVector::from_vec(elements)));
-      data.insert(*key, (kind.clone(), new_matrix));
+      let (key, (kind, _column)) = col;
+      data.insert(*key, (kind.clone(), Column::empty(kind, rows)));
     }
     MechTable { rows: rows, cols: self.cols, data, col_names: self.col_names.clone() }
   }
@@ -238,15 +584,15 @@ impl MechTable {
   pub fn append_table(&mut self, other: &MechTable) -> MResult<()> {
     self.check_table_schema(other)?;
 
-    for (&col_id, (_, other_matrix)) in &other.data {
-      let (_, self_matrix) = self.data.get_mut(&col_id).ok_or_else(|| 
+    for (&col_id, (_, other_column)) in &other.data {
+      let (_, self_column) = self.data.get_mut(&col_id).ok_or_else(|| 
         MechError::new(
           TableColumnNotFoundError { column_id: col_id },
           None
         ).with_compiler_loc()
       )?;
 
-      self_matrix.append(other_matrix).map_err(|err| 
+      self_column.append(other_column).map_err(|err| 
         MechError::new(
           MatrixAppendToTableError { column_id: col_id },
           None
@@ -262,10 +608,10 @@ impl MechTable {
     // Validate schema (this includes column count, types, and optional name checks)
     self.check_record_schema(&record)?;
 
-    // Append each value to the corresponding column in the matrix
+    // Append each value to the corresponding column
     for (&col_id, value) in &record.data {
-      if let Some((_kind, column_matrix)) = self.data.get_mut(&col_id) {
-        let result = column_matrix.push(value.clone());
+      if let Some((_kind, column)) = self.data.get_mut(&col_id) {
+        column.push(value)?;
       } else {
         continue;
       }
@@ -283,8 +629,8 @@ impl MechTable {
     }
 
     let mut data: IndexMap<u64, Value> = IndexMap::new();
-    data = self.data.iter().map(|(key, (kind, matrix))| {
-      let value = matrix.index1d(ix);
+    data = self.data.iter().map(|(key, (kind, column))| {
+      let value = column.index1d(ix);
       let name = self.col_names.get(key).unwrap();
       (hash_str(name), value.clone())
     }).collect();
@@ -294,11 +640,49 @@ impl MechTable {
 
     let mut field_names = self.col_names.clone();
    
-    Some(MechRecord{cols: self.cols, kinds, data, field_names})
+    Some(MechRecord{cols: self.cols, kinds, data, field_names, table_row: None})
   }
 
+  // Tables longer than TABLE_MAX_ROWS show their first and last
+  // TABLE_PREVIEW_ROWS rows, a marker counting the rows in between, and a
+  // summary of each column.
   #[cfg(feature = "pretty_print")]
   pub fn to_html(&self) -> String {
+    if self.rows <= TABLE_MAX_ROWS {
+      return self.html_table(1..self.rows + 1, 0, 0..0, false);
+    }
+    let hidden = self.rows - 2 * TABLE_PREVIEW_ROWS;
+    self.html_table(1..TABLE_PREVIEW_ROWS + 1, hidden, self.rows - TABLE_PREVIEW_ROWS + 1..self.rows + 1, true)
+  }
+
+  // Renders page `page` (0-based) of `page_size` rows, followed by a pager
+  // whose buttons carry the page they lead to in `data-page`.
+  #[cfg(feature = "pretty_print")]
+  pub fn to_html_page(&self, page: usize, page_size: usize) -> String {
+    let page_size = page_size.max(1);
+    let pages = self.rows.div_ceil(page_size).max(1);
+    let page = page.min(pages - 1);
+    let first = page * page_size + 1;
+    let last = (first + page_size - 1).min(self.rows);
+    let mut html = self.html_table(first..last + 1, 0, 0..0, self.rows > page_size);
+    if pages > 1 {
+      let button = |class: &str, label: &str, target: Option<usize>| match target {
+        Some(target) => format!("<button class=\"{}\" data-page=\"{}\">{}</button>", class, target, label),
+        None => format!("<button class=\"{}\" disabled>{}</button>", class, label),
+      };
+      html.push_str(&format!(
+        "<div class=\"mech-table-pager\" data-page=\"{}\" data-pages=\"{}\">{}<span class=\"mech-table-page-label\">Rows {}–{} of {}</span>{}</div>",
+        page, pages,
+        button("mech-table-page-prev", "‹", page.checked_sub(1)),
+        first, last, self.rows,
+        button("mech-table-page-next", "›", if page + 1 < pages { Some(page + 1) } else { None }),
+      ));
+    }
+    html
+  }
+
+  #[cfg(feature = "pretty_print")]
+  fn html_table(&self, head: std::ops::Range<usize>, hidden: usize, tail: std::ops::Range<usize>, summary: bool) -> String {
     let mut html = String::new();
 
     // Start table
@@ -332,21 +716,99 @@ impl MechTable {
 
     // Build tbody
     html.push_str("<tbody class=\"mech-table-body\">");
-    for row_idx in 1..=self.rows {
+    let row_html = |html: &mut String, row_idx: usize| {
         html.push_str("<tr class=\"mech-table-row\">");
-        for (_key, (_kind, matrix)) in self.data.iter() {
-            let value = matrix.index1d(row_idx);
+        for (_key, (_kind, column)) in self.data.iter() {
+            let value = column.index1d(row_idx);
             html.push_str(&format!(
                 "<td class=\"mech-table-column\">{}</td>",
                 value.to_html()
             ));
         }
         html.push_str("</tr>");
+    };
+    for row_idx in head {
+        row_html(&mut html, row_idx);
     }
-    html.push_str("</tbody></table>");
+    if hidden > 0 {
+        html.push_str(&format!(
+            "<tr class=\"mech-table-more\"><td colspan=\"{}\">⋯ {} more rows ⋯</td></tr>",
+            self.data.len().max(1), hidden
+        ));
+    }
+    for row_idx in tail {
+        row_html(&mut html, row_idx);
+    }
+    html.push_str("</tbody>");
+
+    // Build tfoot
+    if summary {
+        html.push_str("<tfoot class=\"mech-table-summary\"><tr>");
+        for summary in self.column_summaries() {
+            html.push_str(&format!(
+                "<td class=\"mech-table-column-summary\">\
+                  <div class=\"mech-table-summary-min\">min {}</div>\
+                  <div class=\"mech-table-summary-max\">max {}</div>\
+                  <div class=\"mech-table-summary-nulls\">{} null</div>\
+                </td>",
+                summary.min.map_or("–".to_string(), |x| x.to_html()),
+                summary.max.map_or("–".to_string(), |x| x.to_html()),
+                summary.nulls
+            ));
+        }
+        html.push_str("</tr></tfoot>");
+    }
+    html.push_str("</table>");
     html
   }
 
+  // The kind, least and greatest cell, and number of empty cells of each
+  // column. Numbers, strings and bools are ordered; the least and greatest of
+  // any other kind are left out.
+  pub fn column_summaries(&self) -> Vec<ColumnSummary> {
+    self.data.iter().map(|(key, (kind, column))| {
+      let mut summary = ColumnSummary {
+        name: self.col_names.get(key).cloned().unwrap_or_else(|| key.to_string()),
+        kind: kind.clone(),
+        min: None,
+        max: None,
+        nulls: 0,
+      };
+      let mut ordered = true;
+      for cell in column.as_vec() {
+        let cell = match cell {
+          Value::Typed(cell, _) => *cell,
+          cell => cell,
+        };
+        if let Value::Empty = cell {
+          summary.nulls += 1;
+          continue;
+        }
+        if !ordered {
+          continue;
+        }
+        match (&summary.min, &summary.max) {
+          (Some(min), Some(max)) => match (summary_order(&cell, min), summary_order(&cell, max)) {
+            (Some(Ordering::Less), _) => summary.min = Some(cell),
+            (_, Some(Ordering::Greater)) => summary.max = Some(cell),
+            (Some(_), Some(_)) => (),
+            _ => ordered = false,
+          },
+          _ if summary_order(&cell, &cell).is_some() => {
+            summary.min = Some(cell.clone());
+            summary.max = Some(cell);
+          }
+          _ => ordered = false,
+        }
+      }
+      if !ordered {
+        summary.min = None;
+        summary.max = None;
+      }
+      summary
+    }).collect()
+  }
+
   pub fn new_table(names: Vec<String>, kinds: Vec<ValueKind>, cols: Vec<Vec<Value>>) -> MechTable {
     let col_count = names.len();
     let row_count = if !cols.is_empty() { cols[0].len() } else { 0 };
@@ -364,13 +826,13 @@ impl MechTable {
         row_count,
         values.len()
       );
-      let matrix = Matrix::DVector(Ref::new(DVector::from_vec(values.clone())));
-      data.insert(col_idx as u64, (kind.clone(), matrix));
+      let column = Column::from_values(kind, values.clone());
+      data.insert(col_idx as u64, (kind.clone(), column));
     }
     MechTable::new(row_count, col_count, data, col_names)
   }
 
-  pub fn new(rows: usize, cols: usize, data: IndexMap<u64,(ValueKind,Matrix<Value>)>, col_names: HashMap<u64,String>) -> MechTable {
+  pub fn new(rows: usize, cols: usize, data: IndexMap<u64,(ValueKind,Column)>, col_names: HashMap<u64,String>) -> MechTable {
     MechTable{rows, cols, data, col_names}
   }
 
@@ -396,10 +858,15 @@ impl MechTable {
     self.cols
   }
 
-  pub fn get(&self, key: &u64) -> Option<&(ValueKind,Matrix<Value>)> {
+  pub fn get(&self, key: &u64) -> Option<&(ValueKind,Column)> {
     self.data.get(key)
   }
 
+  // The id of the column at position `ix` (1-based), in declaration order.
+  pub fn column_id_at(&self, ix: usize) -> Option<u64> {
+    ix.checked_sub(1).and_then(|ix| self.data.get_index(ix)).map(|(id, _)| *id)
+  }
+
    pub fn shape(&self) -> Vec<usize> {
     vec![self.rows,self.cols]
   }
@@ -408,19 +875,37 @@ impl MechTable {
 #[cfg(feature = "pretty_print")]
 impl PrettyPrint for MechTable {
   fn pretty_print(&self) -> String {
+    let truncated = self.rows > TABLE_MAX_ROWS;
+    let summaries = if truncated { self.column_summaries() } else { vec![] };
     let mut builder = Builder::default();
-    for (k,(knd,val)) in &self.data {
+    for (ix, (k,(knd,val))) in self.data.iter().enumerate() {
       let name = self.col_names.get(k).unwrap();
-      let val_string: String = val.as_vec().iter()
+      let cells = val.as_vec();
+      let join = |cells: &[Value]| cells.iter()
         .map(|x| x.pretty_print())
         .collect::<Vec<String>>()
         .join("\n");
+      let val_string: String = if truncated {
+        format!("{}\n⋮\n{}", join(&cells[..TABLE_PREVIEW_ROWS]), join(&cells[self.rows - TABLE_PREVIEW_ROWS..]))
+      } else {
+        join(&cells)
+      };
       let mut col_string = vec![format!("{}<{}>", name.to_string(), knd), val_string];
+      if let Some(summary) = summaries.get(ix) {
+        col_string.push(format!("min {}\nmax {}\n{} null",
+          summary.min.as_ref().map_or("–".to_string(), |x| x.pretty_print().trim().to_string()),
+          summary.max.as_ref().map_or("–".to_string(), |x| x.pretty_print().trim().to_string()),
+          summary.nulls));
+      }
       builder.push_column(col_string);
     }
     let mut table = builder.build();
     table.with(Style::modern_rounded());
-    format!("{table}")
+    if truncated {
+      format!("{table}\n{} more rows", self.rows - 2 * TABLE_PREVIEW_ROWS)
+    } else {
+      format!("{table}")
+    }
   }
 }
 
@@ -501,6 +986,19 @@ impl MechErrorKind for TableColumnNotFoundError {
   }
 }
 
+#[derive(Debug, Clone)]
+pub struct ColumnCellKindMismatchError {
+  pub expected_kind: ValueKind,
+  pub actual_kind: ValueKind,
+}
+
+impl MechErrorKind for ColumnCellKindMismatchError {
+  fn name(&self) -> &str { "ColumnCellKindMismatch" }
+  fn message(&self) -> String {
+    format!("Cannot store a value of kind {} in a column of kind {}.", self.actual_kind, self.expected_kind)
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct MatrixAppendToTableError {
   pub column_id: u64,
@@ -70,7 +70,7 @@ pub enum ValueKind {
   Matrix(Box<ValueKind>,Vec<usize>),  Enum(u64,String),             Record(Vec<(String,ValueKind)>),
   Map(Box<ValueKind>,Box<ValueKind>), Atom(u64,String),             Table(Vec<(String,ValueKind)>, usize), 
   Tuple(Vec<ValueKind>),              Reference(Box<ValueKind>),    Set(Box<ValueKind>, Option<usize>), 
-  Option(Box<ValueKind>),             Kind(Box<ValueKind>),    
+  Option(Box<ValueKind>),             Kind(Box<ValueKind>),         Lambda(Vec<ValueKind>),
 }
 
 impl Display for ValueKind {
@@ -111,6 +111,7 @@ impl Display for ValueKind {
       ValueKind::None => write!(f, "none"),
       ValueKind::Option(x) => write!(f, "{}?", x),
       ValueKind::Kind(x) => write!(f, "<{}>", x),
+      ValueKind::Lambda(x) => write!(f, "λ({})", x.iter().map(|x| format!("{}",x)).collect::<Vec<String>>().join(",")),
     }
   }
 }
@@ -391,6 +392,7 @@ impl ValueKind {
       }
       ValueKind::Option(inner) => inner.align(),
       ValueKind::Kind(inner) => inner.align(),
+      ValueKind::Lambda(_) => ptr_align,
     }
   }
 }
@@ -621,6 +623,8 @@ pub enum Value {
   Tuple(Ref<MechTuple>),
   #[cfg(feature = "enum")]
   Enum(Ref<MechEnum>),
+  #[cfg(feature = "functions")]
+  Lambda(Ref<MechLambda>),
   Id(u64),
   Index(Ref<usize>),
   MutableReference(MutableReference),
@@ -692,6 +696,8 @@ impl Hash for Value {
       Value::Record(x) => x.borrow().hash(state),
       #[cfg(feature = "enum")]
       Value::Enum(x) => x.borrow().hash(state),
+      #[cfg(feature = "functions")]
+      Value::Lambda(x) => x.borrow().code.hash(state),
       #[cfg(any(feature = "string", feature = "variable_define"))]
       Value::String(x) => x.borrow().hash(state),
       #[cfg(all(feature = "matrix", feature = "bool"))]
@@ -992,6 +998,40 @@ impl Value {
       Value::Atom(v) => v.addr(),
       #[cfg(feature = "matrix")]
       Value::MatrixIndex(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "bool"))]
+      Value::MatrixBool(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "string"))]
+      Value::MatrixString(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "u8"))]
+      Value::MatrixU8(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "u16"))]
+      Value::MatrixU16(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "u32"))]
+      Value::MatrixU32(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "u64"))]
+      Value::MatrixU64(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "u128"))]
+      Value::MatrixU128(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "i8"))]
+      Value::MatrixI8(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "i16"))]
+      Value::MatrixI16(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "i32"))]
+      Value::MatrixI32(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "i64"))]
+      Value::MatrixI64(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "i128"))]
+      Value::MatrixI128(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "f32"))]
+      Value::MatrixF32(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "f64"))]
+      Value::MatrixF64(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "rational"))]
+      Value::MatrixR64(v) => v.addr(),
+      #[cfg(all(feature = "matrix", feature = "complex"))]
+      Value::MatrixC64(v) => v.addr(),
+      #[cfg(feature = "matrix")]
+      Value::MatrixValue(v) => v.addr(),
       Value::Index(v) => v.addr(),
       Value::MutableReference(v) => v.addr(),
       _ => todo!(),
@@ -1392,6 +1432,8 @@ impl Value {
       Value::Tuple(x) => x.borrow().size_of(),
       #[cfg(feature = "enum")]
       Value::Enum(x) => x.borrow().size_of(),
+      #[cfg(feature = "functions")]
+      Value::Lambda(_) => 0, // Lambdas are code, so they have no data size
       Value::MutableReference(x) => x.borrow().size_of(),
       Value::Id(_) => 8,
       Value::Index(x) => 8,
@@ -1629,13 +1671,15 @@ impl Value {
           format!("{}<{}>", name, kind)
         }).collect::<Vec<_>>().join(" ");
         let rows = (0..table.rows).map(|r| {
-          table.data.iter().map(|(_, (_, col))| col.index2d(r + 1, 1).format_value_inline()).collect::<Vec<_>>().join(" ")
+          table.data.iter().map(|(_, (_, col))| col.index1d(r + 1).format_value_inline()).collect::<Vec<_>>().join(" ")
         }).collect::<Vec<_>>().join("; ");
         format!("|{}| {}", headers, rows)
       }
       Value::Id(x) => format!("{}", humanize(x)),
       Value::Index(x) => format!("{}", x.borrow()),
       Value::Kind(k) => format!("<{}>", k),
+      #[cfg(feature = "functions")]
+      Value::Lambda(l) => l.borrow().to_string(),
       Value::Typed(value, _) => value.format_value_inline(),
       Value::MutableReference(m) => m.borrow().format_value_inline(),
       Value::IndexAll => ":".to_string(),
@@ -1751,6 +1795,8 @@ impl Value {
       Value::Empty | Value::EmptyKind(_) => vec![1,1],
       Value::IndexAll => vec![0,0],
       Value::Kind(_) => vec![0,0],
+      #[cfg(feature = "functions")]
+      Value::Lambda(_) => vec![1,1],
       Value::Id(x) => vec![0,0],
     }
   }
@@ -1846,6 +1892,8 @@ impl Value {
       Value::Tuple(x) => x.borrow().kind(),
       #[cfg(feature = "enum")]
       Value::Enum(x) => x.borrow().kind(),
+      #[cfg(feature = "functions")]
+      Value::Lambda(x) => x.borrow().kind(),
       Value::MutableReference(x) => ValueKind::Reference(Box::new(x.borrow().kind())),
       Value::Typed(_, kind) => kind.clone(),
       Value::EmptyKind(k) => k.clone(),
@@ -2475,6 +2523,8 @@ impl PrettyPrint for Value {
       Value::IndexAll => builder.push_record(vec![":"]),
       Value::Id(x) => builder.push_record(vec![format!("{}",humanize(x))]),
       Value::Kind(x) => builder.push_record(vec![format!("<{}>",x)]),
+      #[cfg(feature = "functions")]
+      Value::Lambda(x) => builder.push_record(vec![x.borrow().to_string()]),
       x => {
         todo!("{x:#?}");
       },
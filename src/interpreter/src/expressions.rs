@@ -26,17 +26,23 @@ pub fn expression(expr: &Expression, env: Option<&Environment>, p: &Interpreter)
         Expression::SetComprehension(set_comp) => set_comprehension(set_comp, p),
         #[cfg(feature = "matrix_comprehensions")]
         Expression::MatrixComprehension(matrix_comp) => matrix_comprehension(matrix_comp, p),
+        #[cfg(feature = "table_comprehensions")]
+        Expression::TableComprehension(table_comp) => table_comprehension(table_comp, p),
+        #[cfg(feature = "record_comprehensions")]
+        Expression::RecordComprehension(record_comp) => record_comprehension(record_comp, p),
         Expression::Match(match_expr) => match_expression(match_expr, env, p),
         #[cfg(feature = "state_machines")]
         Expression::FsmPipe(fsm_pipe) => crate::state_machines::execute_fsm_pipe(fsm_pipe, env, p),
+        #[cfg(feature = "functions")]
+        Expression::Lambda(lmbd) => lambda(lmbd, env, p),
         x => Err(MechError::new(FeatureNotEnabledError, None)
             .with_compiler_loc()
             .with_tokens(x.tokens())),
     }
 }
 
-#[cfg(any(feature = "set_comprehensions", feature = "matrix_comprehensions"))]
-pub fn pattern_match_value(pattern: &Pattern, value: &Value, env: &mut Environment) -> MResult<()> {
+#[cfg(any(feature = "set_comprehensions", feature = "matrix_comprehensions", feature = "table_comprehensions", feature = "record_comprehensions"))]
+pub fn pattern_match_value(pattern: &Pattern, value: &Value, env: &mut Environment, p: &Interpreter) -> MResult<()> {
     match pattern {
         Pattern::Wildcard => Ok(()),
         Pattern::Expression(expr) => match expr {
@@ -76,7 +82,7 @@ pub fn pattern_match_value(pattern: &Pattern, value: &Value, env: &mut Environme
                     .with_compiler_loc());
                 }
                 for (pttrn, val) in pat_tuple.0.iter().zip(values_brrw.elements.iter()) {
-                    pattern_match_value(pttrn, val, env)?;
+                    pattern_match_value(pttrn, val, env, p)?;
                 }
                 Ok(())
             }
@@ -91,11 +97,25 @@ pub fn pattern_match_value(pattern: &Pattern, value: &Value, env: &mut Environme
         Pattern::TupleStruct(pat_struct) => {
             todo!("Implement tuple struct pattern matching")
         }
+        Pattern::String(_) | Pattern::Map(_) | Pattern::Set(_) | Pattern::Table(_) => {
+            if pattern_matches_value(pattern, value, env, p)? {
+                Ok(())
+            } else {
+                Err(MechError::new(
+                    PatternValueMismatchError {
+                        found: value.kind(),
+                    },
+                    None,
+                )
+                .with_compiler_loc()
+                .with_tokens(pattern.tokens()))
+            }
+        }
         _ => Err(MechError::new(FeatureNotEnabledError, None).with_compiler_loc()),
     }
 }
 
-#[cfg(any(feature = "set_comprehensions", feature = "matrix_comprehensions"))]
+#[cfg(any(feature = "set_comprehensions", feature = "matrix_comprehensions", feature = "table_comprehensions", feature = "record_comprehensions"))]
 fn comprehension_environments(
     qualifiers: &[ComprehensionQualifier],
     comprehension_id: u64,
@@ -113,7 +133,7 @@ fn comprehension_environments(
                     let collection = expression(expr, Some(env), &new_p)?;
                     for elmnt in comprehension_generator_values(&collection)? {
                         let mut new_env = env.clone();
-                        if pattern_match_value(pttrn, &elmnt, &mut new_env).is_ok() {
+                        if pattern_match_value(pttrn, &elmnt, &mut new_env, &new_p).is_ok() {
                             new_envs.push(new_env);
                         }
                     }
@@ -144,7 +164,7 @@ fn comprehension_environments(
     Ok((envs, new_p))
 }
 
-#[cfg(any(feature = "set_comprehensions", feature = "matrix_comprehensions"))]
+#[cfg(any(feature = "set_comprehensions", feature = "matrix_comprehensions", feature = "table_comprehensions", feature = "record_comprehensions"))]
 fn comprehension_generator_values(collection: &Value) -> MResult<Vec<Value>> {
     match collection {
         #[cfg(feature = "set")]
@@ -197,6 +217,14 @@ fn comprehension_generator_values(collection: &Value) -> MResult<Vec<Value>> {
             .collect()),
         #[cfg(feature = "matrix")]
         Value::MatrixValue(matrix) => Ok(matrix.as_vec()),
+        #[cfg(feature = "table")]
+        Value::Table(table) => {
+            let table_brrw = table.borrow();
+            Ok((1..=table_brrw.rows)
+                .filter_map(|ix| table_brrw.get_record(ix))
+                .map(|rcrd| Value::Record(Ref::new(rcrd)))
+                .collect())
+        }
         Value::MutableReference(reference) => comprehension_generator_values(&reference.borrow()),
         x => Err(
             MechError::new(ComprehensionGeneratorError { found: x.kind() }, None)
@@ -205,7 +233,7 @@ fn comprehension_generator_values(collection: &Value) -> MResult<Vec<Value>> {
     }
 }
 
-#[cfg(any(feature = "set_comprehensions", feature = "matrix_comprehensions"))]
+#[cfg(any(feature = "set_comprehensions", feature = "matrix_comprehensions", feature = "table_comprehensions", feature = "record_comprehensions"))]
 fn detach_comprehension_value(value: &Value) -> Value {
     match value {
         Value::MutableReference(reference) => reference.borrow().clone(),
@@ -449,6 +477,341 @@ pub fn matrix_comprehension(matrix_comp: &MatrixComprehension, p: &Interpreter)
     }
 }
 
+// Every row yielded by a record or table comprehension must have the kind of
+// the first row. Annotated fields are already converted by `record`, so this
+// catches unannotated fields whose kind drifts between bindings.
+#[cfg(any(feature = "table_comprehensions", feature = "record_comprehensions"))]
+fn comprehension_row_records(rows: &[Value]) -> MResult<Vec<MechRecord>> {
+    let mut records: Vec<MechRecord> = Vec::with_capacity(rows.len());
+    for (ix, row) in rows.iter().enumerate() {
+        let rcrd = match detach_comprehension_value(row) {
+            Value::Record(rcrd) => rcrd.borrow().clone(),
+            x => {
+                return Err(MechError::new(
+                    ComprehensionRowKindMismatchError {
+                        row: ix + 1,
+                        expected: records.first().map(|r| r.kind()).unwrap_or(ValueKind::Any),
+                        found: x.kind(),
+                    },
+                    None,
+                )
+                .with_compiler_loc())
+            }
+        };
+        if let Some(first) = records.first() {
+            let (expected, found) = (first.kind(), rcrd.kind());
+            if expected != found {
+                return Err(MechError::new(
+                    ComprehensionRowKindMismatchError {
+                        row: ix + 1,
+                        expected,
+                        found,
+                    },
+                    None,
+                )
+                .with_compiler_loc());
+            }
+        }
+        records.push(rcrd);
+    }
+    Ok(records)
+}
+
+#[cfg(feature = "table_comprehensions")]
+fn table_from_comprehension_rows(rows: &[Value]) -> MResult<MechTable> {
+    let records = comprehension_row_records(rows)?;
+    let first = match records.first() {
+        Some(first) => first,
+        None => {
+            return Err(
+                MechError::new(CannotCreateTableFromEmptyRecordListError, None).with_compiler_loc(),
+            )
+        }
+    };
+    let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+    for (col_ix, col_id) in first.data.keys().enumerate() {
+        let column = records
+            .iter()
+            .map(|rcrd| rcrd.data[col_id].clone())
+            .collect::<Vec<Value>>();
+        let kind = first.kinds[col_ix].clone();
+        let column = Column::from_values(&kind, column);
+        data.insert(*col_id, (kind, column));
+    }
+    Ok(MechTable::new(
+        records.len(),
+        first.cols,
+        data,
+        first.field_names.clone(),
+    ))
+}
+
+#[cfg(feature = "record_comprehensions")]
+fn record_from_comprehension_rows(rows: &[Value]) -> MResult<MechRecord> {
+    let records = comprehension_row_records(rows)?;
+    let first = match records.first() {
+        Some(first) => first,
+        None => return Ok(MechRecord::from_vec(vec![])),
+    };
+    let mut fields = Vec::with_capacity(first.cols);
+    for (col_id, name) in first.data.keys().map(|id| (*id, first.field_names[id].clone())) {
+        let column = records
+            .iter()
+            .map(|rcrd| rcrd.data[&col_id].clone())
+            .collect::<Vec<Value>>();
+        let fxn = MatrixHorzCat {}.compile(&column)?;
+        fxn.solve();
+        fields.push(((col_id, name), fxn.out()));
+    }
+    Ok(MechRecord::from_vec(fields))
+}
+
+#[cfg(feature = "table_comprehensions")]
+#[derive(Debug)]
+pub struct ValueTableComprehension {
+    pub arguments: Vec<Value>,
+    pub out: Ref<MechTable>,
+}
+#[cfg(all(feature = "table_comprehensions", feature = "functions"))]
+impl MechFunctionImpl for ValueTableComprehension {
+    fn solve(&self) {
+        let table = table_from_comprehension_rows(&self.arguments)
+            .expect("table/comprehension row kinds changed to incompatible values");
+        *self.out.borrow_mut() = table;
+    }
+    fn out(&self) -> Value {
+        Value::Table(self.out.clone())
+    }
+    fn to_string(&self) -> String {
+        format!("{:#?}", self)
+    }
+}
+#[cfg(all(feature = "table_comprehensions", feature = "functions"))]
+impl MechFunctionFactory for ValueTableComprehension {
+    fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+            FunctionArgs::Nullary(out) => {
+                let out: Ref<MechTable> = unsafe { out.as_unchecked().clone() };
+                Ok(Box::new(ValueTableComprehension {
+                    arguments: Vec::new(),
+                    out,
+                }))
+            }
+            _ => Err(MechError::new(
+                IncorrectNumberOfArguments {
+                    expected: 0,
+                    found: args.len(),
+                },
+                None,
+            )
+            .with_compiler_loc()),
+        }
+    }
+}
+#[cfg(all(feature = "table_comprehensions", feature = "compiler"))]
+impl MechFunctionCompiler for ValueTableComprehension {
+    fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        compile_nullop!(
+            "table/comprehension",
+            self.out,
+            ctx,
+            FeatureFlag::Builtin(FeatureKind::TableComprehensions)
+        );
+    }
+}
+#[cfg(all(feature = "table_comprehensions", feature = "functions"))]
+register_descriptor! {
+  FunctionDescriptor {
+    name: "table/comprehension",
+    ptr: ValueTableComprehension::new,
+  }
+}
+#[cfg(feature = "table_comprehensions")]
+pub struct TableComprehensionDefine {}
+#[cfg(all(feature = "table_comprehensions", feature = "functions"))]
+impl NativeFunctionCompiler for TableComprehensionDefine {
+    fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        let table = table_from_comprehension_rows(arguments)?;
+        Ok(Box::new(ValueTableComprehension {
+            arguments: arguments.clone(),
+            out: Ref::new(table),
+        }))
+    }
+}
+#[cfg(all(feature = "table_comprehensions", feature = "functions"))]
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "table/comprehension",
+    ptr: &TableComprehensionDefine{},
+  }
+}
+
+#[cfg(feature = "record_comprehensions")]
+#[derive(Debug)]
+pub struct ValueRecordComprehension {
+    pub arguments: Vec<Value>,
+    pub out: Ref<Value>,
+}
+#[cfg(all(feature = "record_comprehensions", feature = "functions"))]
+impl MechFunctionImpl for ValueRecordComprehension {
+    fn solve(&self) {
+        let rcrd = record_from_comprehension_rows(&self.arguments)
+            .expect("record/comprehension field kinds changed to incompatible values");
+        *self.out.borrow_mut() = Value::Record(Ref::new(rcrd));
+    }
+    fn out(&self) -> Value {
+        self.out.borrow().clone()
+    }
+    fn to_string(&self) -> String {
+        format!("{:#?}", self)
+    }
+}
+#[cfg(all(feature = "record_comprehensions", feature = "functions"))]
+impl MechFunctionFactory for ValueRecordComprehension {
+    fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+            FunctionArgs::Nullary(out) => Ok(Box::new(ValueRecordComprehension {
+                arguments: Vec::new(),
+                out: Ref::new(out),
+            })),
+            _ => Err(MechError::new(
+                IncorrectNumberOfArguments {
+                    expected: 0,
+                    found: args.len(),
+                },
+                None,
+            )
+            .with_compiler_loc()),
+        }
+    }
+}
+#[cfg(all(feature = "record_comprehensions", feature = "compiler"))]
+impl MechFunctionCompiler for ValueRecordComprehension {
+    fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        compile_nullop!(
+            "record/comprehension",
+            self.out,
+            ctx,
+            FeatureFlag::Builtin(FeatureKind::RecordComprehensions)
+        );
+    }
+}
+#[cfg(all(feature = "record_comprehensions", feature = "functions"))]
+register_descriptor! {
+  FunctionDescriptor {
+    name: "record/comprehension",
+    ptr: ValueRecordComprehension::new,
+  }
+}
+#[cfg(feature = "record_comprehensions")]
+pub struct RecordComprehensionDefine {}
+#[cfg(all(feature = "record_comprehensions", feature = "functions"))]
+impl NativeFunctionCompiler for RecordComprehensionDefine {
+    fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        let rcrd = record_from_comprehension_rows(arguments)?;
+        Ok(Box::new(ValueRecordComprehension {
+            arguments: arguments.clone(),
+            out: Ref::new(Value::Record(Ref::new(rcrd))),
+        }))
+    }
+}
+#[cfg(all(feature = "record_comprehensions", feature = "functions"))]
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "record/comprehension",
+    ptr: &RecordComprehensionDefine{},
+  }
+}
+
+#[cfg(feature = "table_comprehensions")]
+pub fn table_comprehension(table_comp: &TableComprehension, p: &Interpreter) -> MResult<Value> {
+    let comprehension_id = hash_str(&format!("{:?}", table_comp));
+    let (envs, new_p) = comprehension_environments(&table_comp.qualifiers, comprehension_id, p)?;
+    let mut rows = Vec::new();
+    for env in envs {
+        rows.push(record(&table_comp.record, Some(&env), &new_p)?);
+    }
+    // With no rows there is nothing to infer column kinds from, so every field
+    // in the head has to carry an annotation.
+    if rows.is_empty() {
+        let mut columns = Vec::new();
+        for binding in &table_comp.record.bindings {
+            let knd = match &binding.kind {
+                Some(k) => kind_annotation(&k.kind, p)?.to_value_kind(&p.state.borrow().kinds)?,
+                None => {
+                    return Err(MechError::new(
+                        TableComprehensionEmptyKindError {
+                            field: binding.name.to_string(),
+                        },
+                        None,
+                    )
+                    .with_compiler_loc()
+                    .with_tokens(binding.tokens()))
+                }
+            };
+            columns.push((binding.name.to_string(), knd));
+        }
+        return Ok(Value::Table(Ref::new(MechTable::from_kind(ValueKind::Table(columns, 0))?)));
+    }
+    let functions = p.functions();
+    let table_define_id = hash_str("table/comprehension");
+    let table_define = {
+        functions
+            .borrow()
+            .function_compilers
+            .get(&table_define_id)
+            .copied()
+    };
+    match table_define {
+        Some(compiler) => execute_native_function_compiler(compiler, &rows, p),
+        None => Err(MechError::new(
+            MissingFunctionError {
+                function_id: table_define_id,
+            },
+            None,
+        )
+        .with_compiler_loc()),
+    }
+}
+
+#[cfg(feature = "record_comprehensions")]
+pub fn record_comprehension(record_comp: &RecordComprehension, p: &Interpreter) -> MResult<Value> {
+    let comprehension_id = hash_str(&format!("{:?}", record_comp));
+    let (envs, new_p) = comprehension_environments(&record_comp.qualifiers, comprehension_id, p)?;
+    let mut rows = Vec::new();
+    for env in envs {
+        rows.push(record(&record_comp.record, Some(&env), &new_p)?);
+    }
+    if rows.is_empty() {
+        let fields = record_comp
+            .record
+            .bindings
+            .iter()
+            .map(|b| ((b.name.hash(), b.name.to_string()), Value::MatrixValue(Matrix::from_vec(vec![], 0, 0))))
+            .collect();
+        return Ok(Value::Record(Ref::new(MechRecord::from_vec(fields))));
+    }
+    let functions = p.functions();
+    let record_define_id = hash_str("record/comprehension");
+    let record_define = {
+        functions
+            .borrow()
+            .function_compilers
+            .get(&record_define_id)
+            .copied()
+    };
+    match record_define {
+        Some(compiler) => execute_native_function_compiler(compiler, &rows, p),
+        None => Err(MechError::new(
+            MissingFunctionError {
+                function_id: record_define_id,
+            },
+            None,
+        )
+        .with_compiler_loc()),
+    }
+}
+
 #[cfg(feature = "range")]
 pub fn range(rng: &RangeExpression, env: Option<&Environment>, p: &Interpreter) -> MResult<Value> {
     let plan = p.plan();
@@ -611,6 +974,28 @@ pub fn subscript(
                     plan.borrow_mut().push(new_fxn);
                     return Ok(res);
                 }
+                // `T.2` reads the second column, in declaration order.
+                #[cfg(feature = "table")]
+                ValueKind::Table(..) => {
+                    let ix = fxn_input[1].as_usize()?;
+                    let col_id = match val {
+                        Value::Table(t) => t.borrow().column_id_at(ix),
+                        Value::MutableReference(r) => match &*r.borrow() {
+                            Value::Table(t) => t.borrow().column_id_at(ix),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    let col_id = match col_id {
+                        Some(id) => id,
+                        None => return Err(MechError::new(IndexOutOfBoundsError, None).with_compiler_loc().with_tokens(x.tokens())),
+                    };
+                    let new_fxn = AccessColumn {}.compile(&vec![val.clone(), Value::Id(col_id)])?;
+                    new_fxn.solve();
+                    let res = new_fxn.out();
+                    plan.borrow_mut().push(new_fxn);
+                    return Ok(res);
+                }
                 /*ValueKind::Record(_) => {
                   let new_fxn = RecordAccessScalar{}.compile(&fxn_input)?;
                   new_fxn.solve();
@@ -1425,6 +1810,18 @@ pub fn term(trm: &Term, env: Option<&Environment>, p: &Interpreter) -> MResult<V
       #[cfg(feature = "logic_xor")]
       FormulaOperator::Logic(LogicOp::Xor) => LogicXor {}.compile(&vec![lhs, rhs])?,
 
+      // Bits
+      #[cfg(feature = "bits_and")]
+      FormulaOperator::Bits(BitsOp::And) => BitsAnd {}.compile(&vec![lhs, rhs])?,
+      #[cfg(feature = "bits_or")]
+      FormulaOperator::Bits(BitsOp::Or) => BitsOr {}.compile(&vec![lhs, rhs])?,
+      #[cfg(feature = "bits_xor")]
+      FormulaOperator::Bits(BitsOp::Xor) => BitsXor {}.compile(&vec![lhs, rhs])?,
+      #[cfg(feature = "bits_shift")]
+      FormulaOperator::Bits(BitsOp::ShiftLeft) => BitsShiftLeft {}.compile(&vec![lhs, rhs])?,
+      #[cfg(feature = "bits_shift")]
+      FormulaOperator::Bits(BitsOp::ShiftRight) => BitsShiftRight {}.compile(&vec![lhs, rhs])?,
+
       // Table
       #[cfg(feature = "table")]
       FormulaOperator::Table(TableOp::InnerJoin) => TableInnerJoin {}.compile(&vec![lhs, rhs])?,
@@ -1438,6 +1835,10 @@ pub fn term(trm: &Term, env: Option<&Environment>, p: &Interpreter) -> MResult<V
       FormulaOperator::Table(TableOp::LeftSemiJoin) => TableLeftSemiJoin {}.compile(&vec![lhs, rhs])?,
       #[cfg(feature = "table")]
       FormulaOperator::Table(TableOp::LeftAntiJoin) => TableLeftAntiJoin {}.compile(&vec![lhs, rhs])?,
+      #[cfg(feature = "table")]
+      FormulaOperator::Table(TableOp::SortBy) => TableSortBy {}.compile(&vec![lhs, rhs])?,
+      #[cfg(feature = "table")]
+      FormulaOperator::Table(TableOp::Filter) => TableFilter {}.compile_with_callbacks(&vec![lhs, rhs], &mut |value| resolve_callback("table/filter", value, env, p))?,
 
       // Set
       #[cfg(feature = "set_union")]
@@ -1654,12 +2055,48 @@ impl MechErrorKind for ComprehensionGeneratorError {
   }
   fn message(&self) -> String {
       format!(
-        "Comprehension generator must produce a set or matrix, found kind: {:?}",
+        "Comprehension generator must produce a set, matrix, or table, found kind: {:?}",
         self.found
       )
   }
 }
 
+#[derive(Debug, Clone)]
+pub struct ComprehensionRowKindMismatchError {
+  row: usize,
+  expected: ValueKind,
+  found: ValueKind,
+}
+
+impl MechErrorKind for ComprehensionRowKindMismatchError {
+  fn name(&self) -> &str {
+    "ComprehensionRowKindMismatch"
+  }
+  fn message(&self) -> String {
+    format!(
+      "Comprehension row {} has kind {:?}, but earlier rows have kind {:?}",
+      self.row, self.found, self.expected
+    )
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableComprehensionEmptyKindError {
+  field: String,
+}
+
+impl MechErrorKind for TableComprehensionEmptyKindError {
+  fn name(&self) -> &str {
+    "TableComprehensionEmptyKind"
+  }
+  fn message(&self) -> String {
+    format!(
+      "Table comprehension produced no rows and field `{}` has no kind annotation to build an empty column from",
+      self.field
+    )
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct PatternExpectedTupleError {
   found: ValueKind,
@@ -1673,6 +2110,19 @@ impl MechErrorKind for PatternExpectedTupleError {
   }
 }
 
+#[derive(Debug, Clone)]
+pub struct PatternValueMismatchError {
+  found: ValueKind,
+}
+impl MechErrorKind for PatternValueMismatchError {
+  fn name(&self) -> &str {
+    "PatternValueMismatch"
+  }
+  fn message(&self) -> String {
+    format!("Value of kind {} does not match the pattern", self.found)
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct ArityMismatchError {
   expected: usize,
@@ -43,6 +43,8 @@ pub struct Stack {
 
 // Registers a user-written function so it can be called by name later.
 // Hashes the name to a u64 id used as the lookup key throughout the runtime.
+// Definitions that share a name but differ in arity or input kinds are kept
+// side by side as overloads; redefining an existing signature replaces it.
 pub fn function_define(fxn_def: &FunctionDefine, p: &Interpreter) -> MResult<FunctionDefinition> {
   let fxn_name_id = fxn_def.name.hash();
   let mut new_fxn = FunctionDefinition::new(fxn_name_id, fxn_def.name.to_string(), fxn_def.clone());
@@ -63,11 +65,20 @@ pub fn function_define(fxn_def: &FunctionDefine, p: &Interpreter) -> MResult<Fun
 
   // Store the definition and register the human-readable name string in both
   // dictionaries so error messages and debug output can print it.
+  let signature = function_input_kinds(&new_fxn, p).ok();
   let functions = p.functions();
   let mut functions_brrw = functions.borrow_mut();
-  functions_brrw
+  let overloads = functions_brrw
     .user_functions
-    .insert(fxn_name_id, new_fxn.clone());
+    .entry(fxn_name_id)
+    .or_insert_with(Vec::new);
+  let existing = overloads.iter().position(|fxn| {
+    signature.is_some() && fxn.input.len() == new_fxn.input.len() && function_input_kinds(fxn, p).ok() == signature
+  });
+  match existing {
+    Some(ix) => overloads[ix] = new_fxn.clone(),
+    None => overloads.push(new_fxn.clone()),
+  }
   functions_brrw
     .dictionary
     .borrow_mut()
@@ -91,12 +102,23 @@ pub fn function_call(fxn_call: &FunctionCall, env: Option<&Environment>, p: &Int
   let functions = p.functions();
   let fxn_name_id = fxn_call.name.hash();
 
-  // User-defined function: evaluate arguments then run the interpreted body.
-  if let Some(user_fxn) = { functions.borrow().user_functions.get(&fxn_name_id).cloned() } {
+  // Lambda bound to a variable in scope: call it with its captured bindings.
+  if let Some(lmbd) = lookup_lambda(fxn_name_id, env, p) {
+    let mut input_arg_values = vec![];
+    for (_, arg_expr) in fxn_call.args.iter() {
+      input_arg_values.push(expression(arg_expr, env, p)?);
+    }
+    return execute_lambda(&lmbd, &input_arg_values, p);
+  }
+
+  // User-defined function: evaluate arguments, pick the overload that fits
+  // them, then run the interpreted body.
+  if let Some(overloads) = { functions.borrow().user_functions.get(&fxn_name_id).cloned() } {
     let mut input_arg_values = vec![];
     for (_, arg_expr) in fxn_call.args.iter() {
       input_arg_values.push(expression(arg_expr, env, p)?);
     }
+    let user_fxn = select_function_overload(&fxn_call.name, overloads, &input_arg_values, p)?;
     return execute_user_function(&user_fxn, &input_arg_values, p);
   }
 
@@ -116,10 +138,21 @@ pub fn function_call(fxn_call: &FunctionCall, env: Option<&Environment>, p: &Int
   };
   match fxn_compiler {
     Some(fxn_compiler) => {
-      let mut input_arg_values = vec![];
-      for (_, arg_expr) in fxn_call.args.iter() {
-        input_arg_values.push(expression(arg_expr, env, p)?);
+      // Refuse functions that need a capability the embedder hasn't granted.
+      if let Some(capability) = fxn_compiler.capability() {
+        if !p.has_capability(capability) {
+          return Err(MechError::new(
+            MissingCapabilityError {
+              fxn_name: fxn_call.name.to_string(),
+              capability,
+            },
+            None,
+          )
+          .with_compiler_loc()
+          .with_tokens(fxn_call.name.tokens()));
+        }
       }
+      let (input_arg_values, constants) = native_function_arguments(fxn_call, fxn_compiler.parameters(), env, p)?;
       trace_println!(
         p,
         "{}",
@@ -132,7 +165,10 @@ pub fn function_call(fxn_call: &FunctionCall, env: Option<&Environment>, p: &Int
           ),
         )
       );
-      execute_native_function_compiler(fxn_compiler, &input_arg_values, p)
+      // Arguments that are Mech functions become callbacks into this runtime.
+      let fxn_name = fxn_call.name.to_string();
+      let compiled = fxn_compiler.compile_with_constants(&input_arg_values, &constants, &mut |value| resolve_callback(&fxn_name, value, env, p));
+      run_native_function(compiled, &input_arg_values, p)
     }
     // No implementation found under this name at all.
     None => Err(MechError::new(
@@ -146,6 +182,383 @@ pub fn function_call(fxn_call: &FunctionCall, env: Option<&Environment>, p: &Int
   }
 }
 
+// Evaluates the arguments of a native function call, along with whether each
+// one is a constant. When the function names its parameters, a named argument
+// goes to the parameter of that name and a positional one to the parameter
+// after the previous argument.
+fn native_function_arguments(fxn_call: &FunctionCall, parameters: &[&str], env: Option<&Environment>, p: &Interpreter) -> MResult<(Vec<Value>, Vec<bool>)> {
+  let mut input_arg_values = vec![];
+  let mut constants = vec![];
+  if parameters.is_empty() {
+    for (_, arg_expr) in fxn_call.args.iter() {
+      let (value, constant) = native_function_argument(arg_expr, env, p)?;
+      input_arg_values.push(value);
+      constants.push(constant);
+    }
+    return Ok((input_arg_values, constants));
+  }
+  let mut slots: Vec<Option<(Value, bool)>> = vec![];
+  let mut next = 0;
+  for (arg_name, arg_expr) in fxn_call.args.iter() {
+    let ix = match arg_name {
+      Some(arg_name) => match parameters.iter().position(|param| *param == arg_name.to_string()) {
+        Some(ix) => ix,
+        None => {
+          return Err(MechError::new(
+            UnknownArgumentNameError {
+              function_name: fxn_call.name.to_string(),
+              argument_name: arg_name.to_string(),
+              parameters: parameters.iter().map(|param| param.to_string()).collect(),
+            },
+            None,
+          )
+          .with_compiler_loc()
+          .with_tokens(arg_name.tokens()))
+        }
+      },
+      None => next,
+    };
+    if slots.len() <= ix {
+      slots.resize(ix + 1, None);
+    }
+    if slots[ix].is_some() {
+      return Err(MechError::new(
+        DuplicateArgumentError {
+          function_name: fxn_call.name.to_string(),
+          argument_name: parameters.get(ix).map(|param| param.to_string()).unwrap_or_else(|| (ix + 1).to_string()),
+        },
+        None,
+      )
+      .with_compiler_loc()
+      .with_tokens(fxn_call.name.tokens()));
+    }
+    slots[ix] = Some(native_function_argument(arg_expr, env, p)?);
+    next = ix + 1;
+  }
+  // Arguments may only be left out at the end.
+  for (ix, slot) in slots.into_iter().enumerate() {
+    match slot {
+      Some((value, constant)) => {
+        input_arg_values.push(value);
+        constants.push(constant);
+      }
+      None => {
+        return Err(MechError::new(
+          MissingArgumentError {
+            function_name: fxn_call.name.to_string(),
+            argument_name: parameters[ix].to_string(),
+          },
+          None,
+        )
+        .with_compiler_loc()
+        .with_tokens(fxn_call.name.tokens()))
+      }
+    }
+  }
+  Ok((input_arg_values, constants))
+}
+
+// An argument is a constant when it names no variable and every function it
+// adds to the plan is deterministic, so solving again can't change its value.
+fn native_function_argument(arg_expr: &Expression, env: Option<&Environment>, p: &Interpreter) -> MResult<(Value, bool)> {
+  let plan = p.plan();
+  let first_step = plan.0.borrow().len();
+  let value = expression(arg_expr, env, p)?;
+  let names_variable = {
+    let symbols = p.symbols();
+    let symbols_brrw = symbols.borrow();
+    arg_expr.tokens().iter()
+      .filter(|token| token.kind == TokenKind::Identifier)
+      .map(|token| hash_chars(&token.chars))
+      .any(|id| symbols_brrw.symbols.contains_key(&id) || env.map_or(false, |env| env.contains_key(&id)))
+  };
+  let constant = !names_variable && plan.0.borrow()[first_step..].iter().all(|fxn| fxn.deterministic());
+  Ok((value, constant))
+}
+
+// Resolves an argument that a native function calls back into: a lambda, or
+// the name of a lambda or user function. The callback may run whenever the
+// plan solves, which may be in the middle of a step, so it runs in an
+// interpreter of its own that shares this one's functions and capabilities.
+pub(crate) fn resolve_callback(fxn_name: &str, value: &Value, env: Option<&Environment>, p: &Interpreter) -> MResult<MechCallback> {
+  let mut sub_interpreter = Interpreter::new(p.id);
+  sub_interpreter.set_functions(p.functions().clone());
+  sub_interpreter.capabilities = p.capabilities.clone();
+  match detach_value(value) {
+    Value::Lambda(lmbd) => {
+      let lmbd = lmbd.borrow().clone();
+      Ok(MechCallback::new(lmbd.to_string(), Box::new(move |args| execute_lambda(&lmbd, &args, &sub_interpreter))))
+    }
+    Value::String(name) => {
+      let name = name.borrow().clone();
+      let name_id = hash_str(&name);
+      if let Some(lmbd) = lookup_lambda(name_id, env, p) {
+        return Ok(MechCallback::new(name, Box::new(move |args| execute_lambda(&lmbd, &args, &sub_interpreter))));
+      }
+      let overloads = { p.functions().borrow().user_functions.get(&name_id).cloned() };
+      match overloads {
+        Some(overloads) => {
+          let ident = Identifier { name: Token::new(TokenKind::Identifier, SourceRange::default(), name.chars().collect()) };
+          Ok(MechCallback::new(name, Box::new(move |args| {
+            let user_fxn = select_function_overload(&ident, overloads.clone(), &args, &sub_interpreter)?;
+            execute_user_function(&user_fxn, &args, &sub_interpreter)
+          })))
+        }
+        None => Err(MechError::new(MissingFunctionError { function_id: name_id }, None).with_compiler_loc()),
+      }
+    }
+    value => Err(MechError::new(
+      UnhandledFunctionArgumentKind1 { arg: value.kind(), fxn_name: fxn_name.to_string() },
+      None,
+    )
+    .with_compiler_loc()),
+  }
+}
+
+// Overloads
+// ----------------------------------------------------------------------------
+
+// Chooses which definition of an overloaded function to call. A lone
+// definition is always chosen, so its own arity and conversion checks report
+// any problem. Otherwise each candidate with the right arity is scored by how
+// specifically its input kinds match the argument kinds, and the highest score
+// wins. When nothing matches directly, candidates whose input kinds every
+// argument converts to are considered instead. Ties are an error.
+fn select_function_overload(
+  name: &Identifier,
+  mut overloads: Vec<FunctionDefinition>,
+  input_arg_values: &Vec<Value>,
+  p: &Interpreter,
+) -> MResult<FunctionDefinition> {
+  if overloads.len() == 1 {
+    return Ok(overloads.remove(0));
+  }
+  let input_args: Vec<Value> = input_arg_values.iter().map(detach_value).collect();
+  let mut best_score = None;
+  let mut best = vec![];
+  let mut convertible = vec![];
+  for fxn_def in overloads.iter() {
+    if fxn_def.input.len() != input_args.len() {
+      continue;
+    }
+    let input_kinds = function_input_kinds(fxn_def, p)?;
+    match overload_specificity(&input_kinds, &input_args, p) {
+      Some(score) => match best_score {
+        Some(best_so_far) if score < best_so_far => (),
+        Some(best_so_far) if score == best_so_far => best.push(fxn_def),
+        _ => {
+          best_score = Some(score);
+          best = vec![fxn_def];
+        }
+      },
+      None => {
+        let converts = input_args.iter().zip(input_kinds.iter()).all(|(arg, kind)| {
+          arg.clone().convert_to(kind).is_some()
+        });
+        if converts {
+          convertible.push(fxn_def);
+        }
+      }
+    }
+  }
+  let candidates = if best.is_empty() { convertible } else { best };
+  match candidates.len() {
+    1 => Ok(candidates[0].clone()),
+    0 => Err(MechError::new(
+      FunctionOverloadNotFoundError {
+        function_name: name.to_string(),
+        found: input_args.iter().map(|arg| arg.kind()).collect(),
+      },
+      None,
+    )
+    .with_compiler_loc()
+    .with_tokens(name.tokens())),
+    _ => {
+      let mut signatures = vec![];
+      for fxn_def in candidates {
+        signatures.push(function_signature(fxn_def, p)?);
+      }
+      Err(MechError::new(
+        FunctionOverloadAmbiguousError {
+          function_name: name.to_string(),
+          candidates: signatures,
+        },
+        None,
+      )
+      .with_compiler_loc()
+      .with_tokens(name.tokens()))
+    }
+  }
+}
+
+// A recursive call can only loop in place when it resolves to the definition
+// that is already running, which is always the case without overloads.
+fn resolves_to_same_overload(fxn_def: &FunctionDefinition, input_arg_values: &Vec<Value>, p: &Interpreter) -> MResult<bool> {
+  let overloads = { p.functions().borrow().user_functions.get(&fxn_def.id).cloned() }.unwrap_or_default();
+  if overloads.len() <= 1 {
+    return Ok(true);
+  }
+  let selected = select_function_overload(&fxn_def.code.name, overloads, input_arg_values, p)?;
+  Ok(selected.code == fxn_def.code)
+}
+
+// Scores how well a list of arguments fits a list of input kinds: an exact
+// kind match counts for more than a shape-generic matrix match, which counts
+// for more than an `Any` input. Returns None if any argument doesn't fit.
+fn overload_specificity(input_kinds: &Vec<ValueKind>, input_args: &Vec<Value>, p: &Interpreter) -> Option<usize> {
+  let mut score = 0;
+  for (kind, arg) in input_kinds.iter().zip(input_args.iter()) {
+    let arg_kind = arg.kind();
+    score += match kind {
+      ValueKind::Any => 0,
+      #[cfg(all(feature = "enum", feature = "atom"))]
+      ValueKind::Enum(enum_id, _) => {
+        if enum_value_matches(arg.clone(), *enum_id, &p.state.borrow()) { 2 } else { return None; }
+      }
+      kind if *kind == arg_kind => 2,
+      kind if argument_kind_matches(kind, &arg_kind) => 1,
+      _ => return None,
+    };
+  }
+  Some(score)
+}
+
+// Resolves the declared input kinds of a function definition.
+fn function_input_kinds(fxn_def: &FunctionDefinition, p: &Interpreter) -> MResult<Vec<ValueKind>> {
+  let mut kinds = vec![];
+  for (_, input_kind_annotation) in fxn_def.input.iter() {
+    #[cfg(feature = "kind_annotation")]
+    kinds.push(kind_annotation(&input_kind_annotation.kind, p)?.to_value_kind(&p.state.borrow().kinds)?);
+    #[cfg(not(feature = "kind_annotation"))]
+    kinds.push(ValueKind::Any);
+  }
+  Ok(kinds)
+}
+
+fn function_signature(fxn_def: &FunctionDefinition, p: &Interpreter) -> MResult<String> {
+  let kinds = function_input_kinds(fxn_def, p)?
+    .iter()
+    .map(|kind| format!("<{}>", kind))
+    .collect::<Vec<String>>()
+    .join(", ");
+  Ok(format!("{}({})", fxn_def.name, kinds))
+}
+
+// Lambdas
+// ----------------------------------------------------------------------------
+
+// Evaluates a lambda expression into a function value. The symbols and
+// enclosing bindings that the body names are snapshotted into the lambda, so
+// its body resolves free variables at the definition site, not the call site.
+pub fn lambda(lmbd: &Lambda, env: Option<&Environment>, p: &Interpreter) -> MResult<Value> {
+  let mut input = vec![];
+  for param in &lmbd.params {
+    let kind = match &param.kind {
+      #[cfg(feature = "kind_annotation")]
+      Some(knd) => kind_annotation(&knd.kind, p)?.to_value_kind(&p.state.borrow().kinds)?,
+      _ => ValueKind::Any,
+    };
+    input.push(kind);
+  }
+  let free_ids: HashSet<u64> = lmbd.body.tokens().iter()
+    .filter(|token| token.kind == TokenKind::Identifier)
+    .map(|token| hash_chars(&token.chars))
+    .collect();
+  let mut captures = Environment::new();
+  {
+    let symbols = p.symbols();
+    let symbols_brrw = symbols.borrow();
+    for id in free_ids.iter() {
+      if let Some(cell) = symbols_brrw.symbols.get(id) {
+        captures.insert(*id, detach_value(&cell.borrow()));
+      }
+    }
+  }
+  // Bindings from an enclosing comprehension, match arm, or lambda shadow
+  // symbols of the same name.
+  if let Some(env) = env {
+    for id in free_ids.iter() {
+      if let Some(value) = env.get(id) {
+        captures.insert(*id, detach_value(value));
+      }
+    }
+  }
+  Ok(Value::Lambda(Ref::new(MechLambda::new(lmbd.clone(), input, captures))))
+}
+
+// Finds a lambda value bound under the given name, looking in the local
+// environment first and then in the symbol table.
+fn lookup_lambda(name_id: u64, env: Option<&Environment>, p: &Interpreter) -> Option<MechLambda> {
+  let value = match env.and_then(|env| env.get(&name_id)) {
+    Some(value) => detach_value(value),
+    None => {
+      let symbols = p.symbols();
+      let symbols_brrw = symbols.borrow();
+      let cell = symbols_brrw.get(name_id)?;
+      let value = detach_value(&cell.borrow());
+      value
+    }
+  };
+  match value {
+    Value::Lambda(lmbd) => Some(lmbd.borrow().clone()),
+    _ => None,
+  }
+}
+
+// Runs a lambda body in a fresh scope. Arguments are converted to the
+// annotated parameter kinds and layered over the captured environment.
+pub fn execute_lambda(lmbd: &MechLambda, input_arg_values: &Vec<Value>, p: &Interpreter) -> MResult<Value> {
+  if input_arg_values.len() != lmbd.input.len() {
+    return Err(MechError::new(
+      IncorrectNumberOfArguments {
+        expected: lmbd.input.len(),
+        found: input_arg_values.len(),
+      },
+      None,
+    )
+    .with_compiler_loc()
+    .with_tokens(lmbd.code.tokens()));
+  }
+
+  let mut call_env = lmbd.captures.clone();
+  for ((param, kind), input_value) in lmbd.code.params.iter().zip(lmbd.input.iter()).zip(input_arg_values.iter()) {
+    let detached_input = detach_value(input_value);
+    let bound_value = match kind {
+      ValueKind::Any => detached_input,
+      kind => detached_input
+        .clone()
+        .convert_to(kind)
+        .ok_or_else(|| {
+          MechError::new(
+            FunctionInputTypeMismatchError {
+              function_name: lmbd.to_string(),
+              argument_name: param.name.to_string(),
+              expected: kind.clone(),
+              found: detached_input.kind(),
+            },
+            None,
+          )
+          .with_compiler_loc()
+          .with_tokens(param.tokens())
+        })?,
+    };
+    call_env.insert(param.name.hash(), bound_value);
+  }
+
+  trace_println!(
+    p,
+    "{}",
+    format_trace(
+      "fn",
+      format!("enter {}({})", lmbd.to_string(), format_trace_args(input_arg_values)),
+    )
+  );
+  let scope = FunctionScope::enter(p);
+  let output = expression(&lmbd.code.body, Some(&call_env), p).map(|value| detach_value(&value));
+  drop(scope);
+  output
+}
+
 // Asks a native function compiler to select the right concrete implementation
 // for the given argument types, runs it once to produce an initial value, then
 // pushes it onto the reactive plan so it re-runs when its inputs change.
@@ -153,9 +566,17 @@ pub fn execute_native_function_compiler(
   fxn_compiler: &'static dyn NativeFunctionCompiler,
   input_arg_values: &Vec<Value>,
   p: &Interpreter,
+) -> MResult<Value> {
+  run_native_function(fxn_compiler.compile(input_arg_values), input_arg_values, p)
+}
+
+fn run_native_function(
+  compiled: MResult<Box<dyn MechFunction>>,
+  input_arg_values: &Vec<Value>,
+  p: &Interpreter,
 ) -> MResult<Value> {
   let plan = p.plan();
-  match fxn_compiler.compile(input_arg_values) {
+  match compiled {
     Ok(mut new_fxn) => {
       trace_println!(
         p,
@@ -397,14 +818,15 @@ fn execute_function_match_arms(
 
   // Exhaustiveness check: when the single input is an enum type and there is
   // no wildcard arm, every variant must be covered or we report which ones
-  // are missing before even attempting to run.
+  // are missing before even attempting to run. Guarded arms can decline a
+  // value their pattern matches, so they don't count towards coverage.
   #[cfg(all(feature = "kind_annotation", feature = "enum"))]
   {
     let has_wildcard = fxn_def
       .code
       .match_arms
       .iter()
-      .any(|arm| matches!(arm.pattern, Pattern::Wildcard));
+      .any(|arm| arm.guards.is_empty() && matches!(arm.pattern, Pattern::Wildcard));
     if !has_wildcard && fxn_def.input.len() == 1 {
       if let Some((_, kind_annotation_node)) = fxn_def.input.iter().next() {
         let input_kind = kind_annotation(&kind_annotation_node.kind, p)?
@@ -414,7 +836,7 @@ fn execute_function_match_arms(
           if let Some(enum_def) = state_brrw.enums.get(&enum_id) {
             // Collect every variant name that appears in the written arms.
             let mut covered_variants: HashSet<u64> = HashSet::new();
-            for arm in &fxn_def.code.match_arms {
+            for arm in fxn_def.code.match_arms.iter().filter(|arm| arm.guards.is_empty()) {
               match &arm.pattern {
                 #[cfg(feature = "atom")]
                 Pattern::TupleStruct(tuple_struct) => {
@@ -468,7 +890,8 @@ fn execute_function_match_arms(
     }
   }
 
-  // Try each arm in source order; the first one whose pattern matches wins.
+  // Try each arm in source order; the first one whose pattern matches and
+  // whose guards all hold wins.
   for (arm_idx, arm) in fxn_def.code.match_arms.iter().enumerate() {
     let mut env = Environment::new();
     let matched = crate::patterns::pattern_matches_arguments(
@@ -488,7 +911,7 @@ fn execute_function_match_arms(
         ),
       )
     });
-    if matched {
+    if matched && function_arm_guards_pass(fxn_def, arm_idx, arm, &env, p)? {
       // Tail-call optimisation: if the arm body is a direct recursive call
       // with the same arity, return new arguments instead of recursing.
       if let Expression::FunctionCall(fxn_call) = &arm.expression {
@@ -497,7 +920,7 @@ fn execute_function_match_arms(
           for (_, arg_expr) in fxn_call.args.iter() {
             tail_args.push(expression(arg_expr, Some(&env), p)?);
           }
-          if tail_args.len() == fxn_def.input.len() {
+          if tail_args.len() == fxn_def.input.len() && resolves_to_same_overload(fxn_def, &tail_args, p)? {
             trace_println!(
               p,
               "{}",
@@ -539,6 +962,50 @@ fn execute_function_match_arms(
   .with_tokens(fxn_def.code.name.tokens()))
 }
 
+// Evaluates an arm's guards in order with the pattern's bindings in scope.
+// Every guard must evaluate to true for the arm to be taken.
+fn function_arm_guards_pass(
+  fxn_def: &FunctionDefinition,
+  arm_idx: usize,
+  arm: &FunctionMatchArm,
+  env: &Environment,
+  p: &Interpreter,
+) -> MResult<bool> {
+  for (guard_idx, guard) in arm.guards.iter().enumerate() {
+    let guard_passes = match detach_value(&expression(guard, Some(env), p)?) {
+      Value::Bool(x) => *x.borrow(),
+      other => {
+        return Err(MechError::new(
+          FunctionGuardKindMismatchError {
+            function_name: fxn_def.name.clone(),
+            arm_index: arm_idx,
+            guard_index: guard_idx,
+            actual_kind: other.kind(),
+          },
+          None,
+        )
+        .with_compiler_loc()
+        .with_tokens(guard.tokens()));
+      }
+    };
+    trace_println!(
+      p,
+      "{}",
+      format_trace(
+        "match",
+        format!(
+          "arm[{arm_idx}] check guard[{guard_idx}] {}",
+          if guard_passes { "✓" } else { "X" }
+        )
+      )
+    );
+    if !guard_passes {
+      return Ok(false);
+    }
+  }
+  Ok(true)
+}
+
 // Coerces a match-arm result to the function's declared output kind.
 // If no output annotation exists, or conversion fails, the value is returned as-is.
 #[cfg(feature = "kind_annotation")]
@@ -866,6 +1333,60 @@ impl MechErrorKind for MissingFunctionError {
   }
 }
 
+// A call names an argument that the function has no parameter for.
+#[derive(Debug, Clone)]
+pub struct UnknownArgumentNameError {
+  pub function_name: String,
+  pub argument_name: String,
+  pub parameters: Vec<String>,
+}
+
+impl MechErrorKind for UnknownArgumentNameError {
+  fn name(&self) -> &str {
+    "UnknownArgumentName"
+  }
+  fn message(&self) -> String {
+    format!(
+      "Function '{}' has no argument named '{}'; its arguments are {}",
+      self.function_name,
+      self.argument_name,
+      self.parameters.join(", ")
+    )
+  }
+}
+
+// A call gives the same argument twice, by name or by position.
+#[derive(Debug, Clone)]
+pub struct DuplicateArgumentError {
+  pub function_name: String,
+  pub argument_name: String,
+}
+
+impl MechErrorKind for DuplicateArgumentError {
+  fn name(&self) -> &str {
+    "DuplicateArgument"
+  }
+  fn message(&self) -> String {
+    format!("Function '{}' was given argument '{}' more than once", self.function_name, self.argument_name)
+  }
+}
+
+// A call names a later argument but leaves out an earlier one.
+#[derive(Debug, Clone)]
+pub struct MissingArgumentError {
+  pub function_name: String,
+  pub argument_name: String,
+}
+
+impl MechErrorKind for MissingArgumentError {
+  fn name(&self) -> &str {
+    "MissingArgument"
+  }
+  fn message(&self) -> String {
+    format!("Function '{}' is missing argument '{}'", self.function_name, self.argument_name)
+  }
+}
+
 // A function's output variable was declared but never assigned during execution.
 #[derive(Debug, Clone)]
 pub struct FunctionOutputUndefinedError {
@@ -905,6 +1426,67 @@ impl MechErrorKind for FunctionMatchNonExhaustiveError {
   }
 }
 
+// A guard on a function match arm evaluated to something other than a Bool.
+#[derive(Debug, Clone)]
+pub struct FunctionGuardKindMismatchError {
+  pub function_name: String,
+  pub arm_index: usize,
+  pub guard_index: usize,
+  pub actual_kind: ValueKind,
+}
+
+impl MechErrorKind for FunctionGuardKindMismatchError {
+  fn name(&self) -> &str {
+    "FunctionGuardKindMismatch"
+  }
+
+  fn message(&self) -> String {
+    format!(
+      "Function '{}' guard arm[{}] guard[{}] must evaluate to Bool, got '{}'",
+      self.function_name, self.arm_index, self.guard_index, self.actual_kind
+    )
+  }
+}
+
+// No overload of a function accepts the kinds of the given arguments.
+#[derive(Debug, Clone)]
+pub struct FunctionOverloadNotFoundError {
+  pub function_name: String,
+  pub found: Vec<ValueKind>,
+}
+
+impl MechErrorKind for FunctionOverloadNotFoundError {
+  fn name(&self) -> &str {
+    "FunctionOverloadNotFound"
+  }
+
+  fn message(&self) -> String {
+    let found = self.found.iter().map(|kind| format!("<{}>", kind)).collect::<Vec<String>>().join(", ");
+    format!("No overload of function '{}' accepts arguments ({})", self.function_name, found)
+  }
+}
+
+// Two or more overloads of a function match the given arguments equally well.
+#[derive(Debug, Clone)]
+pub struct FunctionOverloadAmbiguousError {
+  pub function_name: String,
+  pub candidates: Vec<String>,
+}
+
+impl MechErrorKind for FunctionOverloadAmbiguousError {
+  fn name(&self) -> &str {
+    "FunctionOverloadAmbiguous"
+  }
+
+  fn message(&self) -> String {
+    format!(
+      "Call to function '{}' is ambiguous between overloads: {}",
+      self.function_name,
+      self.candidates.join(", ")
+    )
+  }
+}
+
 // A value passed to a function argument didn't match the declared kind and
 // couldn't be coerced to it.
 #[derive(Debug, Clone)]
@@ -1,6 +1,6 @@
 use crate::*;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{Cursor, Read, Write};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::rc::Rc;
@@ -37,6 +37,8 @@ pub struct Interpreter {
   #[cfg(feature = "state_machines")]
   pub user_state_machine_specs: Ref<HashMap<u64, FsmSpecification>>,
   pub sub_interpreters: Ref<HashMap<u64, Box<Interpreter>>>,
+  #[cfg(feature = "functions")]
+  pub(crate) capabilities: HashSet<Capability>,
 }
 
 impl Clone for Interpreter {
@@ -68,6 +70,8 @@ impl Clone for Interpreter {
       #[cfg(feature = "state_machines")]
       user_state_machine_specs: self.user_state_machine_specs.clone(),
       sub_interpreters: self.sub_interpreters.clone(),
+      #[cfg(feature = "functions")]
+      capabilities: self.capabilities.clone(),
     }
   }
 }
@@ -120,6 +124,8 @@ impl Interpreter {
       code: Vec::new(),
       #[cfg(feature = "compiler")]
       context: None,
+      #[cfg(feature = "functions")]
+      capabilities: HashSet::new(),
     }
   }
 
@@ -169,7 +175,13 @@ impl Interpreter {
 
   pub fn clear(&mut self) {
     let id = self.id;
+    #[cfg(feature = "functions")]
+    let capabilities = std::mem::take(&mut self.capabilities);
     *self = Interpreter::new(id);
+    #[cfg(feature = "functions")]
+    {
+      self.capabilities = capabilities;
+    }
   }
 
   pub fn set_trace_enabled(&mut self, enabled: bool) {
@@ -266,6 +278,25 @@ impl Interpreter {
     self.state.borrow_mut().functions = functions;
   }
 
+  // Lets programs call functions that need the capability, such as the io
+  // machine's file functions for Capability::FileSystem.
+  #[cfg(feature = "functions")]
+  pub fn grant_capability(&mut self, capability: Capability) {
+    self.capabilities.insert(capability);
+  }
+
+  #[cfg(feature = "functions")]
+  pub fn has_capability(&self, capability: Capability) -> bool {
+    self.capabilities.contains(&capability)
+  }
+
+  // How long until the earliest timer in the plan ticks, or None when nothing
+  // in the plan is driven by a clock.
+  #[cfg(feature = "functions")]
+  pub fn next_tick(&self) -> Option<Duration> {
+    self.state.borrow().plan.borrow().iter().filter_map(|fxn| fxn.next_tick()).min()
+  }
+
   #[cfg(feature = "functions")]
   pub fn step(&mut self, step_id: usize, step_count: u64) -> MResult<Value> {
     let state_brrw = self.state.borrow();
@@ -402,8 +433,57 @@ impl Interpreter {
   }
     
 
+  // Compiled functions record the name of the native function they came from
+  // as a custom feature, so a program's feature list says which capabilities
+  // it needs. The list comes from the program's header, so every instruction
+  // is checked against its function descriptor as well.
+  #[cfg(feature = "program")]
+  fn check_program_capabilities(&self, program: &ParsedProgram) -> MResult<()> {
+    let state_brrw = self.state.borrow();
+    let functions_table = state_brrw.functions.borrow();
+    for feature in program.features.iter() {
+      let capability = match functions_table.function_compilers.get(feature) {
+        Some(fxn_compiler) => fxn_compiler.capability(),
+        None => None,
+      };
+      match capability {
+        Some(capability) if !self.has_capability(capability) => {
+          let fxn_name = inventory::iter::<FunctionCompilerDescriptor>
+            .into_iter()
+            .find(|desc| hash_str(desc.name) == *feature)
+            .map(|desc| desc.name.to_string())
+            .unwrap_or_else(|| format!("{}", feature));
+          return Err(MechError::new(
+            MissingCapabilityError { fxn_name, capability },
+            None,
+          )
+          .with_compiler_loc());
+        }
+        _ => (),
+      }
+    }
+    for fxn_id in program.instrs.iter().filter_map(|instr| instr.fxn_id()) {
+      match functions_table.capabilities.get(&fxn_id) {
+        Some(capability) if !self.has_capability(*capability) => {
+          let fxn_name = functions_table.dictionary.borrow().get(&fxn_id)
+            .cloned()
+            .unwrap_or_else(|| format!("{}", fxn_id));
+          return Err(MechError::new(
+            MissingCapabilityError { fxn_name, capability: *capability },
+            None,
+          )
+          .with_compiler_loc());
+        }
+        _ => (),
+      }
+    }
+    Ok(())
+  }
+
   #[cfg(feature = "program")]
   pub fn run_program(&mut self, program: &ParsedProgram) -> MResult<Value> {
+    // Refuse programs that use functions needing an ungranted capability
+    self.check_program_capabilities(program)?;
     // Reset the instruction pointer
     self.ip = 0;
     // Resize the registers and constant table
@@ -422,7 +502,9 @@ impl Interpreter {
         symbol_table.insert(*id, constant, mutable);
       }
     }
-    // Load the instructions
+    // Load the instructions. A function's output register holds the result
+    // it had when the program was built, which is only safe to reuse for
+    // deterministic functions; the rest are solved again as they load.
     {
       let state_brrw = self.state.borrow();
       let functions_table = state_brrw.functions.borrow();
@@ -438,6 +520,7 @@ impl Interpreter {
               Some(fxn_factory) => {
                 let out = &self.registers[*dst as usize];
                 let fxn = fxn_factory(FunctionArgs::Nullary(out.clone()))?;
+                if !fxn.deterministic() { fxn.solve(); }
                 self.out = fxn.out().clone();
                 state_brrw.add_plan_step(fxn);
               }
@@ -457,6 +540,7 @@ impl Interpreter {
                 let out = &self.registers[*dst as usize];
                 let fxn =
                     fxn_factory(FunctionArgs::Unary(out.clone(), src.clone()))?;
+                if !fxn.deterministic() { fxn.solve(); }
                 self.out = fxn.out().clone();
                 state_brrw.add_plan_step(fxn);
               }
@@ -476,6 +560,7 @@ impl Interpreter {
               let rhs = &self.registers[*rhs as usize];
               let out = &self.registers[*dst as usize];
               let fxn = fxn_factory(FunctionArgs::Binary(out.clone(),lhs.clone(),rhs.clone()))?;
+              if !fxn.deterministic() { fxn.solve(); }
               self.out = fxn.out().clone();
               state_brrw.add_plan_step(fxn);
             }
@@ -500,6 +585,7 @@ impl Interpreter {
                 arg2.clone(),
                 arg3.clone(),
               ))?;
+              if !fxn.deterministic() { fxn.solve(); }
               self.out = fxn.out().clone();
               state_brrw.add_plan_step(fxn);
             }
@@ -526,6 +612,7 @@ impl Interpreter {
                     arg3.clone(),
                     arg4.clone(),
                 ))?;
+                if !fxn.deterministic() { fxn.solve(); }
                 self.out = fxn.out().clone();
                 state_brrw.add_plan_step(fxn);
               }
@@ -546,6 +633,7 @@ impl Interpreter {
                   .collect();
                 let out = &self.registers[*dst as usize];
                 let fxn = fxn_factory(FunctionArgs::Variadic(out.clone(), arg_values))?;
+                if !fxn.deterministic() { fxn.solve(); }
                 self.out = fxn.out().clone();
                 state_brrw.add_plan_step(fxn);
               }
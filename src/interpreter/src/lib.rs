@@ -66,6 +66,8 @@ use mech_core::R64;
 use mech_core::*;
 use mech_core::{hash_str, nodes::Kind as NodeKind, nodes::Matrix as Mat, nodes::*, MResult};
 use mech_core::{Dictionary, Ref, ToValue, ValRef, Value, ValueKind};
+#[cfg(feature = "io")]
+use mech_io::*;
 #[cfg(feature = "logic")]
 use mech_logic::*;
 #[cfg(feature = "math")]
@@ -80,12 +82,22 @@ use mech_range::exclusive_increment::*;
 use mech_range::inclusive::*;
 #[cfg(feature = "range_inclusive")]
 use mech_range::inclusive_increment::*;
+#[cfg(feature = "random")]
+use mech_random::*;
 #[cfg(feature = "set")]
 use mech_set::*;
 #[cfg(feature = "stats")]
 use mech_stats::*;
 #[cfg(feature = "string")]
 use mech_string::*;
+#[cfg(feature = "time")]
+use mech_time::*;
+#[cfg(feature = "signal")]
+use mech_signal::*;
+#[cfg(feature = "calculus")]
+use mech_calculus::*;
+#[cfg(feature = "bits")]
+use mech_bits::*;
 
 #[cfg(any(feature = "map", feature = "table", feature = "record"))]
 use indexmap::map::IndexMap;
@@ -102,6 +114,8 @@ pub mod interpreter;
 pub mod literals;
 pub mod mechdown;
 pub mod patterns;
+#[cfg(feature = "persist")]
+pub mod persist;
 #[cfg(feature = "state_machines")]
 pub mod state_machines;
 pub mod statements;
@@ -118,6 +132,8 @@ pub use crate::interpreter::*;
 pub use crate::literals::*;
 pub use crate::mechdown::*;
 pub use crate::patterns::*;
+#[cfg(feature = "persist")]
+pub use crate::persist::*;
 #[cfg(feature = "state_machines")]
 pub use crate::state_machines::*;
 pub use crate::statements::*;
@@ -146,10 +162,20 @@ pub use mech_logic::*;
 pub use mech_math::*;
 #[cfg(feature = "matrix")]
 pub use mech_matrix::*;
+#[cfg(feature = "random")]
+pub use mech_random::*;
 #[cfg(feature = "set")]
 pub use mech_set::*;
 #[cfg(feature = "stats")]
 pub use mech_stats::*;
+#[cfg(feature = "time")]
+pub use mech_time::*;
+#[cfg(feature = "signal")]
+pub use mech_signal::*;
+#[cfg(feature = "calculus")]
+pub use mech_calculus::*;
+#[cfg(feature = "bits")]
+pub use mech_bits::*;
 
 pub fn load_stdkinds(kinds: &mut KindTable) {
   #[cfg(feature = "u8")]
@@ -196,6 +222,10 @@ pub fn load_stdlib(fxns: &mut Functions) {
     fxns.function_compilers
       .insert(hash_str(fxn_comp.name), fxn_comp.ptr);
   }
+
+  for fxn_cap in inventory::iter::<FunctionCapabilityDescriptor> {
+    fxns.capabilities.insert(hash_str(fxn_cap.name), fxn_cap.capability);
+  }
 }
 
 fn format_duration(d: Duration) -> String {
@@ -93,6 +93,7 @@ pub fn section_element(element: &SectionElement, p: &Interpreter) -> MResult<Val
 
         let mut new_sub_interpreter =  Interpreter::new(code_id);
         new_sub_interpreter.set_functions(p.functions().clone());
+        new_sub_interpreter.capabilities = p.capabilities.clone();
 
         let mut pp = sub_interpreters
           .entry(code_id)
@@ -173,6 +174,7 @@ pub fn section_element(element: &SectionElement, p: &Interpreter) -> MResult<Val
         let mut sub_interpreters = p.sub_interpreters.borrow_mut();
         let mut new_sub_interpreter = Interpreter::new(mika_interp_id);
         new_sub_interpreter.set_functions(p.functions().clone());
+        new_sub_interpreter.capabilities = p.capabilities.clone();
         let pp = sub_interpreters
           .entry(mika_interp_id)
           .or_insert(Box::new(new_sub_interpreter))
@@ -17,6 +17,31 @@ pub enum PatternMatchSemantics {
   OptionGuard,  // Option guard semantics: pattern expressions are evaluated for each value and must evaluate to true for all to match.
 }
 
+// Checks an argument kind against a declared parameter kind. References are
+// looked through, and a matrix parameter without dimensions accepts a matrix
+// of any shape with the same element kind. Used by state machine arguments
+// and user function overload resolution.
+pub(crate) fn argument_kind_matches(expected: &ValueKind, actual: &ValueKind) -> bool {
+  fn strip_references<'a>(kind: &'a ValueKind) -> &'a ValueKind {
+    match kind {
+      ValueKind::Reference(inner) => strip_references(inner.as_ref()),
+      _ => kind,
+    }
+  }
+
+  let expected = strip_references(expected);
+  let actual = strip_references(actual);
+
+  match (expected, actual) {
+    (ValueKind::Matrix(expected_element, expected_dims), ValueKind::Matrix(actual_element, _actual_dims))
+      if expected_dims.is_empty() =>
+    {
+      expected_element.as_ref() == actual_element.as_ref()
+    }
+    _ => expected == actual,
+  }
+}
+
 // Entry point for multi-argument dispatch. When a function is called with 
 // multiple arguments, this wraps them as if they were a tuple and delegates 
 // to pattern_matches_value.
@@ -51,6 +76,10 @@ pub fn pattern_matches_value(pattern: &Pattern, value: &Value, env: &mut Environ
 // - Expression(Var()) - if the variable is already in the environment, checks equality (used for repeated variable patterns).
 // - Expression(other) - attempts to extract a variable id from more complex expression wrappers (parentheticals, single-term formulas) and handles them like Var.
 // - TupleStruct - matches a tagged tuple
+// - String - matches a literal prefix and/or suffix, binding the text between them.
+// - Map - looks up each key and matches its value; without `…` no other keys are allowed.
+// - Set - checks membership of each element; without `…` no other elements are allowed.
+// - Table - destructures columns by name; without `…` no other columns are allowed.
 pub fn pattern_matches_value_with_semantics(pattern: &Pattern, value: &Value, env: &mut Environment, p: &Interpreter, semantics: PatternMatchSemantics) -> MResult<bool> {
   let detached_value = deep_detach_value(value);
   match pattern {
@@ -200,6 +229,96 @@ pub fn pattern_matches_value_with_semantics(pattern: &Pattern, value: &Value, en
       }
       return Ok(false);
     }
+    #[cfg(feature = "string")]
+    Pattern::String(pattern_string) => {
+      let text = match &detached_value {
+        Value::String(text) => text.borrow().clone(),
+        _ => return Ok(false),
+      };
+      let prefix = pattern_string.prefix.as_ref().map(|prefix| prefix.to_string()).unwrap_or_default();
+      let suffix = pattern_string.suffix.as_ref().map(|suffix| suffix.to_string()).unwrap_or_default();
+      if text.len() < prefix.len() + suffix.len() || !text.starts_with(&prefix) || !text.ends_with(&suffix) {
+        return Ok(false);
+      }
+      match &pattern_string.binding {
+        Some(binding) => {
+          let middle = text[prefix.len()..text.len() - suffix.len()].to_string();
+          pattern_matches_value_with_semantics(binding, &Value::String(Ref::new(middle)), env, p, semantics)
+        }
+        None => Ok(true),
+      }
+    }
+    #[cfg(feature = "map")]
+    Pattern::Map(pattern_map) => {
+      let map = match &detached_value {
+        Value::Map(map) => map.clone(),
+        _ => return Ok(false),
+      };
+      let map_brrw = map.borrow();
+      if !pattern_map.rest && map_brrw.map.len() != pattern_map.entries.len() {
+        return Ok(false);
+      }
+      for entry in &pattern_map.entries {
+        let key = deep_detach_value(&expression(&entry.key, Some(env), p)?);
+        match map_brrw.map.get(&key) {
+          Some(value) => {
+            if !pattern_matches_value_with_semantics(&entry.value, value, env, p, semantics)? {
+              return Ok(false);
+            }
+          }
+          None => return Ok(false),
+        }
+      }
+      Ok(true)
+    }
+    #[cfg(feature = "set")]
+    Pattern::Set(pattern_set) => {
+      let set = match &detached_value {
+        Value::Set(set) => set.clone(),
+        _ => return Ok(false),
+      };
+      let set_brrw = set.borrow();
+      if !pattern_set.rest && set_brrw.set.len() != pattern_set.elements.len() {
+        return Ok(false);
+      }
+      for element in &pattern_set.elements {
+        let element = deep_detach_value(&expression(element, Some(env), p)?);
+        if !set_brrw.set.contains(&element) {
+          return Ok(false);
+        }
+      }
+      Ok(true)
+    }
+    #[cfg(feature = "table")]
+    Pattern::Table(pattern_table) => {
+      let table = match &detached_value {
+        Value::Table(table) => table.clone(),
+        _ => return Ok(false),
+      };
+      if !pattern_table.rest && table.borrow().cols != pattern_table.columns.len() {
+        return Ok(false);
+      }
+      for column in &pattern_table.columns {
+        let column_id = column.name.hash();
+        if table.borrow().get(&column_id).is_none() {
+          return Ok(false);
+        }
+        let access_fxn = TableAccessColumn{}.compile(&vec![Value::Table(table.clone()), Value::Id(column_id)])?;
+        access_fxn.solve();
+        let column_value = access_fxn.out();
+        let matched = match &column.pattern {
+          Some(column_pattern) => pattern_matches_value_with_semantics(column_pattern, &column_value, env, p, semantics)?,
+          None => {
+            let binding = Pattern::Expression(Expression::Var(Var{name: column.name.clone(), kind: None}));
+            pattern_matches_value_with_semantics(&binding, &column_value, env, p, semantics)?
+          }
+        };
+        if !matched {
+          return Ok(false);
+        }
+      }
+      Ok(true)
+    }
     x => Err(MechError::new(FeatureNotEnabledError, Some(format!("Pattern not enabled: {:?}", x))).with_compiler_loc().with_tokens(x.tokens())), 
   }
 }
@@ -222,6 +341,21 @@ pub fn pattern_to_value(pattern: &Pattern, env: &Environment, p: &Interpreter) -
   match pattern {
     Pattern::Wildcard => Ok(Value::Empty),
     Pattern::Expression(expr) => expression(expr, Some(env), p),
+    // Map and set patterns without a rest marker describe a whole value, so
+    // they can be rebuilt when every part of them is a plain expression.
+    #[cfg(feature = "map")]
+    Pattern::Map(map) if !map.rest => {
+      let mut elements = vec![];
+      for entry in &map.entries {
+        match &entry.value {
+          Pattern::Expression(value) => elements.push(Mapping{key: entry.key.clone(), value: value.clone()}),
+          _ => return Err(MechError::new(FeatureNotEnabledError, None).with_compiler_loc().with_tokens(entry.value.tokens())),
+        }
+      }
+      crate::structures::map(&Map{elements}, Some(env), p)
+    }
+    #[cfg(feature = "set")]
+    Pattern::Set(set) if !set.rest => crate::structures::set(&Set{elements: set.elements.clone()}, Some(env), p),
     #[cfg(feature = "tuple")]
     Pattern::Tuple(pattern_tuple) => {
       let mut values = Vec::with_capacity(pattern_tuple.0.len());
@@ -342,6 +476,24 @@ fn collect_pattern_variable_ids(pattern: &Pattern, ids: &mut Vec<u64>) {
         collect_pattern_variable_ids(item, ids);
       }
     }
+    Pattern::String(string) => {
+      if let Some(binding) = &string.binding {
+        collect_pattern_variable_ids(binding, ids);
+      }
+    }
+    Pattern::Map(map) => {
+      for entry in &map.entries {
+        collect_pattern_variable_ids(&entry.value, ids);
+      }
+    }
+    Pattern::Table(table) => {
+      for column in &table.columns {
+        match &column.pattern {
+          Some(pattern) => collect_pattern_variable_ids(pattern, ids),
+          None => ids.push(column.name.hash()),
+        }
+      }
+    }
     _ => {}
   }
 }
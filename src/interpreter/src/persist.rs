@@ -0,0 +1,361 @@
+use crate::*;
+use indexmap::map::IndexMap;
+use serde_json::{json, Value as Json};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Persist
+// ----------------------------------------------------------------------------
+
+// Keeps the values of selected mutable symbols in a directory so a program
+// can pick up where it left off after a restart. The directory holds two
+// files:
+//
+//   snapshot.json   {"seq": n, "symbols": [entry, ..]}, the compacted state
+//   wal.jsonl       one entry per line, appended after each step
+//
+// An entry is {"seq": n, "name": "x", "kind": <kind>, "value": <json>}, with
+// the value encoded as in mech-io's JSON functions and decoded back at its
+// recorded kind. Only symbols whose value changed since they were last
+// written are appended. On open, the snapshot is loaded and then the log is
+// replayed over it, skipping entries already folded into the snapshot and
+// cutting off a torn last line left by a crash. Once the log reaches `compact_after`
+// entries, the state is written to a fresh snapshot and the log is emptied.
+// Symbols whose values have no JSON encoding, such as index matrices, are
+// left out and listed in `skipped`.
+
+pub const PERSIST_SNAPSHOT_FILE: &str = "snapshot.json";
+pub const PERSIST_LOG_FILE: &str = "wal.jsonl";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersistEntry {
+  pub seq: u64,
+  pub kind: ValueKind,
+  pub value: Json,
+}
+
+#[derive(Debug)]
+pub struct Persister {
+  pub dir: PathBuf,
+  // Names of the symbols to persist; all mutable symbols when None.
+  pub symbols: Option<Vec<String>>,
+  pub compact_after: usize,
+  pub entries: IndexMap<String, PersistEntry>,
+  pub skipped: IndexMap<String, ValueKind>,
+  seq: u64,
+  log_len: usize,
+  log: File,
+}
+
+impl Persister {
+
+  pub fn open(dir: impl Into<PathBuf>) -> MResult<Persister> {
+    let dir = dir.into();
+    fs::create_dir_all(&dir).map_err(|err| persist_io_error(&dir, err))?;
+    let mut entries = IndexMap::new();
+    let mut seq = 0;
+    let snapshot_path = dir.join(PERSIST_SNAPSHOT_FILE);
+    if snapshot_path.exists() {
+      seq = read_snapshot(&snapshot_path, &mut entries)?;
+    }
+    let log_path = dir.join(PERSIST_LOG_FILE);
+    let log_len = if log_path.exists() { replay_log(&log_path, &mut seq, &mut entries)? } else { 0 };
+    let log = OpenOptions::new().create(true).append(true).open(&log_path).map_err(|err| persist_io_error(&log_path, err))?;
+    Ok(Persister { dir, symbols: None, compact_after: 1024, entries, skipped: IndexMap::new(), seq, log_len, log })
+  }
+
+  pub fn with_symbols(mut self, names: Vec<String>) -> Self {
+    self.symbols = Some(names);
+    self
+  }
+
+  pub fn with_compact_after(mut self, entries: usize) -> Self {
+    self.compact_after = entries.max(1);
+    self
+  }
+
+  // Writes the persisted values into the interpreter's symbols and returns
+  // how many were restored. Values are copied into the existing cells, so
+  // functions already compiled against a symbol see the restored value on
+  // their next step. Symbols the program no longer defines, or whose kind has
+  // changed, are left as the program set them.
+  pub fn restore(&self, intrp: &Interpreter) -> MResult<usize> {
+    let mut restored = 0;
+    for (name, cell) in self.selected_symbols(intrp) {
+      let entry = match self.entries.get(&name) {
+        Some(entry) => entry,
+        None => continue,
+      };
+      if !same_kind_family(&cell.borrow().kind(), &entry.kind) {
+        continue;
+      }
+      let value = json_to_kind(&entry.value, &entry.kind)?;
+      let written = write_in_place(&cell.borrow(), &value);
+      if !written {
+        *cell.borrow_mut() = value;
+      }
+      restored += 1;
+    }
+    Ok(restored)
+  }
+
+  // Appends an entry for each selected symbol whose value differs from the
+  // one last written, then syncs the log. Returns the number of entries
+  // appended.
+  pub fn record(&mut self, intrp: &Interpreter) -> MResult<usize> {
+    let log_path = self.dir.join(PERSIST_LOG_FILE);
+    let mut lines = String::new();
+    let mut appended = 0;
+    for (name, cell) in self.selected_symbols(intrp) {
+      let value = cell.borrow();
+      let kind = value.kind();
+      let json = match value_to_json(&value) {
+        Ok(json) => json,
+        Err(_) => {
+          self.skipped.entry(name).or_insert(kind);
+          continue;
+        }
+      };
+      if let Some(entry) = self.entries.get(&name) {
+        if entry.kind == kind && entry.value == json {
+          continue;
+        }
+      }
+      self.seq += 1;
+      let line = json!({"seq": self.seq, "name": name, "kind": kind_to_json(&kind)?, "value": json});
+      lines.push_str(&line.to_string());
+      lines.push('\n');
+      self.entries.insert(name, PersistEntry { seq: self.seq, kind, value: json });
+      appended += 1;
+    }
+    if appended > 0 {
+      self.log.write_all(lines.as_bytes()).map_err(|err| persist_io_error(&log_path, err))?;
+      self.log.sync_data().map_err(|err| persist_io_error(&log_path, err))?;
+      self.log_len += appended;
+      if self.log_len >= self.compact_after {
+        self.compact()?;
+      }
+    }
+    Ok(appended)
+  }
+
+  // Folds the log into the snapshot. The snapshot is written to a temporary
+  // file and renamed over the old one before the log is truncated, so a crash
+  // at any point leaves a snapshot and log that replay to the same state.
+  pub fn compact(&mut self) -> MResult<()> {
+    let snapshot_path = self.dir.join(PERSIST_SNAPSHOT_FILE);
+    let temp_path = self.dir.join(format!("{}.tmp", PERSIST_SNAPSHOT_FILE));
+    let mut symbols = Vec::with_capacity(self.entries.len());
+    for (name, entry) in &self.entries {
+      symbols.push(json!({"seq": entry.seq, "name": name, "kind": kind_to_json(&entry.kind)?, "value": entry.value}));
+    }
+    let snapshot = json!({"seq": self.seq, "symbols": symbols});
+    let mut file = File::create(&temp_path).map_err(|err| persist_io_error(&temp_path, err))?;
+    file.write_all(snapshot.to_string().as_bytes()).map_err(|err| persist_io_error(&temp_path, err))?;
+    file.sync_all().map_err(|err| persist_io_error(&temp_path, err))?;
+    fs::rename(&temp_path, &snapshot_path).map_err(|err| persist_io_error(&snapshot_path, err))?;
+    let log_path = self.dir.join(PERSIST_LOG_FILE);
+    self.log.set_len(0).map_err(|err| persist_io_error(&log_path, err))?;
+    self.log.sync_all().map_err(|err| persist_io_error(&log_path, err))?;
+    self.log_len = 0;
+    Ok(())
+  }
+
+  pub fn log_len(&self) -> usize {
+    self.log_len
+  }
+
+  fn selected_symbols(&self, intrp: &Interpreter) -> Vec<(String, ValRef)> {
+    let symbols = intrp.symbols();
+    let symbols_brrw = symbols.borrow();
+    let dictionary = symbols_brrw.dictionary.borrow();
+    let mut selected: Vec<(String, ValRef)> = match &self.symbols {
+      Some(names) => names.iter().filter_map(|name| {
+        symbols_brrw.get_mutable(hash_str(name)).map(|cell| (name.clone(), cell))
+      }).collect(),
+      None => symbols_brrw.mutable_variables.iter().filter_map(|(id, cell)| {
+        dictionary.get(id).map(|name| (name.clone(), cell.clone()))
+      }).collect(),
+    };
+    selected.sort_by(|a, b| a.0.cmp(&b.0));
+    selected
+  }
+
+}
+
+fn kind_to_json(kind: &ValueKind) -> MResult<Json> {
+  serde_json::to_value(kind).map_err(|err| MechError::new(
+      PersistEncodeError { kind: kind.clone(), source: err.to_string() },
+      None
+    ).with_compiler_loc()
+  )
+}
+
+// Reads one snapshot or log entry; None if the line is malformed.
+fn parse_entry(json: &Json) -> Option<(String, PersistEntry)> {
+  let name = json.get("name")?.as_str()?.to_string();
+  let seq = json.get("seq")?.as_u64()?;
+  let kind = serde_json::from_value(json.get("kind")?.clone()).ok()?;
+  let value = json.get("value")?.clone();
+  Some((name, PersistEntry { seq, kind, value }))
+}
+
+fn read_snapshot(path: &Path, entries: &mut IndexMap<String, PersistEntry>) -> MResult<u64> {
+  let corrupt = |line: usize, source: String| MechError::new(
+    PersistCorruptLogError { file_path: path.display().to_string(), line, source },
+    None
+  ).with_compiler_loc();
+  let text = fs::read_to_string(path).map_err(|err| persist_io_error(path, err))?;
+  let snapshot: Json = serde_json::from_str(&text).map_err(|err| corrupt(err.line(), err.to_string()))?;
+  let seq = snapshot.get("seq").and_then(Json::as_u64).ok_or_else(|| corrupt(1, "missing \"seq\"".to_string()))?;
+  let symbols = snapshot.get("symbols").and_then(Json::as_array).ok_or_else(|| corrupt(1, "missing \"symbols\"".to_string()))?;
+  for symbol in symbols {
+    let (name, entry) = parse_entry(symbol).ok_or_else(|| corrupt(1, format!("malformed entry {}", symbol)))?;
+    entries.insert(name, entry);
+  }
+  Ok(seq)
+}
+
+// Replays the log over `entries` and returns the number of entries it holds.
+// A malformed last line is a write cut short by a crash and is cut from the
+// file, so the next entry appended starts on a line of its own; a malformed
+// line anywhere else is an error.
+fn replay_log(path: &Path, seq: &mut u64, entries: &mut IndexMap<String, PersistEntry>) -> MResult<usize> {
+  let bytes = fs::read(path).map_err(|err| persist_io_error(path, err))?;
+  let lines = bytes.split_inclusive(|byte| *byte == b'\n').collect::<Vec<_>>();
+  let mut log_len = 0;
+  let mut line_start = 0;
+  let mut torn_at = None;
+  for (ix, line) in lines.iter().enumerate() {
+    let start = line_start;
+    line_start += line.len();
+    if line.iter().all(|byte| byte.is_ascii_whitespace()) {
+      continue;
+    }
+    let entry = serde_json::from_slice::<Json>(line).ok().and_then(|json| parse_entry(&json));
+    let (name, entry) = match entry {
+      Some(entry) => entry,
+      None if ix + 1 == lines.len() => {
+        torn_at = Some(start);
+        break;
+      },
+      None => return Err(MechError::new(
+          PersistCorruptLogError { file_path: path.display().to_string(), line: ix + 1, source: "malformed entry".to_string() },
+          None
+        ).with_compiler_loc()
+      ),
+    };
+    log_len += 1;
+    if entry.seq <= *seq {
+      continue;
+    }
+    *seq = entry.seq;
+    entries.insert(name, entry);
+  }
+  let repair = match torn_at {
+    Some(end) => OpenOptions::new().write(true).open(path).and_then(|file| file.set_len(end as u64)),
+    None if !bytes.is_empty() && !bytes.ends_with(b"\n") => OpenOptions::new().append(true).open(path).and_then(|mut file| file.write_all(b"\n")),
+    None => Ok(()),
+  };
+  repair.map_err(|err| persist_io_error(path, err))?;
+  Ok(log_len)
+}
+
+// Restoring only makes sense into a symbol of the same sort of value; sizes
+// are free to differ, since tables grow and matrices resize.
+fn same_kind_family(current: &ValueKind, persisted: &ValueKind) -> bool {
+  match (current, persisted) {
+    (ValueKind::Matrix(a, _), ValueKind::Matrix(b, _)) => a == b,
+    (ValueKind::Enum(a, _), ValueKind::Enum(b, _)) => a == b,
+    (ValueKind::Atom(_, _), ValueKind::Atom(_, _)) => true,
+    (ValueKind::Table(_, _), ValueKind::Table(_, _)) => true,
+    (ValueKind::Set(a, _), ValueKind::Set(b, _)) => a == b,
+    (ValueKind::Reference(a), b) => same_kind_family(a, b),
+    (a, b) => a == b,
+  }
+}
+
+macro_rules! write_in_place_arms {
+  ($sink:expr, $source:expr, [$($scalar:ident, $scalar_feature:tt);+ $(;)?], [$($matrix:ident, $matrix_feature:tt);+ $(;)?]) => {
+    match ($sink, $source) {
+      (Value::MutableReference(sink), source) => write_in_place(&sink.borrow(), source),
+      (sink, Value::MutableReference(source)) => write_in_place(sink, &source.borrow()),
+      $(
+        #[cfg(feature = $scalar_feature)]
+        (Value::$scalar(sink), Value::$scalar(source)) => {
+          *sink.borrow_mut() = source.borrow().clone();
+          true
+        },
+      )+
+      $(
+        #[cfg(all(feature = "matrix", feature = $matrix_feature))]
+        (Value::$matrix(sink), Value::$matrix(source)) if sink.shape() == source.shape() => {
+          for (ix, x) in source.as_vec().into_iter().enumerate() {
+            sink.set_index1d(ix, x);
+          }
+          true
+        },
+      )+
+      _ => false,
+    }
+  };
+}
+
+// Copies `source` into the cells behind `sink`. Returns false when the two
+// don't share a representation, e.g. a matrix whose size has changed.
+fn write_in_place(sink: &Value, source: &Value) -> bool {
+  write_in_place_arms!(sink, source,
+    [Bool, "bool"; String, "string"; U8, "u8"; U16, "u16"; U32, "u32"; U64, "u64"; U128, "u128";
+     I8, "i8"; I16, "i16"; I32, "i32"; I64, "i64"; I128, "i128"; F32, "f32"; F64, "f64";
+     C64, "complex"; R64, "rational"; Atom, "atom"; Set, "set"; Map, "map"; Record, "record";
+     Table, "table"; Tuple, "tuple"; Enum, "enum"],
+    [MatrixBool, "bool"; MatrixString, "string"; MatrixU8, "u8"; MatrixU16, "u16"; MatrixU32, "u32";
+     MatrixU64, "u64"; MatrixU128, "u128"; MatrixI8, "i8"; MatrixI16, "i16"; MatrixI32, "i32";
+     MatrixI64, "i64"; MatrixI128, "i128"; MatrixF32, "f32"; MatrixF64, "f64";
+     MatrixC64, "complex"; MatrixR64, "rational"])
+}
+
+fn persist_io_error(path: &Path, err: std::io::Error) -> MechError {
+  MechError::new(
+    PersistIoError { file_path: path.display().to_string(), source: err.to_string() },
+    None
+  ).with_compiler_loc()
+}
+
+#[derive(Debug, Clone)]
+pub struct PersistIoError {
+  pub file_path: String,
+  pub source: String,
+}
+impl MechErrorKind for PersistIoError {
+  fn name(&self) -> &str { "PersistIo" }
+  fn message(&self) -> String {
+    format!("Failed to access persisted state at {}: {}", self.file_path, self.source)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct PersistCorruptLogError {
+  pub file_path: String,
+  pub line: usize,
+  pub source: String,
+}
+impl MechErrorKind for PersistCorruptLogError {
+  fn name(&self) -> &str { "PersistCorruptLog" }
+  fn message(&self) -> String {
+    format!("Persisted state in {} is corrupt at line {}: {}", self.file_path, self.line, self.source)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct PersistEncodeError {
+  pub kind: ValueKind,
+  pub source: String,
+}
+impl MechErrorKind for PersistEncodeError {
+  fn name(&self) -> &str { "PersistEncode" }
+  fn message(&self) -> String {
+    format!("Failed to encode kind {} for the persisted state: {}", self.kind, self.source)
+  }
+}
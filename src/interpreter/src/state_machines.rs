@@ -8,27 +8,6 @@ use std::collections::HashSet;
 // Finite State Machines
 // ----------------------------------------------------------------------------
 
-fn fsm_argument_kind_matches(expected: &ValueKind, actual: &ValueKind) -> bool {
-  fn strip_references<'a>(kind: &'a ValueKind) -> &'a ValueKind {
-    match kind {
-      ValueKind::Reference(inner) => strip_references(inner.as_ref()),
-      _ => kind,
-    }
-  }
-
-  let expected = strip_references(expected);
-  let actual = strip_references(actual);
-
-  match (expected, actual) {
-    (ValueKind::Matrix(expected_element, expected_dims), ValueKind::Matrix(actual_element, _actual_dims))
-      if expected_dims.is_empty() =>
-    {
-      expected_element.as_ref() == actual_element.as_ref()
-    }
-    _ => expected == actual,
-  }
-}
-
 // Review: how does this fail?
 pub fn register_fsm_implementation(fsm: &FsmImplementation, p: &Interpreter) -> MResult<()> {
   let fsm_id = fsm.name.hash();
@@ -102,7 +81,7 @@ pub fn execute_fsm_pipe(fsm_pipe: &FsmPipe, env: Option<&Environment>, p: &Inter
       let expected_kind = kind_annotation(&kind_annotation_node.kind, p)?
           .to_value_kind(&p.state.borrow().kinds)?;
       let actual_kind = arg_value.kind();
-      if !fsm_argument_kind_matches(&expected_kind, &actual_kind) {
+      if !argument_kind_matches(&expected_kind, &actual_kind) {
         return Err(MechError::new(
           FsmArgumentKindMismatchError {
             argument: arg_decl.name.to_string(),
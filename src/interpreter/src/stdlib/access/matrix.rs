@@ -865,8 +865,13 @@ impl NativeFunctionCompiler for MatrixAccessScalar {
     }
     let ixes = arguments.clone().split_off(1);
     let mat = arguments[0].clone();
-    if let (Value::MatrixValue(source), [Value::Index(ix)]) = (mat.clone(), ixes.as_slice()) {
-      let element_kind = match mat.kind() {
+    // Vectors of records or tables, such as the result of a split, are held by reference.
+    let elements = match &mat {
+      Value::MutableReference(r) => r.borrow().clone(),
+      x => x.clone(),
+    };
+    if let (Value::MatrixValue(source), [Value::Index(ix)]) = (elements, ixes.as_slice()) {
+      let element_kind = match mat.deref_kind() {
         ValueKind::Matrix(elem, _) => (*elem).clone(),
         _ => ValueKind::Any,
       };
@@ -8,17 +8,16 @@ macro_rules! impl_col_access_fxn {
   ($fxn_name:ident, $vector_size:ident, $out_type:ty) => {
     #[derive(Debug)]
     struct $fxn_name {
-      source: Matrix<Value>,
+      source: Ref<DVector<$out_type>>,
       out: Ref<$vector_size<$out_type>>,
     }
     impl MechFunctionImpl for $fxn_name {
       fn solve(&self) {
+        let source = self.source.borrow();
         let out_ptr = self.out.as_mut_ptr();
         unsafe { 
-          for i in 1..=self.source.shape()[0] {
-            paste! {
-              (&mut (*out_ptr))[i-1] = self.source.index1d(i).[<as_ $out_type:lower>]().unwrap().borrow().clone();
-            }
+          for i in 0..source.len() {
+            (&mut (*out_ptr))[i] = source[i].clone();
           }
         }
       }
@@ -30,7 +29,7 @@ macro_rules! impl_col_access_fxn {
       fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
         let mut registers = [0, 0];
         registers[0] = compile_register_brrw!(self.out, ctx);
-        registers[1] = compile_register!(self.source, ctx);
+        registers[1] = compile_register_brrw!(self.source, ctx);
         ctx.features.insert(FeatureFlag::Builtin(FeatureKind::Access));
         ctx.emit_unop(
           hash_str(stringify!($fxn_name)),
@@ -103,15 +102,15 @@ macro_rules! impl_access_column_table_match_arms {
             $(
               $(
                 #[cfg(all(feature = $type_string, feature = "matrix1"))]
-                (Some((ValueKind::$lhs_type,value)),1) => Ok(Box::new([<TableAccessCol $lhs_type M1>]{source: value.clone(), out: Ref::new(Matrix1::from_element($default)) })),
+                (Some((ValueKind::$lhs_type,Column::$lhs_type(Matrix::DVector(value)))),1) => Ok(Box::new([<TableAccessCol $lhs_type M1>]{source: value.clone(), out: Ref::new(Matrix1::from_element($default)) })),
                 #[cfg(all(feature = $type_string, feature = "vector2"))]
-                (Some((ValueKind::$lhs_type,value)),2) => Ok(Box::new([<TableAccessCol $lhs_type V2>]{source: value.clone(), out: Ref::new(Vector2::from_element($default)) })),
+                (Some((ValueKind::$lhs_type,Column::$lhs_type(Matrix::DVector(value)))),2) => Ok(Box::new([<TableAccessCol $lhs_type V2>]{source: value.clone(), out: Ref::new(Vector2::from_element($default)) })),
                 #[cfg(all(feature = $type_string, feature = "vector3"))]
-                (Some((ValueKind::$lhs_type,value)),3) => Ok(Box::new([<TableAccessCol $lhs_type V3>]{source: value.clone(), out: Ref::new(Vector3::from_element($default)) })),
+                (Some((ValueKind::$lhs_type,Column::$lhs_type(Matrix::DVector(value)))),3) => Ok(Box::new([<TableAccessCol $lhs_type V3>]{source: value.clone(), out: Ref::new(Vector3::from_element($default)) })),
                 #[cfg(all(feature = $type_string, feature = "vector4"))]
-                (Some((ValueKind::$lhs_type,value)),4) => Ok(Box::new([<TableAccessCol $lhs_type V4>]{source: value.clone(), out: Ref::new(Vector4::from_element($default)) })),
+                (Some((ValueKind::$lhs_type,Column::$lhs_type(Matrix::DVector(value)))),4) => Ok(Box::new([<TableAccessCol $lhs_type V4>]{source: value.clone(), out: Ref::new(Vector4::from_element($default)) })),
                 #[cfg(all(feature = $type_string, feature = "vectord"))]
-                (Some((ValueKind::$lhs_type,value)),n) => Ok(Box::new([<TableAccessCol $lhs_type VD>]{source: value.clone(), out: Ref::new(DVector::from_element(n,$default)) })),
+                (Some((ValueKind::$lhs_type,Column::$lhs_type(Matrix::DVector(value)))),n) => Ok(Box::new([<TableAccessCol $lhs_type VD>]{source: value.clone(), out: Ref::new(DVector::from_element(n,$default)) })),
               )+
             )+
             // Column not found
@@ -127,9 +126,9 @@ macro_rules! impl_access_column_table_match_arms {
 fn impl_access_column_table_fxn(source: Value, key: Value) -> MResult<Box<dyn MechFunction>> {
   if let (Value::Table(tbl), Value::Id(k)) = (&source, &key) {
     let tbl_brrw = tbl.borrow();
-    if let Some((ValueKind::Option(_), value)) = tbl_brrw.get(k) {
+    if let Some((_, column @ Column::Value(_))) = tbl_brrw.get(k) {
       return Ok(Box::new(TableAccessSwizzle {
-        out: Value::MatrixValue(value.clone()),
+        out: column.to_value(),
       }));
     }
   }
@@ -216,9 +215,15 @@ impl MechFunctionImpl for TableAccessScalarF {
     let table = self.source.borrow();
     let mut record = self.out.borrow_mut();
     let row_ix = *self.ix.borrow();
-    for (key, (kind, matrix)) in table.data.iter() {
-      let value = matrix.index1d(row_ix);
-      record.data.insert(*key, value.clone());
+    for (key, (_kind, column)) in table.data.iter() {
+      // Update the record's fields in place so anything holding them sees the new row.
+      let updated = match record.data.get(key) {
+        Some(field) => column.copy_cell_into(row_ix, field),
+        None => false,
+      };
+      if !updated {
+        record.data.insert(*key, column.index1d(row_ix));
+      }
     }
   }
   fn out(&self) -> Value { Value::Record(self.out.clone()) }
@@ -259,10 +264,11 @@ impl NativeFunctionCompiler for TableAccessScalar {
     match (tbl.clone(), ix1.clone()) {
       #[cfg(feature = "table")]
       (Value::Table(source), Value::Index(ix)) => {
-        let record = match source.borrow().get_record(*ix.borrow()) {
+        let mut record = match source.borrow().get_record(*ix.borrow()) {
           Some(record) => record,
           None => return Err(MechError::new(UnhandledFunctionArgumentKind2 { arg: (tbl.kind(), ix1.kind()), fxn_name: "TableAccessScalar".to_string() }, None).with_compiler_loc()),
         };
+        record.table_row = Some((source.clone(), ix.clone()));
         Ok(Box::new(TableAccessScalarF{source: source.clone(), ix: ix.clone(), out: Ref::new(record) }))
       }
       (Value::MutableReference(src_ref), Value::Index(ix)) => {
@@ -270,10 +276,11 @@ impl NativeFunctionCompiler for TableAccessScalar {
         match &*src_ref_brrw {
           #[cfg(feature = "table")]
           Value::Table(source) => {
-            let record = match source.borrow().get_record(*ix.borrow()) {
+            let mut record = match source.borrow().get_record(*ix.borrow()) {
               Some(record) => record,
               None => return Err(MechError::new(UnhandledFunctionArgumentKind2 { arg: (tbl.kind(), ix1.kind()), fxn_name: "TableAccessScalar".to_string() }, None).with_compiler_loc()),
             };
+            record.table_row = Some((source.clone(), ix.clone()));
             Ok(Box::new(TableAccessScalarF{source: source.clone(), ix: ix.clone(), out: Ref::new(record) }))
           }
           _ => Err(MechError::new(UnhandledFunctionArgumentKind2 { arg: (tbl.kind(), ix1.kind()), fxn_name: "TableAccessScalar".to_string() }, None).with_compiler_loc()),
@@ -299,11 +306,12 @@ impl MechFunctionImpl for TableAccessRangeIndex {
     let mut out_table = self.out.borrow_mut();
     let ix_brrw = self.ix.borrow();
 
-    for (key, (_kind, matrix)) in table.data.iter() {
-      let (_out_kind, out_matrix) = out_table.data.get_mut(key).unwrap();
-      for (out_i, i) in ix_brrw.iter().enumerate() {
-        let value = matrix.index1d(*i);
-        out_matrix.set_index1d(out_i, value.clone());
+    // A column that fails to copy stops the update, and the output keeps
+    // its last value.
+    for (key, (_kind, column)) in table.data.iter() {
+      let (_out_kind, out_column) = out_table.data.get_mut(key).unwrap();
+      if column.copy_rows(ix_brrw.as_slice(), out_column).is_err() {
+        return;
       }
     }
   }
@@ -344,27 +352,19 @@ impl MechFunctionImpl for TableAccessRangeBool {
   fn solve(&self) {
     let table = self.source.borrow();
     let ix_brrw = self.ix.borrow();
-    let true_count = ix_brrw.iter().filter(|&&b| b).count();
+    // Rows (1-based) selected by the logical index
+    let rows: Vec<usize> = ix_brrw.iter().enumerate().filter(|(_, flag)| **flag).map(|(i, _)| i + 1).collect();
 
     let mut out_table = self.out.borrow_mut();
 
-    for (key, (_kind, matrix)) in table.data.iter() {
-      let (_out_kind, out_matrix) = out_table.data.get_mut(key).unwrap();
-
-      // Resize output to match number of true entries
-      out_matrix.resize_vertically(true_count, Value::Empty);
-
-      // Fill with contiguous values
-      let mut push_index = 0;
-      for (i, flag) in ix_brrw.iter().enumerate() {
-        if *flag {
-          let value = matrix.index1d(i + 1); // 1-based indexing; use `i` if 0-based
-          out_matrix.set_index1d(push_index, value.clone());
-          push_index += 1;
-        }
+    // As above, the row count only changes once every column is copied.
+    for (key, (_kind, column)) in table.data.iter() {
+      let (_out_kind, out_column) = out_table.data.get_mut(key).unwrap();
+      if column.copy_rows(&rows, out_column).is_err() {
+        return;
       }
     }
-    out_table.rows = true_count;
+    out_table.rows = rows.len();
   }
   fn out(&self) -> Value { Value::Table(self.out.clone()) }
   fn to_string(&self) -> String {format!("{:#?}", self)}
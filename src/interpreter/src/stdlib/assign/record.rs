@@ -37,7 +37,59 @@ where
   }
 }
 
+// Record Set Table Row -------------------------------------------------------
+
+// Records read from a table row (T[1]) hold their fields unboxed from the
+// table's columns, so a field update is also written back to the row.
+#[cfg(feature = "table")]
+pub struct RecordAssignTableRow {
+  pub assign: Box<dyn MechFunction>,
+  pub field: Value,
+  pub table: Ref<MechTable>,
+  pub row: Ref<usize>,
+  pub column: u64,
+}
+#[cfg(feature = "table")]
+impl MechFunctionImpl for RecordAssignTableRow {
+  fn solve(&self) {
+    self.assign.solve();
+    let table = self.table.borrow();
+    let row = *self.row.borrow();
+    // The write is checked against the column when this function is built
+    // and the field keeps its kind, so only a row that has since gone out of
+    // range is skipped here.
+    if let Some((_, column)) = table.data.get(&self.column) {
+      if row >= 1 && row <= table.rows {
+        column.set_index1d(row - 1, &self.field).ok();
+      }
+    }
+  }
+  fn out(&self) -> Value { self.assign.out() }
+  fn to_string(&self) -> String { format!("RecordAssignTableRow {{ column: {}, row: {}, assign: {} }}", self.column, self.row.borrow(), self.assign.to_string()) }
+}
+#[cfg(all(feature = "table", feature = "compiler"))]
+impl MechFunctionCompiler for RecordAssignTableRow {
+  fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+    self.assign.compile(ctx)
+  }
+}
+
 fn impl_set_record_column_fxn(sink: Value, source: Value, key: Value) -> MResult<Box<dyn MechFunction>> {
+  let fxn = impl_set_record_field_fxn(sink.clone(), source, key.clone())?;
+  #[cfg(feature = "table")]
+  if let (Value::Record(rcrd), Value::Id(k)) = (&sink, &key) {
+    let rcrd_brrw = rcrd.borrow();
+    if let (Some((table, row)), Some(field)) = (&rcrd_brrw.table_row, rcrd_brrw.data.get(k)) {
+      if let Some((_, column)) = table.borrow().data.get(k) {
+        column.set_index1d(*row.borrow() - 1, field)?;
+      }
+      return Ok(Box::new(RecordAssignTableRow{ assign: fxn, field: field.clone(), table: table.clone(), row: row.clone(), column: *k }));
+    }
+  }
+  Ok(fxn)
+}
+
+fn impl_set_record_field_fxn(sink: Value, source: Value, key: Value) -> MResult<Box<dyn MechFunction>> {
   match (&sink, &source, &key) {
     (Value::Record(rcrd),source,Value::Id(k)) => {
       let rcrd_brrw = rcrd.borrow();
@@ -8,11 +8,11 @@ use na::{Vector3, DVector, Vector2, Vector4, RowDVector, Matrix1, Matrix3, Matri
 // Table Set ------------------------------------------------------------------
 
 macro_rules! impl_col_set_fxn {
-  ($fxn_name:ident, $vector_size_in:ident, $vector_size_out:ident, $out_type:ty) => {
+  ($fxn_name:ident, $vector_size_in:ident, $out_type:ty) => {
     #[derive(Debug)]
     struct $fxn_name {
       source: Ref<$vector_size_in<$out_type>>,
-      sink: Ref<$vector_size_out<Value>>,
+      sink: Ref<DVector<$out_type>>,
     }
     impl MechFunctionImpl for $fxn_name {
       fn solve(&self) {
@@ -20,24 +20,22 @@ macro_rules! impl_col_set_fxn {
         let sink_ptr = self.sink.as_mut_ptr();
         unsafe { 
           for i in 0..(*source_ptr).len() {
-            paste! {
-              (&mut (*sink_ptr))[i] = Value::[<$out_type:camel>](Ref::new((*source_ptr).index(i).clone()));
-            }
+            (&mut (*sink_ptr))[i] = (*source_ptr).index(i).clone();
           }
         }
       }
-      fn out(&self) -> Value { Value::MatrixValue(Matrix::$vector_size_out(self.sink.clone())) }
+      fn out(&self) -> Value { self.sink.to_value() }
       fn to_string(&self) -> String { format!("{:#?}", self) }
     }
     #[cfg(feature = "compiler")]
     impl MechFunctionCompiler for $fxn_name 
     where
       $vector_size_in<$out_type>: CompileConst + ConstElem + AsValueKind,
-      $vector_size_out<Value>: CompileConst + ConstElem + AsValueKind,
+      DVector<$out_type>: CompileConst + ConstElem + AsValueKind,
       $out_type: CompileConst + ConstElem + AsValueKind,
     {
       fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
-        let name = format!("{}<{},{}>", stringify!($fxn_name), $vector_size_in::<$out_type>::as_value_kind(), $vector_size_out::<Value>::as_value_kind());
+        let name = format!("{}<{},{}>", stringify!($fxn_name), $vector_size_in::<$out_type>::as_value_kind(), DVector::<$out_type>::as_value_kind());
         compile_unop!(name, self.sink, self.source, ctx, FeatureFlag::Builtin(FeatureKind::Assign) );
       }
     }
@@ -47,24 +45,16 @@ macro_rules! impl_col_set_fxn {
 macro_rules! impl_col_set_fxn_shapes {
   ($type:ident) => {
     paste!{
-      #[cfg(feature = "matrix1")]
-      impl_col_set_fxn!([<TableSetCol $type:camel M1>], Matrix1, Matrix1, $type);
-      #[cfg(feature = "vector2")]
-      impl_col_set_fxn!([<TableSetCol $type:camel V2>], Vector2, Vector2, $type);
-      #[cfg(feature = "vector3")]
-      impl_col_set_fxn!([<TableSetCol $type:camel V3>], Vector3, Vector3, $type);
-      #[cfg(feature = "vector4")]
-      impl_col_set_fxn!([<TableSetCol $type:camel V4>], Vector4, Vector4, $type);
       #[cfg(feature = "vectord")]
-      impl_col_set_fxn!([<TableSetCol $type:camel VD>], DVector, DVector, $type);
+      impl_col_set_fxn!([<TableSetCol $type:camel VD>], DVector, $type);
       #[cfg(all(feature = "vectord", feature = "vector4"))]
-      impl_col_set_fxn!([<TableSetCol $type:camel VDV4>], Vector4, DVector, $type);
+      impl_col_set_fxn!([<TableSetCol $type:camel VDV4>], Vector4, $type);
       #[cfg(all(feature = "vectord", feature = "vector3"))]
-      impl_col_set_fxn!([<TableSetCol $type:camel VDV3>], Vector3, DVector, $type);
+      impl_col_set_fxn!([<TableSetCol $type:camel VDV3>], Vector3, $type);
       #[cfg(all(feature = "vectord", feature = "vector2"))]
-      impl_col_set_fxn!([<TableSetCol $type:camel VDV2>], Vector2, DVector, $type);
+      impl_col_set_fxn!([<TableSetCol $type:camel VDV2>], Vector2, $type);
       #[cfg(all(feature = "vectord", feature = "matrix1"))]
-      impl_col_set_fxn!([<TableSetCol $type:camel VDM1>], Matrix1, DVector, $type);
+      impl_col_set_fxn!([<TableSetCol $type:camel VDM1>], Matrix1, $type);
     }
   }
 }
@@ -110,24 +100,16 @@ macro_rules! impl_set_column_match_arms {
           let tbl_brrw = tbl.borrow();
           match (tbl_brrw.get(&k), tbl_brrw.rows(), source) {
             $(
-              #[cfg(all(feature = $type_feature, feature = "matrix1"))]
-              (Some((ValueKind::$lhs_type, Matrix::Matrix1(sink))), 1, Value::[<Matrix $lhs_type>](Matrix::Matrix1(source))) =>Ok(Box::new([<TableSetCol $lhs_type M1>]{ source: source.clone(), sink: sink.clone() })),
-              #[cfg(all(feature = $type_feature, feature = "vector2"))]
-              (Some((ValueKind::$lhs_type, Matrix::Vector2(sink))), 2, Value::[<Matrix $lhs_type>](Matrix::Vector2(source))) =>Ok(Box::new([<TableSetCol $lhs_type V2>]{ source: source.clone(), sink: sink.clone() })),
-              #[cfg(all(feature = $type_feature, feature = "vector3"))]
-              (Some((ValueKind::$lhs_type, Matrix::Vector3(sink))), 3, Value::[<Matrix $lhs_type>](Matrix::Vector3(source))) =>Ok(Box::new([<TableSetCol $lhs_type V3>]{ source: source.clone(), sink: sink.clone() })),
-              #[cfg(all(feature = $type_feature, feature = "vector4"))]
-              (Some((ValueKind::$lhs_type, Matrix::Vector4(sink))), 4, Value::[<Matrix $lhs_type>](Matrix::Vector4(source))) =>Ok(Box::new([<TableSetCol $lhs_type V4>]{ source: source.clone(), sink: sink.clone() })),
               #[cfg(all(feature = $type_feature, feature = "vectord"))]
-              (Some((ValueKind::$lhs_type, Matrix::DVector(sink))), n, Value::[<Matrix $lhs_type>](Matrix::DVector(source))) =>Ok(Box::new([<TableSetCol $lhs_type VD>]{ source: source.clone(), sink: sink.clone() })),
+              (Some((ValueKind::$lhs_type, Column::$lhs_type(Matrix::DVector(sink)))), n, Value::[<Matrix $lhs_type>](Matrix::DVector(source))) =>Ok(Box::new([<TableSetCol $lhs_type VD>]{ source: source.clone(), sink: sink.clone() })),
               #[cfg(all(feature = $type_feature, feature = "vectord", feature = "vector4"))]
-              (Some((ValueKind::$lhs_type, Matrix::DVector(sink))), n, Value::[<Matrix $lhs_type>](Matrix::Vector4(source))) =>Ok(Box::new([<TableSetCol $lhs_type VDV4>]{ source: source.clone(), sink: sink.clone() })),
+              (Some((ValueKind::$lhs_type, Column::$lhs_type(Matrix::DVector(sink)))), n, Value::[<Matrix $lhs_type>](Matrix::Vector4(source))) =>Ok(Box::new([<TableSetCol $lhs_type VDV4>]{ source: source.clone(), sink: sink.clone() })),
               #[cfg(all(feature = $type_feature, feature = "vectord", feature = "vector3"))]
-              (Some((ValueKind::$lhs_type, Matrix::DVector(sink))), n, Value::[<Matrix $lhs_type>](Matrix::Vector3(source))) =>Ok(Box::new([<TableSetCol $lhs_type VDV3>]{ source: source.clone(), sink: sink.clone() })),
+              (Some((ValueKind::$lhs_type, Column::$lhs_type(Matrix::DVector(sink)))), n, Value::[<Matrix $lhs_type>](Matrix::Vector3(source))) =>Ok(Box::new([<TableSetCol $lhs_type VDV3>]{ source: source.clone(), sink: sink.clone() })),
               #[cfg(all(feature = $type_feature, feature = "vectord", feature = "vector2"))]
-              (Some((ValueKind::$lhs_type, Matrix::DVector(sink))), n, Value::[<Matrix $lhs_type>](Matrix::Vector2(source))) =>Ok(Box::new([<TableSetCol $lhs_type VDV2>]{ source: source.clone(), sink: sink.clone() })),
+              (Some((ValueKind::$lhs_type, Column::$lhs_type(Matrix::DVector(sink)))), n, Value::[<Matrix $lhs_type>](Matrix::Vector2(source))) =>Ok(Box::new([<TableSetCol $lhs_type VDV2>]{ source: source.clone(), sink: sink.clone() })),
               #[cfg(all(feature = $type_feature, feature = "vectord", feature = "matrix1"))]
-              (Some((ValueKind::$lhs_type, Matrix::DVector(sink))), n, Value::[<Matrix $lhs_type>](Matrix::Matrix1(source))) =>Ok(Box::new([<TableSetCol $lhs_type VDM1>]{ source: source.clone(), sink: sink.clone() })),
+              (Some((ValueKind::$lhs_type, Column::$lhs_type(Matrix::DVector(sink)))), n, Value::[<Matrix $lhs_type>](Matrix::Matrix1(source))) =>Ok(Box::new([<TableSetCol $lhs_type VDM1>]{ source: source.clone(), sink: sink.clone() })),
             )+
             x => return Err(MechError::new(
               UndefinedTableColumnError { id: k.clone() }, None).with_compiler_loc()
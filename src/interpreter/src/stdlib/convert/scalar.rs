@@ -98,11 +98,18 @@ where T: Debug + Clone + PartialEq + Into<Value> + 'static,
     let mut out_table = self.out.borrow_mut();
     let rows = arg.rows().min(out_table.rows);
 
+    // A cell that doesn't convert to its column's kind stops the update, and
+    // the rest of the table keeps its last value.
     for (col_ix, (ix, (col_kind, out_col))) in out_table.data.iter_mut().enumerate() {
       for row_ix in 0..rows {
-        let value = arg.index2d(row_ix + 1, col_ix + 1).clone().into();
-        let converted_value = value.convert_to(col_kind).unwrap();
-        out_col.set_index1d(row_ix, converted_value);
+        let value: Value = arg.index2d(row_ix + 1, col_ix + 1).clone().into();
+        let converted_value = match value.convert_to(col_kind) {
+          Some(converted_value) => converted_value,
+          None => return,
+        };
+        if out_col.set_index1d(row_ix, &converted_value).is_err() {
+          return;
+        }
       }
     }
   }
@@ -1,8 +1,9 @@
 use crate::stdlib::*;
 use indexmap::map::IndexMap;
-use mech_core::matrix::Matrix;
-use na::DVector;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
 #[derive(Debug, Clone, Copy)]
 enum JoinMode {
@@ -14,157 +15,136 @@ enum JoinMode {
     LeftAnti,
 }
 
+// The comparison a join condition applies between a left and a right cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JoinOp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl JoinOp {
+    fn holds(&self, ord: Option<Ordering>) -> bool {
+        match (self, ord) {
+            (JoinOp::Eq, Some(Ordering::Equal)) => true,
+            (JoinOp::Lt, Some(Ordering::Less)) => true,
+            (JoinOp::Le, Some(Ordering::Less | Ordering::Equal)) => true,
+            (JoinOp::Gt, Some(Ordering::Greater)) => true,
+            (JoinOp::Ge, Some(Ordering::Greater | Ordering::Equal)) => true,
+            _ => false,
+        }
+    }
+}
+
+// A join condition `lhs op rhs` between a left and a right column id.
+#[derive(Debug, Clone)]
+struct JoinKey {
+    lhs: u64,
+    op: JoinOp,
+    rhs: u64,
+}
+
+// An output column, and the left/right columns its cells are taken from.
+struct JoinColumn {
+    id: u64,
+    kind: ValueKind,
+    name: String,
+    lhs: Option<u64>,
+    rhs: Option<u64>,
+}
+
 #[derive(Debug)]
 struct TableJoinFxn {
     lhs: Ref<MechTable>,
     rhs: Ref<MechTable>,
+    // The `on:` argument the keys were parsed from, kept so a compiled
+    // program can parse them again.
+    on: Option<Value>,
     out: Ref<MechTable>,
     mode: JoinMode,
+    keys: Vec<JoinKey>,
 }
 
 impl TableJoinFxn {
-    fn build_joined_table(lhs: &MechTable, rhs: &MechTable, mode: JoinMode) -> MResult<MechTable> {
-        let rhs_name_to_id: HashMap<String, u64> = rhs
-            .col_names
-            .iter()
-            .map(|(id, name)| (name.clone(), *id))
-            .collect();
-
-        let mut common_cols: Vec<(u64, u64)> = vec![];
-        for (lhs_id, lhs_name) in &lhs.col_names {
-            if let Some(rhs_id) = rhs_name_to_id.get(lhs_name) {
-                common_cols.push((*lhs_id, *rhs_id));
-            }
-        }
-
-        let common_rhs: HashSet<u64> = common_cols.iter().map(|(_, rhs_id)| *rhs_id).collect();
-        let common_lhs: HashSet<u64> = common_cols.iter().map(|(lhs_id, _)| *lhs_id).collect();
-
-        let mut output_cols: Vec<(u64, ValueKind, String)> = vec![];
-        for (lhs_id, (kind, _)) in lhs.data.iter() {
-            let name = lhs
-                .col_names
-                .get(lhs_id)
-                .cloned()
-                .unwrap_or_else(|| lhs_id.to_string());
-            let out_kind = if !common_lhs.contains(lhs_id)
-                && matches!(mode, JoinMode::RightOuter | JoinMode::FullOuter)
-            {
-                make_optional_kind(kind)
-            } else {
-                kind.clone()
-            };
-            output_cols.push((*lhs_id, out_kind, name));
+    // Builds the join and computes its first result, so that a key naming a
+    // column either table lacks is reported where the join is written.
+    fn build(arguments: &Vec<Value>, mode: JoinMode) -> MResult<Self> {
+        if arguments.len() != 2 && arguments.len() != 3 {
+            return Err(MechError::new(
+                IncorrectNumberOfArguments {
+                    expected: if arguments.len() < 2 { 2 } else { 3 },
+                    found: arguments.len(),
+                },
+                None,
+            )
+            .with_compiler_loc());
         }
-        for (rhs_id, (kind, _)) in rhs.data.iter() {
-            if common_rhs.contains(rhs_id) {
-                continue;
+        let (lhs, rhs) = match (resolve_table(&arguments[0]), resolve_table(&arguments[1])) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => {
+                return Err(MechError::new(
+                    UnhandledFunctionArgumentKind2 {
+                        arg: (arguments[0].kind(), arguments[1].kind()),
+                        fxn_name: "table/join".to_string(),
+                    },
+                    None,
+                )
+                .with_compiler_loc())
             }
-            let name = rhs
-                .col_names
-                .get(rhs_id)
-                .cloned()
-                .unwrap_or_else(|| rhs_id.to_string());
-            let out_kind = if matches!(mode, JoinMode::LeftOuter | JoinMode::FullOuter) {
-                make_optional_kind(kind)
-            } else {
-                kind.clone()
-            };
-            output_cols.push((*rhs_id, out_kind, name));
-        }
+        };
+        let keys = match arguments.get(2) {
+            Some(on) => parse_join_keys(&lhs.borrow(), &rhs.borrow(), on)?,
+            None => natural_join_keys(&lhs.borrow(), &rhs.borrow()),
+        };
+        let out = Ref::new(TableJoinFxn::build_joined_table(&lhs.borrow(), &rhs.borrow(), mode, &keys)?);
+        Ok(TableJoinFxn {
+            lhs,
+            rhs,
+            on: arguments.get(2).cloned(),
+            out,
+            mode,
+            keys,
+        })
+    }
 
-        if matches!(mode, JoinMode::LeftSemi | JoinMode::LeftAnti) {
-            output_cols = lhs
-                .data
-                .iter()
-                .map(|(lhs_id, (kind, _))| {
-                    let name = lhs
-                        .col_names
-                        .get(lhs_id)
-                        .cloned()
-                        .unwrap_or_else(|| lhs_id.to_string());
-                    (*lhs_id, kind.clone(), name)
-                })
-                .collect();
-        }
+    fn build_joined_table(lhs: &MechTable, rhs: &MechTable, mode: JoinMode, keys: &[JoinKey]) -> MResult<MechTable> {
+        let output_cols = join_output_columns(lhs, rhs, mode, keys);
+        let matches = match_rows(lhs, rhs, keys)?;
 
-        let mut out_rows: Vec<HashMap<u64, Value>> = vec![];
+        // Each output row is a pair of (lhs row, rhs row), 1-based
+        let mut out_rows: Vec<(Option<usize>, Option<usize>)> = vec![];
         let mut rhs_matched: Vec<bool> = vec![false; rhs.rows];
 
-        for lhs_row in 1..=lhs.rows {
-            let mut matched_rhs: Vec<usize> = vec![];
-            for rhs_row in 1..=rhs.rows {
-                if rows_match(lhs, lhs_row, rhs, rhs_row, &common_cols) {
-                    matched_rhs.push(rhs_row);
-                }
-            }
-
+        for (lhs_ix, matched_rhs) in matches.into_iter().enumerate() {
+            let lhs_row = lhs_ix + 1;
             match mode {
-                JoinMode::Inner => {
+                JoinMode::Inner | JoinMode::RightOuter => {
+                    // unmatched rhs rows of a right outer join are added below
                     for rhs_row in matched_rhs {
                         rhs_matched[rhs_row - 1] = true;
-                        out_rows.push(merge_rows(lhs, lhs_row, rhs, rhs_row, &common_rhs, false));
-                    }
-                }
-                JoinMode::LeftOuter => {
-                    if matched_rhs.is_empty() {
-                        out_rows.push(merge_rows(lhs, lhs_row, rhs, 0, &common_rhs, true));
-                    } else {
-                        for rhs_row in matched_rhs {
-                            rhs_matched[rhs_row - 1] = true;
-                            out_rows.push(merge_rows(
-                                lhs,
-                                lhs_row,
-                                rhs,
-                                rhs_row,
-                                &common_rhs,
-                                false,
-                            ));
-                        }
-                    }
-                }
-                JoinMode::RightOuter => {
-                    if matched_rhs.is_empty() {
-                        // handled when iterating unmatched rhs rows below
-                    } else {
-                        for rhs_row in matched_rhs {
-                            rhs_matched[rhs_row - 1] = true;
-                            out_rows.push(merge_rows(
-                                lhs,
-                                lhs_row,
-                                rhs,
-                                rhs_row,
-                                &common_rhs,
-                                false,
-                            ));
-                        }
+                        out_rows.push((Some(lhs_row), Some(rhs_row)));
                     }
                 }
-                JoinMode::FullOuter => {
+                JoinMode::LeftOuter | JoinMode::FullOuter => {
                     if matched_rhs.is_empty() {
-                        out_rows.push(merge_rows(lhs, lhs_row, rhs, 0, &common_rhs, true));
+                        out_rows.push((Some(lhs_row), None));
                     } else {
                         for rhs_row in matched_rhs {
                             rhs_matched[rhs_row - 1] = true;
-                            out_rows.push(merge_rows(
-                                lhs,
-                                lhs_row,
-                                rhs,
-                                rhs_row,
-                                &common_rhs,
-                                false,
-                            ));
+                            out_rows.push((Some(lhs_row), Some(rhs_row)));
                         }
                     }
                 }
                 JoinMode::LeftSemi => {
                     if !matched_rhs.is_empty() {
-                        out_rows.push(lhs_only_row(lhs, lhs_row));
+                        out_rows.push((Some(lhs_row), None));
                     }
                 }
                 JoinMode::LeftAnti => {
                     if matched_rhs.is_empty() {
-                        out_rows.push(lhs_only_row(lhs, lhs_row));
+                        out_rows.push((Some(lhs_row), None));
                     }
                 }
             }
@@ -172,57 +152,28 @@ impl TableJoinFxn {
 
         if matches!(mode, JoinMode::RightOuter | JoinMode::FullOuter) {
             for rhs_row in 1..=rhs.rows {
-                if rhs_matched[rhs_row - 1] {
-                    continue;
-                }
-                let mut row = HashMap::new();
-
-                if !matches!(mode, JoinMode::LeftSemi | JoinMode::LeftAnti) {
-                    for (lhs_id, _) in lhs.data.iter() {
-                        if let Some((_, rhs_id)) = common_cols.iter().find(|(l, _)| l == lhs_id) {
-                            let value = rhs
-                                .data
-                                .get(rhs_id)
-                                .map(|(_, col)| col.index1d(rhs_row))
-                                .unwrap_or(Value::Empty);
-                            row.insert(*lhs_id, value);
-                        } else {
-                            row.insert(*lhs_id, Value::Empty);
-                        }
-                    }
-                    for (rhs_id, _) in rhs.data.iter() {
-                        if common_rhs.contains(rhs_id) {
-                            continue;
-                        }
-                        let value = rhs
-                            .data
-                            .get(rhs_id)
-                            .map(|(_, col)| col.index1d(rhs_row))
-                            .unwrap_or(Value::Empty);
-                        row.insert(*rhs_id, value);
-                    }
+                if !rhs_matched[rhs_row - 1] {
+                    out_rows.push((None, Some(rhs_row)));
                 }
-
-                out_rows.push(row);
             }
         }
 
-        let mut data: IndexMap<u64, (ValueKind, Matrix<Value>)> = IndexMap::new();
+        let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
         let mut col_names: HashMap<u64, String> = HashMap::new();
 
-        for (col_id, kind, name) in &output_cols {
-            let mut values = Vec::with_capacity(out_rows.len());
-            for row in &out_rows {
-                values.push(row.get(col_id).cloned().unwrap_or(Value::Empty));
-            }
-            data.insert(
-                *col_id,
-                (
-                    kind.clone(),
-                    Matrix::DVector(Ref::new(DVector::from_vec(values))),
-                ),
-            );
-            col_names.insert(*col_id, name.clone());
+        for out_col in &output_cols {
+            let lhs_col = out_col.lhs.and_then(|id| lhs.data.get(&id)).map(|(_, col)| col);
+            let rhs_col = out_col.rhs.and_then(|id| rhs.data.get(&id)).map(|(_, col)| col);
+            let picks: Vec<Option<(&Column, usize)>> = out_rows
+                .iter()
+                .map(|(lhs_row, rhs_row)| match (lhs_col, lhs_row, rhs_col, rhs_row) {
+                    (Some(col), Some(row), _, _) => Some((col, *row)),
+                    (_, _, Some(col), Some(row)) => Some((col, *row)),
+                    _ => None,
+                })
+                .collect();
+            data.insert(out_col.id, (out_col.kind.clone(), gather_column(&out_col.kind, &picks)?));
+            col_names.insert(out_col.id, out_col.name.clone());
         }
 
         Ok(MechTable {
@@ -234,6 +185,217 @@ impl TableJoinFxn {
     }
 }
 
+// Left columns come first and keep their names. An equality key with the same
+// name on both sides is merged into the left column, which takes the right
+// value on rows with no left match. Any other right column whose name is
+// already taken is renamed to `name-right` (then `name-right-2`, ...).
+fn join_output_columns(lhs: &MechTable, rhs: &MechTable, mode: JoinMode, keys: &[JoinKey]) -> Vec<JoinColumn> {
+    let col_name = |table: &MechTable, id: &u64| table.col_names.get(id).cloned().unwrap_or_else(|| id.to_string());
+    let merged: HashMap<u64, u64> = keys
+        .iter()
+        .filter(|key| key.op == JoinOp::Eq && col_name(lhs, &key.lhs) == col_name(rhs, &key.rhs))
+        .map(|key| (key.lhs, key.rhs))
+        .collect();
+    let semi = matches!(mode, JoinMode::LeftSemi | JoinMode::LeftAnti);
+
+    let mut output_cols: Vec<JoinColumn> = vec![];
+    let mut used_names: HashSet<String> = HashSet::new();
+    let mut used_ids: HashSet<u64> = HashSet::new();
+    for (lhs_id, (kind, _)) in lhs.data.iter() {
+        let rhs_id = if semi { None } else { merged.get(lhs_id).copied() };
+        let kind = if rhs_id.is_none() && matches!(mode, JoinMode::RightOuter | JoinMode::FullOuter) {
+            make_optional_kind(kind)
+        } else {
+            kind.clone()
+        };
+        let name = col_name(lhs, lhs_id);
+        used_names.insert(name.clone());
+        used_ids.insert(*lhs_id);
+        output_cols.push(JoinColumn { id: *lhs_id, kind, name, lhs: Some(*lhs_id), rhs: rhs_id });
+    }
+    if semi {
+        return output_cols;
+    }
+    let merged_rhs: HashSet<u64> = merged.values().copied().collect();
+    for (rhs_id, (kind, _)) in rhs.data.iter() {
+        if merged_rhs.contains(rhs_id) {
+            continue;
+        }
+        let kind = if matches!(mode, JoinMode::LeftOuter | JoinMode::FullOuter) {
+            make_optional_kind(kind)
+        } else {
+            kind.clone()
+        };
+        let mut name = col_name(rhs, rhs_id);
+        if used_names.contains(&name) {
+            let base = format!("{}-right", name);
+            name = base.clone();
+            let mut n = 2;
+            while used_names.contains(&name) {
+                name = format!("{}-{}", base, n);
+                n += 1;
+            }
+        }
+        let id = if used_ids.contains(rhs_id) { hash_str(&name) } else { *rhs_id };
+        used_names.insert(name.clone());
+        used_ids.insert(id);
+        output_cols.push(JoinColumn { id, kind, name, lhs: None, rhs: Some(*rhs_id) });
+    }
+    output_cols
+}
+
+// For each left row, the right rows (1-based, ascending) satisfying every key.
+// Equality keys are matched with a hash join on the right table. Joins on
+// inequality keys alone sort the right table on the first of them and
+// binary-search it for each left row.
+fn match_rows(lhs: &MechTable, rhs: &MechTable, keys: &[JoinKey]) -> MResult<Vec<Vec<usize>>> {
+    let mut conditions: Vec<(&Column, JoinOp, &Column)> = vec![];
+    for key in keys {
+        let lhs_col = table_column(lhs, key.lhs)?;
+        let rhs_col = table_column(rhs, key.rhs)?;
+        conditions.push((lhs_col, key.op, rhs_col));
+    }
+    let holds = |lhs_row: usize, rhs_row: usize| {
+        conditions.iter().all(|(lhs_col, op, rhs_col)| match op {
+            JoinOp::Eq => lhs_col.cell_eq(lhs_row, rhs_col, rhs_row),
+            op => op.holds(lhs_col.cell_cmp(lhs_row, rhs_col, rhs_row)),
+        })
+    };
+    let equalities: Vec<(&Column, &Column)> = conditions
+        .iter()
+        .filter(|(_, op, _)| *op == JoinOp::Eq)
+        .map(|(lhs_col, _, rhs_col)| (*lhs_col, *rhs_col))
+        .collect();
+    let mut matches = Vec::with_capacity(lhs.rows);
+
+    match conditions.iter().find(|(_, op, _)| *op != JoinOp::Eq) {
+        Some((lhs_col, op, rhs_col)) if equalities.is_empty() => {
+            // Sort-merge: right rows ordered by the range key. Cells that are
+            // unordered even against themselves (NaN, empty) never match.
+            let mut sorted: Vec<usize> = (1..=rhs.rows)
+                .filter(|row| rhs_col.cell_cmp(*row, rhs_col, *row).is_some())
+                .collect();
+            sorted.sort_by(|a, b| rhs_col.cell_cmp(*a, rhs_col, *b).unwrap_or(Ordering::Equal));
+            for lhs_row in 1..=lhs.rows {
+                let cmp = |rhs_row: &usize| lhs_col.cell_cmp(lhs_row, rhs_col, *rhs_row);
+                let candidates = match op {
+                    JoinOp::Lt | JoinOp::Le => &sorted[sorted.partition_point(|row| !op.holds(cmp(row)))..],
+                    _ => &sorted[..sorted.partition_point(|row| op.holds(cmp(row)))],
+                };
+                let mut rows: Vec<usize> = candidates.iter().copied().filter(|rhs_row| holds(lhs_row, *rhs_row)).collect();
+                rows.sort_unstable();
+                matches.push(rows);
+            }
+        }
+        _ => {
+            // Hash join on the equality keys, verifying every condition per candidate.
+            let key_hash = |cols: &mut dyn Iterator<Item = &Column>, row: usize| {
+                let mut hasher = DefaultHasher::new();
+                for col in cols {
+                    col.hash_cell(row, &mut hasher);
+                }
+                hasher.finish()
+            };
+            let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+            for rhs_row in 1..=rhs.rows {
+                let hash = key_hash(&mut equalities.iter().map(|(_, rhs_col)| *rhs_col), rhs_row);
+                buckets.entry(hash).or_default().push(rhs_row);
+            }
+            for lhs_row in 1..=lhs.rows {
+                let hash = key_hash(&mut equalities.iter().map(|(lhs_col, _)| *lhs_col), lhs_row);
+                let rows = match buckets.get(&hash) {
+                    Some(candidates) => candidates.iter().copied().filter(|rhs_row| holds(lhs_row, *rhs_row)).collect(),
+                    None => vec![],
+                };
+                matches.push(rows);
+            }
+        }
+    }
+    Ok(matches)
+}
+
+fn resolve_table(value: &Value) -> Option<Ref<MechTable>> {
+    match value {
+        Value::Table(t) => Some(t.clone()),
+        Value::MutableReference(r) => resolve_table(&r.borrow()),
+        _ => None,
+    }
+}
+
+fn table_column(table: &MechTable, id: u64) -> MResult<&Column> {
+    match table.data.get(&id) {
+        Some((_, col)) => Ok(col),
+        None => Err(MechError::new(TableColumnNotFoundError { column_id: id }, None).with_compiler_loc()),
+    }
+}
+
+// Without explicit keys, tables are joined on equality of every column name
+// they share.
+fn natural_join_keys(lhs: &MechTable, rhs: &MechTable) -> Vec<JoinKey> {
+    lhs.data
+        .keys()
+        .filter_map(|lhs_id| {
+            let name = lhs.col_names.get(lhs_id)?;
+            let rhs_id = rhs.col_names.iter().find(|(_, n)| *n == name).map(|(id, _)| *id)?;
+            Some(JoinKey { lhs: *lhs_id, op: JoinOp::Eq, rhs: rhs_id })
+        })
+        .collect()
+}
+
+// Parses the `on:` argument: a string or a matrix of strings, each either a
+// column name both tables share (`"id"`) or a condition between a left and a
+// right column (`"id = user-id"`, `"t >= start"`, `"t < end"`).
+fn parse_join_keys(lhs: &MechTable, rhs: &MechTable, on: &Value) -> MResult<Vec<JoinKey>> {
+    let conditions = match on {
+        Value::String(s) => vec![s.borrow().clone()],
+        Value::MatrixString(m) => m.as_vec(),
+        Value::MutableReference(r) => return parse_join_keys(lhs, rhs, &r.borrow()),
+        x => {
+            return Err(MechError::new(
+                UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: "table/join".to_string() },
+                None,
+            )
+            .with_compiler_loc())
+        }
+    };
+    if conditions.is_empty() {
+        return Err(MechError::new(InvalidJoinConditionError { condition: "".to_string() }, None).with_compiler_loc());
+    }
+    let ops = [("<=", JoinOp::Le), (">=", JoinOp::Ge), ("==", JoinOp::Eq), ("=", JoinOp::Eq), ("<", JoinOp::Lt), (">", JoinOp::Gt)];
+    let mut keys = vec![];
+    for condition in conditions {
+        let (lhs_name, op, rhs_name) = match ops.iter().find_map(|(tok, op)| condition.find(tok).map(|ix| (ix, tok.len(), *op))) {
+            Some((ix, len, op)) => (condition[..ix].trim(), op, condition[ix + len..].trim()),
+            None => (condition.trim(), JoinOp::Eq, condition.trim()),
+        };
+        if lhs_name.is_empty() || rhs_name.is_empty() {
+            return Err(MechError::new(InvalidJoinConditionError { condition: condition.clone() }, None).with_compiler_loc());
+        }
+        keys.push(JoinKey { lhs: join_column_id(lhs, lhs_name)?, op, rhs: join_column_id(rhs, rhs_name)? });
+    }
+    Ok(keys)
+}
+
+fn join_column_id(table: &MechTable, name: &str) -> MResult<u64> {
+    table
+        .col_names
+        .iter()
+        .find(|(_, n)| n.as_str() == name)
+        .map(|(id, _)| *id)
+        .ok_or_else(|| MechError::new(JoinColumnNotFoundError { name: name.to_string() }, None).with_compiler_loc())
+}
+
+// Builds a column of `kind` from (column, row) picks; missing picks stay empty.
+fn gather_column(kind: &ValueKind, picks: &[Option<(&Column, usize)>]) -> MResult<Column> {
+    let column = Column::empty(kind, picks.len());
+    for (ix, pick) in picks.iter().enumerate() {
+        if let Some((src, row)) = pick {
+            column.copy_cell(ix + 1, src, *row)?;
+        }
+    }
+    Ok(column)
+}
+
 fn make_optional_kind(kind: &ValueKind) -> ValueKind {
     match kind {
         ValueKind::Option(_) => kind.clone(),
@@ -241,38 +403,34 @@ fn make_optional_kind(kind: &ValueKind) -> ValueKind {
     }
 }
 
-impl MechFunctionFactory for TableJoinFxn {
-    fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
-        match args {
-            FunctionArgs::Binary(out, arg1, arg2) => {
-                let lhs: Ref<MechTable> = unsafe { arg1.as_unchecked() }.clone();
-                let rhs: Ref<MechTable> = unsafe { arg2.as_unchecked() }.clone();
-                let out: Ref<MechTable> = unsafe { out.as_unchecked() }.clone();
-                Ok(Box::new(TableJoinFxn {
-                    lhs,
-                    rhs,
-                    out,
-                    mode: JoinMode::Inner,
-                }))
-            }
-            _ => Err(MechError::new(
-                IncorrectNumberOfArguments {
-                    expected: 2,
-                    found: args.len(),
-                },
-                None,
-            )
-            .with_compiler_loc()),
+// Compiled joins are loaded by mode, from the tables and the `on:` argument.
+fn new_table_join(args: FunctionArgs, mode: JoinMode) -> MResult<Box<dyn MechFunction>> {
+    match args {
+        FunctionArgs::Variadic(out, arguments) => {
+            let mut fxn = TableJoinFxn::build(&arguments, mode)?;
+            fxn.out = unsafe { out.as_unchecked() }.clone();
+            fxn.solve();
+            Ok(Box::new(fxn))
         }
+        _ => Err(MechError::new(
+            IncorrectNumberOfArguments {
+                expected: 3,
+                found: args.len(),
+            },
+            None,
+        )
+        .with_compiler_loc()),
     }
 }
 
 impl MechFunctionImpl for TableJoinFxn {
+    // The join was checked when it was built. If a source table later loses
+    // a key column, the join can't be computed and the last result is kept.
     fn solve(&self) {
         unsafe {
             let lhs = &*self.lhs.as_ptr();
             let rhs = &*self.rhs.as_ptr();
-            if let Ok(joined) = Self::build_joined_table(lhs, rhs, self.mode) {
+            if let Ok(joined) = Self::build_joined_table(lhs, rhs, self.mode, &self.keys) {
                 *self.out.as_mut_ptr() = joined;
             }
         }
@@ -290,169 +448,95 @@ impl MechFunctionImpl for TableJoinFxn {
 impl MechFunctionCompiler for TableJoinFxn {
     fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
         let name = format!("TableJoinFxn::{:?}", self.mode);
-        compile_binop!(
+        let mut args = vec![Value::Table(self.lhs.clone()), Value::Table(self.rhs.clone())];
+        args.extend(self.on.iter().cloned());
+        compile_value_varop!(
             name,
-            self.out,
-            self.lhs,
-            self.rhs,
+            Value::Table(self.out.clone()),
+            args,
             ctx,
             FeatureFlag::Builtin(FeatureKind::Functions)
         );
     }
 }
 
-fn rows_match(
-    lhs: &MechTable,
-    lhs_row: usize,
-    rhs: &MechTable,
-    rhs_row: usize,
-    common_cols: &[(u64, u64)],
-) -> bool {
-    common_cols.iter().all(|(lhs_col, rhs_col)| {
-        let lhs_val = lhs.data.get(lhs_col).map(|(_, col)| col.index1d(lhs_row));
-        let rhs_val = rhs.data.get(rhs_col).map(|(_, col)| col.index1d(rhs_row));
-        lhs_val == rhs_val
-    })
-}
-
-fn merge_rows(
-    lhs: &MechTable,
-    lhs_row: usize,
-    rhs: &MechTable,
-    rhs_row: usize,
-    common_rhs: &HashSet<u64>,
-    rhs_empty: bool,
-) -> HashMap<u64, Value> {
-    let mut row = HashMap::new();
-    for (lhs_id, _) in lhs.data.iter() {
-        let value = lhs
-            .data
-            .get(lhs_id)
-            .map(|(_, col)| col.index1d(lhs_row))
-            .unwrap_or(Value::Empty);
-        row.insert(*lhs_id, value);
-    }
-    for (rhs_id, _) in rhs.data.iter() {
-        if common_rhs.contains(rhs_id) {
-            continue;
-        }
-        let value = if rhs_empty || rhs_row == 0 {
-            Value::Empty
-        } else {
-            rhs.data
-                .get(rhs_id)
-                .map(|(_, col)| col.index1d(rhs_row))
-                .unwrap_or(Value::Empty)
-        };
-        row.insert(*rhs_id, value);
-    }
-    row
-}
-
-fn lhs_only_row(lhs: &MechTable, lhs_row: usize) -> HashMap<u64, Value> {
-    lhs.data
-        .iter()
-        .map(|(lhs_id, _)| {
-            let value = lhs
-                .data
-                .get(lhs_id)
-                .map(|(_, col)| col.index1d(lhs_row))
-                .unwrap_or(Value::Empty);
-            (*lhs_id, value)
-        })
-        .collect()
-}
-
-fn compile_table_join(arguments: &Vec<Value>, mode: JoinMode) -> MResult<Box<dyn MechFunction>> {
-    if arguments.len() != 2 {
-        return Err(MechError::new(
-            IncorrectNumberOfArguments {
-                expected: 2,
-                found: arguments.len(),
-            },
-            None,
-        )
-        .with_compiler_loc());
-    }
-
-    let resolve = |v: &Value| -> Option<Ref<MechTable>> {
-        match v {
-            Value::Table(t) => Some(t.clone()),
-            Value::MutableReference(r) => match &*r.borrow() {
-                Value::Table(t) => Some(t.clone()),
-                _ => None,
-            },
-            _ => None,
+macro_rules! register_table_join {
+    ($($mode:ident),+ $(,)?) => {
+        paste! {
+            $(
+                fn [<new_table_join_ $mode:snake>](args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+                    new_table_join(args, JoinMode::$mode)
+                }
+                register_descriptor! {
+                  FunctionDescriptor {
+                    name: concat!("TableJoinFxn::", stringify!($mode)),
+                    ptr: [<new_table_join_ $mode:snake>],
+                  }
+                }
+            )+
         }
     };
-
-    let lhs = resolve(&arguments[0]);
-    let rhs = resolve(&arguments[1]);
-
-    match (lhs, rhs) {
-        (Some(lhs), Some(rhs)) => {
-            let out = Ref::new(TableJoinFxn::build_joined_table(
-                &lhs.borrow(),
-                &rhs.borrow(),
-                mode,
-            )?);
-            Ok(Box::new(TableJoinFxn {
-                lhs,
-                rhs,
-                out,
-                mode,
-            }))
-        }
-        _ => Err(MechError::new(
-            UnhandledFunctionArgumentKind2 {
-                arg: (arguments[0].kind(), arguments[1].kind()),
-                fxn_name: "table/join".to_string(),
-            },
-            None,
-        )
-        .with_compiler_loc()),
-    }
 }
 
+register_table_join!(Inner, LeftOuter, RightOuter, FullOuter, LeftSemi, LeftAnti);
+
 pub struct TableInnerJoin {}
 impl NativeFunctionCompiler for TableInnerJoin {
     fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
-        compile_table_join(arguments, JoinMode::Inner)
+        Ok(Box::new(TableJoinFxn::build(arguments, JoinMode::Inner)?))
+    }
+    fn parameters(&self) -> &'static [&'static str] {
+        &["lhs", "rhs", "on"]
     }
 }
 
 pub struct TableLeftOuterJoin {}
 impl NativeFunctionCompiler for TableLeftOuterJoin {
     fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
-        compile_table_join(arguments, JoinMode::LeftOuter)
+        Ok(Box::new(TableJoinFxn::build(arguments, JoinMode::LeftOuter)?))
+    }
+    fn parameters(&self) -> &'static [&'static str] {
+        &["lhs", "rhs", "on"]
     }
 }
 
 pub struct TableRightOuterJoin {}
 impl NativeFunctionCompiler for TableRightOuterJoin {
     fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
-        compile_table_join(arguments, JoinMode::RightOuter)
+        Ok(Box::new(TableJoinFxn::build(arguments, JoinMode::RightOuter)?))
+    }
+    fn parameters(&self) -> &'static [&'static str] {
+        &["lhs", "rhs", "on"]
     }
 }
 
 pub struct TableFullOuterJoin {}
 impl NativeFunctionCompiler for TableFullOuterJoin {
     fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
-        compile_table_join(arguments, JoinMode::FullOuter)
+        Ok(Box::new(TableJoinFxn::build(arguments, JoinMode::FullOuter)?))
+    }
+    fn parameters(&self) -> &'static [&'static str] {
+        &["lhs", "rhs", "on"]
     }
 }
 
 pub struct TableLeftSemiJoin {}
 impl NativeFunctionCompiler for TableLeftSemiJoin {
     fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
-        compile_table_join(arguments, JoinMode::LeftSemi)
+        Ok(Box::new(TableJoinFxn::build(arguments, JoinMode::LeftSemi)?))
+    }
+    fn parameters(&self) -> &'static [&'static str] {
+        &["lhs", "rhs", "on"]
     }
 }
 
 pub struct TableLeftAntiJoin {}
 impl NativeFunctionCompiler for TableLeftAntiJoin {
     fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
-        compile_table_join(arguments, JoinMode::LeftAnti)
+        Ok(Box::new(TableJoinFxn::build(arguments, JoinMode::LeftAnti)?))
+    }
+    fn parameters(&self) -> &'static [&'static str] {
+        &["lhs", "rhs", "on"]
     }
 }
 
@@ -492,3 +576,1283 @@ register_descriptor! {
     ptr: &TableLeftAntiJoin{},
   }
 }
+
+// Group By -------------------------------------------------------------------
+
+// How an aggregation reduces the rows of one group to a single cell. Column
+// ids refer to the grouped table.
+#[derive(Debug)]
+enum Aggregate {
+    Sum(u64),
+    Mean(u64),
+    Min(u64),
+    Max(u64),
+    Count,
+    First(u64),
+    Last(u64),
+    // A user function or lambda, given the group's column, or the group's
+    // rows as a table when no column is named.
+    User(MechCallback, Option<u64>),
+}
+
+macro_rules! sum_group_rows {
+    ($column:expr, $rows:expr, $($variant:ident, $ty:ty, $feature:tt);+ $(;)?) => {
+        match $column {
+            $(
+                #[cfg(feature = $feature)]
+                Column::$variant(Matrix::DVector(v)) => {
+                    let v = v.borrow();
+                    Some(Value::$variant(Ref::new($rows.iter().fold(<$ty>::default(), |acc, row| acc + v[row - 1]))))
+                }
+            )+
+            _ => None,
+        }
+    };
+}
+
+macro_rules! mean_group_rows {
+    ($column:expr, $rows:expr, $($variant:ident, $feature:tt);+ $(;)?) => {
+        match $column {
+            $(
+                #[cfg(feature = $feature)]
+                Column::$variant(Matrix::DVector(v)) => {
+                    let v = v.borrow();
+                    Some($rows.iter().map(|row| v[row - 1] as f64).sum::<f64>() / $rows.len() as f64)
+                }
+            )+
+            _ => None,
+        }
+    };
+}
+
+// table/group-by(T, by, aggs) groups the rows of T on the `by` columns and
+// reduces each group with the aggregations in the `aggs` record. Columns are
+// given by name or by 1-based position. Each output row holds a group's key,
+// in order of first appearance, followed by one column per aggregation. The
+// groups are recomputed whenever T changes.
+#[derive(Debug)]
+pub struct TableGroupByFxn {
+    table: Ref<MechTable>,
+    by: Value,
+    aggs: Option<Value>,
+    keys: Vec<u64>,
+    aggregates: Vec<(String, Aggregate)>,
+    out: Ref<MechTable>,
+}
+
+impl TableGroupByFxn {
+    fn build(arguments: &Vec<Value>, resolve: &mut CallbackResolver) -> MResult<Self> {
+        if arguments.len() != 2 && arguments.len() != 3 {
+            return Err(MechError::new(
+                IncorrectNumberOfArguments {
+                    expected: 3,
+                    found: arguments.len(),
+                },
+                None,
+            )
+            .with_compiler_loc());
+        }
+        let table = match resolve_table(&arguments[0]) {
+            Some(table) => table,
+            None => {
+                return Err(MechError::new(
+                    UnhandledFunctionArgumentKind1 { arg: arguments[0].kind(), fxn_name: "table/group-by".to_string() },
+                    None,
+                )
+                .with_compiler_loc())
+            }
+        };
+        let (keys, aggregates) = {
+            let table = table.borrow();
+            let keys = parse_group_keys(&table, &arguments[1])?;
+            let aggregates = match arguments.get(2) {
+                Some(aggs) => parse_aggregates(&table, aggs, resolve)?,
+                None => vec![],
+            };
+            (keys, aggregates)
+        };
+        let out = Ref::new(group_by_table(&table.borrow(), &keys, &aggregates)?);
+        Ok(Self {
+            table,
+            by: arguments[1].clone(),
+            aggs: arguments.get(2).cloned(),
+            keys,
+            aggregates,
+            out,
+        })
+    }
+}
+
+fn group_by_table(table: &MechTable, keys: &[u64], aggregates: &[(String, Aggregate)]) -> MResult<MechTable> {
+    let groups = group_rows(table, keys)?;
+    let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+    let mut col_names: HashMap<u64, String> = HashMap::new();
+    let firsts: Vec<usize> = groups.iter().map(|rows| rows[0]).collect();
+    for key in keys {
+        let (kind, column) = table.data.get(key).ok_or_else(|| MechError::new(TableColumnNotFoundError { column_id: *key }, None).with_compiler_loc())?;
+        let mut out = Column::empty(kind, 0);
+        column.copy_rows(&firsts, &mut out)?;
+        data.insert(*key, (kind.clone(), out));
+        col_names.insert(*key, table.col_names.get(key).cloned().unwrap_or_default());
+    }
+    for (name, aggregate) in aggregates {
+        let id = hash_str(name);
+        if data.contains_key(&id) {
+            return Err(MechError::new(DuplicateGroupByColumnError { name: name.clone() }, None).with_compiler_loc());
+        }
+        let (kind, column) = aggregate_groups(table, &groups, name, aggregate)?;
+        data.insert(id, (kind, column));
+        col_names.insert(id, name.clone());
+    }
+    let cols = data.len();
+    Ok(MechTable::new(groups.len(), cols, data, col_names))
+}
+
+impl MechFunctionFactory for TableGroupByFxn {
+    fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+            FunctionArgs::Variadic(out, arguments) => {
+                // Programs with user aggregations don't compile, so there is
+                // nothing to call back into.
+                let mut fxn = TableGroupByFxn::build(&arguments, &mut |_| {
+                    Err(MechError::new(CallbackNotCompilableError { fxn_name: "table/group-by".to_string() }, None).with_compiler_loc())
+                })?;
+                fxn.out = unsafe { out.as_unchecked() }.clone();
+                fxn.solve();
+                Ok(Box::new(fxn))
+            }
+            _ => Err(MechError::new(
+                IncorrectNumberOfArguments {
+                    expected: 3,
+                    found: args.len(),
+                },
+                None,
+            )
+            .with_compiler_loc()),
+        }
+    }
+}
+
+impl MechFunctionImpl for TableGroupByFxn {
+    fn solve(&self) {
+        let grouped = group_by_table(&self.table.borrow(), &self.keys, &self.aggregates);
+        if let Ok(grouped) = grouped {
+            *self.out.borrow_mut() = grouped;
+        }
+    }
+
+    fn out(&self) -> Value {
+        Value::Table(self.out.clone())
+    }
+    fn to_string(&self) -> String {
+        format!("{:#?}", self)
+    }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for TableGroupByFxn {
+    fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        if self.aggregates.iter().any(|(_, aggregate)| matches!(aggregate, Aggregate::User(..))) {
+            return Err(MechError::new(CallbackNotCompilableError { fxn_name: "table/group-by".to_string() }, None).with_compiler_loc());
+        }
+        let mut args = vec![Value::Table(self.table.clone()), self.by.clone()];
+        args.extend(self.aggs.iter().cloned());
+        compile_value_varop!(
+            "TableGroupByFxn",
+            Value::Table(self.out.clone()),
+            args,
+            ctx,
+            FeatureFlag::Builtin(FeatureKind::Functions)
+        );
+    }
+}
+
+register_descriptor! {
+  FunctionDescriptor {
+    name: "TableGroupByFxn",
+    ptr: TableGroupByFxn::new,
+  }
+}
+
+pub struct TableGroupBy {}
+impl NativeFunctionCompiler for TableGroupBy {
+    fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+        self.compile_with_callbacks(arguments, &mut |_| {
+            Err(MechError::new(CallbackNotCompilableError { fxn_name: "table/group-by".to_string() }, None).with_compiler_loc())
+        })
+    }
+    fn parameters(&self) -> &'static [&'static str] {
+        &["table", "by", "aggs"]
+    }
+    fn compile_with_callbacks(&self, arguments: &Vec<Value>, resolve: &mut CallbackResolver) -> MResult<Box<dyn MechFunction>> {
+        Ok(Box::new(TableGroupByFxn::build(arguments, resolve)?))
+    }
+}
+
+register_descriptor! {
+  FunctionCompilerDescriptor {
+    name: "table/group-by",
+    ptr: &TableGroupBy{},
+  }
+}
+
+// Partitions the rows (1-based) by their key cells, in order of first
+// appearance. Without keys every row falls in one group.
+fn group_rows(table: &MechTable, keys: &[u64]) -> MResult<Vec<Vec<usize>>> {
+    let cols = keys.iter().map(|key| table_column(table, *key)).collect::<MResult<Vec<&Column>>>()?;
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+    for row in 1..=table.rows {
+        let mut hasher = DefaultHasher::new();
+        for col in &cols {
+            col.hash_cell(row, &mut hasher);
+        }
+        let candidates = buckets.entry(hasher.finish()).or_default();
+        let group = candidates.iter().copied().find(|group| {
+            let first = groups[*group][0];
+            cols.iter().all(|col| col.cell_eq(row, col, first))
+        });
+        match group {
+            Some(group) => groups[group].push(row),
+            None => {
+                candidates.push(groups.len());
+                groups.push(vec![row]);
+            }
+        }
+    }
+    Ok(groups)
+}
+
+fn aggregate_groups(table: &MechTable, groups: &[Vec<usize>], name: &str, aggregate: &Aggregate) -> MResult<(ValueKind, Column)> {
+    let unsupported = |id: &u64| -> MechError {
+        let kind = table.data.get(id).map(|(kind, _)| kind.clone()).unwrap_or(ValueKind::Any);
+        MechError::new(UnsupportedAggregationError { name: name.to_string(), kind }, None).with_compiler_loc()
+    };
+    // Aggregations that pick one row per group keep the column's kind.
+    let pick = |id: &u64, choose: &dyn Fn(&Column, &[usize]) -> usize| -> MResult<(ValueKind, Column)> {
+        let (kind, column) = table.data.get(id).ok_or_else(|| MechError::new(TableColumnNotFoundError { column_id: *id }, None).with_compiler_loc())?;
+        let rows: Vec<usize> = groups.iter().map(|rows| choose(column, rows)).collect();
+        let mut out = Column::empty(kind, 0);
+        column.copy_rows(&rows, &mut out)?;
+        Ok((kind.clone(), out))
+    };
+    let extreme = |want: Ordering| {
+        move |column: &Column, rows: &[usize]| {
+            rows.iter().copied().fold(rows[0], |best, row| if column.cell_cmp(row, column, best) == Some(want) { row } else { best })
+        }
+    };
+    match aggregate {
+        Aggregate::First(id) => pick(id, &|_, rows| rows[0]),
+        Aggregate::Last(id) => pick(id, &|_, rows| rows[rows.len() - 1]),
+        Aggregate::Min(id) => pick(id, &extreme(Ordering::Less)),
+        Aggregate::Max(id) => pick(id, &extreme(Ordering::Greater)),
+        #[cfg(feature = "u64")]
+        Aggregate::Count => {
+            let counts = groups.iter().map(|rows| Value::U64(Ref::new(rows.len() as u64))).collect();
+            Ok((ValueKind::U64, Column::from_values(&ValueKind::U64, counts)))
+        }
+        Aggregate::Sum(id) => {
+            let (kind, column) = table.data.get(id).ok_or_else(|| unsupported(id))?;
+            let mut sums = vec![];
+            for rows in groups {
+                let sum = sum_group_rows!(column, rows,
+                    U8, u8, "u8"; U16, u16, "u16"; U32, u32, "u32"; U64, u64, "u64"; U128, u128, "u128";
+                    I8, i8, "i8"; I16, i16, "i16"; I32, i32, "i32"; I64, i64, "i64"; I128, i128, "i128";
+                    F32, f32, "f32"; F64, f64, "f64";
+                );
+                sums.push(sum.ok_or_else(|| unsupported(id))?);
+            }
+            Ok((kind.clone(), Column::from_values(kind, sums)))
+        }
+        #[cfg(feature = "f64")]
+        Aggregate::Mean(id) => {
+            let (_, column) = table.data.get(id).ok_or_else(|| unsupported(id))?;
+            let mut means = vec![];
+            for rows in groups {
+                let mean = mean_group_rows!(column, rows,
+                    U8, "u8"; U16, "u16"; U32, "u32"; U64, "u64"; U128, "u128";
+                    I8, "i8"; I16, "i16"; I32, "i32"; I64, "i64"; I128, "i128";
+                    F32, "f32"; F64, "f64";
+                );
+                means.push(Value::F64(Ref::new(mean.ok_or_else(|| unsupported(id))?)));
+            }
+            Ok((ValueKind::F64, Column::from_values(&ValueKind::F64, means)))
+        }
+        Aggregate::User(fxn, column) => {
+            let mut results = vec![];
+            for rows in groups {
+                let arg = match column {
+                    Some(id) => {
+                        let src = table_column(table, *id)?;
+                        let mut out = Column::empty(&src.cell_kind(), 0);
+                        src.copy_rows(rows, &mut out)?;
+                        out.to_value()
+                    }
+                    None => Value::Table(Ref::new(group_table(table, rows)?)),
+                };
+                results.push(fxn.call(vec![arg])?);
+            }
+            // The column takes the kind every result shares.
+            let kind = match results.first().map(|result| result.kind()) {
+                Some(kind) if results.iter().all(|result| result.kind() == kind) => kind,
+                _ => ValueKind::Any,
+            };
+            let column = Column::from_values(&kind, results);
+            Ok((kind, column))
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(MechError::new(UnknownAggregationError { name: name.to_string() }, None).with_compiler_loc()),
+    }
+}
+
+// The given rows (1-based) of `table`, in order, with all of its columns.
+fn group_table(table: &MechTable, rows: &[usize]) -> MResult<MechTable> {
+    let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+    for (id, (kind, column)) in &table.data {
+        let mut out = Column::empty(kind, 0);
+        column.copy_rows(rows, &mut out)?;
+        data.insert(*id, (kind.clone(), out));
+    }
+    Ok(MechTable::new(rows.len(), table.cols, data, table.col_names.clone()))
+}
+
+// Parses a column argument such as `by`: a column name or 1-based position,
+// or a matrix of either.
+fn parse_group_keys(table: &MechTable, by: &Value) -> MResult<Vec<u64>> {
+    match by {
+        Value::String(name) => Ok(vec![column_id_by_name(table, &name.borrow())?]),
+        Value::MatrixString(names) => names.as_vec().iter().map(|name| column_id_by_name(table, name)).collect(),
+        Value::MutableReference(r) => parse_group_keys(table, &r.borrow()),
+        x => match x.as_usize() {
+            Ok(ix) => Ok(vec![column_id_at_position(table, ix)?]),
+            Err(_) => x.as_vecusize()?.into_iter().map(|ix| column_id_at_position(table, ix)).collect(),
+        },
+    }
+}
+
+// Parses the `aggs` record. Each field names an output column and is either a
+// lambda, given each group as a table, or a string `"fxn(column)"` where fxn
+// is sum, mean, min, max, count, first, last or the name of a user function.
+// A user function without a column is given each group as a table.
+fn parse_aggregates(table: &MechTable, aggs: &Value, resolve: &mut CallbackResolver) -> MResult<Vec<(String, Aggregate)>> {
+    let record = match aggs {
+        Value::Record(record) => record.borrow().clone(),
+        Value::MutableReference(r) => return parse_aggregates(table, &r.borrow(), resolve),
+        x => {
+            return Err(MechError::new(
+                UnhandledFunctionArgumentKind1 { arg: x.kind(), fxn_name: "table/group-by".to_string() },
+                None,
+            )
+            .with_compiler_loc())
+        }
+    };
+    let mut aggregates = vec![];
+    for (id, value) in record.data.iter() {
+        let name = record.field_names.get(id).cloned().unwrap_or_default();
+        let aggregate = match value {
+            #[cfg(feature = "functions")]
+            Value::Lambda(_) => Aggregate::User(resolve(value)?, None),
+            Value::String(spec) => parse_aggregate(table, &name, &spec.borrow(), resolve)?,
+            _ => return Err(MechError::new(InvalidAggregationError { spec: value.to_string() }, None).with_compiler_loc()),
+        };
+        aggregates.push((name, aggregate));
+    }
+    Ok(aggregates)
+}
+
+fn parse_aggregate(table: &MechTable, name: &str, spec: &str, resolve: &mut CallbackResolver) -> MResult<Aggregate> {
+    let invalid = || MechError::new(InvalidAggregationError { spec: spec.to_string() }, None).with_compiler_loc();
+    let spec = spec.trim();
+    let (fxn, column) = match spec.find('(') {
+        Some(ix) if spec.ends_with(')') => (spec[..ix].trim(), spec[ix + 1..spec.len() - 1].trim()),
+        Some(_) => return Err(invalid()),
+        None => (spec, ""),
+    };
+    if fxn.is_empty() {
+        return Err(invalid());
+    }
+    let column = match column {
+        "" => None,
+        column => match column.parse::<usize>() {
+            Ok(ix) => Some(column_id_at_position(table, ix)?),
+            Err(_) => Some(column_id_by_name(table, column)?),
+        },
+    };
+    let aggregate = match (fxn, column) {
+        ("count", _) => Aggregate::Count,
+        ("sum", Some(id)) => Aggregate::Sum(id),
+        ("mean", Some(id)) => Aggregate::Mean(id),
+        ("min", Some(id)) => Aggregate::Min(id),
+        ("max", Some(id)) => Aggregate::Max(id),
+        ("first", Some(id)) => Aggregate::First(id),
+        ("last", Some(id)) => Aggregate::Last(id),
+        ("sum" | "mean" | "min" | "max" | "first" | "last", None) => return Err(invalid()),
+        (fxn, column) => {
+            let fxn = resolve(&Value::String(Ref::new(fxn.to_string()))).map_err(|err| match err.kind_as::<MissingFunctionError>() {
+                Some(_) => MechError::new(UnknownAggregationError { name: name.to_string() }, None).with_compiler_loc(),
+                None => err,
+            })?;
+            Aggregate::User(fxn, column)
+        }
+    };
+    Ok(aggregate)
+}
+
+fn column_id_by_name(table: &MechTable, name: &str) -> MResult<u64> {
+    table
+        .col_names
+        .iter()
+        .find(|(_, n)| n.as_str() == name)
+        .map(|(id, _)| *id)
+        .ok_or_else(|| MechError::new(GroupByColumnNotFoundError { column: name.to_string() }, None).with_compiler_loc())
+}
+
+fn column_id_at_position(table: &MechTable, ix: usize) -> MResult<u64> {
+    table
+        .column_id_at(ix)
+        .ok_or_else(|| MechError::new(GroupByColumnNotFoundError { column: ix.to_string() }, None).with_compiler_loc())
+}
+
+// Sort, Filter, Distinct and Windows ------------------------------------------
+
+macro_rules! cumsum_column {
+    ($column:expr, $($variant:ident, $ty:ty, $feature:tt);+ $(;)?) => {
+        match $column {
+            $(
+                #[cfg(feature = $feature)]
+                Column::$variant(Matrix::DVector(v)) => {
+                    let mut acc = <$ty>::default();
+                    let sums: Vec<$ty> = v.borrow().iter().map(|x| { acc = acc + *x; acc }).collect();
+                    Some(Column::$variant(Matrix::DVector(Ref::new(DVector::from_vec(sums)))))
+                }
+            )+
+            _ => None,
+        }
+    };
+}
+
+macro_rules! column_as_f64 {
+    ($column:expr, $($variant:ident, $feature:tt);+ $(;)?) => {
+        match $column {
+            $(
+                #[cfg(feature = $feature)]
+                Column::$variant(Matrix::DVector(v)) => Some(v.borrow().iter().map(|x| *x as f64).collect::<Vec<f64>>()),
+            )+
+            _ => None,
+        }
+    };
+}
+
+#[derive(Debug)]
+enum TableTransform {
+    // Column ids, each with whether it sorts descending.
+    SortBy(Vec<(u64, bool)>),
+    // Keeps the rows where the predicate holds.
+    Filter(FilterPredicate),
+    // Keeps the first row of each distinct combination of the columns.
+    Distinct(Vec<u64>),
+    // New column `name` holding column `column` of the row `n` rows before
+    // (lag) or after (lead), or the mean of the last `n` rows, or the sum of
+    // all rows so far.
+    Lag { column: u64, n: usize, name: String },
+    Lead { column: u64, n: usize, name: String },
+    RollingMean { column: u64, n: usize, name: String },
+    CumSum { column: u64, name: String },
+}
+
+impl TableTransform {
+    // The name a compiled program loads the transform by.
+    fn name(&self) -> &'static str {
+        match self {
+            TableTransform::SortBy(_) => "SortBy",
+            TableTransform::Filter(_) => "Filter",
+            TableTransform::Distinct(_) => "Distinct",
+            TableTransform::Lag { .. } => "Lag",
+            TableTransform::Lead { .. } => "Lead",
+            TableTransform::RollingMean { .. } => "RollingMean",
+            TableTransform::CumSum { .. } => "CumSum",
+        }
+    }
+}
+
+// A bool per row, or a function given the table that returns one, called
+// again each time the table changes.
+#[derive(Debug)]
+enum FilterPredicate {
+    Mask(Value),
+    Function(MechCallback),
+}
+
+#[derive(Debug)]
+struct TableTransformFxn {
+    source: Ref<MechTable>,
+    // The arguments after the table, kept so a compiled program can parse
+    // the transform again.
+    args: Vec<Value>,
+    out: Ref<MechTable>,
+    transform: TableTransform,
+}
+
+impl TableTransformFxn {
+    fn new(source: Ref<MechTable>, args: &[Value], transform: TableTransform) -> MResult<Self> {
+        let out = Ref::new(Self::build(&source.borrow(), &transform)?);
+        Ok(TableTransformFxn { source, args: args.to_vec(), out, transform })
+    }
+
+    fn build(table: &MechTable, transform: &TableTransform) -> MResult<MechTable> {
+        match transform {
+            TableTransform::SortBy(keys) => group_table(table, &sort_rows(table, keys)?),
+            TableTransform::Filter(FilterPredicate::Mask(mask)) => group_table(table, &filter_rows(table, mask)?),
+            TableTransform::Filter(FilterPredicate::Function(predicate)) => {
+                let mask = predicate.call(vec![Value::Table(Ref::new(table.clone()))])?;
+                group_table(table, &filter_rows(table, &mask)?)
+            }
+            TableTransform::Distinct(keys) => {
+                let firsts: Vec<usize> = group_rows(table, keys)?.iter().map(|rows| rows[0]).collect();
+                group_table(table, &firsts)
+            }
+            TableTransform::Lag { column, n, name } => {
+                let shifted = (1..=table.rows).map(|row| row.checked_sub(*n).filter(|row| *row >= 1)).collect::<Vec<_>>();
+                with_column(table, name, shifted_column(table, *column, &shifted)?)
+            }
+            TableTransform::Lead { column, n, name } => {
+                let shifted = (1..=table.rows).map(|row| Some(row + n).filter(|row| *row <= table.rows)).collect::<Vec<_>>();
+                with_column(table, name, shifted_column(table, *column, &shifted)?)
+            }
+            TableTransform::RollingMean { column, n, name } => {
+                let cells = numeric_cells(table, *column)?;
+                let means = (0..cells.len())
+                    .map(|ix| {
+                        let window = &cells[(ix + 1).saturating_sub(*n)..=ix];
+                        Value::F64(Ref::new(window.iter().sum::<f64>() / window.len() as f64))
+                    })
+                    .collect();
+                with_column(table, name, (ValueKind::F64, Column::from_values(&ValueKind::F64, means)))
+            }
+            TableTransform::CumSum { column, name } => {
+                let (kind, src) = table.data.get(column).ok_or_else(|| MechError::new(TableColumnNotFoundError { column_id: *column }, None).with_compiler_loc())?;
+                let sums = cumsum_column!(src,
+                    U8, u8, "u8"; U16, u16, "u16"; U32, u32, "u32"; U64, u64, "u64"; U128, u128, "u128";
+                    I8, i8, "i8"; I16, i16, "i16"; I32, i32, "i32"; I64, i64, "i64"; I128, i128, "i128";
+                    F32, f32, "f32"; F64, f64, "f64";
+                );
+                let sums = sums.ok_or_else(|| MechError::new(UnsupportedAggregationError { name: name.clone(), kind: kind.clone() }, None).with_compiler_loc())?;
+                with_column(table, name, (kind.clone(), sums))
+            }
+        }
+    }
+}
+
+impl MechFunctionImpl for TableTransformFxn {
+    fn solve(&self) {
+        unsafe {
+            let source = &*self.source.as_ptr();
+            if let Ok(table) = Self::build(source, &self.transform) {
+                *self.out.as_mut_ptr() = table;
+            }
+        }
+    }
+
+    fn out(&self) -> Value {
+        Value::Table(self.out.clone())
+    }
+    fn to_string(&self) -> String {
+        format!("{:#?}", self)
+    }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for TableTransformFxn {
+    fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        if let TableTransform::Filter(FilterPredicate::Function(_)) = self.transform {
+            return Err(MechError::new(CallbackNotCompilableError { fxn_name: "table/filter".to_string() }, None).with_compiler_loc());
+        }
+        let name = format!("TableTransformFxn::{}", self.transform.name());
+        let mut args = vec![Value::Table(self.source.clone())];
+        args.extend(self.args.iter().cloned());
+        compile_value_varop!(
+            name,
+            Value::Table(self.out.clone()),
+            args,
+            ctx,
+            FeatureFlag::Builtin(FeatureKind::Functions)
+        );
+    }
+}
+
+fn numeric_cells(table: &MechTable, column: u64) -> MResult<Vec<f64>> {
+    let (kind, src) = table.data.get(&column).ok_or_else(|| MechError::new(TableColumnNotFoundError { column_id: column }, None).with_compiler_loc())?;
+    let cells = column_as_f64!(src,
+        U8, "u8"; U16, "u16"; U32, "u32"; U64, "u64"; U128, "u128";
+        I8, "i8"; I16, "i16"; I32, "i32"; I64, "i64"; I128, "i128";
+        F32, "f32"; F64, "f64";
+    );
+    cells.ok_or_else(|| {
+        let name = table.col_names.get(&column).cloned().unwrap_or_default();
+        MechError::new(UnsupportedAggregationError { name, kind: kind.clone() }, None).with_compiler_loc()
+    })
+}
+
+// A stable sort of the rows (1-based) on the keys, in order.
+fn sort_rows(table: &MechTable, keys: &[(u64, bool)]) -> MResult<Vec<usize>> {
+    let cols = keys
+        .iter()
+        .map(|(id, descending)| Ok((table_column(table, *id)?, *descending)))
+        .collect::<MResult<Vec<(&Column, bool)>>>()?;
+    let mut rows: Vec<usize> = (1..=table.rows).collect();
+    rows.sort_by(|a, b| {
+        for (col, descending) in &cols {
+            let ord = col.cell_cmp(*a, col, *b).unwrap_or(Ordering::Equal);
+            let ord = if *descending { ord.reverse() } else { ord };
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+        Ordering::Equal
+    });
+    Ok(rows)
+}
+
+// The rows (1-based) where the predicate, a bool per row or a single bool
+// for every row, is true.
+fn filter_rows(table: &MechTable, predicate: &Value) -> MResult<Vec<usize>> {
+    let keep = match predicate {
+        Value::MutableReference(r) => return filter_rows(table, &r.borrow()),
+        Value::Bool(b) => vec![*b.borrow(); table.rows],
+        x => x.as_vecbool()?,
+    };
+    if keep.len() != table.rows {
+        return Err(MechError::new(TableFilterLengthError { rows: table.rows, found: keep.len() }, None).with_compiler_loc());
+    }
+    Ok((1..=table.rows).filter(|row| keep[row - 1]).collect())
+}
+
+// Column `column` of the given rows, left empty where a row is None.
+fn shifted_column(table: &MechTable, column: u64, rows: &[Option<usize>]) -> MResult<(ValueKind, Column)> {
+    let (kind, src) = table.data.get(&column).ok_or_else(|| MechError::new(TableColumnNotFoundError { column_id: column }, None).with_compiler_loc())?;
+    let kind = make_optional_kind(kind);
+    let picks: Vec<Option<(&Column, usize)>> = rows.iter().map(|row| row.map(|row| (src, row))).collect();
+    Ok((kind.clone(), gather_column(&kind, &picks)?))
+}
+
+// `table` with a new column appended.
+fn with_column(table: &MechTable, name: &str, (kind, column): (ValueKind, Column)) -> MResult<MechTable> {
+    let id = hash_str(name);
+    if table.data.contains_key(&id) || table.col_names.values().any(|n| n == name) {
+        return Err(MechError::new(DuplicateWindowColumnError { name: name.to_string() }, None).with_compiler_loc());
+    }
+    let mut data = table.data.clone();
+    let mut col_names = table.col_names.clone();
+    data.insert(id, (kind, column));
+    col_names.insert(id, name.to_string());
+    Ok(MechTable::new(table.rows, table.cols + 1, data, col_names))
+}
+
+// Parses the sort keys: a column name or position, or a matrix of them. A name
+// may be followed by `asc` or `desc`.
+fn parse_sort_keys(table: &MechTable, by: &Value) -> MResult<Vec<(u64, bool)>> {
+    let specs = match by {
+        Value::MutableReference(r) => return parse_sort_keys(table, &r.borrow()),
+        Value::String(s) => vec![s.borrow().clone()],
+        Value::MatrixString(m) => m.as_vec(),
+        x => return Ok(parse_group_keys(table, x)?.into_iter().map(|id| (id, false)).collect()),
+    };
+    let mut keys = vec![];
+    for spec in specs {
+        let mut words = spec.split_whitespace();
+        let (name, direction) = (words.next().unwrap_or(""), words.next());
+        let descending = match (direction, words.next()) {
+            (None, None) | (Some("asc"), None) => false,
+            (Some("desc"), None) => true,
+            _ => return Err(MechError::new(InvalidSortKeyError { key: spec.clone() }, None).with_compiler_loc()),
+        };
+        let id = match name.parse::<usize>() {
+            Ok(ix) => column_id_at_position(table, ix)?,
+            Err(_) => column_id_by_name(table, name)?,
+        };
+        keys.push((id, descending));
+    }
+    Ok(keys)
+}
+
+fn transform_source(arguments: &Vec<Value>, fxn_name: &str, min: usize, max: usize) -> MResult<Ref<MechTable>> {
+    if arguments.len() < min || arguments.len() > max {
+        return Err(MechError::new(IncorrectNumberOfArguments { expected: max, found: arguments.len() }, None).with_compiler_loc());
+    }
+    resolve_table(&arguments[0]).ok_or_else(|| {
+        MechError::new(UnhandledFunctionArgumentKind1 { arg: arguments[0].kind(), fxn_name: fxn_name.to_string() }, None).with_compiler_loc()
+    })
+}
+
+// The column and window size arguments of a window function; the size
+// defaults to 1.
+fn window_args(table: &MechTable, arguments: &Vec<Value>) -> MResult<(u64, usize, String)> {
+    let column = match parse_group_keys(table, &arguments[1])?.as_slice() {
+        [column] => *column,
+        _ => return Err(MechError::new(UnhandledFunctionArgumentKind1 { arg: arguments[1].kind(), fxn_name: "table/window".to_string() }, None).with_compiler_loc()),
+    };
+    let n = match arguments.get(2) {
+        Some(n) => n.as_usize()?,
+        None => 1,
+    };
+    let name = table.col_names.get(&column).cloned().unwrap_or_default();
+    Ok((column, n, name))
+}
+
+// Parses the arguments of a transform and computes its first result.
+fn table_transform(fxn_name: &str, arguments: &Vec<Value>, resolve: &mut CallbackResolver) -> MResult<TableTransformFxn> {
+    let (min, max) = match fxn_name {
+        "table/distinct" => (1, 2),
+        "table/lag" | "table/lead" => (2, 3),
+        "table/rolling-mean" => (3, 3),
+        _ => (2, 2),
+    };
+    let source = transform_source(arguments, fxn_name, min, max)?;
+    let transform = {
+        let table = source.borrow();
+        match fxn_name {
+            "table/sort-by" => TableTransform::SortBy(parse_sort_keys(&table, &arguments[1])?),
+            "table/filter" => match detach_value(&arguments[1]) {
+                Value::String(_) => TableTransform::Filter(FilterPredicate::Function(resolve(&arguments[1])?)),
+                #[cfg(feature = "functions")]
+                Value::Lambda(_) => TableTransform::Filter(FilterPredicate::Function(resolve(&arguments[1])?)),
+                _ => TableTransform::Filter(FilterPredicate::Mask(arguments[1].clone())),
+            },
+            "table/distinct" => TableTransform::Distinct(match arguments.get(1) {
+                Some(by) => parse_group_keys(&table, by)?,
+                None => table.data.keys().copied().collect(),
+            }),
+            "table/lag" => {
+                let (column, n, name) = window_args(&table, arguments)?;
+                TableTransform::Lag { column, n, name: format!("{}-lag", name) }
+            }
+            "table/lead" => {
+                let (column, n, name) = window_args(&table, arguments)?;
+                TableTransform::Lead { column, n, name: format!("{}-lead", name) }
+            }
+            "table/rolling-mean" => {
+                let (column, n, name) = window_args(&table, arguments)?;
+                if n == 0 {
+                    return Err(MechError::new(InvalidWindowSizeError, None).with_compiler_loc());
+                }
+                TableTransform::RollingMean { column, n, name: format!("{}-rolling-mean", name) }
+            }
+            _ => {
+                let (column, _, name) = window_args(&table, arguments)?;
+                TableTransform::CumSum { column, name: format!("{}-cumsum", name) }
+            }
+        }
+    };
+    TableTransformFxn::new(source, &arguments[1..], transform)
+}
+
+// Compiled transforms are loaded from the table and the transform's other
+// arguments. Programs with a filter function don't compile, so there is
+// nothing to call back into.
+fn new_table_transform(args: FunctionArgs, fxn_name: &str) -> MResult<Box<dyn MechFunction>> {
+    match args {
+        FunctionArgs::Variadic(out, arguments) => {
+            let mut fxn = table_transform(fxn_name, &arguments, &mut |_| {
+                Err(MechError::new(CallbackNotCompilableError { fxn_name: fxn_name.to_string() }, None).with_compiler_loc())
+            })?;
+            fxn.out = unsafe { out.as_unchecked() }.clone();
+            fxn.solve();
+            Ok(Box::new(fxn))
+        }
+        _ => Err(MechError::new(
+            IncorrectNumberOfArguments {
+                expected: 2,
+                found: args.len(),
+            },
+            None,
+        )
+        .with_compiler_loc()),
+    }
+}
+
+macro_rules! register_table_transform {
+    ($($struct_name:ident, $transform:ident, $fxn_name:tt);+ $(;)?) => {
+        paste! {
+            $(
+                pub struct $struct_name {}
+                impl NativeFunctionCompiler for $struct_name {
+                    fn compile(&self, arguments: &Vec<Value>) -> MResult<Box<dyn MechFunction>> {
+                        self.compile_with_callbacks(arguments, &mut |_| {
+                            Err(MechError::new(CallbackNotCompilableError { fxn_name: $fxn_name.to_string() }, None).with_compiler_loc())
+                        })
+                    }
+                    fn compile_with_callbacks(&self, arguments: &Vec<Value>, resolve: &mut CallbackResolver) -> MResult<Box<dyn MechFunction>> {
+                        Ok(Box::new(table_transform($fxn_name, arguments, resolve)?))
+                    }
+                }
+                register_descriptor! {
+                  FunctionCompilerDescriptor {
+                    name: $fxn_name,
+                    ptr: &$struct_name{},
+                  }
+                }
+                fn [<new_table_transform_ $transform:snake>](args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+                    new_table_transform(args, $fxn_name)
+                }
+                register_descriptor! {
+                  FunctionDescriptor {
+                    name: concat!("TableTransformFxn::", stringify!($transform)),
+                    ptr: [<new_table_transform_ $transform:snake>],
+                  }
+                }
+            )+
+        }
+    };
+}
+
+register_table_transform!(
+    TableSortBy, SortBy, "table/sort-by";
+    TableFilter, Filter, "table/filter";
+    TableDistinct, Distinct, "table/distinct";
+    TableLag, Lag, "table/lag";
+    TableLead, Lead, "table/lead";
+    TableRollingMean, RollingMean, "table/rolling-mean";
+    TableCumSum, CumSum, "table/cumsum";
+);
+
+// Split and Flatten ------------------------------------------------------------
+
+// `rows >- T` holds each record of T, in row order, and `groups >- T by k`
+// one table per value of column k, in order of first appearance. The parts
+// are split again whenever T changes.
+#[derive(Debug)]
+pub struct TableSplitFxn {
+    table: Ref<MechTable>,
+    key: Option<u64>,
+    out: Ref<DVector<Value>>,
+}
+
+impl TableSplitFxn {
+    pub fn build(source: &Value, by: Option<&str>) -> MResult<Self> {
+        let table = resolve_table(source).ok_or_else(|| MechError::new(SplitTableKindError { kind: source.kind() }, None).with_compiler_loc())?;
+        let key = match by {
+            Some(name) => Some(column_id_by_name(&table.borrow(), name)?),
+            None => None,
+        };
+        let out = Ref::new(DVector::from_vec(split_parts(&table.borrow(), key)?));
+        Ok(Self { table, key, out })
+    }
+}
+
+fn split_parts(table: &MechTable, key: Option<u64>) -> MResult<Vec<Value>> {
+    match key {
+        Some(key) => group_rows(table, &[key])?
+            .iter()
+            .map(|rows| Ok(Value::Table(Ref::new(group_table(table, rows)?))))
+            .collect(),
+        None => Ok((1..=table.rows)
+            .filter_map(|row| table.get_record(row))
+            .map(|record| Value::Record(Ref::new(record)))
+            .collect()),
+    }
+}
+
+// Writes the rows of `table` into the records split from it before, keeping
+// their Refs so that fields read from them follow. Returns false when the
+// records no longer line up with the rows.
+fn refresh_split_records(table: &MechTable, records: &DVector<Value>) -> bool {
+    if records.len() != table.rows {
+        return false;
+    }
+    for (ix, record) in records.iter().enumerate() {
+        let record = match record {
+            Value::Record(record) => record.borrow(),
+            _ => return false,
+        };
+        for (id, (_, column)) in table.data.iter() {
+            let name = table.col_names.get(id).cloned().unwrap_or_default();
+            match record.data.get(&hash_str(&name)) {
+                Some(field) if column.copy_cell_into(ix + 1, field) => (),
+                _ => return false,
+            }
+        }
+    }
+    true
+}
+
+impl MechFunctionImpl for TableSplitFxn {
+    fn solve(&self) {
+        let table = self.table.borrow();
+        if self.key.is_none() && refresh_split_records(&table, &self.out.borrow()) {
+            return;
+        }
+        if let Ok(parts) = split_parts(&table, self.key) {
+            *self.out.borrow_mut() = DVector::from_vec(parts);
+        }
+    }
+
+    fn out(&self) -> Value {
+        Value::MatrixValue(Matrix::DVector(self.out.clone()))
+    }
+    fn to_string(&self) -> String {
+        format!("{:#?}", self)
+    }
+}
+
+// A vector of records or tables can't be written as a constant yet.
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for TableSplitFxn {
+    fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        Err(MechError::new(UnsupportedValueKindError { kind: self.out().kind() }, None).with_compiler_loc())
+    }
+}
+
+// `T -< source` as a plan step, so T is flattened again whenever the source
+// changes.
+#[derive(Debug)]
+pub struct TableFlattenFxn {
+    source: Value,
+    out: Ref<MechTable>,
+}
+
+impl TableFlattenFxn {
+    pub fn build(source: Value) -> MResult<Self> {
+        let out = Ref::new(flatten_table(&source)?);
+        Ok(Self { source, out })
+    }
+}
+
+impl MechFunctionFactory for TableFlattenFxn {
+    fn new(args: FunctionArgs) -> MResult<Box<dyn MechFunction>> {
+        match args {
+            FunctionArgs::Variadic(out, arguments) if arguments.len() == 1 => {
+                let mut fxn = TableFlattenFxn::build(arguments[0].clone())?;
+                fxn.out = unsafe { out.as_unchecked() }.clone();
+                fxn.solve();
+                Ok(Box::new(fxn))
+            }
+            _ => Err(MechError::new(
+                IncorrectNumberOfArguments {
+                    expected: 1,
+                    found: args.len(),
+                },
+                None,
+            )
+            .with_compiler_loc()),
+        }
+    }
+}
+
+impl MechFunctionImpl for TableFlattenFxn {
+    fn solve(&self) {
+        if let Ok(table) = flatten_table(&self.source) {
+            *self.out.borrow_mut() = table;
+        }
+    }
+
+    fn out(&self) -> Value {
+        Value::Table(self.out.clone())
+    }
+    fn to_string(&self) -> String {
+        format!("{:#?}", self)
+    }
+}
+
+#[cfg(feature = "compiler")]
+impl MechFunctionCompiler for TableFlattenFxn {
+    fn compile(&self, ctx: &mut CompileCtx) -> MResult<Register> {
+        compile_value_varop!(
+            "TableFlattenFxn",
+            Value::Table(self.out.clone()),
+            vec![self.source.clone()],
+            ctx,
+            FeatureFlag::Builtin(FeatureKind::Functions)
+        );
+    }
+}
+
+register_descriptor! {
+  FunctionDescriptor {
+    name: "TableFlattenFxn",
+    ptr: TableFlattenFxn::new,
+  }
+}
+
+// One row being flattened, as named cells in column order.
+type FlatRow = Vec<(String, Value)>;
+
+// `T -< source`: the rows of a record, a table, or a vector of either, in
+// one table. Record cells become `column-field` columns, and a table cell
+// repeats its row once per nested row. Columns missing from some rows take
+// an optional kind.
+pub fn flatten_table(source: &Value) -> MResult<MechTable> {
+    let mut rows = vec![];
+    flatten_rows(source, &mut rows)?;
+    let mut flat = vec![];
+    for row in rows {
+        expand_row(row, &mut flat)?;
+    }
+
+    let mut names: Vec<String> = vec![];
+    for row in &flat {
+        for (name, _) in row {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    let mut data: IndexMap<u64, (ValueKind, Column)> = IndexMap::new();
+    let mut col_names: HashMap<u64, String> = HashMap::new();
+    for name in names {
+        let cells: Vec<Option<Value>> = flat
+            .iter()
+            .map(|row| row.iter().find(|(n, _)| *n == name).map(|(_, value)| value.clone()))
+            .collect();
+        let mut kinds = cells.iter().flatten().map(|value| value.kind());
+        let first = kinds.next().unwrap_or(ValueKind::Any);
+        let kind = if kinds.all(|kind| kind == first) { first } else { ValueKind::Any };
+        let kind = if cells.iter().any(|cell| cell.is_none()) { make_optional_kind(&kind) } else { kind };
+        let values = cells.into_iter().map(|cell| cell.unwrap_or(Value::Empty)).collect();
+        let id = hash_str(&name);
+        data.insert(id, (kind.clone(), Column::from_values(&kind, values)));
+        col_names.insert(id, name);
+    }
+    let cols = data.len();
+    Ok(MechTable::new(flat.len(), cols, data, col_names))
+}
+
+fn flatten_rows(value: &Value, rows: &mut Vec<FlatRow>) -> MResult<()> {
+    match value {
+        Value::MutableReference(r) => flatten_rows(&r.borrow(), rows),
+        Value::Record(record) => {
+            let record = record.borrow();
+            rows.push(
+                record
+                    .data
+                    .iter()
+                    .map(|(id, value)| (record.field_names.get(id).cloned().unwrap_or_default(), value.clone()))
+                    .collect(),
+            );
+            Ok(())
+        }
+        Value::Table(table) => {
+            let table = table.borrow();
+            for row in 1..=table.rows {
+                rows.push(
+                    table
+                        .data
+                        .iter()
+                        .map(|(id, (_, column))| (table.col_names.get(id).cloned().unwrap_or_default(), column.index1d(row)))
+                        .collect(),
+                );
+            }
+            Ok(())
+        }
+        Value::MatrixValue(m) => {
+            for value in m.as_vec() {
+                flatten_rows(&value, rows)?;
+            }
+            Ok(())
+        }
+        x => Err(MechError::new(FlattenTableKindError { kind: x.kind() }, None).with_compiler_loc()),
+    }
+}
+
+fn is_nested(value: &Value) -> bool {
+    match value {
+        Value::MutableReference(r) => is_nested(&r.borrow()),
+        Value::Record(_) | Value::Table(_) => true,
+        Value::MatrixValue(m) => m.as_vec().iter().all(is_nested),
+        _ => false,
+    }
+}
+
+// Expands the first nested cell of the row, then the rest recursively.
+fn expand_row(row: FlatRow, out: &mut Vec<FlatRow>) -> MResult<()> {
+    let ix = match row.iter().position(|(_, value)| is_nested(value)) {
+        Some(ix) => ix,
+        None => {
+            out.push(row);
+            return Ok(());
+        }
+    };
+    let (name, cell) = row[ix].clone();
+    let mut nested = vec![];
+    flatten_rows(&cell, &mut nested)?;
+    if nested.is_empty() {
+        let mut row = row;
+        row.remove(ix);
+        return expand_row(row, out);
+    }
+    for inner in nested {
+        let mut expanded = row[..ix].to_vec();
+        expanded.extend(inner.into_iter().map(|(field, value)| (format!("{}-{}", name, field), value)));
+        expanded.extend(row[ix + 1..].iter().cloned());
+        expand_row(expanded, out)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct JoinColumnNotFoundError {
+    pub name: String,
+}
+impl MechErrorKind for JoinColumnNotFoundError {
+    fn name(&self) -> &str {
+        "JoinColumnNotFound"
+    }
+    fn message(&self) -> String {
+        format!("Join key column '{}' is not defined in the joined table.", self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidJoinConditionError {
+    pub condition: String,
+}
+impl MechErrorKind for InvalidJoinConditionError {
+    fn name(&self) -> &str {
+        "InvalidJoinCondition"
+    }
+    fn message(&self) -> String {
+        format!("Invalid join condition '{}'. Expected a column name or `left-column op right-column` with op one of = < <= > >=.", self.condition)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GroupByColumnNotFoundError {
+    pub column: String,
+}
+impl MechErrorKind for GroupByColumnNotFoundError {
+    fn name(&self) -> &str {
+        "GroupByColumnNotFound"
+    }
+    fn message(&self) -> String {
+        format!("Column '{}' is not defined in the grouped table.", self.column)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidAggregationError {
+    pub spec: String,
+}
+impl MechErrorKind for InvalidAggregationError {
+    fn name(&self) -> &str {
+        "InvalidAggregation"
+    }
+    fn message(&self) -> String {
+        format!("Invalid aggregation '{}'. Expected a lambda or `fxn(column)` with fxn one of sum mean min max count first last, or a user function.", self.spec)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnknownAggregationError {
+    pub name: String,
+}
+impl MechErrorKind for UnknownAggregationError {
+    fn name(&self) -> &str {
+        "UnknownAggregation"
+    }
+    fn message(&self) -> String {
+        format!("Aggregation for column '{}' does not name a function in scope.", self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnsupportedAggregationError {
+    pub name: String,
+    pub kind: ValueKind,
+}
+impl MechErrorKind for UnsupportedAggregationError {
+    fn name(&self) -> &str {
+        "UnsupportedAggregation"
+    }
+    fn message(&self) -> String {
+        format!("Aggregation for column '{}' is not defined for cells of kind {}.", self.name, self.kind)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateGroupByColumnError {
+    pub name: String,
+}
+impl MechErrorKind for DuplicateGroupByColumnError {
+    fn name(&self) -> &str {
+        "DuplicateGroupByColumn"
+    }
+    fn message(&self) -> String {
+        format!("Aggregation column '{}' is already a column of the grouped table.", self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SplitTableKindError {
+    pub kind: ValueKind,
+}
+impl MechErrorKind for SplitTableKindError {
+    fn name(&self) -> &str {
+        "SplitTableKind"
+    }
+    fn message(&self) -> String {
+        format!("Only tables can be split, found a value of kind {}.", self.kind)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FlattenTableKindError {
+    pub kind: ValueKind,
+}
+impl MechErrorKind for FlattenTableKindError {
+    fn name(&self) -> &str {
+        "FlattenTableKind"
+    }
+    fn message(&self) -> String {
+        format!("Only records, tables and vectors of them can be flattened, found a value of kind {}.", self.kind)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidSortKeyError {
+    pub key: String,
+}
+impl MechErrorKind for InvalidSortKeyError {
+    fn name(&self) -> &str {
+        "InvalidSortKey"
+    }
+    fn message(&self) -> String {
+        format!("Invalid sort key '{}'. Expected a column name, optionally followed by `asc` or `desc`.", self.key)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableFilterLengthError {
+    pub rows: usize,
+    pub found: usize,
+}
+impl MechErrorKind for TableFilterLengthError {
+    fn name(&self) -> &str {
+        "TableFilterLength"
+    }
+    fn message(&self) -> String {
+        format!("Filter predicate has {} elements but the table has {} rows.", self.found, self.rows)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateWindowColumnError {
+    pub name: String,
+}
+impl MechErrorKind for DuplicateWindowColumnError {
+    fn name(&self) -> &str {
+        "DuplicateWindowColumn"
+    }
+    fn message(&self) -> String {
+        format!("Window column '{}' is already a column of the table.", self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidWindowSizeError;
+impl MechErrorKind for InvalidWindowSizeError {
+    fn name(&self) -> &str {
+        "InvalidWindowSize"
+    }
+    fn message(&self) -> String {
+        "Rolling window size must be at least 1.".to_string()
+    }
+}
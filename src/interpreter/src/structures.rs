@@ -153,6 +153,8 @@ pub fn record(rcrd: &Record, env: Option<&Environment>, p: &Interpreter) -> MRes
     kinds,
     data,
     field_names,
+    #[cfg(feature = "table")]
+    table_row: None,
   })))
 }
 
@@ -269,14 +271,14 @@ macro_rules! handle_value_kind {
   ($value_kind:ident, $val:expr, $field_label:expr, $data_map:expr, $converter:ident) => {{
     let mut vals = Vec::new();
     let id = $field_label; // <- FIXED: it's already a u64
-    for x in $val.as_vec().iter() {
+    for x in $val.iter() {
       match x.$converter() {
-        Ok(u) => vals.push(u.to_value()),
+        Ok(u) => vals.push(u.borrow().clone()),
         Err(_) => {
           return Err(MechError::new(
             TableColumnKindMismatchError { 
               column_id: id, 
-              expected_kind: $value_kind.clone(), 
+              expected_kind: ValueKind::$value_kind, 
               actual_kind: x.kind() 
             },
             None
@@ -284,7 +286,7 @@ macro_rules! handle_value_kind {
         }
       }
     }
-    $data_map.insert(id, ($value_kind.clone(), Value::to_matrixd(vals.clone(), vals.len(), 1)));
+    $data_map.insert(id, (ValueKind::$value_kind, Column::$value_kind(Matrix::DVector(Ref::new(na::DVector::from_vec(vals))))));
   }};
 }
 
@@ -293,61 +295,51 @@ macro_rules! handle_value_kind {
 fn handle_column_kind(
     kind: ValueKind,
     id: u64,
-    val: Matrix<Value>,
-    data_map: &mut IndexMap<u64,(ValueKind,Matrix<Value>)>
+    val: Vec<Value>,
+    data_map: &mut IndexMap<u64,(ValueKind,Column)>
 ) -> MResult<()> 
 {
   match kind {
     #[cfg(feature = "i8")]
-    ValueKind::I8   => handle_value_kind!(kind, val, id, data_map, as_i8),
+    ValueKind::I8   => handle_value_kind!(I8, val, id, data_map, as_i8),
     #[cfg(feature = "i16")]
-    ValueKind::I16  => handle_value_kind!(kind, val, id, data_map, as_i16),
+    ValueKind::I16  => handle_value_kind!(I16, val, id, data_map, as_i16),
     #[cfg(feature = "i32")]
-    ValueKind::I32  => handle_value_kind!(kind, val, id, data_map, as_i32),
+    ValueKind::I32  => handle_value_kind!(I32, val, id, data_map, as_i32),
     #[cfg(feature = "i64")]
-    ValueKind::I64  => handle_value_kind!(kind, val, id, data_map, as_i64),
+    ValueKind::I64  => handle_value_kind!(I64, val, id, data_map, as_i64),
     #[cfg(feature = "i128")]
-    ValueKind::I128 => handle_value_kind!(kind, val, id, data_map, as_i128),
+    ValueKind::I128 => handle_value_kind!(I128, val, id, data_map, as_i128),
 
     #[cfg(feature = "u8")]
-    ValueKind::U8   => handle_value_kind!(kind, val, id, data_map, as_u8),
+    ValueKind::U8   => handle_value_kind!(U8, val, id, data_map, as_u8),
     #[cfg(feature = "u16")]
-    ValueKind::U16  => handle_value_kind!(kind, val, id, data_map, as_u16),
+    ValueKind::U16  => handle_value_kind!(U16, val, id, data_map, as_u16),
     #[cfg(feature = "u32")]
-    ValueKind::U32  => handle_value_kind!(kind, val, id, data_map, as_u32),
+    ValueKind::U32  => handle_value_kind!(U32, val, id, data_map, as_u32),
     #[cfg(feature = "u64")]
-    ValueKind::U64  => handle_value_kind!(kind, val, id, data_map, as_u64),
+    ValueKind::U64  => handle_value_kind!(U64, val, id, data_map, as_u64),
     #[cfg(feature = "u128")]
-    ValueKind::U128 => handle_value_kind!(kind, val, id, data_map, as_u128),
+    ValueKind::U128 => handle_value_kind!(U128, val, id, data_map, as_u128),
 
     #[cfg(feature = "f32")]
-    ValueKind::F32  => handle_value_kind!(kind, val, id, data_map, as_f32),
+    ValueKind::F32  => handle_value_kind!(F32, val, id, data_map, as_f32),
     #[cfg(feature = "f64")]
-    ValueKind::F64  => handle_value_kind!(kind, val, id, data_map, as_f64),
+    ValueKind::F64  => handle_value_kind!(F64, val, id, data_map, as_f64),
 
     #[cfg(feature = "string")]
-    ValueKind::String => handle_value_kind!(kind, val, id, data_map, as_string),
+    ValueKind::String => handle_value_kind!(String, val, id, data_map, as_string),
 
     #[cfg(feature = "complex")]
-    ValueKind::C64 => handle_value_kind!(kind, val, id, data_map, as_c64),
+    ValueKind::C64 => handle_value_kind!(C64, val, id, data_map, as_c64),
 
     #[cfg(feature = "rational")]
-    ValueKind::R64 => handle_value_kind!(kind, val, id, data_map, as_r64),
+    ValueKind::R64 => handle_value_kind!(R64, val, id, data_map, as_r64),
 
     #[cfg(feature = "bool")]
-    ValueKind::Bool => {
-      let vals: Vec<Value> = val.as_vec()
-          .iter()
-          .map(|x| x.as_bool().unwrap().to_value())
-          .collect();
-      data_map.insert(id, (ValueKind::Bool, Value::to_matrix(vals.clone(), vals.len(), 1)));
-    }
+    ValueKind::Bool => handle_value_kind!(Bool, val, id, data_map, as_bool),
     ValueKind::Any => {
-      let vals: Vec<Value> = val.as_vec()
-          .iter()
-          .map(|x| x.clone())
-          .collect();
-      data_map.insert(id, (ValueKind::Any, Value::to_matrix(vals.clone(), vals.len(), 1)));
+      data_map.insert(id, (ValueKind::Any, Column::from_values(&ValueKind::Any, val)));
     }
 
     x => {
@@ -383,7 +375,7 @@ pub fn table(t: &Table, env: Option<&Environment>, p: &Interpreter) -> MResult<V
   }
 
   // Build table
-  let mut data_map: IndexMap<u64,(ValueKind,Matrix<Value>)> = IndexMap::new();
+  let mut data_map: IndexMap<u64,(ValueKind,Column)> = IndexMap::new();
 
   for ((id, knd, _name), column) in headings.iter().zip(data.iter()) {
     let id_u64 = id.as_u64().unwrap().borrow().clone();
@@ -399,11 +391,8 @@ pub fn table(t: &Table, env: Option<&Environment>, p: &Interpreter) -> MResult<V
       _ => knd.clone(),
     };
 
-    // Convert column to matrix
-    let val = Value::to_matrix(column.clone(), column.len(), 1);
-
     // Dispatch conversion
-    handle_column_kind(actual_kind, id_u64, val, &mut data_map)?;
+    handle_column_kind(actual_kind, id_u64, column.clone(), &mut data_map)?;
   }
 
   // Assign names
@@ -185,6 +185,14 @@ pub(crate) fn summarize_function_pattern(pattern: &Pattern) -> String {
       tuple_struct.name.to_string(),
       tuple_struct.patterns.len()
     ),
+    Pattern::String(string) => format!(
+      "string(prefix={},suffix={})",
+      string.prefix.is_some(),
+      string.suffix.is_some()
+    ),
+    Pattern::Map(map) => format!("map(len={},rest={})", map.entries.len(), map.rest),
+    Pattern::Set(set) => format!("set(len={},rest={})", set.elements.len(), set.rest),
+    Pattern::Table(table) => format!("table(cols={},rest={})", table.columns.len(), table.rest),
   }
 }
 
@@ -306,6 +314,14 @@ pub fn summarize_pattern(pattern: &Pattern) -> String {
                 tuple_struct.patterns.len()
             )
         }
+        Pattern::String(string) => format!(
+            "string(prefix={}, suffix={})",
+            string.prefix.is_some(),
+            string.suffix.is_some()
+        ),
+        Pattern::Map(map) => format!("map(len={}, rest={})", map.entries.len(), map.rest),
+        Pattern::Set(set) => format!("set(len={}, rest={})", set.elements.len(), set.rest),
+        Pattern::Table(table) => format!("table(cols={}, rest={})", table.columns.len(), table.rest),
     }
 }
 
@@ -384,6 +400,11 @@ fn formula_operator_symbol(op: &FormulaOperator) -> &'static str {
         FormulaOperator::Logic(LogicOp::Or) => "||",
         FormulaOperator::Logic(LogicOp::Xor) => "xor",
         FormulaOperator::Logic(LogicOp::Not) => "!",
+        FormulaOperator::Bits(BitsOp::And) => "&&&",
+        FormulaOperator::Bits(BitsOp::Or) => "|||",
+        FormulaOperator::Bits(BitsOp::Xor) => "^^^",
+        FormulaOperator::Bits(BitsOp::ShiftLeft) => "<<",
+        FormulaOperator::Bits(BitsOp::ShiftRight) => ">>",
         _ => "?",
     }
 }
@@ -246,8 +246,8 @@ impl MechFileSystem {
                   return Err(e);
                 }
               }
-            // load comma-separated values (csv) files
-            } else if f.extension() == Some(OsStr::new("csv")) {
+            // load comma/tab-separated values (csv/tsv), parquet and json files
+            } else if f.extension() == Some(OsStr::new("csv")) || f.extension() == Some(OsStr::new("tsv")) || f.extension() == Some(OsStr::new("parquet")) || f.extension() == Some(OsStr::new("json")) {
               match sources.add_source(&f.display().to_string(), src) {
                 Ok(_) => {
                   println!(
@@ -401,37 +401,14 @@ impl MechSources {
     let file_id = hash_str(&path.display().to_string());
     let new_source = read_mech_source_file(&path)?;
 
+    // update the tree
+    let (new_tree, new_html) = self.to_tree_and_html(&new_source)?;
+
     // Get the stale sources
     let mut source = self.sources.get_mut(&file_id).unwrap();
     let mut tree = self.trees.get_mut(&file_id).unwrap();
     let mut html = self.html.get_mut(&file_id).unwrap();
 
-    // update the tree
-    let (new_tree, new_html) = match source {
-      MechSourceCode::String(source) => match parser::parse(&source) {
-        Ok(tree) => {
-          let mut formatter = Formatter::new();
-          let mech_html =
-            formatter.format_html(&tree, self.stylesheet.clone(), self.shim.clone());
-          (MechSourceCode::Tree(tree), MechSourceCode::Html(mech_html))
-        }
-        Err(err) => return Err(err),
-      },
-      MechSourceCode::Html(html) => {
-        // TODO If it's HTML, we can parse it as a Mech source code.
-        (
-          MechSourceCode::Tree(core::Program {
-            title: None,
-            body: core::Body { sections: vec![] },
-          }),
-          MechSourceCode::Html(html.clone()),
-        )
-      }
-      _ => {
-        todo!("Handle other source formats?");
-      }
-    };
-
     // update
     *source = new_source;
     *html = new_html;
@@ -848,16 +825,18 @@ pub fn read_mech_source_file(path: &Path) -> MResult<MechSourceCode> {
             }
           }
         }
-        Some("csv") => {
+        // csv, tsv, parquet and json files are bound to a variable named after the file
+        Some(ext @ ("csv" | "tsv" | "parquet" | "json")) => {
           match File::open(path) {
-            Ok(mut file) => {
-              //println!("{} {}", "[Loading]".truecolor(153,221,85), path.display());
-              let mut buffer = String::new();
-              let mut rdr = csv::Reader::from_reader(file);
-              for result in rdr.records() {
-                println!("{:?}", result);
-              }
-              todo!();
+            Ok(_) => {
+              let file_path = path.to_string_lossy().to_string();
+              let name = table_name_from_path(path);
+              let reader = match ext {
+                "parquet" => "io/read-parquet",
+                "json" => "io/read-json",
+                _ => "io/read-csv",
+              };
+              Ok(MechSourceCode::Tree(table_loader_program(&name, reader, &file_path)))
             }
             Err(err) => Err(MechError::new(
               FileOpenFailed {
@@ -888,6 +867,44 @@ pub fn read_mech_source_file(path: &Path) -> MResult<MechSourceCode> {
   }
 }
 
+// Turn a file stem like "2024 sales.csv" into a Mech identifier ("table-2024-sales").
+fn table_name_from_path(path: &Path) -> String {
+  let stem = path
+    .file_stem()
+    .and_then(OsStr::to_str)
+    .unwrap_or("table");
+  let name: String = stem
+    .chars()
+    .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '-' })
+    .collect();
+  match name.chars().next() {
+    Some(c) if c.is_alphabetic() => name,
+    _ => format!("table-{}", name),
+  }
+}
+
+// Builds the program `name := reader("file_path")`. The path goes into the
+// tree as a string literal as is, so quotes or backslashes in it need no
+// escaping.
+fn table_loader_program(name: &str, reader: &str, file_path: &str) -> core::Program {
+  let token = |kind: core::TokenKind, text: &str| core::Token::new(kind, core::SourceRange::default(), text.chars().collect());
+  let path = core::Literal::String(core::MechString { text: token(core::TokenKind::String, file_path) });
+  let call = core::FunctionCall {
+    name: core::Identifier { name: token(core::TokenKind::Identifier, reader) },
+    args: vec![(None, core::Expression::Literal(path))],
+  };
+  let define = core::VariableDefine {
+    mutable: false,
+    var: core::Var { name: core::Identifier { name: token(core::TokenKind::Identifier, name) }, kind: None },
+    expression: core::Expression::FunctionCall(call),
+  };
+  let code = core::MechCode::Statement(core::Statement::VariableDefine(define));
+  core::Program {
+    title: None,
+    body: core::Body { sections: vec![core::Section { subtitle: None, elements: vec![core::SectionElement::MechCode(vec![(code, None)])] }] },
+  }
+}
+
 fn looks_like_mech_include(content: &str) -> bool {
   let trimmed = content.trim();
   trimmed.ends_with(".mec")
@@ -1203,6 +1220,14 @@ mod tests {
       "This is an inline thing: `{path/to/file.mec}` and this should stay literal.\nIncluded\n"
     );
   }
+
+  #[test]
+  fn table_names_come_from_file_stems() {
+    assert_eq!(table_name_from_path(Path::new("data/sales.csv")), "sales");
+    assert_eq!(table_name_from_path(Path::new("q1 report.v2.parquet")), "q1-report-v2");
+    assert_eq!(table_name_from_path(Path::new("2024_totals.csv")), "table-2024-totals");
+    assert_eq!(table_name_from_path(Path::new("déjà-vu.json")), "déjà-vu");
+  }
 }
 
 #[derive(Debug, Clone)]
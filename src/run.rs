@@ -101,6 +101,24 @@ pub fn run_mech_code(
           Err(err) => return Err(err),
         }
       }
+      MechSourceCode::Tree(tree) => {
+        if tree_flag {
+          print_tree!(tree);
+        }
+        let now = Instant::now();
+        let result = intrp.interpret(tree);
+        let elapsed_time = now.elapsed();
+        let cycle_duration = elapsed_time.as_nanos() as f64;
+        if time_flag {
+          println!("Cycle Time: {} ns", cycle_duration);
+        }
+        if debug_flag {
+          print_symbols!(intrp);
+          print_plan!(intrp);
+          print_bytecode(code);
+        }
+        return result;
+      }
       MechSourceCode::ByteCode(bc_program) => {
         let now = Instant::now();
         let result = intrp.run_program(&ParsedProgram::from_bytes(bc_program)?);
@@ -122,6 +140,43 @@ pub fn run_mech_code(
   Ok(Value::Empty)
 }
 
+#[cfg(feature = "persist")]
+pub use mech_interpreter::persist::*;
+
+// Opens the persisted state in `dir`, restores it into the program's symbols
+// and records the state as it now stands. When anything was restored the
+// plan is solved again so the symbols that depend on it catch up, and the
+// program's new result is returned with the persister.
+#[cfg(feature = "persist")]
+pub fn persist_mech_code(intrp: &mut Interpreter, dir: &str, symbols: Option<Vec<String>>) -> MResult<(Persister, Option<Value>)> {
+  let mut persister = Persister::open(dir)?;
+  if let Some(symbols) = symbols {
+    persister = persister.with_symbols(symbols);
+  }
+  let restored = persister.restore(intrp)?;
+  let mut result = None;
+  if restored > 0 {
+    println!("{} Restored {} symbol(s) from {}", "[Persist]".truecolor(153, 221, 85), restored, dir);
+    if !intrp.plan().is_empty() {
+      result = Some(intrp.step(0, 1)?);
+    }
+  }
+  record_mech_code(&mut persister, intrp)?;
+  Ok((persister, result))
+}
+
+// Records the persisted symbols after a step, warning once about each symbol
+// whose value can't be persisted.
+#[cfg(feature = "persist")]
+pub fn record_mech_code(persister: &mut Persister, intrp: &Interpreter) -> MResult<usize> {
+  let skipped = persister.skipped.len();
+  let appended = persister.record(intrp)?;
+  for (name, kind) in persister.skipped.iter().skip(skipped) {
+    println!("{} Not persisting {}: values of kind {} can't be saved", "[Warning]".truecolor(246, 192, 78), name, kind);
+  }
+  Ok(appended)
+}
+
 fn print_bytecode(fs: &MechFileSystem) {
   let sources = fs.sources();
   let sources = sources.read().unwrap();
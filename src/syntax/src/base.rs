@@ -55,7 +55,6 @@ macro_rules! ws1_leaf {
       }
       let (input, _) = whitespace1(input)?;
       let start = input.loc();
-      let byte = input.graphemes[input.cursor];
       let (input, _) = tag($byte)(input)?;
       let end = input.loc();
       let (input, _) = whitespace1(input)?;
@@ -169,6 +168,8 @@ leaf!(underline_sigil, "__", TokenKind::UnderlineSigil);
 leaf!(section_sigil, "§", TokenKind::SectionSigil);
 leaf!(mika_section_open, "⸢", TokenKind::MikaSectionOpen);
 leaf!(mika_section_close, "⸥", TokenKind::MikaSectionClose);
+leaf!(sort_by_operator, "⇅", TokenKind::TableOperator);
+leaf!(filter_operator, "⋔", TokenKind::TableOperator);
 leaf!(prompt_sigil, ">:", TokenKind::PromptSigil);
 
 ws0_leaf!(assign_operator, "=", TokenKind::AssignOperator);
@@ -182,6 +183,10 @@ ws0_leaf!(transition_operator_a, "->", TokenKind::TransitionOperator);
 ws0_leaf!(transition_operator_u, "→", TokenKind::TransitionOperator);
 ws0_leaf!(generator_arrow, "<-", TokenKind::GeneratorArrow);
 ws0_leaf!(generator_arrow_u, "←", TokenKind::GeneratorArrow);
+// Split and flatten need whitespace on both sides, so that `a >-1` stays a
+// comparison with a negative number.
+ws1_leaf!(split_operator, ">-", TokenKind::SplitOperator);
+ws1_leaf!(flatten_operator, "-<", TokenKind::FlattenOperator);
 ws0_leaf!(spread_operator_a, "...", TokenKind::SpreadOperator);
 ws0_leaf!(spread_operator_u, "…", TokenKind::SpreadOperator);
 
@@ -248,9 +253,9 @@ pub fn any_token(mut input: ParseString) -> ParseResult<Token> {
   }
 }
 
-// forbidden-emoji := box-drawing | other-forbidden-shapes ;
+// forbidden-emoji := box-drawing | table-operator | other-forbidden-shapes ;
 pub fn forbidden_emoji(input: ParseString) -> ParseResult<Token> {
-  alt((box_drawing_emoji, nbsp, thin_space, mika_section_open, mika_section_close))(input)
+  alt((box_drawing_emoji, nbsp, thin_space, mika_section_open, mika_section_close, sort_by_operator, filter_operator))(input)
 }
 
 // emoji := (!forbidden-emoji, emoji-grapheme) ;
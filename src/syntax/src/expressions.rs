@@ -33,34 +33,44 @@ like literals and variables.
 - `formula`: entry point
 - `l1`: logical operators (e.g., `and`, `or`)
 - `l2`: comparisons (e.g., `==`, `<`, `>`)
-- `l3`: addition and subtraction (`+`, `-`)
-- `l4`: multiplication, division, matrix operations
-- `l5`: exponentiation (`^`)
-- `l6`: table operations (e.g., joins)
-- `l7`: set operations (e.g., union, intersection)
+- `l3`: bitwise operations (e.g., `&&&`, `<<`)
+- `l4`: addition and subtraction (`+`, `-`)
+- `l5`: multiplication, division, matrix operations
+- `l6`: exponentiation (`^`)
+- `l7`: table operations (e.g., joins)
+- `l8`: set operations (e.g., union, intersection)
 - `factor`: atomic units (literals, function calls, variables, etc.)
 */
 
-// expression := fsm-pipe | set-comprehension | matrix-comprehension | range-expression | formula ;
+// expression := lambda | fsm-pipe | set-comprehension | record-comprehension | matrix-comprehension | table-comprehension | range-expression | formula ;
 pub fn expression(input: ParseString) -> ParseResult<Expression> {
+  if let Ok((input, lmbd)) = lambda(input.clone()) {
+    return Ok((input, Expression::Lambda(Box::new(lmbd))));
+  }
   let (input, expr) = match fsm_pipe(input.clone()) {
     Ok((input, pipe)) => (input, Expression::FsmPipe(pipe)),
     Err(_) => match set_comprehension(input.clone()) {
       Ok((input, sc)) => (input, Expression::SetComprehension(Box::new(sc))),
-      Err(_) => match matrix_comprehension(input.clone()) {
-        Ok((input, mc)) => (input, Expression::MatrixComprehension(Box::new(mc))),
-        Err(_) => match range_expression(input.clone()) {
-          Ok((input, rng)) => (input, Expression::Range(Box::new(rng))),
-          Err(_) => match match_expression(input.clone()) {
-            Ok((input, expr)) => (input, Expression::Match(Box::new(expr))),
-            Err(_) => match formula(input.clone()) {
-              Ok((input, Factor::Expression(expr))) => (input, *expr),
-              Ok((input, fctr)) => (input, Expression::Formula(fctr)),
-              Err(err) => {
-                return Err(err);
-              }
+      Err(_) => match record_comprehension(input.clone()) {
+        Ok((input, rc)) => (input, Expression::RecordComprehension(Box::new(rc))),
+        Err(_) => match matrix_comprehension(input.clone()) {
+          Ok((input, mc)) => (input, Expression::MatrixComprehension(Box::new(mc))),
+          Err(_) => match table_comprehension(input.clone()) {
+            Ok((input, tc)) => (input, Expression::TableComprehension(Box::new(tc))),
+            Err(_) => match range_expression(input.clone()) {
+              Ok((input, rng)) => (input, Expression::Range(Box::new(rng))),
+              Err(_) => match match_expression(input.clone()) {
+                Ok((input, expr)) => (input, Expression::Match(Box::new(expr))),
+                Err(_) => match formula(input.clone()) {
+                  Ok((input, Factor::Expression(expr))) => (input, *expr),
+                  Ok((input, fctr)) => (input, Expression::Formula(fctr)),
+                  Err(err) => {
+                    return Err(err);
+                  }
+                }
+              },
             }
-          },
+          }
         }
       }
     }
@@ -68,6 +78,17 @@ pub fn expression(input: ParseString) -> ParseResult<Expression> {
   Ok((input, expr))
 }
 
+// lambda := "\", "(", list0(list-separator, var), ")", output-operator, expression ;
+pub fn lambda(input: ParseString) -> ParseResult<Lambda> {
+  let (input, _) = backslash(input)?;
+  let (input, _) = left_parenthesis(input)?;
+  let (input, params) = separated_list0(list_separator, var)(input)?;
+  let (input, _) = right_parenthesis(input)?;
+  let (input, _) = output_operator(input)?;
+  let (input, body) = expression(input)?;
+  Ok((input, Lambda{params, body}))
+}
+
 // match-expression := expression, "?", whitespace*, match-arm+, period? ;
 pub fn match_expression(input: ParseString) -> ParseResult<MatchExpression> {
   let (input, source) = factor(input)?;
@@ -122,40 +143,48 @@ pub fn l2(input: ParseString) -> ParseResult<Factor> {
   Ok((input, factor))
 }
 
-// l3 := l4, (add-sub-operator, l4)* ;
+// l3 := l4, (bits-operator, l4)* ;
 pub fn l3(input: ParseString) -> ParseResult<Factor> {
   let (input, lhs) = l4(input)?;
-  let (input, rhs) = many0(pair(add_sub_operator,cut(l4)))(input)?;
+  let (input, rhs) = many0(pair(bits_operator,cut(l4)))(input)?;
   let factor = if rhs.is_empty() { lhs } else { Factor::Term(Box::new(Term { lhs, rhs })) };
   Ok((input, factor))
 }
 
-// l4 := l5, (mul-div-operator | matrix-operator, l5)* ;
+// l4 := l5, (add-sub-operator, l5)* ;
 pub fn l4(input: ParseString) -> ParseResult<Factor> {
   let (input, lhs) = l5(input)?;
-  let (input, rhs) = many0(pair(alt((mul_div_operator, matrix_operator)),cut(l5)))(input)?;
+  let (input, rhs) = many0(pair(add_sub_operator,cut(l5)))(input)?;
   let factor = if rhs.is_empty() { lhs } else { Factor::Term(Box::new(Term { lhs, rhs })) };
   Ok((input, factor))
 }
 
-// l5 := l6, (power-operator, l6)* ;
+// l5 := l6, (mul-div-operator | matrix-operator, l6)* ;
 pub fn l5(input: ParseString) -> ParseResult<Factor> {
   let (input, lhs) = l6(input)?;
-  let (input, rhs) = many0(pair(power_operator,cut(l6)))(input)?;
+  let (input, rhs) = many0(pair(alt((mul_div_operator, matrix_operator)),cut(l6)))(input)?;
   let factor = if rhs.is_empty() { lhs } else { Factor::Term(Box::new(Term { lhs, rhs })) };
   Ok((input, factor))
 }
 
-// l6 := l7, (table-operator, l7)* ;
+// l6 := l7, (power-operator, l7)* ;
 pub fn l6(input: ParseString) -> ParseResult<Factor> {
   let (input, lhs) = l7(input)?;
-  let (input, rhs) = many0(pair(table_operator,cut(l7)))(input)?;
+  let (input, rhs) = many0(pair(power_operator,cut(l7)))(input)?;
   let factor = if rhs.is_empty() { lhs } else { Factor::Term(Box::new(Term { lhs, rhs })) };
   Ok((input, factor))
 }
 
-// l7 := factor, (set-operator, factor)* ;
+// l7 := l8, (table-operator, l8)* ;
 pub fn l7(input: ParseString) -> ParseResult<Factor> {
+  let (input, lhs) = l8(input)?;
+  let (input, rhs) = many0(pair(table_operator,cut(l8)))(input)?;
+  let factor = if rhs.is_empty() { lhs } else { Factor::Term(Box::new(Term { lhs, rhs })) };
+  Ok((input, factor))
+}
+
+// l8 := factor, (set-operator, factor)* ;
+pub fn l8(input: ParseString) -> ParseResult<Factor> {
   let (input, lhs) = factor(input)?;
   let (input, rhs) = many0(pair(set_operator,cut(factor)))(input)?;
   let factor = if rhs.is_empty() { lhs } else { Factor::Term(Box::new(Term { lhs, rhs })) };
@@ -298,6 +327,7 @@ pub fn modulus(input: ParseString) -> ParseResult<MulDivOp> {
 // power := "^" ;
 pub fn power(input: ParseString) -> ParseResult<PowerOp> {
   let (input, _) = ws0e(input)?;
+  let (input, _) = is_not(tag("^^"))(input)?;
   let (input, _) = tag("^")(input)?;
   let (input, _) = ws0e(input)?;
   Ok((input, PowerOp::Pow))
@@ -497,12 +527,61 @@ pub fn xor(input: ParseString) -> ParseResult<LogicOp> {
   Ok((input, LogicOp::Xor))
 }
 
+// Bits expressions
+// ----------------------------------------------------------------------------
+
+// bits-operator := bit-and | bit-or | bit-xor | shift-left | shift-right ;
+pub fn bits_operator(input: ParseString) -> ParseResult<FormulaOperator> {
+  let (input, op) = alt((bit_and, bit_or, bit_xor, shift_left, shift_right))(input)?;
+  Ok((input, FormulaOperator::Bits(op)))
+}
+
+// bit-and := "&&&" ;
+pub fn bit_and(input: ParseString) -> ParseResult<BitsOp> {
+  let (input, _) = ws0e(input)?;
+  let (input, _) = tag("&&&")(input)?;
+  let (input, _) = ws0e(input)?;
+  Ok((input, BitsOp::And))
+}
+
+// bit-or := "|||" ;
+pub fn bit_or(input: ParseString) -> ParseResult<BitsOp> {
+  let (input, _) = ws0e(input)?;
+  let (input, _) = tag("|||")(input)?;
+  let (input, _) = ws0e(input)?;
+  Ok((input, BitsOp::Or))
+}
+
+// bit-xor := "^^^" ;
+pub fn bit_xor(input: ParseString) -> ParseResult<BitsOp> {
+  let (input, _) = ws0e(input)?;
+  let (input, _) = tag("^^^")(input)?;
+  let (input, _) = ws0e(input)?;
+  Ok((input, BitsOp::Xor))
+}
+
+// shift-left := "<<" ;
+pub fn shift_left(input: ParseString) -> ParseResult<BitsOp> {
+  let (input, _) = ws0e(input)?;
+  let (input, _) = tag("<<")(input)?;
+  let (input, _) = ws0e(input)?;
+  Ok((input, BitsOp::ShiftLeft))
+}
+
+// shift-right := ">>" ;
+pub fn shift_right(input: ParseString) -> ParseResult<BitsOp> {
+  let (input, _) = ws0e(input)?;
+  let (input, _) = tag(">>")(input)?;
+  let (input, _) = ws0e(input)?;
+  Ok((input, BitsOp::ShiftRight))
+}
+
 // Table Operations
 // ----------------------------------------------------------------------------
 
-// table-operator := join | left-join | right-join | full-join | left-semi-join | left-anti-join ;
+// table-operator := join | left-join | right-join | full-join | left-semi-join | left-anti-join | sort-by | filter ;
 fn table_operator(input: ParseString) -> ParseResult<FormulaOperator> {
-  let (input, op) = alt((join,left_join,right_join,full_join,left_semi_join,left_anti_join))(input)?;
+  let (input, op) = alt((join,left_join,right_join,full_join,left_semi_join,left_anti_join,sort_by,filter))(input)?;
   Ok((input, FormulaOperator::Table(op)))
 }
 
@@ -554,6 +633,22 @@ fn left_anti_join(input: ParseString) -> ParseResult<TableOp> {
   Ok((input, TableOp::LeftAntiJoin))
 }
 
+// sort-by := "⇅" ;
+fn sort_by(input: ParseString) -> ParseResult<TableOp> {
+  let (input, _) = ws0e(input)?;
+  let (input, _) = sort_by_operator(input)?;
+  let (input, _) = ws0e(input)?;
+  Ok((input, TableOp::SortBy))
+}
+
+// filter := "⋔" ;
+fn filter(input: ParseString) -> ParseResult<TableOp> {
+  let (input, _) = ws0e(input)?;
+  let (input, _) = filter_operator(input)?;
+  let (input, _) = ws0e(input)?;
+  Ok((input, TableOp::Filter))
+}
+
 
 // Set Operations
 // ----------------------------------------------------------------------------
@@ -689,6 +784,49 @@ pub fn matrix_comprehension(input: ParseString) -> ParseResult<MatrixComprehensi
   Ok((input, MatrixComprehension{ expression: expr, qualifiers: quals }))
 }
 
+// Record and Table Comprehensions
+// ----------------------------------------------------------------------------
+
+// record-comprehension := "{", binding+, "|", [comprehension-qualifier, ","], "}" ;
+pub fn record_comprehension(input: ParseString) -> ParseResult<RecordComprehension> {
+  let (input, _) = left_brace(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, bindings) = many1(binding)(input)?;
+  let (input, _) = bar(input)?;
+  let (input, _) = space_tab0(input)?;
+  let (input, quals) = comprehension_qualifiers(input)?;
+  let (input, _) = space_tab0(input)?;
+  let (input, _) = right_brace(input)?;
+  Ok((input, RecordComprehension{ record: Record{bindings}, qualifiers: quals }))
+}
+
+// table-comprehension := "|", record, "|", [comprehension-qualifier, ","], "|" ;
+pub fn table_comprehension(input: ParseString) -> ParseResult<TableComprehension> {
+  let (input, _) = bar(input)?;
+  let (input, _) = space_tab0(input)?;
+  let (input, rcrd) = record(input)?;
+  let (input, _) = space_tab0(input)?;
+  let (input, _) = bar(input)?;
+  let (input, _) = space_tab0(input)?;
+  let (input, quals) = comprehension_qualifiers(input)?;
+  let (input, _) = space_tab0(input)?;
+  let (input, _) = bar(input)?;
+  Ok((input, TableComprehension{ record: rcrd, qualifiers: quals }))
+}
+
+// Record and table comprehensions yield one row per binding environment, so
+// like matrix comprehensions they need a generator or let to produce one.
+fn comprehension_qualifiers(input: ParseString) -> ParseResult<Vec<ComprehensionQualifier>> {
+  let (input, quals) = separated_list1(list_separator, comprehension_qualifier)(input)?;
+  if !quals.iter().any(|q| matches!(q, ComprehensionQualifier::Generator(_) | ComprehensionQualifier::Let(_))) {
+    return Err(nom::Err::Error(ParseError::new(
+      input,
+      "Comprehension requires at least one generator (<-) or let (:=) qualifier",
+    )));
+  }
+  Ok((input, quals))
+}
+
 // set-qualifier := generator | expression | variable-define  ;
 pub fn comprehension_qualifier(input: ParseString) -> ParseResult<ComprehensionQualifier> {
   match generator(input.clone()) {
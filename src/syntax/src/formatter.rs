@@ -707,7 +707,7 @@ impl Formatter {
           let style_str = option_map
             .elements
             .iter()
-            .filter(|(k, _)| k.to_string() != "output")
+            .filter(|(k, _)| k.to_string() != "output" && k.to_string() != "page-size")
             .map(|(k, v)| {
               let clean_value = v.to_string().trim_matches('"').to_string();
               format!("{}: {}", k.to_string(), clean_value)
@@ -733,8 +733,12 @@ impl Formatter {
         } else {
           format!("<div class=\"mech-code-block-namespace\"><a href=\"#{}\">{}</a></div>", block_id, namespace_str)
         };
+        let page_size_attr = match block.config.page_size {
+          Some(page_size) => format!(" mech-page-size=\"{}\"", page_size),
+          None => "".to_string(),
+        };
         let output_node = if block.config.output {
-          format!("<div class=\"mech-block-output\" id=\"{}:{}\"></div>", output_id, intrp_id)
+          format!("<div class=\"mech-block-output\" id=\"{}:{}\"{}></div>", output_id, intrp_id, page_size_attr)
         } else {
           "".to_string()
         };
@@ -1709,6 +1713,10 @@ impl Formatter {
       Pattern::Array(arr) => self.pattern_array(arr),
       Pattern::Expression(expr) => self.expression(expr),
       Pattern::TupleStruct(tuple_struct) => self.pattern_tuple_struct(tuple_struct),
+      Pattern::String(string) => self.pattern_string(string),
+      Pattern::Map(map) => self.pattern_map(map),
+      Pattern::Set(set) => self.pattern_set(set),
+      Pattern::Table(table) => self.pattern_table(table),
     };
     if self.html {
       format!("<span class=\"mech-pattern\">{}</span>",p)
@@ -1717,6 +1725,74 @@ impl Formatter {
     }
   }
 
+  pub fn pattern_string(&mut self, node: &PatternString) -> String {
+    let prefix = node.prefix.as_ref().map(|prefix| format!("{}…", self.string(prefix))).unwrap_or_default();
+    let binding = node.binding.as_ref().map(|binding| self.pattern(binding)).unwrap_or_default();
+    let suffix = node.suffix.as_ref().map(|suffix| format!("…{}", self.string(suffix))).unwrap_or_default();
+    if self.html {
+      format!("<span class=\"mech-pattern-string\">{}{}{}</span>", prefix, binding, suffix)
+    } else {
+      format!("{}{}{}", prefix, binding, suffix)
+    }
+  }
+
+  pub fn pattern_map(&mut self, node: &PatternMap) -> String {
+    let mut entries = vec![];
+    for entry in &node.entries {
+      let key = self.expression(&entry.key);
+      let value = self.pattern(&entry.value);
+      entries.push(format!("{}: {}", key, value));
+    }
+    if node.rest {
+      entries.push("…".to_string());
+    }
+    let entries = entries.join(", ");
+    if self.html {
+      format!("<span class=\"mech-pattern-map\"><span class=\"mech-left-brace\">{{</span>{}<span class=\"mech-right-brace\">}}</span></span>", entries)
+    } else {
+      format!("{{{}}}", entries)
+    }
+  }
+
+  pub fn pattern_set(&mut self, node: &PatternSet) -> String {
+    let mut elements = vec![];
+    for element in &node.elements {
+      elements.push(self.expression(element));
+    }
+    if node.rest {
+      elements.push("…".to_string());
+    }
+    let elements = elements.join(", ");
+    if self.html {
+      format!("<span class=\"mech-pattern-set\"><span class=\"mech-left-brace\">{{</span>{}<span class=\"mech-right-brace\">}}</span></span>", elements)
+    } else {
+      format!("{{{}}}", elements)
+    }
+  }
+
+  pub fn pattern_table(&mut self, node: &PatternTable) -> String {
+    let mut columns = vec![];
+    for column in &node.columns {
+      let name = column.name.to_string();
+      match &column.pattern {
+        Some(pattern) => {
+          let pattern = self.pattern(pattern);
+          columns.push(format!("{}: {}", name, pattern));
+        }
+        None => columns.push(name),
+      }
+    }
+    if node.rest {
+      columns.push("…".to_string());
+    }
+    let columns = columns.join(", ");
+    if self.html {
+      format!("<span class=\"mech-pattern-table\"><span class=\"mech-bar\">|</span>{}<span class=\"mech-bar\">|</span></span>", columns)
+    } else {
+      format!("|{}|", columns)
+    }
+  }
+
   pub fn pattern_tuple_struct(&mut self, node: &PatternTupleStruct) -> String {
     let name = node.name.to_string();
     let mut patterns = "".to_string();
@@ -1880,11 +1956,16 @@ impl Formatter {
         .map(|(ix, arm)| {
           let branch = if ix + 1 == node.match_arms.len() { "└" } else { "├" };
           let pattern = self.pattern(&arm.pattern);
+          let guards = arm
+            .guards
+            .iter()
+            .map(|guard| format!(", {}", self.expression(guard)))
+            .collect::<String>();
           let expression = self.expression(&arm.expression);
           if self.html {
-            format!("<div class=\"mech-function-match-arm\"><span class=\"mech-function-branch\">{}</span><span class=\"mech-function-pattern\">{}</span> <span class=\"mech-function-arrow\">⇒</span><span class=\"mech-function-expression\">{}</span></div>", branch, pattern, expression)
+            format!("<div class=\"mech-function-match-arm\"><span class=\"mech-function-branch\">{}</span><span class=\"mech-function-pattern\">{}{}</span> <span class=\"mech-function-arrow\">⇒</span><span class=\"mech-function-expression\">{}</span></div>", branch, pattern, guards, expression)
           } else {
-            format!("  {} {} => {}", branch, pattern, expression)
+            format!("  {} {}{} => {}", branch, pattern, guards, expression)
           }
         })
         .collect::<Vec<_>>()
@@ -1985,6 +2066,8 @@ impl Formatter {
       Statement::TupleDestructure(tpl_dstrct) => self.tuple_destructure(tpl_dstrct),
       Statement::KindDefine(kind_def) => self.kind_define(kind_def),
       Statement::EnumDefine(enum_def) => self.enum_define(enum_def),
+      Statement::SplitTable(split) => self.split_table(split),
+      Statement::FlattenTable(flatten) => self.flatten_table(flatten),
       _ => todo!(),
       //Statement::FsmDeclare(fsm_decl) => self.fsm_declare(fsm_decl, src),
     };
@@ -1995,6 +2078,35 @@ impl Formatter {
     }
   }
 
+  pub fn split_table(&mut self, node: &SplitTable) -> String {
+    let name = node.name.to_string();
+    let source = self.expression(&node.source);
+    let by = match &node.by {
+      Some(key) => key.to_string(),
+      None => "".to_string(),
+    };
+    if self.html {
+      let id = format!("{}:{}",hash_str(&name),self.interpreter_id);
+      let by = if by.is_empty() { by } else { format!("<span class=\"mech-split-by-op\">by</span><span class=\"mech-split-key\">{}</span>", by) };
+      format!("<span class=\"mech-split-table\"><span id=\"{}\" class=\"mech-var-name mech-clickable\">{}</span><span class=\"mech-split-op\">&gt;-</span><span class=\"mech-split-source\">{}</span>{}</span>", id, name, source, by)
+    } else if by.is_empty() {
+      format!("{} >- {}", name, source)
+    } else {
+      format!("{} >- {} by {}", name, source, by)
+    }
+  }
+
+  pub fn flatten_table(&mut self, node: &FlattenTable) -> String {
+    let name = node.name.to_string();
+    let source = self.expression(&node.source);
+    if self.html {
+      let id = format!("{}:{}",hash_str(&name),self.interpreter_id);
+      format!("<span class=\"mech-flatten-table\"><span id=\"{}\" class=\"mech-var-name mech-clickable\">{}</span><span class=\"mech-flatten-op\">-&lt;</span><span class=\"mech-flatten-source\">{}</span></span>", id, name, source)
+    } else {
+      format!("{} -< {}", name, source)
+    }
+  }
+
   pub fn enum_define(&mut self, node: &EnumDefine) -> String {
     let name = node.name.to_string();
     let mut variants = "".to_string();
@@ -2146,8 +2258,11 @@ impl Formatter {
       Expression::Range(range) => self.range_expression(range),
       Expression::SetComprehension(set_comp) => self.set_comprehension(set_comp),
       Expression::MatrixComprehension(matrix_comp) => self.matrix_comprehension(matrix_comp),
+      Expression::TableComprehension(table_comp) => self.table_comprehension(table_comp),
+      Expression::RecordComprehension(record_comp) => self.record_comprehension(record_comp),
       Expression::Match(match_expr) => self.match_expression(match_expr),
       Expression::FsmPipe(fsm_pipe) => self.fsm_pipe(fsm_pipe),
+      Expression::Lambda(lambda) => self.lambda(lambda),
       x => todo!("Unhandled Expression: {:#?}", x),
     };
     if self.html {
@@ -2157,6 +2272,16 @@ impl Formatter {
     }
   }
 
+  pub fn lambda(&mut self, node: &Lambda) -> String {
+    let params = node.params.iter().map(|param| self.var(param)).collect::<Vec<String>>().join(", ");
+    let body = self.expression(&node.body);
+    if self.html {
+      format!("<span class=\"mech-lambda\"><span class=\"mech-lambda-params\">\\({})</span> <span class=\"mech-lambda-arrow\">⇒</span> <span class=\"mech-lambda-body\">{}</span></span>", params, body)
+    } else {
+      format!("\\({}) ⇒ {}", params, body)
+    }
+  }
+
   pub fn pattern_array(&mut self, node: &PatternArray) -> String {
     let mut parts: Vec<String> = vec![];
     for p in &node.prefix {
@@ -2336,6 +2461,58 @@ impl Formatter {
     }
   }
 
+  pub fn table_comprehension(&mut self, node: &TableComprehension) -> String {
+    let rcrd = self.record(&node.record);
+    let quals = node.qualifiers
+      .iter()
+      .map(|q| self.comprehension_qualifier(q))
+      .collect::<Vec<_>>()
+      .join(", ");
+
+    if self.html {
+      format!(
+        "<span class=\"mech-table-comprehension\">
+          <span class=\"mech-bar start\">|</span>
+          <span class=\"mech-comp-expr\">{}</span>
+          <span class=\"mech-comp-bar\">|</span>
+          <span class=\"mech-comp-quals\">{}</span>
+          <span class=\"mech-bar end\">|</span>
+        </span>",
+        rcrd, quals
+      )
+    } else {
+      format!("| {} | {} |", rcrd, quals)
+    }
+  }
+
+  pub fn record_comprehension(&mut self, node: &RecordComprehension) -> String {
+    let bindings = node.record.bindings
+      .iter()
+      .map(|b| self.binding(b))
+      .collect::<Vec<_>>()
+      .join(", ");
+    let quals = node.qualifiers
+      .iter()
+      .map(|q| self.comprehension_qualifier(q))
+      .collect::<Vec<_>>()
+      .join(", ");
+
+    if self.html {
+      format!(
+        "<span class=\"mech-record-comprehension\">
+          <span class=\"mech-start-brace\">{{</span>
+          <span class=\"mech-comp-expr\">{}</span>
+          <span class=\"mech-comp-bar\">|</span>
+          <span class=\"mech-comp-quals\">{}</span>
+          <span class=\"mech-end-brace\">}}</span>
+        </span>",
+        bindings, quals
+      )
+    } else {
+      format!("{{ {} | {} }}", bindings, quals)
+    }
+  }
+
   pub fn comprehension_qualifier(&mut self, node: &ComprehensionQualifier) -> String {
     match node {
       ComprehensionQualifier::Generator((pattern, expr)) => {
@@ -3006,6 +3183,7 @@ pub fn matrix_column_elements(&mut self, column_elements: &[&MatrixColumn]) -> S
       FormulaOperator::Vec(op) => self.vec_op(op),
       FormulaOperator::Comparison(op) => self.comparison_op(op),
       FormulaOperator::Logic(op) => self.logic_op(op),
+      FormulaOperator::Bits(op) => self.bits_op(op),
       FormulaOperator::Table(op) => self.table_op(op),
       FormulaOperator::Set(op) => self.set_op(op),
     };
@@ -3024,6 +3202,8 @@ pub fn matrix_column_elements(&mut self, column_elements: &[&MatrixColumn]) -> S
       TableOp::FullOuterJoin => "⟗".to_string(),
       TableOp::LeftSemiJoin => "⋉".to_string(),
       TableOp::LeftAntiJoin => "▷".to_string(),
+      TableOp::SortBy => "⇅".to_string(),
+      TableOp::Filter => "⋔".to_string(),
     }
   }
 
@@ -3095,6 +3275,16 @@ pub fn matrix_column_elements(&mut self, column_elements: &[&MatrixColumn]) -> S
     }
   }
 
+  pub fn bits_op(&mut self, node: &BitsOp) -> String {
+    match node {
+      BitsOp::And => "&&&".to_string(),
+      BitsOp::Or => "|||".to_string(),
+      BitsOp::Xor => "^^^".to_string(),
+      BitsOp::ShiftLeft => "<<".to_string(),
+      BitsOp::ShiftRight => ">>".to_string(),
+    }
+  }
+
   pub fn boolean(&mut self, node: &Token) -> String {
     let b = node.to_string();
     if self.html {
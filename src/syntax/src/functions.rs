@@ -8,7 +8,7 @@ use crate::*;
 use nom::{
   IResult,
   branch::alt,
-  sequence::tuple as nom_tuple,
+  sequence::{tuple as nom_tuple, preceded},
   combinator::{opt, eof},
   multi::{many1, many_till, many0, separated_list1,separated_list0},
   Err,
@@ -73,11 +73,13 @@ fn function_define_match_arms(
   }))
 }
 
+// function_match_arm := ("├" | "└" | "|"), (pattern, ("," , expression)*, output_operator)?, expression ;
 fn function_match_arm(input: ParseString) -> ParseResult<FunctionMatchArm> {
   let (input, _) = whitespace0(input)?;
   let (input, _) = alt((box_t_left, box_bl, bar))(input)?;
   let (input, _) = whitespace0(input)?;
   if let Ok((input, pattern)) = crate::patterns::pattern(input.clone()) {
+    let (input, guards) = many0(preceded(list_separator, expression))(input)?;
     if let Ok((input, _)) = whitespace0(input) {
       if let Ok((input, _)) = output_operator(input) {
         let (input, _) = whitespace0(input)?;
@@ -85,6 +87,7 @@ fn function_match_arm(input: ParseString) -> ParseResult<FunctionMatchArm> {
         let (input, _) = opt(alt((whitespace1, statement_separator)))(input)?;
         return Ok((input, FunctionMatchArm {
           pattern,
+          guards,
           expression: expr,
         }));
       }
@@ -94,6 +97,7 @@ fn function_match_arm(input: ParseString) -> ParseResult<FunctionMatchArm> {
   let (input, _) = opt(alt((whitespace1, statement_separator)))(input)?;
   Ok((input, FunctionMatchArm {
     pattern: Pattern::Wildcard,
+    guards: vec![],
     expression: expr,
   }))
 }
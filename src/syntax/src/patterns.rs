@@ -1,7 +1,8 @@
 #[macro_use]
 use crate::*;
+use nom::sequence::tuple as nom_tuple;
 
-// pattern := pattern_atom_struct | pattern_tuple_struct | wildcard | pattern_array | pattern_tuple | expression ;
+// pattern := pattern_atom_struct | pattern_tuple_struct | wildcard | pattern_string | pattern_map | pattern_set | pattern_table | pattern_array | pattern_tuple | expression ;
 pub fn pattern(input: ParseString) -> ParseResult<Pattern> {
   match pattern_atom_struct(input.clone()) {
     Ok((input, tpl)) => {return Ok((input, Pattern::TupleStruct(tpl)))},
@@ -15,6 +16,22 @@ pub fn pattern(input: ParseString) -> ParseResult<Pattern> {
     Ok((input, _)) => {return Ok((input, Pattern::Wildcard))},
     _ => ()
   }
+  match pattern_string(input.clone()) {
+    Ok((input, string)) => {return Ok((input, Pattern::String(string)))},
+    _ => ()
+  }
+  match pattern_map(input.clone()) {
+    Ok((input, map)) => {return Ok((input, Pattern::Map(map)))},
+    _ => ()
+  }
+  match pattern_set(input.clone()) {
+    Ok((input, set)) => {return Ok((input, Pattern::Set(set)))},
+    _ => ()
+  }
+  match pattern_table(input.clone()) {
+    Ok((input, table)) => {return Ok((input, Pattern::Table(table)))},
+    _ => ()
+  }
   match pattern_array(input.clone()) {
     Ok((input, arr)) => {return Ok((input, Pattern::Array(arr)))},
     _ => ()
@@ -257,3 +274,106 @@ pub fn pattern_tuple(input: ParseString) -> ParseResult<PatternTuple> {
   let (input, _) = right_parenthesis(input)?;
   Ok((input, PatternTuple(patterns)))
 }
+
+// pattern-string := (string, spread-operator)?, identifier?, (spread-operator, string)? ;
+pub fn pattern_string(input: ParseString) -> ParseResult<PatternString> {
+  let (input, prefix) = opt(nom_tuple((string, spread_operator)))(input)?;
+  let (input, binding) = opt(pattern_string_binding)(input)?;
+  let (input, suffix) = opt(nom_tuple((spread_operator, string)))(input)?;
+  if prefix.is_none() && suffix.is_none() {
+    return Err(nom::Err::Error(ParseError::new(
+      input,
+      "String pattern needs a prefix or a suffix",
+    )));
+  }
+  let binding = binding.map(|name| Box::new(Pattern::Expression(Expression::Var(Var{name, kind: None}))));
+  Ok((input, PatternString{
+    prefix: prefix.map(|(prefix, _)| prefix),
+    binding,
+    suffix: suffix.map(|(_, suffix)| suffix),
+  }))
+}
+
+// pattern-string-binding := identifier ;
+// Like identifier, but stops at a spread operator so `name…".csv"` splits cleanly.
+fn pattern_string_binding(input: ParseString) -> ParseResult<Identifier> {
+  let (input, (first, mut rest)) = nom_tuple((
+    alt((alpha_token, pattern_string_emoji)),
+    many0(alt((alpha_token, digit_token, identifier_symbol, pattern_string_emoji))),
+  ))(input)?;
+  let mut tokens = vec![first];
+  tokens.append(&mut rest);
+  let mut merged = Token::merge_tokens(&mut tokens).unwrap();
+  merged.kind = TokenKind::Identifier;
+  Ok((input, Identifier{name: merged}))
+}
+
+fn pattern_string_emoji(input: ParseString) -> ParseResult<Token> {
+  let (input, _) = is_not(spread_operator)(input)?;
+  emoji(input)
+}
+
+// pattern-rest := list-separator, spread-operator ;
+fn pattern_rest(input: ParseString) -> ParseResult<()> {
+  let (input, _) = list_separator(input)?;
+  let (input, _) = spread_operator(input)?;
+  Ok((input, ()))
+}
+
+// pattern-map := "{", whitespace*, list1(list-separator, pattern-map-entry), pattern-rest?, whitespace*, "}" ;
+pub fn pattern_map(input: ParseString) -> ParseResult<PatternMap> {
+  let (input, _) = left_brace(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, entries) = separated_list1(list_separator, pattern_map_entry)(input)?;
+  let (input, rest) = opt(pattern_rest)(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, _) = right_brace(input)?;
+  Ok((input, PatternMap{entries, rest: rest.is_some()}))
+}
+
+// pattern-map-entry := expression, whitespace*, ":", whitespace*, pattern ;
+pub fn pattern_map_entry(input: ParseString) -> ParseResult<PatternMapEntry> {
+  let (input, key) = expression(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, _) = colon(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, value) = pattern(input)?;
+  Ok((input, PatternMapEntry{key, value}))
+}
+
+// pattern-set := "{", whitespace*, list1(list-separator, pattern-set-element), pattern-rest?, whitespace*, "}" ;
+pub fn pattern_set(input: ParseString) -> ParseResult<PatternSet> {
+  let (input, _) = left_brace(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, elements) = separated_list1(list_separator, pattern_set_element)(input)?;
+  let (input, rest) = opt(pattern_rest)(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, _) = right_brace(input)?;
+  Ok((input, PatternSet{elements, rest: rest.is_some()}))
+}
+
+// pattern-set-element := expression ;
+// A bare spread would otherwise parse as a variable named `…`.
+fn pattern_set_element(input: ParseString) -> ParseResult<Expression> {
+  let (input, _) = is_not(spread_operator)(input)?;
+  expression(input)
+}
+
+// pattern-table := "|", whitespace*, list1(list-separator, pattern-table-column), pattern-rest?, whitespace*, "|" ;
+pub fn pattern_table(input: ParseString) -> ParseResult<PatternTable> {
+  let (input, _) = bar(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, columns) = separated_list1(list_separator, pattern_table_column)(input)?;
+  let (input, rest) = opt(pattern_rest)(input)?;
+  let (input, _) = whitespace0(input)?;
+  let (input, _) = bar(input)?;
+  Ok((input, PatternTable{columns, rest: rest.is_some()}))
+}
+
+// pattern-table-column := identifier, (whitespace*, ":", whitespace*, pattern)? ;
+pub fn pattern_table_column(input: ParseString) -> ParseResult<PatternTableColumn> {
+  let (input, _) = is_not(spread_operator)(input)?;
+  let (input, name) = identifier(input)?;
+  let (input, pattern) = opt(nom_tuple((whitespace0, colon, whitespace0, pattern)))(input)?;
+  Ok((input, PatternTableColumn{name, pattern: pattern.map(|(_, _, _, pattern)| pattern)}))
+}
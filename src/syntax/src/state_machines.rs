@@ -61,6 +61,35 @@ fn validate_fsm_value_pattern(ptrn: &Pattern) -> MResult<()> {
       }
       Ok(())
     }
+    Pattern::Map(map) => {
+      if map.rest {
+        return Err(invalid_fsm_value_pattern(
+          "Map rest syntax (`, …`) is only valid in pattern-matching positions",
+          ptrn,
+        ));
+      }
+      for entry in map.entries.iter() {
+        validate_fsm_value_pattern(&entry.value)?;
+      }
+      Ok(())
+    }
+    Pattern::Set(set) => {
+      if set.rest {
+        return Err(invalid_fsm_value_pattern(
+          "Set rest syntax (`, …`) is only valid in pattern-matching positions",
+          ptrn,
+        ));
+      }
+      Ok(())
+    }
+    Pattern::String(_) => Err(invalid_fsm_value_pattern(
+      "String prefix/suffix patterns are only valid in pattern-matching positions",
+      ptrn,
+    )),
+    Pattern::Table(_) => Err(invalid_fsm_value_pattern(
+      "Table column patterns are only valid in pattern-matching positions",
+      ptrn,
+    )),
     Pattern::Expression(_) => Ok(()),
   }
 }
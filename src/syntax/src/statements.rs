@@ -103,31 +103,33 @@ pub fn exp_assign_operator(input: ParseString) -> ParseResult<OpAssignOp> {
   Ok((input, OpAssignOp::Exp))
 }
 
-// split_data := (identifier | table), <!stmt_operator>, space*, split_operator, <space+>, <expression> ;
-/*pub fn split_data(input: ParseString) -> ParseResult<ParserNode> {
-  /*let msg1 = "Expects spaces around operator";
-  let msg2 = "Expects expression";
-  let (input, table) = alt((identifier, table))(input)?;
-  let (input, _) = labelr!(null(is_not(stmt_operator)), skip_nil, msg1)(input)?;
-  let (input, _) = many0(space)(input)?;
+// split-table := identifier, split-operator, expression, split-table-by? ;
+pub fn split_table(input: ParseString) -> ParseResult<SplitTable> {
+  let msg = "Expects expression";
+  let (input, name) = identifier(input)?;
   let (input, _) = split_operator(input)?;
-  let (input, _) = labelr!(null(many1(space)), skip_nil, msg1)(input)?;
-  let (input, expression) = label!(expression, msg2)(input)?;*/
-  Ok((input, ParserNode::SplitData{children: vec![]}))
-}*/
-
-// flatten_data := identifier, <!stmt_operator>, space*, flatten_operator, <space+>, <expression> ;
-/*pub fn flatten_data(input: ParseString) -> ParseResult<ParserNode> {
-  /*let msg1 = "Expects spaces around operator";
-  let msg2 = "Expects expression";
-  let (input, table) = identifier(input)?;
-  let (input, _) = labelr!(null(is_not(stmt_operator)), skip_nil, msg1)(input)?;
-  let (input, _) = many0(space)(input)?;
+  let (input, source) = label!(expression, msg)(input)?;
+  let (input, by) = opt(split_table_by)(input)?;
+  Ok((input, SplitTable{name, source, by}))
+}
+
+// split-table-by := space-tab+, "by", space-tab+, identifier ;
+pub fn split_table_by(input: ParseString) -> ParseResult<Identifier> {
+  let (input, _) = many1(space_tab)(input)?;
+  let (input, _) = tag("by")(input)?;
+  let (input, _) = many1(space_tab)(input)?;
+  let (input, key) = identifier(input)?;
+  Ok((input, key))
+}
+
+// flatten-table := identifier, flatten-operator, expression ;
+pub fn flatten_table(input: ParseString) -> ParseResult<FlattenTable> {
+  let msg = "Expects expression";
+  let (input, name) = identifier(input)?;
   let (input, _) = flatten_operator(input)?;
-  let (input, _) = labelr!(null(many1(space)), skip_nil, msg1)(input)?;
-  let (input, expression) = label!(expression, msg2)(input)?;*/
-  Ok((input, ParserNode::FlattenData{children: vec![]}))
-}*/
+  let (input, source) = label!(expression, msg)(input)?;
+  Ok((input, FlattenTable{name, source}))
+}
 
 // variable-define := tilde?, var, !assign-operator, define-operator, expression ;
 pub fn variable_define(input: ParseString) -> ParseResult<VariableDefine> {
@@ -170,19 +172,6 @@ pub fn op_assign(input: ParseString) -> ParseResult<OpAssign> {
 // parser for the second line of the output table, generate the 
 // var name if there is one.
 
-// split_operator := ">-" ;
-/*pub fn split_operator(input: ParseString) -> ParseResult<ParserNode> {
-  let (input, _) = tag(">-")(input)?;
-  Ok((input, ParserNode::Null))
-}*/
-
-// flatten_operator := "-<" ;
-/*pub fn flatten_operator(input: ParseString) -> ParseResult<ParserNode> {
-  let (input, _) = tag("-<")(input)?;
-  Ok((input, ParserNode::Null))
-}*/
-
-
 // tuple-destructure := "(", list1(identifier, comma), ")", ":=", expression ;
 fn tuple_destructure(input: ParseString) -> ParseResult<TupleDestructure> {
   let (input, _) = left_parenthesis(input)?;
@@ -193,7 +182,7 @@ fn tuple_destructure(input: ParseString) -> ParseResult<TupleDestructure> {
   Ok((input, TupleDestructure{vars, expression}))
 }
 
-// statement := variable-define | variable-assign | op-assign | enum-define | tuple-destructure | kind-define ;
+// statement := variable-define | variable-assign | op-assign | enum-define | tuple-destructure | kind-define | split-table | flatten-table ;
 pub fn statement(input: ParseString) -> ParseResult<Statement> {
   let parsers: Vec<(&'static str,Box<dyn Fn(ParseString) -> ParseResult<Statement>>)> = vec![
     ("fsm_declare", Box::new(|i| fsm_declare(i).map(|(i, v)| (i, Statement::FsmDeclare(v))))),
@@ -203,6 +192,8 @@ pub fn statement(input: ParseString) -> ParseResult<Statement> {
     ("enum_define", Box::new(|i| enum_define(i).map(|(i, v)| (i, Statement::EnumDefine(v))))),
     ("tuple_destructure", Box::new(|i| tuple_destructure(i).map(|(i, v)| (i, Statement::TupleDestructure(v))))),
     ("kind_define", Box::new(|i| kind_define(i).map(|(i, v)| (i, Statement::KindDefine(v))))),
+    ("split_table", Box::new(|i| split_table(i).map(|(i, v)| (i, Statement::SplitTable(v))))),
+    ("flatten_table", Box::new(|i| flatten_table(i).map(|(i, v)| (i, Statement::FlattenTable(v))))),
   ];
   alt_best(input, &parsers)
 }
@@ -181,6 +181,12 @@ fn new_interpreter(id: u64) -> Interpreter {
   #[cfg(feature = "set_comprehensions")]
   fxns.function_compilers.insert(hash_str("set/comprehension"), &SetComprehensionDefine{});
 
+  // Record and table functions
+  #[cfg(feature = "table_comprehensions")]
+  fxns.function_compilers.insert(hash_str("table/comprehension"), &TableComprehensionDefine{});
+  #[cfg(feature = "record_comprehensions")]
+  fxns.function_compilers.insert(hash_str("record/comprehension"), &RecordComprehensionDefine{});
+
   intrp
 
 }
@@ -230,6 +236,84 @@ fn format_output_value_html(output: &Value) -> String {
   )
 }
 
+// Paged table outputs, keyed by block id. Each block gets one delegated
+// click listener for its pager buttons; re-rendering a block swaps the table
+// it pages through rather than adding another listener.
+#[cfg(feature = "table")]
+struct TablePager {
+  block: Element,
+  table: Ref<MechTable>,
+  page_size: usize,
+  on_click: Closure<dyn FnMut(web_sys::MouseEvent)>,
+}
+
+#[cfg(feature = "table")]
+thread_local! {
+  static TABLE_PAGERS: RefCell<HashMap<String, TablePager>> = RefCell::new(HashMap::new());
+}
+
+// Shows the first page of `table` in `block` and points the block's pager at it.
+#[cfg(feature = "table")]
+fn render_paged_table(block: &Element, table: Ref<MechTable>, page_size: usize) {
+  let id = block.id();
+  TABLE_PAGERS.with(|pagers| {
+    let mut pagers = pagers.borrow_mut();
+    match pagers.get_mut(&id) {
+      Some(pager) if pager.block == *block => {
+        pager.table = table.clone();
+        pager.page_size = page_size;
+      }
+      _ => {
+        if let Some(old) = pagers.remove(&id) {
+          let _ = old.block.remove_event_listener_with_callback("click", old.on_click.as_ref().unchecked_ref());
+        }
+        let pager_id = id.clone();
+        let pager_block = block.clone();
+        let on_click = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+          let button = match event.target().and_then(|target| target.dyn_into::<Element>().ok()) {
+            Some(target) => match target.closest(".mech-table-pager button[data-page]") {
+              Ok(Some(button)) => button,
+              _ => return,
+            },
+            None => return,
+          };
+          let target = match button.get_attribute("data-page").and_then(|page| page.parse::<usize>().ok()) {
+            Some(target) => target,
+            None => return,
+          };
+          let pager = TABLE_PAGERS.with(|pagers| pagers.borrow().get(&pager_id).map(|pager| (pager.table.clone(), pager.page_size)));
+          if let Some((table, page_size)) = pager {
+            render_table_page(&pager_block, table, target, page_size);
+          }
+        }) as Box<dyn FnMut(_)>);
+        block.add_event_listener_with_callback("click", on_click.as_ref().unchecked_ref()).unwrap();
+        pagers.insert(id.clone(), TablePager { block: block.clone(), table: table.clone(), page_size, on_click });
+      }
+    }
+  });
+  render_table_page(block, table, 0, page_size);
+}
+
+// Writes page `page` of a table output into `block`.
+#[cfg(feature = "table")]
+fn render_table_page(block: &Element, table: Ref<MechTable>, page: usize, page_size: usize) {
+  let kind_str = html_escape(&format!("{}", table.borrow().kind()));
+  block.set_inner_html(&format!(
+    "<div class=\"mech-output-kind\">{}</div><div class=\"mech-output-value\">{}</div>",
+    kind_str,
+    table.borrow().to_html_page(page, page_size)
+  ));
+}
+
+#[cfg(feature = "table")]
+fn output_table(output: &Value) -> Option<Ref<MechTable>> {
+  match output {
+    Value::Table(table) => Some(table.clone()),
+    Value::MutableReference(value) => output_table(&value.borrow()),
+    _ => None,
+  }
+}
+
 #[wasm_bindgen(start)]
 pub fn main() -> Result<(), JsValue> {
   //let mut wasm_mech = WasmMech::new();
@@ -954,6 +1038,14 @@ pub fn attach_repl(&mut self, repl_id: &str) {
                 continue;
               }
             };
+            // Tables in blocks with a page size are rendered a page at a time.
+            #[cfg(feature = "table")]
+            if let Some(page_size) = block.get_attribute("mech-page-size").and_then(|size| size.parse::<usize>().ok()) {
+              if let Some(table) = output_table(output) {
+                render_paged_table(&block, table, page_size);
+                continue;
+              }
+            }
             // set the inner html of the block to the output value html
             block.set_inner_html(&format_output_value_html(output));
           }
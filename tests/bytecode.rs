@@ -67,11 +67,161 @@ bytecode_test!(bytecode_math_atan2,"math/atan2(1, 1)",Value::F64(Ref::new(std::f
 bytecode_test!(bytecode_math_atan22,"math/atan(1, 1)",Value::F64(Ref::new(std::f64::consts::FRAC_PI_4)));
 bytecode_test!(bytecode_matrix_matmul_transpose,"[1 2 3] ** [4 5 6]'",Value::MatrixF64(Matrix::from_vec(vec![32.0], 1, 1)));
 bytecode_test!(bytecode_matrix_dot,"matrix/dot([1 2 3],[4 5 6])",Value::F64(Ref::new(32.0)));
+bytecode_test!(bytecode_matrix_inverse,"matrix/inverse([2 0; 0 4])",Value::MatrixF64(Matrix::from_vec(vec![0.5,0.0,0.0,0.25], 2, 2)));
+bytecode_test!(bytecode_matrix_trace,"matrix/trace([1 2; 3 4])",Value::F64(Ref::new(5.0)));
+bytecode_test!(bytecode_matrix_determinant,"matrix/determinant([2 0; 0 4])",Value::F64(Ref::new(8.0)));
+bytecode_test!(bytecode_matrix_identity,"matrix/identity(2)",Value::MatrixF64(Matrix::from_vec(vec![1.0,0.0,0.0,1.0], 2, 2)));
+bytecode_test!(bytecode_matrix_linspace,"matrix/linspace(0, 1, 3)",Value::MatrixF64(Matrix::from_vec(vec![0.0,0.5,1.0], 1, 3)));
+bytecode_test!(bytecode_matrix_reshape,"matrix/reshape([1 2 3 4], 2, 2)",Value::MatrixF64(Matrix::from_vec(vec![1.0,2.0,3.0,4.0], 2, 2)));
+bytecode_test!(bytecode_matrix_concat,"matrix/concat(2, [1 2], [3])",Value::MatrixF64(Matrix::from_vec(vec![1.0,2.0,3.0], 1, 3)));
 bytecode_test!(bytecode_range_inclusive,"1..=4",Value::MatrixF64(Matrix::from_vec(vec![1.0,2.0,3.0,4.0], 1, 4)));
 bytecode_test!(bytecode_range_inclusive_d,"1..=5",Value::MatrixF64(Matrix::from_vec(vec![1.0,2.0,3.0,4.0,5.0], 1, 5)));
 bytecode_test!(bytecode_range_inclusive_refs,"a := 1; b :=4 ; a..=b",Value::MatrixF64(Matrix::from_vec(vec![1.0,2.0,3.0,4.0], 1, 4)));
 bytecode_test!(bytecode_range_exclusive,"1..5",Value::MatrixF64(Matrix::from_vec(vec![1.0,2.0,3.0,4.0], 1, 4)));
 bytecode_test!(bytecode_stats_sum_column,"stats/sum/column([1 2 3])",Value::MatrixF64(Matrix::from_vec(vec![6.0], 1, 1)));
+bytecode_test!(bytecode_stats_mean_row,"stats/mean/row([1 2; 3 6])",Value::MatrixF64(Matrix::from_vec(vec![2.0, 4.0], 1, 2)));
+bytecode_test!(bytecode_stats_quantile,"stats/quantile([3 1 4 1 5], 0.5)",Value::F64(Ref::new(3.0)));
+bytecode_test!(bytecode_stats_cumsum_column,"stats/cumsum/column([1 2; 3 4])",Value::MatrixF64(Matrix::from_vec(vec![1.0, 3.0, 3.0, 7.0], 2, 2)));
+bytecode_test!(bytecode_random_integer,"random/integer(3, 2, 2, 1, 3)",Value::MatrixF64(Matrix::from_vec(vec![2.0,2.0,2.0], 1, 3)));
+bytecode_test!(bytecode_random_choice,"random/choice(3, [4 4], 2, 1)",Value::MatrixF64(Matrix::from_vec(vec![4.0,4.0], 2, 1)));
+bytecode_test!(bytecode_time_format,"time/format(86400)",Value::String(Ref::new("1970-01-02T00:00:00Z".to_string())));
+bytecode_test!(bytecode_time_parse_duration,r#"time/parse-duration("2m 30s")"#,Value::F64(Ref::new(150.0)));
+bytecode_test!(bytecode_signal_convolve,"signal/convolve([1 2 3], [0 1 0.5])",Value::MatrixF64(Matrix::from_vec(vec![0.0, 1.0, 2.5, 4.0, 1.5], 1, 5)));
+bytecode_test!(bytecode_signal_fft,"signal/fft([1 0 0 0])",Value::MatrixC64(Matrix::from_vec(vec![C64::new(1.0, 0.0); 4], 1, 4)));
+#[test]
+fn bytecode_lambda_unsupported() {
+  let mut intrp = Interpreter::new(0);
+  let tree = parser::parse(r#"f := \(x) => x * 2"#).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let err = intrp.compile().unwrap_err();
+  assert_eq!(err.kind_name(), "UnsupportedValueKind");
+}
+#[test]
+fn bytecode_calculus_unsupported() {
+  let mut intrp = Interpreter::new(0);
+  let tree = parser::parse(r#"area := calculus/integrate(\(x) => x * x, 0, 1)"#).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let err = intrp.compile().unwrap_err();
+  assert_eq!(err.kind_name(), "CallbackNotCompilable");
+}
+#[test]
+fn bytecode_io_requires_capability() {
+  let path = std::env::temp_dir().join(format!("mech-{}-bytecode.txt", std::process::id()));
+  let path = path.to_string_lossy().replace('\\', "/");
+  std::fs::write(&path, "saved").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.grant_capability(Capability::FileSystem);
+  let tree = parser::parse(&format!(r#"io/read-file("{}")"#, path)).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let bytecode = intrp.compile().unwrap();
+  let prog = ParsedProgram::from_bytes(&bytecode).unwrap();
+  let err = Interpreter::new(0).run_program(&prog).unwrap_err();
+  assert_eq!(err.kind_name(), "MissingCapability");
+  std::fs::write(&path, "changed").unwrap();
+  let mut loaded = Interpreter::new(0);
+  loaded.grant_capability(Capability::FileSystem);
+  assert_eq!(loaded.run_program(&prog).unwrap(), Value::String(Ref::new("changed".to_string())));
+}
+#[test]
+fn bytecode_io_capability_without_feature() {
+  let path = std::env::temp_dir().join(format!("mech-{}-bytecode-stripped.txt", std::process::id()));
+  let path = path.to_string_lossy().replace('\\', "/");
+  std::fs::write(&path, "secret").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.grant_capability(Capability::FileSystem);
+  let tree = parser::parse(&format!(r#"io/read-file("{}")"#, path)).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let bytecode = intrp.compile().unwrap();
+  let mut prog = ParsedProgram::from_bytes(&bytecode).unwrap();
+  prog.features.retain(|feature| *feature != hash_str("io/read-file"));
+  let err = Interpreter::new(0).run_program(&prog).unwrap_err();
+  assert_eq!(err.kind_name(), "MissingCapability");
+}
+#[test]
+fn bytecode_io_write_file() {
+  let path = std::env::temp_dir().join(format!("mech-{}-bytecode-write.txt", std::process::id()));
+  let path = path.to_string_lossy().replace('\\', "/");
+  let mut intrp = Interpreter::new(0);
+  intrp.grant_capability(Capability::FileSystem);
+  let tree = parser::parse(&format!(r#"io/write-file("{}", "saved")"#, path)).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let bytecode = intrp.compile().unwrap();
+  let prog = ParsedProgram::from_bytes(&bytecode).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  let mut loaded = Interpreter::new(0);
+  loaded.grant_capability(Capability::FileSystem);
+  loaded.run_program(&prog).unwrap();
+  assert_eq!(std::fs::read_to_string(&path).unwrap(), "saved");
+}
+#[test]
+fn bytecode_io_read_csv_schema() {
+  let path = std::env::temp_dir().join(format!("mech-{}-bytecode.csv", std::process::id()));
+  let path = path.to_string_lossy().replace('\\', "/");
+  std::fs::write(&path, "x,y\n1,a\n2,b\n").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.grant_capability(Capability::FileSystem);
+  let tree = parser::parse(&format!(r#"io/read-csv("{}", <|x<u8> y<string>|>)"#, path)).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let bytecode = intrp.compile().unwrap();
+  let prog = ParsedProgram::from_bytes(&bytecode).unwrap();
+  std::fs::write(&path, "x,y\n1,a\n2,b\n3,c\n").unwrap();
+  let mut loaded = Interpreter::new(0);
+  loaded.grant_capability(Capability::FileSystem);
+  let result = loaded.run_program(&prog).unwrap();
+  assert_eq!(result.kind(), ValueKind::Table(vec![
+    ("x".to_string(), ValueKind::U8),
+    ("y".to_string(), ValueKind::String),
+  ], 3));
+}
+#[test]
+fn bytecode_io_read_json() {
+  let path = std::env::temp_dir().join(format!("mech-{}-bytecode.json", std::process::id()));
+  let path = path.to_string_lossy().replace('\\', "/");
+  std::fs::write(&path, "[1, 2]").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.grant_capability(Capability::FileSystem);
+  let tree = parser::parse(&format!(r#"io/read-json("{}")"#, path)).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let bytecode = intrp.compile().unwrap();
+  let prog = ParsedProgram::from_bytes(&bytecode).unwrap();
+  std::fs::write(&path, "[1, 2, 3]").unwrap();
+  let mut loaded = Interpreter::new(0);
+  loaded.grant_capability(Capability::FileSystem);
+  assert_eq!(loaded.run_program(&prog).unwrap(), Value::MatrixF64(Matrix::from_vec(vec![1.0, 2.0, 3.0], 1, 3)));
+}
+#[test]
+fn bytecode_io_from_json_kind() {
+  let mut intrp = Interpreter::new(0);
+  let tree = parser::parse(r#"io/from-json("[300, 2]", <[u16]>)"#).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let bytecode = intrp.compile().unwrap();
+  let prog = ParsedProgram::from_bytes(&bytecode).unwrap();
+  let mut loaded = Interpreter::new(0);
+  loaded.run_program(&prog).unwrap();
+  assert_eq!(loaded.step(0, 1).unwrap(), Value::MatrixU16(Matrix::from_vec(vec![300, 2], 1, 2)));
+}
+#[test]
+fn bytecode_io_parquet_round_trip() {
+  let path = std::env::temp_dir().join(format!("mech-{}-bytecode.parquet", std::process::id()));
+  let path = path.to_string_lossy().replace('\\', "/");
+  let mut intrp = Interpreter::new(0);
+  intrp.grant_capability(Capability::FileSystem);
+  let tree = parser::parse(&format!(r#"T := |x<f64> y<bool>| 1 true | 2 false |
+io/write-parquet("{}", T)
+io/read-parquet("{}")"#, path, path)).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let bytecode = intrp.compile().unwrap();
+  let prog = ParsedProgram::from_bytes(&bytecode).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  let mut loaded = Interpreter::new(0);
+  loaded.grant_capability(Capability::FileSystem);
+  let result = loaded.run_program(&prog).unwrap();
+  assert_eq!(result.kind(), ValueKind::Table(vec![
+    ("x".to_string(), ValueKind::F64),
+    ("y".to_string(), ValueKind::Bool),
+  ], 2));
+  assert!(std::path::Path::new(&path).exists());
+}
 bytecode_test!(bytecode_matrix_index_assign,"~x := [1 2 3]; x[1] = 10",Value::MatrixF64(Matrix::from_vec(vec![10.0,2.0,3.0], 1, 3)));
 bytecode_test!(bytecode_matrix_index_assign_bool,"~x := [1 2 3]; x[[true false true]] = [4 5 6]",Value::MatrixF64(Matrix::from_vec(vec![4.0,2.0,6.0], 1, 3)));
 bytecode_test!(bytecode_matrix_index_assign_bool_all,"~x := [1 2 3]; x[true] = [4 5 6]",Value::MatrixF64(Matrix::from_vec(vec![4.0,5.0,6.0], 1, 3)));
@@ -125,4 +275,40 @@ bytecode_test!(bytecode_define_table, "x := |x<f64> y<u64>| 1 2 | 3 4 |", Value:
   ],
 ))));
 bytecode_test!(bytecode_define_table_eq, "x := |x<f64> y<bool>| 1 true | 3 false |; y := |x<f64> y<bool>| 1 true | 3 false |; x == y", Value::Bool(Ref::new(true)));
+bytecode_test!(bytecode_table_join, r#"A := |id<u64> a<u64>| 1 10 | 2 20 |; B := |id<u64> b<u64>| 2 200 | 3 300 |; J := table/join(A, B); J == |id<u64> a<u64> b<u64>| 2 20 200 |"#, Value::Bool(Ref::new(true)));
+bytecode_test!(bytecode_table_join_on, r#"A := |id<u64> a<u64>| 1 10 | 2 20 |; B := |uid<u64> b<u64>| 2 200 | 1 100 |; J := table/join(A, B, on: "id = uid"); J == |id<u64> a<u64> uid<u64> b<u64>| 1 10 1 100 | 2 20 2 200 |"#, Value::Bool(Ref::new(true)));
+bytecode_test!(bytecode_table_sort_by, r#"T := |x<f64> y<f64>| 3 30 | 1 10 | 2 20 |; S := T ⇅ "x desc"; S == |x<f64> y<f64>| 3 30 | 2 20 | 1 10 |"#, Value::Bool(Ref::new(true)));
+bytecode_test!(bytecode_table_filter, r#"T := |x<f64> y<f64>| 3 30 | 1 10 | 2 20 |; F := table/filter(T, [true; false; true]); F == |x<f64> y<f64>| 3 30 | 2 20 |"#, Value::Bool(Ref::new(true)));
+#[test]
+fn bytecode_table_filter_function_unsupported() {
+  let mut intrp = Interpreter::new(0);
+  let tree = parser::parse(r#"T := |x<f64>| 1 | 2 |; F := table/filter(T, \(t) => t.x > 1.5)"#).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let err = intrp.compile().unwrap_err();
+  assert_eq!(err.kind_name(), "CallbackNotCompilable");
+}
+bytecode_test!(bytecode_table_group_by, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |; G := table/group-by(T, by: "k", aggs: {total: "sum(x)"}); G == |k<string> total<f64>| "a" 4 | "b" 2 |"#, Value::Bool(Ref::new(true)));
+bytecode_test!(bytecode_table_flatten_record, "F -< {a: 1, b: 2}\nF == |a<f64> b<f64>| 1 2 |", Value::Bool(Ref::new(true)));
+#[test]
+fn bytecode_table_split_unsupported() {
+  let mut intrp = Interpreter::new(0);
+  let tree = parser::parse("T := |k<string> x<f64>| \"a\" 1 |\nrows >- T").unwrap();
+  intrp.interpret(&tree).unwrap();
+  let err = intrp.compile().unwrap_err();
+  assert_eq!(err.kind_name(), "UnsupportedValueKind");
+}
+#[test]
+fn bytecode_table_group_by_user_aggregation_unsupported() {
+  let mut intrp = Interpreter::new(0);
+  let tree = parser::parse(r#"T := |k<string> x<f64>| "a" 1 |; G := table/group-by(T, by: "k", aggs: {h: \(g) => g.x[1]})"#).unwrap();
+  intrp.interpret(&tree).unwrap();
+  let err = intrp.compile().unwrap_err();
+  assert_eq!(err.kind_name(), "CallbackNotCompilable");
+}
+bytecode_test!(bytecode_string_replace_regex, r##"string/replace("x12y345", "[0-9]+", "#", true)"##, Value::String(Ref::new("x#y#".to_string())));
+bytecode_test!(bytecode_string_split_regex, r#"string/split(["a:1"; "b:2"], "\\s*:\\s*", true)"#, Value::MatrixString(Matrix::from_vec(vec!["a".to_string(), "b".to_string(), "1".to_string(), "2".to_string()], 2, 2)));
+bytecode_test!(bytecode_string_join, r#"string/join(["a" "b" "c"], "-")"#, Value::String(Ref::new("a-b-c".to_string())));
+bytecode_test!(bytecode_string_pad_left, r#"string/pad-left("7", 3, "0")"#, Value::String(Ref::new("007".to_string())));
+bytecode_test!(bytecode_string_contains, r#"string/contains(["abc" "xyz"], "b")"#, Value::MatrixBool(Matrix::from_vec(vec![true, false], 1, 2)));
+bytecode_test!(bytecode_string_find, r#"string/find("née!", "!")"#, Value::U64(Ref::new(4)));
 //bytecode_test!(bytecode_set_union, "x := {1 2 3}; y := {3 4 5}; x ∪ y", Value::Set(Ref::new(MechSet::from_vec(vec![Value::F64(Ref::new(1.0)),Value::F64(Ref::new(2.0)),Value::F64(Ref::new(3.0)),Value::F64(Ref::new(4.0)),Value::F64(Ref::new(5.0))]))));
\ No newline at end of file
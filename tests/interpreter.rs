@@ -28,6 +28,19 @@ macro_rules! test_interpreter {
   )
 }
 
+/// Check that interpreting the input fails with the expected error kind
+macro_rules! test_interpreter_error {
+  ($func:ident, $input:tt, $kind:expr) => (
+    #[test]
+    fn $func() {
+      let tree = parser::parse(&$input).unwrap();
+      let mut intrp = Interpreter::new(0);
+      let err = intrp.interpret(&tree).unwrap_err();
+      assert_eq!(err.kind_name(), $kind);
+    }
+  )
+}
+
 /////////////////////////////////////////////////////////////////////////////////
 
 test_interpreter!(interpret_literal_integer, "123", Value::F64(Ref::new(123.0)));
@@ -314,6 +327,46 @@ test_interpreter!(
   Value::U64(Ref::new(3))
 );
 
+test_interpreter!(
+  interpret_match_string_prefix_pattern,
+  r#"route := "/users/42"; y := route? | "/posts/"…id => "post " + id | "/users/"…id => "user " + id | * => "none".; y + """#,
+  Value::String(Ref::new("user 42".to_string()))
+);
+test_interpreter!(
+  interpret_match_string_suffix_pattern,
+  r#"file := "data.csv"; y := file? | name…".json" => "json " + name | name…".csv" => "csv " + name | * => "other".; y + """#,
+  Value::String(Ref::new("csv data".to_string()))
+);
+test_interpreter!(
+  interpret_match_string_prefix_suffix_pattern,
+  r#"file := "img/cat.png"; y := file? | "img/"…name…".png" => name | * => "".; y + """#,
+  Value::String(Ref::new("cat".to_string()))
+);
+test_interpreter!(
+  interpret_match_map_key_pattern,
+  r#"msg := {"type": "ping", "id": "7", "ttl": "3"}; y := msg? | {"type": "pong", …} => "" | {"type": "ping", "id": id, …} => id | * => "".; y + """#,
+  Value::String(Ref::new("7".to_string()))
+);
+test_interpreter!(
+  interpret_match_map_exact_keys_pattern,
+  r#"msg := {"type": "ping", "id": "7"}; y := msg? | {"type": t} => 1 | {"id": *, "type": *} => 2 | * => 3.; y + 0"#,
+  Value::F64(Ref::new(2.0))
+);
+test_interpreter!(
+  interpret_match_set_membership_pattern,
+  r#"roles := {"admin", "editor"}; y := roles? | {"owner", …} => 1 | {"admin", …} => 2 | * => 3.; y + 0"#,
+  Value::F64(Ref::new(2.0))
+);
+test_interpreter!(
+  interpret_match_set_exact_pattern,
+  r#"roles := {"admin", "editor"}; y := roles? | {"admin"} => 1 | {"editor", "admin"} => 2 | * => 3.; y + 0"#,
+  Value::F64(Ref::new(2.0))
+);
+test_interpreter!(
+  interpret_match_table_column_pattern,
+  r#"t := | x<f64> y<f64> z<f64> | 1 2 3 | 4 5 6 |; s := t? | |x, y| => [0 0] | |x, y: b, …| => x + b | * => [0 0].; s + 0"#,
+  Value::MatrixF64(Matrix::from_vec(vec![3.0, 9.0], 2, 1))
+);
 test_interpreter!(interpret_option_match_tuple_struct_pattern, "state := (:Done, 9u64); y := state? | :Done(x) => x | * => 0u64.; y + 0u64", Value::U64(Ref::new(9)));
 #[test]
 fn interpret_tagged_union_match_requires_exhaustive_arms() {
@@ -975,6 +1028,92 @@ test_interpreter!(interpret_function_recursive_power,r#"power(x<u64>, y<u64>) =>
   ├ (*, 0) => 1
   └ (x, y) => x * power(x, y - 1<u64>).
 power(2<u64>, 10<u64>)"#, Value::U64(Ref::new(1024)));
+#[cfg(feature = "u64")]
+test_interpreter!(interpret_function_overload_by_kind, r#"describe(x<f64>) = y<f64> := y := x * 2.
+describe(x<u64>) = y<u64> := y := x + 1<u64>.
+describe(3<u64>)"#, Value::U64(Ref::new(4)));
+#[cfg(feature = "u64")]
+test_interpreter!(interpret_function_overload_by_kind_2, r#"describe(x<f64>) = y<f64> := y := x * 2.
+describe(x<u64>) = y<u64> := y := x + 1<u64>.
+describe(3)"#, Value::F64(Ref::new(6.0)));
+#[cfg(feature = "u64")]
+test_interpreter!(interpret_function_overload_recursive, r#"count(x<f64>) => <f64>
+  └ x => count(1<u64>).
+count(x<u64>) => <f64>
+  └ x => 10.
+count(5)"#, Value::F64(Ref::new(10.0)));
+test_interpreter!(interpret_function_overload_by_arity, r#"area(r<f64>) = a<f64> := a := 3 * r * r.
+area(w<f64>, h<f64>) = a<f64> := a := w * h.
+area(2) + area(2, 3)"#, Value::F64(Ref::new(18.0)));
+test_interpreter!(interpret_function_overload_most_specific, r#"total(x<[f64]>) = y<f64> := y := 1.
+total(x<[f64]:1,3>) = y<f64> := y := 2.
+total([1 2 3]) * 10 + total([1 2])"#, Value::F64(Ref::new(21.0)));
+test_interpreter!(interpret_function_redefine_replaces, r#"foo(x<f64>) = y<f64> := y := x + 1.
+foo(x<f64>) = y<f64> := y := x + 2.
+foo(1)"#, Value::F64(Ref::new(3.0)));
+#[test]
+fn interpret_function_overload_ambiguous() {
+  let s = r#"pick(x<[f64]>, y<[f64]:1,2>) = z<f64> := z := 1.
+pick(x<[f64]:1,2>, y<[f64]>) = z<f64> := z := 2.
+pick([1 2], [3 4])"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert!(format!("{:?}", err).contains("FunctionOverloadAmbiguous"));
+}
+test_interpreter!(interpret_lambda_call, r#"inc := \(x) => x + 1; inc(2)"#, Value::F64(Ref::new(3.0)));
+test_interpreter!(interpret_lambda_call_2_args, r#"add := \(x<f64>, y<f64>) => x + y; add(2, 3)"#, Value::F64(Ref::new(5.0)));
+test_interpreter!(interpret_lambda_capture, r#"k := 10; scale := \(x) => x * k; scale(4)"#, Value::F64(Ref::new(40.0)));
+test_interpreter!(interpret_lambda_capture_lexical, r#"k := 10; scale := \(x) => x * k; apply := \(k) => scale(k); apply(3)"#, Value::F64(Ref::new(30.0)));
+test_interpreter!(interpret_lambda_nested, r#"a := 1; outer := \(x) => x + a; inner := \(y) => outer(y) * 2; inner(3)"#, Value::F64(Ref::new(8.0)));
+#[test]
+fn interpret_lambda_wrong_arity() {
+  let s = r#"inc := \(x) => x + 1; inc(1, 2)"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert!(format!("{:?}", err).contains("IncorrectNumberOfArguments"));
+}
+#[test]
+fn interpret_lambda_captures_free_variables() {
+  let tree = parser::parse(r#"k := 10; unused := [1 2 3]; scale := \(x) => x * k"#).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  match symbol_value(&intrp, "scale") {
+    Value::Lambda(lmbd) => {
+      let captured = lmbd.borrow().captures.keys().cloned().collect::<Vec<u64>>();
+      assert_eq!(captured, vec![hash_str("k")]);
+    }
+    x => panic!("Expected a lambda, found {:?}", x),
+  }
+}
+test_interpreter!(interpret_function_match_guard, r#"sign(x<f64>) => <f64>
+  ├ n, n > 0 => 1
+  ├ n, n < 0 => -1
+  └ * => 0.
+sign(-3) * 10 + sign(4)"#, Value::F64(Ref::new(-9.0)));
+test_interpreter!(interpret_function_match_multiple_guards, r#"band(x<f64>) => <f64>
+  ├ n, n > 0, n < 10 => 1
+  ├ n, n >= 10 => 2
+  └ * => 0.
+band(5) * 100 + band(50) * 10 + band(-1)"#, Value::F64(Ref::new(120.0)));
+#[cfg(feature = "u64")]
+test_interpreter!(interpret_function_match_guard_tail_call, r#"collatz(n<u64>, steps<u64>) => <u64>
+  ├ (1<u64>, steps) => steps
+  ├ (n, steps), n % 2<u64> == 0<u64> => collatz(n / 2<u64>, steps + 1<u64>)
+  └ (n, steps) => collatz(3<u64> * n + 1<u64>, steps + 1<u64>).
+collatz(6<u64>, 0<u64>)"#, Value::U64(Ref::new(8)));
+#[test]
+fn interpret_function_match_guard_must_be_bool() {
+  let s = r#"bad(x<f64>) => <f64>
+  ├ n, n + 1 => 1
+  └ * => 0.
+bad(1)"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert!(format!("{:?}", err).contains("FunctionGuardKindMismatch"));
+}
 test_interpreter!(interpret_function_call_native_vector, "math/sin([1.570796327 1.570796327])", Value::MatrixF64(Matrix::from_vec(vec![1.0, 1.0], 1, 2)));
 test_interpreter!(interpret_function_call_native, r#"math/sin(1.5707963267948966)"#, Value::F64(Ref::new(1.0)));
 test_interpreter!(interpret_function_call_native_cos, r#"math/cos(0.0)"#, Value::F64(Ref::new(1.0)));
@@ -1120,6 +1259,41 @@ test_interpreter!(interpret_vertcat_m2r2, "x := [5 2;3 4]; y := [8 9];z := [x;y]
 test_interpreter!(interpret_vertcat_r2m2x3, "x := [1 2 3; 4 5 6]; y := [7 8 9]; z := [y;x]", Value::MatrixF64(Matrix::from_vec(vec![7.0, 1.0, 4.0, 8.0, 2.0, 5.0, 9.0, 3.0, 6.0], 3, 3)));
 
 test_interpreter!(interpret_stats_sum_rowm2, "x := [1 2; 4 5]; y := stats/sum/row(x);", Value::MatrixF64(Matrix::from_vec(vec![5.0, 7.0], 1, 2)));
+test_interpreter!(interpret_stats_mean, "x := [1 2 3; 4 5 9]; y := stats/mean(x);", Value::F64(Ref::new(4.0)));
+test_interpreter!(interpret_stats_mean_row, "x := [1 2 3; 4 5 9]; y := stats/mean/row(x);", Value::MatrixF64(Matrix::from_vec(vec![2.5, 3.5, 6.0], 1, 3)));
+test_interpreter!(interpret_stats_mean_column, "x := [1 2 3; 4 5 9]; y := stats/mean/column(x);", Value::MatrixF64(Matrix::from_vec(vec![2.0, 6.0], 2, 1)));
+test_interpreter!(interpret_stats_var, "x := [1 2 3 4]; y := stats/var(x);", Value::F64(Ref::new(5.0 / 3.0)));
+test_interpreter!(interpret_stats_std_row, "x := [1 2; 3 6]; y := stats/std/row(x);", Value::MatrixF64(Matrix::from_vec(vec![2.0f64.sqrt(), 8.0f64.sqrt()], 1, 2)));
+test_interpreter!(interpret_stats_median, "x := [4 1 3 2]; y := stats/median(x);", Value::F64(Ref::new(2.5)));
+test_interpreter!(interpret_stats_quantile, "x := [3 1 4 1 5]; y := stats/quantile(x, 0.25);", Value::F64(Ref::new(1.0)));
+test_interpreter!(interpret_stats_quantile_column, "x := [1 2 3 4; 10 20 30 40]; y := stats/quantile/column(x, 0.5);", Value::MatrixF64(Matrix::from_vec(vec![2.5, 25.0], 2, 1)));
+#[cfg(feature = "u8")]
+test_interpreter!(interpret_stats_max_u8, "x := [1u8 9u8 2u8]; y := stats/max(x);", Value::U8(Ref::new(9)));
+test_interpreter!(interpret_stats_min_column, "x := [3 1 4; 1 5 9]; y := stats/min/column(x);", Value::MatrixF64(Matrix::from_vec(vec![1.0, 1.0], 2, 1)));
+#[cfg(feature = "u64")]
+test_interpreter!(interpret_stats_argmax_row, "x := [3 1 4; 1 5 9]; y := stats/argmax/row(x);", Value::MatrixU64(Matrix::from_vec(vec![1, 2, 2], 1, 3)));
+#[cfg(feature = "u64")]
+test_interpreter!(interpret_stats_argmin, "x := [2 5 1 1]; y := stats/argmin(x);", Value::U64(Ref::new(3)));
+test_interpreter!(interpret_stats_cumsum, "x := [1 2; 3 4]; y := stats/cumsum(x);", Value::MatrixF64(Matrix::from_vec(vec![1.0, 4.0, 6.0, 10.0], 2, 2)));
+test_interpreter!(interpret_stats_cumsum_row, "x := [1 2; 3 4]; y := stats/cumsum/row(x);", Value::MatrixF64(Matrix::from_vec(vec![1.0, 4.0, 2.0, 6.0], 2, 2)));
+test_interpreter!(interpret_stats_cumprod_column, "x := [1 2; 3 4]; y := stats/cumprod/column(x);", Value::MatrixF64(Matrix::from_vec(vec![1.0, 3.0, 2.0, 12.0], 2, 2)));
+test_interpreter!(interpret_stats_cov, "x := [1 2 3 4]; y := [2 4 6 8]; z := stats/cov(x, y);", Value::F64(Ref::new(10.0 / 3.0)));
+test_interpreter!(interpret_stats_cov_row, "x := [1 2; 2 4; 3 6]; y := stats/cov/row(x);", Value::MatrixF64(Matrix::from_vec(vec![1.0, 2.0, 2.0, 4.0], 2, 2)));
+#[cfg(feature = "u64")]
+test_interpreter!(interpret_stats_histogram, "x := [1 2 2 3 7 8 10]; h := stats/histogram(x, 3); y := h.count;", Value::MatrixU64(Matrix::from_vec(vec![4, 0, 3], 3, 1)));
+
+#[test]
+fn interpret_stats_corr() {
+  let tree = parser::parse("x := [1 2 3 4]; y := [8 6 4 2]; z := stats/corr(x, y);").unwrap();
+  let mut intrp = Interpreter::new(0);
+  let result = intrp.interpret(&tree).unwrap();
+  let corr = result.as_f64().unwrap().borrow().clone();
+  assert!((corr + 1.0).abs() < 1e-12, "{}", corr);
+}
+
+test_interpreter_error!(interpret_stats_quantile_out_of_range, "stats/quantile([1 2 3], 1.5)", "StatsQuantileRange");
+test_interpreter_error!(interpret_stats_cov_shape_mismatch, "stats/cov([1 2 3], [1 2 3 4])", "StatsShapeMismatch");
+test_interpreter_error!(interpret_stats_histogram_zero_bins, "stats/histogram([1 2 3], 0)", "StatsHistogramBins");
 
 test_interpreter!(interpret_add_assign, "~x := 10; x += 20", Value::F64(Ref::new(30.0)));
 test_interpreter!(interpret_add_assign_formula, "ix := [1 1 2 3]; y := 5; ~x := [1 2 3 4]; x[ix] += y;", Value::MatrixF64(Matrix::from_vec(vec![11.0, 7.0, 8.0, 4.0], 1, 4)));
@@ -1324,6 +1498,211 @@ test_interpreter!(interpret_table_left_anti_join_symbol, r#"A := |id<u64> a<u64>
 #[cfg(all(feature = "table", feature = "u64"))]
 test_interpreter!(interpret_table_left_anti_join_word, r#"A := |id<u64> a<u64>| 1 10 | 2 20 | 3 30 |; B := |id<u64> b<u64>| 2 200 | 3 300 | 4 400 |; J := table/left-anti-join(A, B); J.a[1]"#, Value::U64(Ref::new(10)));
 
+test_interpreter!(interpret_table_join_on_multiple_keys, r#"A := |id<u64> k<u64> a<u64>| 1 1 10 | 1 2 20 | 2 1 30 |; B := |id<u64> k<u64> b<u64>| 2 1 300 | 1 2 200 |; J := table/join(A, B, on: ["id" "k"]); J.a[1] + J.b[1] + J.a[2] + J.b[2]"#, Value::U64(Ref::new(550)));
+test_interpreter!(interpret_table_join_on_differently_named_keys, r#"A := |id<u64> a<u64>| 1 10 | 2 20 |; B := |uid<u64> b<u64>| 2 200 | 3 300 |; J := table/join(A, B, on: "id = uid"); J.uid[1]"#, Value::U64(Ref::new(2)));
+test_interpreter!(interpret_table_join_renames_colliding_columns, r#"A := |id<u64> v<u64>| 1 10 | 2 20 |; B := |id<u64> v<u64>| 1 100 | 2 200 |; J := table/join(A, B, on: "id"); J.v[2] + J.v-right[2]"#, Value::U64(Ref::new(220)));
+test_interpreter!(interpret_table_join_on_range, r#"E := |t<f64>| 1 | 5 | 9 |; W := |start<f64> stop<f64>| 0 4 | 4 8 |; J := table/join(E, W, on: ["t >= start" "t < stop"]); J.t[2] + J.start[2]"#, Value::F64(Ref::new(9.0)));
+test_interpreter!(interpret_table_join_on_inequality_multiple_matches, r#"A := |x<u64>| 1 | 3 |; B := |y<u64>| 0 | 2 | 4 |; J := table/join(A, B, on: "x > y"); J.y"#, Value::MatrixU64(Matrix::from_vec(vec![0, 0, 2], 3, 1)));
+test_interpreter!(interpret_table_left_outer_join_on_range, r#"E := |t<f64>| 1 | 9 |; W := |start<f64> stop<f64>| 0 4 |; J := table/left-outer-join(E, W, on: ["t >= start" "t < stop"]); J.t[2]"#, Value::F64(Ref::new(9.0)));
+test_interpreter!(interpret_table_join_named_arguments, r#"A := |id<u64> a<u64>| 1 10 | 2 20 |; B := |uid<u64> b<u64>| 2 200 | 3 300 |; J := table/join(on: "id = uid", rhs: B, lhs: A); J.a[1]"#, Value::U64(Ref::new(20)));
+
+#[test]
+fn interpret_table_join_unknown_argument_name() {
+  let s = r#"A := |id<u64>| 1 |; B := |id<u64>| 1 |; J := table/join(A, B, key: "id")"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert_eq!(err.kind_name(), "UnknownArgumentName");
+}
+
+#[test]
+fn interpret_table_join_too_many_arguments() {
+  let s = r#"A := |id<u64>| 1 |; B := |id<u64>| 1 |; J := table/join(A, B, "id", "id")"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert_eq!(err.kind_name(), "IncorrectNumberOfArguments");
+  assert!(err.kind_message().contains("Expected 3 arguments"));
+}
+test_interpreter_error!(interpret_table_join_on_missing_column, r#"A := |id<u64>| 1 |; B := |uid<u64>| 1 |; J := table/join(A, B, on: "id = key")"#, "JoinColumnNotFound");
+test_interpreter!(interpret_table_group_by_sum, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |; G := table/group-by(T, by: "k", aggs: {total: "sum(x)"}); G.total"#, Value::MatrixF64(Matrix::from_vec(vec![4.0, 2.0], 2, 1)));
+test_interpreter!(interpret_table_group_by_count_is_u64, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |; G := table/group-by(T, by: "k", aggs: {n: "count"}); G.n[1]"#, Value::U64(Ref::new(2)));
+test_interpreter!(interpret_table_group_by_mean_is_f64, r#"T := |k<string> x<u64>| "a" 1 | "a" 4 | "a" 1 |; G := table/group-by(T, by: "k", aggs: {m: "mean(x)"}); G.m[1]"#, Value::F64(Ref::new(2.0)));
+test_interpreter!(interpret_table_group_by_min_max, r#"T := |k<string> x<u64>| "a" 1 | "a" 4 | "a" 2 |; G := table/group-by(T, by: "k", aggs: {lo: "min(x)", hi: "max(x)"}); G.lo[1] * 10u64 + G.hi[1]"#, Value::U64(Ref::new(14)));
+test_interpreter!(interpret_table_group_by_first_last, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |; G := table/group-by(T, by: "k", aggs: {f: "first(x)", l: "last(x)"}); G.f[1] * 10 + G.l[1]"#, Value::F64(Ref::new(13.0)));
+test_interpreter!(interpret_table_group_by_multiple_keys_by_position, r#"T := |k<string> g<u64> x<f64>| "a" 1 1 | "a" 2 2 | "a" 1 3 |; G := table/group-by(T, by: [1 2], aggs: {total: "sum(3)"}); G.total[1]"#, Value::F64(Ref::new(4.0)));
+test_interpreter!(interpret_table_group_by_user_function, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |
+
+head-twice(v<[f64]>) => <f64>
+  | v[1] * 2.
+
+G := table/group-by(T, by: "k", aggs: {h: "head-twice(x)"}); G.h[2]"#, Value::F64(Ref::new(4.0)));
+test_interpreter!(interpret_table_group_by_lambda, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |; G := table/group-by(T, by: "k", aggs: {h: \(g) => g.x[1] * 10}); G.h[2]"#, Value::F64(Ref::new(20.0)));
+test_interpreter!(interpret_table_dot_index_column, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 |; G := table/group-by(T, by: 1, aggs: {n: "count"}); G.1[2]"#, Value::String(Ref::new("b".to_string())));
+
+#[test]
+fn interpret_table_group_by_unsupported_aggregation() {
+  let s = r#"T := |k<string> x<f64>| "a" 1 |; G := table/group-by(T, by: "k", aggs: {s: "sum(k)"})"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert!(format!("{:?}", err).contains("UnsupportedAggregation"));
+}
+test_interpreter!(interpret_table_group_by_named_arguments_in_any_order, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |; G := table/group-by(T, aggs: {total: "sum(x)"}, by: "k"); G.total[1]"#, Value::F64(Ref::new(4.0)));
+
+#[test]
+fn interpret_table_group_by_unknown_argument_name() {
+  let s = r#"T := |k<string> x<f64>| "a" 1 |; G := table/group-by(T, key: "k", aggs: {n: "count"})"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert_eq!(err.kind_name(), "UnknownArgumentName");
+}
+
+#[test]
+fn interpret_table_group_by_unknown_user_aggregation() {
+  let s = r#"T := |k<string> x<f64>| "a" 1 |; G := table/group-by(T, by: "k", aggs: {h: "no-such-fxn(x)"})"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert_eq!(err.kind_name(), "UnknownAggregation");
+}
+
+#[test]
+fn interpret_table_group_by_reacts_to_table() {
+  let s = r#"~T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |; G := table/group-by(T, by: "k", aggs: {total: "sum(x)"}); T.x = [10; 20; 30]"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  intrp.step(0, 1).unwrap();
+  match symbol_value(&intrp, "G") {
+    Value::Table(table) => {
+      let total = table.borrow().data.get(&hash_str("total")).unwrap().1.to_value();
+      assert_eq!(total, Value::MatrixF64(Matrix::from_vec(vec![40.0, 20.0], 2, 1)));
+    }
+    value => panic!("{:?}", value),
+  }
+}
+test_interpreter!(interpret_split_table_into_records, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |
+rows >- T
+r := rows[2]
+r.x"#, Value::F64(Ref::new(2.0)));
+test_interpreter!(interpret_split_table_by_key, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |
+groups >- T by k
+g := groups[1]
+g.x[2]"#, Value::F64(Ref::new(3.0)));
+test_interpreter!(interpret_flatten_split_records, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |
+rows >- T
+F -< rows
+F.x[3]"#, Value::F64(Ref::new(3.0)));
+test_interpreter!(interpret_flatten_split_groups, r#"T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |
+groups >- T by k
+F -< groups
+F.k[3]"#, Value::String(Ref::new("b".to_string())));
+test_interpreter!(interpret_flatten_nested_record, r#"F -< {a: 1, p: {x: 2, y: 3}}
+F.p-y[1]"#, Value::F64(Ref::new(3.0)));
+test_interpreter!(interpret_flatten_record_column, r#"N := |n<string> p<*>| "a" {x: 1, y: 2} | "b" {x: 3, y: 4} |
+F -< N
+F.p-x[2]"#, Value::F64(Ref::new(3.0)));
+test_interpreter!(interpret_flatten_table_column, r#"A := |x<f64>| 1 | 2 |
+N := |n<string> t<*>| "a" A | "b" A |
+F -< N
+F.t-x[3] + F.t-x[4]"#, Value::F64(Ref::new(3.0)));
+test_interpreter!(interpret_greater_than_negative_is_not_split, "a := 1; a >-1", Value::Bool(Ref::new(true)));
+
+#[test]
+fn interpret_split_and_flatten_react_to_table() {
+  let s = r#"~T := |k<string> x<f64>| "a" 1 | "b" 2 | "a" 3 |
+rows >- T
+r := rows[2]
+y := r.x
+groups >- T by k
+F -< rows
+T.x = [10; 20; 30]"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  intrp.step(0, 1).unwrap();
+  assert_eq!(symbol_value(&intrp, "y"), Value::F64(Ref::new(20.0)));
+  match symbol_value(&intrp, "F") {
+    Value::Table(table) => {
+      let x = table.borrow().data.get(&hash_str("x")).unwrap().1.to_value();
+      assert_eq!(x, Value::MatrixF64(Matrix::from_vec(vec![10.0, 20.0, 30.0], 3, 1)));
+    }
+    value => panic!("{:?}", value),
+  }
+  match symbol_value(&intrp, "groups") {
+    Value::MatrixValue(groups) => match groups.index1d(1) {
+      Value::Table(table) => {
+        let x = table.borrow().data.get(&hash_str("x")).unwrap().1.to_value();
+        assert_eq!(x, Value::MatrixF64(Matrix::from_vec(vec![10.0, 30.0], 2, 1)));
+      }
+      value => panic!("{:?}", value),
+    },
+    value => panic!("{:?}", value),
+  }
+}
+
+#[test]
+fn interpret_split_table_requires_table() {
+  let s = "x := 1\nrows >- x";
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert!(format!("{:?}", err).contains("SplitTableKind"));
+}
+
+#[test]
+fn format_split_and_flatten_tables() {
+  let s = "rows >- T\ngroups >- T by k\nF -< groups";
+  let tree = parser::parse(&s).unwrap();
+  let formatted = formatter::Formatter::new().format(&tree);
+  assert_eq!(formatted.trim_end(), s);
+  let reparsed = parser::parse(&formatted).unwrap();
+  assert_eq!(formatter::Formatter::new().format(&reparsed), formatted);
+}
+
+test_interpreter!(interpret_table_sort_by, r#"T := |x<f64> y<f64>| 3 30 | 1 10 | 2 20 |; S := table/sort-by(T, "x"); S.y"#, Value::MatrixF64(Matrix::from_vec(vec![10.0, 20.0, 30.0], 3, 1)));
+test_interpreter!(interpret_table_sort_by_is_stable, r#"T := |x<f64> y<f64>| 1 1 | 0 2 | 1 3 | 0 4 |; S := table/sort-by(T, "x"); S.y"#, Value::MatrixF64(Matrix::from_vec(vec![2.0, 4.0, 1.0, 3.0], 4, 1)));
+test_interpreter!(interpret_table_sort_by_multiple_columns_desc, r#"T := |x<f64> y<f64>| 1 1 | 2 2 | 1 3 |; S := table/sort-by(T, ["x" "y desc"]); S.y"#, Value::MatrixF64(Matrix::from_vec(vec![3.0, 1.0, 2.0], 3, 1)));
+test_interpreter!(interpret_table_sort_by_operator, r#"T := |x<f64> y<f64>| 3 30 | 1 10 | 2 20 |; S := T ⇅ "x desc"; S.y[1]"#, Value::F64(Ref::new(30.0)));
+test_interpreter!(interpret_table_filter, r#"T := |x<f64> y<f64>| 3 30 | 1 10 | 2 20 |; F := table/filter(T, T.x > 1.5); F.y"#, Value::MatrixF64(Matrix::from_vec(vec![30.0, 20.0], 2, 1)));
+test_interpreter!(interpret_table_filter_operator, r#"T := |x<f64> y<f64>| 3 30 | 1 10 | 2 20 |; F := T ⋔ (T.x < 2.5); F.y"#, Value::MatrixF64(Matrix::from_vec(vec![10.0, 20.0], 2, 1)));
+test_interpreter!(interpret_table_filter_lambda, r#"T := |x<f64> y<f64>| 3 30 | 1 10 | 2 20 |; F := table/filter(T, \(t) => t.x > 1.5); F.y"#, Value::MatrixF64(Matrix::from_vec(vec![30.0, 20.0], 2, 1)));
+test_interpreter!(interpret_greek_letter_identifiers, "σ := 2; τ := 3; y := [σ τ]; y[1] + y[2]", Value::F64(Ref::new(5.0)));
+
+#[test]
+fn interpret_table_filter_reacts_to_table() {
+  let s = r#"~T := |x<f64> y<f64>| 3 30 | 1 10 | 2 20 |; F := table/filter(T, \(t) => t.x > 1.5); T.x = [1; 2; 3]"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  intrp.step(0, 1).unwrap();
+  match symbol_value(&intrp, "F") {
+    Value::Table(table) => {
+      let y = table.borrow().data.get(&hash_str("y")).unwrap().1.to_value();
+      assert_eq!(y, Value::MatrixF64(Matrix::from_vec(vec![10.0, 20.0], 2, 1)));
+    }
+    value => panic!("{:?}", value),
+  }
+}
+test_interpreter!(interpret_table_distinct, r#"T := |x<f64> y<f64>| 1 1 | 2 2 | 1 1 | 1 3 |; D := table/distinct(T); D.y"#, Value::MatrixF64(Matrix::from_vec(vec![1.0, 2.0, 3.0], 3, 1)));
+test_interpreter!(interpret_table_distinct_on_columns, r#"T := |x<f64> y<f64>| 1 1 | 2 2 | 1 3 |; D := table/distinct(T, "x"); D.y"#, Value::MatrixF64(Matrix::from_vec(vec![1.0, 2.0], 2, 1)));
+test_interpreter!(interpret_table_lag, r#"T := |x<f64>| 1 | 2 | 3 |; L := table/lag(T, "x"); L.x-lag[3]"#, Value::Typed(Box::new(Value::F64(Ref::new(2.0))), ValueKind::Option(Box::new(ValueKind::F64))));
+test_interpreter!(interpret_table_lag_leaves_gap_empty, r#"T := |x<f64>| 1 | 2 | 3 |; L := table/lag(T, "x"); L.x-lag[1]"#, Value::Typed(Box::new(Value::Empty), ValueKind::Option(Box::new(ValueKind::F64))));
+test_interpreter!(interpret_table_lead, r#"T := |x<f64>| 1 | 2 | 3 |; L := table/lead(T, "x", 2); L.x-lead[1]"#, Value::Typed(Box::new(Value::F64(Ref::new(3.0))), ValueKind::Option(Box::new(ValueKind::F64))));
+test_interpreter!(interpret_table_rolling_mean, r#"T := |x<f64>| 1 | 3 | 5 |; R := table/rolling-mean(T, "x", 2); R.x-rolling-mean"#, Value::MatrixF64(Matrix::from_vec(vec![1.0, 2.0, 4.0], 3, 1)));
+test_interpreter!(interpret_table_cumsum, r#"T := |x<f64>| 1 | 3 | 5 |; C := table/cumsum(T, "x"); C.x-cumsum"#, Value::MatrixF64(Matrix::from_vec(vec![1.0, 4.0, 9.0], 3, 1)));
+
+#[test]
+fn interpret_table_sort_by_invalid_key() {
+  let s = r#"T := |x<f64>| 1 | 2 |; S := table/sort-by(T, "x sideways")"#;
+  let tree = parser::parse(&s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert!(format!("{:?}", err).contains("InvalidSortKey"));
+}
+
 #[cfg(feature = "u64")]
 test_interpreter!(interpret_matrix_reshape,r#"x:=[1 3; 2 4]; y<[u64]:4,1> := x"#, Value::MatrixU64(Matrix::from_vec(vec![1, 2, 3, 4], 4, 1)));
 
@@ -1360,6 +1739,347 @@ A := [2.0, 1.0, -1.0
       -2.0, 1.0, 2.0]"#, Value::MatrixF64(Matrix::from_vec(vec![2.0, -3.0, -2.0, 1.0, -1.0, 1.0, -1.0, 2.0, 2.0], 3, 3)));
 
 test_interpreter!(interpret_matrix_solve, r#"A := [2.0, 1.0, -1.0;-3.0, -1.0, 2.0;-2.0, 1.0, 2.0];b := [8.0, -11.0, -3.0]'; math/round(A \ b)"#, Value::MatrixF64(Matrix::from_vec(vec![2.0, 3.0, -1.0], 3, 1)));
+test_interpreter!(interpret_matrix_inverse, "math/round(matrix/inverse([4 7; 2 6]) * 10)", Value::MatrixF64(Matrix::from_vec(vec![6.0, -2.0, -7.0, 4.0], 2, 2)));
+test_interpreter!(interpret_matrix_determinant, "math/round(matrix/determinant([4 7; 2 6]))", Value::F64(Ref::new(10.0)));
+test_interpreter!(interpret_matrix_trace, "matrix/trace([1 2; 3 4])", Value::F64(Ref::new(5.0)));
+test_interpreter!(interpret_matrix_rank, "matrix/rank([1 2; 2 4])", Value::U64(Ref::new(1)));
+test_interpreter!(interpret_matrix_norm, "matrix/norm([3 4])", Value::F64(Ref::new(5.0)));
+test_interpreter!(interpret_matrix_pinv, "A := [1 2; 3 4]; math/round(matrix/pinv(A) ** A)", Value::MatrixF64(Matrix::from_vec(vec![1.0, 0.0, 0.0, 1.0], 2, 2)));
+test_interpreter!(interpret_matrix_pinv_rectangular, "A := [1 2 3; 4 5 6]; math/round(A ** matrix/pinv(A))", Value::MatrixF64(Matrix::from_vec(vec![1.0, 0.0, 0.0, 1.0], 2, 2)));
+test_interpreter!(interpret_matrix_lu, "r := matrix/lu([0 1; 2 3]); r.u", Value::MatrixF64(Matrix::from_vec(vec![2.0, 0.0, 3.0, 1.0], 2, 2)));
+test_interpreter!(interpret_matrix_lu_permutation, "A := [0 1; 2 3]; r := matrix/lu(A); r.p ** A - r.l ** r.u", Value::MatrixF64(Matrix::from_vec(vec![0.0, 0.0, 0.0, 0.0], 2, 2)));
+test_interpreter!(interpret_matrix_qr, "r := matrix/qr([1 2; 3 4; 5 6]); math/round(r.q ** r.r)", Value::MatrixF64(Matrix::from_vec(vec![1.0, 3.0, 5.0, 2.0, 4.0, 6.0], 3, 2)));
+test_interpreter!(interpret_matrix_cholesky, "r := matrix/cholesky([4 2; 2 5]); r.l", Value::MatrixF64(Matrix::from_vec(vec![2.0, 1.0, 0.0, 2.0], 2, 2)));
+test_interpreter!(interpret_matrix_svd, "r := matrix/svd([3 0; 0 4]); r.s", Value::MatrixF64(Matrix::DMatrix(Ref::new(na::DMatrix::from_vec(2, 1, vec![4.0, 3.0])))));
+test_interpreter!(interpret_matrix_eig, "r := matrix/eig([2 1; 1 2]); math/round(r.values)", Value::MatrixF64(Matrix::DMatrix(Ref::new(na::DMatrix::from_vec(2, 1, vec![1.0, 3.0])))));
+
+test_interpreter_error!(interpret_matrix_inverse_singular, "matrix/inverse([1 2; 2 4])", "MatrixSingular");
+test_interpreter_error!(interpret_matrix_inverse_not_square, "matrix/inverse([1 2 3; 4 5 6])", "MatrixNotSquare");
+test_interpreter_error!(interpret_matrix_determinant_not_square, "matrix/determinant([1 2 3; 4 5 6])", "MatrixNotSquare");
+test_interpreter_error!(interpret_matrix_cholesky_not_positive_definite, "matrix/cholesky([1 2; 2 1])", "MatrixNotPositiveDefinite");
+test_interpreter_error!(interpret_matrix_eig_not_symmetric, "matrix/eig([1 2; 3 1])", "MatrixNotSymmetric");
+
+test_interpreter!(interpret_matrix_identity, "matrix/identity(2)", Value::MatrixF64(Matrix::from_vec(vec![1.0, 0.0, 0.0, 1.0], 2, 2)));
+test_interpreter!(interpret_matrix_identity_rect, "matrix/identity(2, 3)", Value::MatrixF64(Matrix::from_vec(vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0], 2, 3)));
+test_interpreter!(interpret_matrix_zeros, "matrix/zeros(2, 1)", Value::MatrixF64(Matrix::from_vec(vec![0.0, 0.0], 2, 1)));
+test_interpreter!(interpret_matrix_ones, "matrix/ones(1, 3)", Value::MatrixF64(Matrix::from_vec(vec![1.0, 1.0, 1.0], 1, 3)));
+test_interpreter!(interpret_matrix_linspace, "matrix/linspace(0, 1, 5)", Value::MatrixF64(Matrix::from_vec(vec![0.0, 0.25, 0.5, 0.75, 1.0], 1, 5)));
+test_interpreter!(interpret_matrix_linspace_ref, "a := 2; matrix/linspace(a, 4, 3)", Value::MatrixF64(Matrix::from_vec(vec![2.0, 3.0, 4.0], 1, 3)));
+test_interpreter!(interpret_matrix_diag_vector, "matrix/diag([1 2])", Value::MatrixF64(Matrix::from_vec(vec![1.0, 0.0, 0.0, 2.0], 2, 2)));
+test_interpreter!(interpret_matrix_diag_matrix, "matrix/diag([1 2 3; 4 5 6])", Value::MatrixF64(Matrix::from_vec(vec![1.0, 5.0], 2, 1)));
+test_interpreter!(interpret_matrix_meshgrid, "g := matrix/meshgrid([1 2 3], [4 5]); g.y", Value::MatrixF64(Matrix::from_vec(vec![4.0, 5.0, 4.0, 5.0, 4.0, 5.0], 2, 3)));
+test_interpreter!(interpret_matrix_repeat, "matrix/repeat([1 2], 2, 2)", Value::MatrixF64(Matrix::from_vec(vec![1.0, 1.0, 2.0, 2.0, 1.0, 1.0, 2.0, 2.0], 2, 4)));
+test_interpreter!(interpret_matrix_repeat_string, r#"matrix/repeat(["a"; "b"], 1, 2)"#, Value::MatrixString(Matrix::from_vec(vec!["a".to_string(), "b".to_string(), "a".to_string(), "b".to_string()], 2, 2)));
+test_interpreter!(interpret_matrix_reshape_fxn, "matrix/reshape([1 2 3; 4 5 6], 3, 2)", Value::MatrixF64(Matrix::from_vec(vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0], 3, 2)));
+test_interpreter!(interpret_matrix_flip, "matrix/flip([1 2; 3 4])", Value::MatrixF64(Matrix::from_vec(vec![3.0, 1.0, 4.0, 2.0], 2, 2)));
+test_interpreter!(interpret_matrix_flip_row, "matrix/flip([1 2 3])", Value::MatrixF64(Matrix::from_vec(vec![3.0, 2.0, 1.0], 1, 3)));
+test_interpreter!(interpret_matrix_flip_dim, "matrix/flip([1 2; 3 4], 2)", Value::MatrixF64(Matrix::from_vec(vec![2.0, 4.0, 1.0, 3.0], 2, 2)));
+test_interpreter!(interpret_matrix_rotate, "matrix/rotate([1 2 3; 4 5 6])", Value::MatrixF64(Matrix::from_vec(vec![3.0, 2.0, 1.0, 6.0, 5.0, 4.0], 3, 2)));
+test_interpreter!(interpret_matrix_rotate_back, "matrix/rotate([1 2 3; 4 5 6], -1)", Value::MatrixF64(Matrix::from_vec(vec![4.0, 5.0, 6.0, 1.0, 2.0, 3.0], 3, 2)));
+test_interpreter!(interpret_matrix_rotate_half, "matrix/rotate([1 2; 3 4], 2)", Value::MatrixF64(Matrix::from_vec(vec![4.0, 2.0, 3.0, 1.0], 2, 2)));
+test_interpreter!(interpret_matrix_concat_rows, "matrix/concat(1, [1 2], [3 4; 5 6])", Value::MatrixF64(Matrix::from_vec(vec![1.0, 3.0, 5.0, 2.0, 4.0, 6.0], 3, 2)));
+test_interpreter!(interpret_matrix_concat_cols, "matrix/concat(2, [1; 2], [3 4; 5 6])", Value::MatrixF64(Matrix::from_vec(vec![1.0, 2.0, 3.0, 5.0, 4.0, 6.0], 2, 3)));
+
+test_interpreter_error!(interpret_matrix_zeros_empty_shape, "matrix/zeros(0)", "MatrixArgument");
+test_interpreter_error!(interpret_matrix_identity_fractional_size, "matrix/identity(1.5)", "MatrixArgument");
+test_interpreter_error!(interpret_matrix_flip_bad_dimension, "matrix/flip([1 2], 3)", "MatrixArgument");
+test_interpreter_error!(interpret_matrix_reshape_size_mismatch, "matrix/reshape([1 2 3], 2, 2)", "MatrixReshape");
+test_interpreter_error!(interpret_matrix_concat_rows_shape_mismatch, "matrix/concat(1, [1 2], [1 2 3])", "MatrixConcatShape");
+test_interpreter_error!(interpret_matrix_concat_cols_shape_mismatch, "matrix/concat(2, [1; 2], [1 2 3])", "MatrixConcatShape");
+#[test]
+fn interpret_matrix_constant_size() {
+  // Sizes that fold to constants take their type from the size, even through
+  // arithmetic; a column is never a matrixd.
+  assert!(!matches!(interpret_value("matrix/zeros(1 + 1, 1)").unwrap(), Value::MatrixF64(Matrix::DMatrix(_))));
+  assert!(!matches!(interpret_value("a := 2; matrix/linspace(a, 4, 3)").unwrap(), Value::MatrixF64(Matrix::DMatrix(_))));
+  // A size read from a variable may change, so it gives a matrixd.
+  assert!(matches!(interpret_value("n := 2; matrix/zeros(n, 1)").unwrap(), Value::MatrixF64(Matrix::DMatrix(_))));
+  assert!(matches!(interpret_value("n := 2; matrix/reshape([1 2 3 4], n, 4 / n)").unwrap(), Value::MatrixF64(Matrix::DMatrix(_))));
+}
+#[test]
+fn interpret_matrix_reactive_size() {
+  let tree = parser::parse("~n := 2; z := matrix/ones(n, 1); r := matrix/rotate([1 2 3], n)").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  assert_eq!(symbol_value(&intrp, "z").shape(), vec![2, 1]);
+  intrp.interpret(&parser::parse("n = 3").unwrap()).unwrap();
+  intrp.step(0, 1).unwrap();
+  let z = symbol_value(&intrp, "z");
+  assert_eq!(z.shape(), vec![3, 1]);
+  assert_eq!(interpret_f64s_of(&z), vec![1.0, 1.0, 1.0]);
+  // Three quarter turns stand the row up as a column.
+  let r = symbol_value(&intrp, "r");
+  assert_eq!(r.shape(), vec![3, 1]);
+  assert_eq!(interpret_f64s_of(&r), vec![1.0, 2.0, 3.0]);
+}
+
+fn interpret_f64s(src: &str) -> Vec<f64> {
+  interpret_f64s_of(&interpret_value(src).unwrap())
+}
+fn interpret_f64s_of(value: &Value) -> Vec<f64> {
+  match value {
+    Value::MatrixF64(m) => m.as_vec(),
+    Value::F64(x) => vec![*x.borrow()],
+    value => panic!("{:?}", value),
+  }
+}
+#[test]
+fn interpret_random_seeded() {
+  let a = interpret_f64s("random/uniform(42, 0, 1, 3, 4)");
+  assert_eq!(a, interpret_f64s("random/uniform(42, 0, 1, 3, 4)"));
+  assert_ne!(a, interpret_f64s("random/uniform(43, 0, 1, 3, 4)"));
+  assert_eq!(a.len(), 12);
+  assert!(a.iter().all(|x| *x >= 0.0 && *x < 1.0));
+}
+#[test]
+fn interpret_random_draws() {
+  let x = interpret_f64s("random/uniform(1, -2, 2)");
+  assert!(x[0] >= -2.0 && x[0] < 2.0);
+  let x = interpret_f64s("random/integer(7, 1, 6, 1, 200)");
+  assert!(x.iter().all(|x| x.fract() == 0.0 && *x >= 1.0 && *x <= 6.0));
+  assert!((1..=6).all(|n| x.contains(&(n as f64))));
+  let x = interpret_f64s("random/normal(3, 10, 2, 1, 2000)");
+  let mean = x.iter().sum::<f64>() / x.len() as f64;
+  assert!((mean - 10.0).abs() < 0.2, "{}", mean);
+}
+#[test]
+fn interpret_random_kinds() {
+  let value = interpret_value("random/integer(5, 1<u8>, 6<u8>, 2, 3)").unwrap();
+  assert_eq!(value.kind().to_string(), "[u8]:2,3");
+  assert_eq!(interpret_value("random/integer(5, -3<i32>, 3<i32>)").unwrap().kind(), ValueKind::I32);
+  assert_eq!(interpret_value("random/uniform(5, 0<f32>, 1<f32>)").unwrap().kind(), ValueKind::F32);
+}
+#[test]
+fn interpret_random_choice_shuffle() {
+  let x = interpret_f64s("random/choice(11, [2 4 8], 4, 5)");
+  assert_eq!(x.len(), 20);
+  assert!(x.iter().all(|x| [2.0, 4.0, 8.0].contains(x)));
+  let mut x = interpret_f64s("random/shuffle(11, [1 2 3 4 5 6 7 8])");
+  assert_ne!(x, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+  x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  assert_eq!(x, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+  match interpret_value(r#"random/choice(2, ["a" "b"])"#).unwrap() {
+    Value::String(x) => assert!(["a", "b"].contains(&x.borrow().as_str())),
+    value => panic!("{:?}", value),
+  }
+}
+#[test]
+fn interpret_random_nondeterministic() {
+  let tree = parser::parse("x := random/uniform(42, 0, 1, 1, 4)").unwrap();
+  let mut intrp = Interpreter::new(0);
+  let first = intrp.interpret(&tree).unwrap();
+  let plan = intrp.plan();
+  assert!(plan.0.borrow().iter().any(|fxn| !fxn.deterministic()));
+  let first = format!("{:?}", first);
+  intrp.step(0, 1).unwrap();
+  assert_ne!(first, format!("{:?}", symbol_value(&intrp, "x")));
+  let plan = interpret_plan("x := [1 2 3] + 1");
+  assert!(plan.0.borrow().iter().all(|fxn| fxn.deterministic()));
+}
+#[test]
+fn interpret_random_integer_wide() {
+  let value = interpret_value("lo := 9007199254740992<i64> + 1<i64>; random/integer(9, lo, lo + 1<i64>, 1, 50)").unwrap();
+  match value {
+    Value::MatrixI64(m) => {
+      let x = m.as_vec();
+      assert!(x.iter().all(|x| *x == 9007199254740993 || *x == 9007199254740994), "{:?}", x);
+      assert!(x.contains(&9007199254740993) && x.contains(&9007199254740994), "{:?}", x);
+    },
+    value => panic!("{:?}", value),
+  }
+}
+fn interpret_plan(src: &str) -> Plan {
+  let tree = parser::parse(src).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  intrp.plan()
+}
+test_interpreter_error!(interpret_random_fractional_seed, "random/uniform(1.5, 0, 1)", "RandomArgument");
+test_interpreter_error!(interpret_random_empty_shape, "random/uniform(1, 0, 1, 0, 2)", "RandomArgument");
+test_interpreter_error!(interpret_random_choice_fractional_shape, "random/choice(1, [1 2], 2.5, 1)", "RandomArgument");
+
+test_interpreter!(interpret_time_format, r#"time/format(1709209800.5)"#, Value::String(Ref::new("2024-02-29T12:30:00.500Z".to_string())));
+test_interpreter!(interpret_time_format_pattern, r#"time/format([0 86400], "%a %d %b %Y")"#, Value::MatrixString(Matrix::from_vec(vec!["Thu 01 Jan 1970".to_string(), "Fri 02 Jan 1970".to_string()], 1, 2)));
+test_interpreter!(interpret_time_parse, r#"[time/parse("2024-02-29T12:30:00Z") time/parse("2024-02-29T14:30:00+02:00") time/parse("2024-02-29")]"#, Value::MatrixF64(Matrix::from_vec(vec![1709209800.0, 1709209800.0, 1709164800.0], 1, 3)));
+test_interpreter!(interpret_time_parse_pattern, r#"time/parse(["29/02/2024 12:30"; "01/03/2024 00:00"], "%d/%m/%Y %H:%M")"#, Value::MatrixF64(Matrix::from_vec(vec![1709209800.0, 1709251200.0], 2, 1)));
+test_interpreter!(interpret_time_round_trip, r#"t := time/parse("2001-09-09T01:46:40Z"); time/format(t + 90)"#, Value::String(Ref::new("2001-09-09T01:48:10Z".to_string())));
+test_interpreter!(interpret_time_format_duration, "time/format-duration([90061.5 0 -0.25 5400])", Value::MatrixString(Matrix::from_vec(vec!["1d 1h 1m 1.5s".to_string(), "0s".to_string(), "-0.25s".to_string(), "1h 30m".to_string()], 1, 4)));
+test_interpreter!(interpret_time_parse_duration, r#"time/parse-duration(["1d 1h 1m 1.5s" "-1h30m" "250ms" "90"])"#, Value::MatrixF64(Matrix::from_vec(vec![90061.5, -5400.0, 0.25, 90.0], 1, 4)));
+#[test]
+fn interpret_time_clock() {
+  let before = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs_f64();
+  let now = interpret_f64s("time/now()")[0];
+  assert!(now >= before && now - before < 5.0, "{} {}", before, now);
+  let elapsed = interpret_f64s("time/elapsed(time/parse(\"2020-01-01\"))")[0];
+  assert!(elapsed > 0.0);
+  let tree = parser::parse("x := time/now()").unwrap();
+  let mut intrp = Interpreter::new(0);
+  let first = intrp.interpret(&tree).unwrap().as_f64().map(|x| *x.borrow()).unwrap();
+  assert!(intrp.plan().0.borrow().iter().any(|fxn| !fxn.deterministic()));
+  std::thread::sleep(std::time::Duration::from_millis(10));
+  let later = intrp.step(0, 1).unwrap().as_f64().map(|x| *x.borrow()).unwrap();
+  assert!(later > first, "{} {}", first, later);
+}
+#[test]
+fn interpret_time_timer() {
+  let tree = parser::parse("t := time/timer(0.05); y := t * 2<u64>").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  assert_eq!(symbol_value(&intrp, "y"), Value::U64(Ref::new(0)));
+  let wait = intrp.next_tick().unwrap();
+  assert!(wait <= std::time::Duration::from_millis(50));
+  std::thread::sleep(std::time::Duration::from_millis(120));
+  intrp.step(0, 1).unwrap();
+  match symbol_value(&intrp, "y") {
+    Value::U64(y) => assert!(*y.borrow() >= 4, "{}", y.borrow()),
+    value => panic!("{:?}", value),
+  }
+  assert!(interpret_plan("x := [1 2 3] + 1").0.borrow().iter().all(|fxn| fxn.next_tick().is_none()));
+}
+test_interpreter_error!(interpret_time_parse_unrecognized, r#"time/parse("yesterday")"#, "TimeParse");
+test_interpreter_error!(interpret_time_parse_pattern_invalid_date, r#"time/parse("2024-13-01", "%Y-%m-%d")"#, "TimeParse");
+test_interpreter_error!(interpret_time_parse_duration_unknown_unit, r#"time/parse-duration("5 parsecs")"#, "TimeParse");
+test_interpreter_error!(interpret_time_format_invalid_pattern, r#"time/format(0, "%Q")"#, "TimeFormat");
+test_interpreter_error!(interpret_time_format_string_argument, r#"time/format("today")"#, "TimeArgument");
+test_interpreter_error!(interpret_time_timer_zero_period, "time/timer(0)", "TimeArgument");
+
+test_interpreter!(interpret_signal_convolve, "signal/convolve([1 2 3], [0 1 0.5])", Value::MatrixF64(Matrix::from_vec(vec![0.0, 1.0, 2.5, 4.0, 1.5], 1, 5)));
+test_interpreter!(interpret_signal_correlate, "signal/correlate([1 2 3], [0 1 0.5])", Value::MatrixF64(Matrix::from_vec(vec![0.5, 2.0, 3.5, 3.0, 0.0], 1, 5)));
+test_interpreter!(interpret_signal_moving_average, "signal/moving-average([1 2 3 4 5], 2)", Value::MatrixF64(Matrix::from_vec(vec![1.0, 1.5, 2.5, 3.5, 4.5], 1, 5)));
+test_interpreter!(interpret_signal_exponential_smoothing, "signal/exponential-smoothing([2 4 4], 0.5)", Value::MatrixF64(Matrix::from_vec(vec![2.0, 3.0, 3.5], 1, 3)));
+test_interpreter!(interpret_signal_fir, "signal/fir([1 2 3 4], [0.5 0.5])", Value::MatrixF64(Matrix::from_vec(vec![0.5, 1.5, 2.5, 3.5], 1, 4)));
+test_interpreter!(interpret_signal_fir_column, "signal/fir([1; 2; 3], [1 1])", Value::MatrixF64(Matrix::from_vec(vec![1.0, 3.0, 5.0], 3, 1)));
+test_interpreter!(interpret_signal_fft_impulse, "signal/fft([1 0 0 0])", Value::MatrixC64(Matrix::from_vec(vec![C64::new(1.0, 0.0); 4], 1, 4)));
+fn interpret_c64s(src: &str) -> Vec<C64> {
+  match interpret_value(src).unwrap() {
+    Value::MatrixC64(m) => m.as_vec(),
+    value => panic!("{:?}", value),
+  }
+}
+#[test]
+fn interpret_signal_fft() {
+  let spectrum = interpret_c64s("signal/fft([1 2 3 4])");
+  let expected = [(10.0, 0.0), (-2.0, 2.0), (-2.0, 0.0), (-2.0, -2.0)];
+  assert_eq!(spectrum.len(), 4);
+  for (x, (re, im)) in spectrum.iter().zip(expected) {
+    assert!((x.0.re - re).abs() < 1e-12 && (x.0.im - im).abs() < 1e-12, "{:?}", spectrum);
+  }
+  let signal = interpret_c64s("x := [1 -2 3.5 0 7]; signal/ifft(signal/fft(x))");
+  for (x, re) in signal.iter().zip([1.0, -2.0, 3.5, 0.0, 7.0]) {
+    assert!((x.0.re - re).abs() < 1e-12 && x.0.im.abs() < 1e-12, "{:?}", signal);
+  }
+}
+#[test]
+fn interpret_signal_biquad_state() {
+  let tree = parser::parse("x := 1; y := signal/biquad(x, [1 0 0], [1 -0.5 0])").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  assert!(intrp.plan().0.borrow().iter().any(|fxn| !fxn.deterministic()));
+  let output = |intrp: &Interpreter| symbol_value(intrp, "y").as_f64().map(|y| *y.borrow()).unwrap();
+  let mut outputs = vec![output(&intrp)];
+  for _ in 0..2 {
+    intrp.step(0, 1).unwrap();
+    outputs.push(output(&intrp));
+  }
+  assert_eq!(outputs, vec![1.0, 1.5, 1.75]);
+  assert_eq!(interpret_f64s("signal/biquad([1 0 0 0], [0.5 0.5 0], [2 0 0])"), vec![0.25, 0.25, 0.0, 0.0]);
+}
+test_interpreter_error!(interpret_signal_moving_average_zero_window, "signal/moving-average([1 2], 0)", "SignalArgument");
+test_interpreter_error!(interpret_signal_exponential_smoothing_alpha_out_of_range, "signal/exponential-smoothing([1 2], 1.5)", "SignalArgument");
+test_interpreter_error!(interpret_signal_convolve_matrix, "signal/convolve([1 2; 3 4], [1])", "SignalArgument");
+test_interpreter_error!(interpret_signal_fft_string, r#"signal/fft("abc")"#, "SignalArgument");
+test_interpreter_error!(interpret_signal_biquad_short_coefficients, "signal/biquad(1, [1 0], [1 0 0])", "SignalArgument");
+test_interpreter_error!(interpret_signal_biquad_zero_leading_denominator, "signal/biquad(1, [1 0 0], [0 1 1])", "SignalArgument");
+
+fn assert_close(actual: Vec<f64>, expected: &[f64], tol: f64) {
+  assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+  for (x, y) in actual.iter().zip(expected) {
+    assert!((x - y).abs() < tol, "{:?} != {:?}", actual, expected);
+  }
+}
+#[test]
+fn interpret_calculus_integrate() {
+  assert_close(interpret_f64s(r#"calculus/integrate(\(x) => x ^ 2, 0, 3)"#), &[9.0], 1e-12);
+  assert_close(interpret_f64s(r#"calculus/integrate(\(x) => x ^ 2, 0, 3, "trapezoid", 10)"#), &[9.045], 1e-12);
+  assert_close(interpret_f64s(r#"calculus/integrate(\(x) => x ^ 2, 3, 0, "simpson", 5)"#), &[-9.0], 1e-12);
+  assert_close(interpret_f64s("sq(x<f64>) = z<f64> :=\n  z := x * x.\ncalculus/integrate(\"sq\", 0, 3)"), &[9.0], 1e-12);
+  assert_close(interpret_f64s(r#"f := \(x) => 2 * x; calculus/integrate("f", 0, 1)"#), &[1.0], 1e-12);
+}
+#[test]
+fn interpret_calculus_find_root() {
+  assert_close(interpret_f64s(r#"calculus/find-root(\(x) => x ^ 2 - 2, 0, 2)"#), &[2f64.sqrt()], 1e-12);
+  assert_close(interpret_f64s(r#"calculus/find-root(\(x) => math/cos(x) - x, 0, 1, 0.000001)"#), &[0.7390851332151607], 1e-6);
+}
+#[test]
+fn interpret_calculus_minimize() {
+  assert_close(interpret_f64s(r#"calculus/minimize(\(x) => (x - 3) ^ 2, 0)"#), &[3.0], 1e-6);
+  assert_close(interpret_f64s(r#"calculus/minimize(\(v) => (v[1] - 1) ^ 2 + (v[2] - 2) ^ 2, [0 0])"#), &[1.0, 2.0], 1e-6);
+}
+#[test]
+fn interpret_calculus_ode45() {
+  let e = std::f64::consts::E;
+  assert_close(interpret_f64s(r#"calculus/ode45(\(t, y) => -y, [0 1 2], 1)"#), &[1.0, 1.0 / e, 1.0 / (e * e)], 1e-5);
+  // y'' = -y as a system; the columns are y and y' at t = 0 and t = π / 2.
+  let src = r#"calculus/ode45(\(t, y) => [y[2]; -y[1]], [0 1.5707963267948966], [0; 1], 0.000000001)"#;
+  assert_close(interpret_f64s(src), &[0.0, 1.0, 1.0, 0.0], 1e-7);
+}
+#[test]
+fn interpret_calculus_rk4_step() {
+  assert_close(interpret_f64s(r#"calculus/rk4-step(\(t, y) => [y[2] -y[1]], 0, [1 0], 0.1)"#), &[0.9950041666666667, -0.09983333333333333], 1e-15);
+  let tree = parser::parse(r#"~y := 1; y = calculus/rk4-step(\(t, y) => -y, 0, y, 0.1)"#).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  let output = |intrp: &Interpreter| symbol_value(intrp, "y").as_f64().map(|y| *y.borrow()).unwrap();
+  let mut outputs = vec![output(&intrp)];
+  for _ in 0..2 {
+    intrp.step(0, 1).unwrap();
+    outputs.push(output(&intrp));
+  }
+  assert_close(outputs, &[(-0.1f64).exp(), (-0.2f64).exp(), (-0.3f64).exp()], 1e-6);
+}
+test_interpreter_error!(interpret_calculus_integrate_non_function, "calculus/integrate(3, 0, 1)", "CalculusArgument");
+test_interpreter_error!(interpret_calculus_integrate_unknown_method, r#"calculus/integrate(\(x) => x, 0, 1, "midpoint")"#, "CalculusArgument");
+test_interpreter_error!(interpret_calculus_integrate_vector_result, r#"calculus/integrate(\(x) => [x x], 0, 1)"#, "CalculusResult");
+test_interpreter_error!(interpret_calculus_integrate_missing_function, r#"calculus/integrate("missing", 0, 1)"#, "MissingFunction");
+test_interpreter_error!(interpret_calculus_find_root_no_sign_change, r#"calculus/find-root(\(x) => x ^ 2 + 1, 0, 1)"#, "CalculusConvergence");
+test_interpreter_error!(interpret_calculus_ode45_decreasing_span, r#"calculus/ode45(\(t, y) => y, [0 2 1], 1)"#, "CalculusArgument");
+test_interpreter_error!(interpret_calculus_rk4_step_shape_mismatch, r#"calculus/rk4-step(\(t, y) => [y y], 0, 1, 0.1)"#, "CalculusResult");
+test_interpreter!(interpret_bits_and, "bits/and(12<u8>, 10<u8>)", Value::U8(Ref::new(8)));
+test_interpreter!(interpret_bits_or, "bits/or(12<u8>, 10<u8>)", Value::U8(Ref::new(14)));
+test_interpreter!(interpret_bits_xor, "bits/xor(12<u8>, 10<u8>)", Value::U8(Ref::new(6)));
+test_interpreter!(interpret_bits_not_u8, "bits/not(12<u8>)", Value::U8(Ref::new(243)));
+test_interpreter!(interpret_bits_not_i8, "bits/not(12<i8>)", Value::I8(Ref::new(-13)));
+test_interpreter!(interpret_bits_and_i64, "bits/and(-1<i64>, 0xff)", Value::I64(Ref::new(255)));
+test_interpreter!(interpret_bits_shift_left, "bits/shift-left(3<u16>, 4<u16>)", Value::U16(Ref::new(48)));
+test_interpreter!(interpret_bits_shift_left_out, "bits/shift-left(1<u8>, 8<u8>)", Value::U8(Ref::new(0)));
+test_interpreter!(interpret_bits_shift_right_signed, "bits/shift-right(-8<i8>, 2<i8>)", Value::I8(Ref::new(-2)));
+test_interpreter!(interpret_bits_shift_right_signed_out, "bits/shift-right(-8<i8>, 100<i8>)", Value::I8(Ref::new(-1)));
+test_interpreter!(interpret_bits_shift_negative, "bits/shift-left(5<i32>, -1<i32>)", Value::I32(Ref::new(0)));
+test_interpreter!(interpret_bits_rotate_left, "bits/rotate-left(129<u8>, 1<u8>)", Value::U8(Ref::new(3)));
+test_interpreter!(interpret_bits_rotate_right, "bits/rotate-right(1<u8>, 9<u8>)", Value::U8(Ref::new(128)));
+test_interpreter!(interpret_bits_rotate_negative, "bits/rotate-right(1<i8>, -1<i8>)", Value::I8(Ref::new(2)));
+test_interpreter!(interpret_bits_popcount, "bits/popcount(-1<i16>)", Value::I16(Ref::new(16)));
+test_interpreter!(interpret_bits_leading_zeros, "bits/leading-zeros(1<u32>)", Value::U32(Ref::new(31)));
+test_interpreter!(interpret_bits_trailing_zeros, "bits/trailing-zeros(0<u64>)", Value::U64(Ref::new(64)));
+test_interpreter!(interpret_bits_get, "bits/get(5<u8>, 2<u8>)", Value::Bool(Ref::new(true)));
+test_interpreter!(interpret_bits_get_out_of_range, "bits/get(-1<i8>, 8<i8>)", Value::Bool(Ref::new(false)));
+test_interpreter!(interpret_bits_set, "bits/set(5<u128>, 127<u128>)", Value::U128(Ref::new(5 | 1 << 127)));
+test_interpreter!(interpret_bits_clear, "bits/clear(5<u8>, 0<u8>)", Value::U8(Ref::new(4)));
+test_interpreter!(interpret_bits_and_matrix_scalar, "bits/and([1<u8> 2<u8> 3<u8>], 1<u8>)", Value::MatrixU8(Matrix::from_vec(vec![1, 0, 1], 1, 3)));
+test_interpreter!(interpret_bits_shift_scalar_matrix, "bits/shift-left(1<u8>, [0<u8> 1<u8> 2<u8>])", Value::MatrixU8(Matrix::from_vec(vec![1, 2, 4], 1, 3)));
+test_interpreter!(interpret_bits_xor_matrix, "bits/xor([1<i8> 2<i8> 3<i8>], [1<i8> -1<i8> 3<i8>])", Value::MatrixI8(Matrix::from_vec(vec![0, -3, 0], 1, 3)));
+test_interpreter!(interpret_bits_not_matrix, "bits/not([0<u8> 255<u8>])", Value::MatrixU8(Matrix::from_vec(vec![255, 0], 1, 2)));
+test_interpreter!(interpret_bits_popcount_matrix, "bits/popcount([1<u8>; 3<u8>; 7<u8>])", Value::MatrixU8(Matrix::from_vec(vec![1, 2, 3], 3, 1)));
+test_interpreter!(interpret_bits_get_matrix, "bits/get([5<u8> 6<u8> 7<u8>], 0<u8>)", Value::MatrixBool(Matrix::from_vec(vec![true, false, true], 1, 3)));
+test_interpreter!(interpret_bits_operator_and, "12<u8> &&& 10<u8>", Value::U8(Ref::new(8)));
+test_interpreter!(interpret_bits_operator_or, "12<u8> ||| 10<u8>", Value::U8(Ref::new(14)));
+test_interpreter!(interpret_bits_operator_xor, "12<u8> ^^^ 10<u8>", Value::U8(Ref::new(6)));
+test_interpreter!(interpret_bits_operator_shift_left, "1<u32> << 4<u32>", Value::U32(Ref::new(16)));
+test_interpreter!(interpret_bits_operator_shift_right, "-16<i16> >> 2<i16>", Value::I16(Ref::new(-4)));
+test_interpreter!(interpret_bits_operator_precedence, "1<u8> << 1<u8> + 2<u8> ||| 1<u8>", Value::U8(Ref::new(9)));
+test_interpreter!(interpret_bits_operator_compare, "1<u8> << 2<u8> > 3<u8>", Value::Bool(Ref::new(true)));
+test_interpreter!(interpret_bits_operator_matrix, "[1<u8> 2<u8> 4<u8>] >> 1<u8>", Value::MatrixU8(Matrix::from_vec(vec![0, 1, 2], 1, 3)));
+test_interpreter!(interpret_logic_xor_operator, "true ^^ false", Value::Bool(Ref::new(true)));
 
 test_interpreter!(interpret_set_union, r#"A := {1, 2, 3}; B := {2, 3, 4}; U := A ∪ B"#, Value::Set(Ref::new(MechSet::from_vec(vec![Value::F64(Ref::new(1.0)), Value::F64(Ref::new(2.0)), Value::F64(Ref::new(3.0)), Value::F64(Ref::new(4.0))]))));
 test_interpreter!(interpret_set_intersection, r#"A := {1, 2, 3}; B := {2, 3, 4}; U := A ∩ B"#, Value::Set(Ref::new(MechSet::from_vec(vec![Value::F64(Ref::new(2.0)), Value::F64(Ref::new(3.0))]))));
@@ -1393,8 +2113,491 @@ test_interpreter!(interpret_set_comprehension_fof, r#"pairs:= {(1,2), (1,3), (2,
 
 test_interpreter!(interpret_matrix_comprehension, r#"[ x * x | x <- [1 2 3 4], y := 2, (x % 2) == 0 ]"#, Value::MatrixF64(Matrix::from_vec(vec![4.0, 16.0], 1, 2)));
 test_interpreter!(interpret_matrix_comprehension_variable, r#"qq := [1 2 3 4]; [ x * x | x <- qq, y := 2, (x % 2) != 0 ]"#, Value::MatrixF64(Matrix::from_vec(vec![1.0, 9.0], 1, 2)));
+test_interpreter!(interpret_table_comprehension, r#"|{x: a, y: a * 2} | a <- [1 2 3], a > 1|"#, Value::Table(Ref::new(MechTable::from_records(vec![MechRecord::new(vec![("x",Value::F64(Ref::new(2.0))),("y",Value::F64(Ref::new(4.0)))]),MechRecord::new(vec![("x",Value::F64(Ref::new(3.0))),("y",Value::F64(Ref::new(6.0)))]),]).expect("Failed to create MechTable"))));
+test_interpreter!(interpret_table_comprehension_over_table_rows, r#"t := |a<f64> b<f64>| 1 2 | 3 4 |; |{s: r.a + r.b, d: r.b - r.a} | r <- t|"#, Value::Table(Ref::new(MechTable::from_records(vec![MechRecord::new(vec![("s",Value::F64(Ref::new(3.0))),("d",Value::F64(Ref::new(1.0)))]),MechRecord::new(vec![("s",Value::F64(Ref::new(7.0))),("d",Value::F64(Ref::new(1.0)))]),]).expect("Failed to create MechTable"))));
+#[cfg(feature = "u8")]
+test_interpreter!(interpret_table_comprehension_annotated_head, r#"|{x<u8>: a} | a <- [1 2]|"#, Value::Table(Ref::new(MechTable::from_records(vec![MechRecord::new(vec![("x",Value::U8(Ref::new(1)))]),MechRecord::new(vec![("x",Value::U8(Ref::new(2)))]),]).expect("Failed to create MechTable"))));
+#[test]
+fn interpret_table_comprehension_empty_uses_head_kinds() {
+  let tree = parser::parse(r#"|{x<f64>: a, y<bool>: a > 1} | a <- [1 2], a > 5|"#).unwrap();
+  let mut intrp = Interpreter::new(0);
+  match intrp.interpret(&tree).unwrap() {
+    Value::Table(table) => {
+      let table = table.borrow();
+      assert_eq!(table.rows, 0);
+      assert_eq!(table.kind(), ValueKind::Table(vec![("x".to_string(), ValueKind::F64), ("y".to_string(), ValueKind::Bool)], 0));
+    }
+    x => panic!("Expected a table, found {:?}", x),
+  }
+}
+#[test]
+fn interpret_table_comprehension_empty_requires_annotations() {
+  let tree = parser::parse(r#"|{x: a} | a <- [1 2], a > 5|"#).unwrap();
+  let mut intrp = Interpreter::new(0);
+  assert!(intrp.interpret(&tree).is_err());
+}
+#[test]
+fn interpret_table_comprehension_row_kind_mismatch() {
+  let tree = parser::parse(r#"|{x: 1..=a} | a <- [2 3]|"#).unwrap();
+  let mut intrp = Interpreter::new(0);
+  assert!(intrp.interpret(&tree).is_err());
+}
+test_interpreter!(interpret_record_comprehension, r#"{x: a, y: a * 2 | a <- [1 2 3], a != 2}"#, Value::Record(Ref::new(MechRecord::new(vec![("x",Value::MatrixF64(Matrix::from_vec(vec![1.0, 3.0], 1, 2))),("y",Value::MatrixF64(Matrix::from_vec(vec![2.0, 6.0], 1, 2)))]))));
+test_interpreter!(interpret_record_comprehension_over_table_rows, r#"t := |a<f64> b<f64>| 1 2 | 3 4 |; c := {total: r.a + r.b | r <- t}; c.total"#, Value::MatrixF64(Matrix::from_vec(vec![3.0, 7.0], 1, 2)));
+
+fn fixture_path(name: &str) -> String {
+  let path = std::env::temp_dir().join(format!("mech-{}-{}", std::process::id(), name));
+  path.to_string_lossy().replace('\\', "/")
+}
+fn write_csv_fixture(name: &str, contents: &str) -> String {
+  let path = fixture_path(name);
+  std::fs::write(&path, contents).unwrap();
+  path
+}
+fn interpret_csv_table(src: &str) -> MResult<MechTable> {
+  let tree = parser::parse(src).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.grant_capability(Capability::FileSystem);
+  match intrp.interpret(&tree)? {
+    Value::Table(table) => Ok(table.borrow().clone()),
+    x => panic!("Expected a table, found {:?}", x),
+  }
+}
+#[test]
+fn interpret_io_read_csv_infers_column_kinds() {
+  let path = write_csv_fixture("infer.csv", "name,age,score,active,big,note\nalice,31,1.5,true,40000,hi\nbob,-4,2,false,1,\n");
+  let table = interpret_csv_table(&format!(r#"io/read-csv("{}")"#, path)).unwrap();
+  assert_eq!(table.rows, 2);
+  assert_eq!(table.kind(), ValueKind::Table(vec![
+    ("name".to_string(), ValueKind::String),
+    ("age".to_string(), ValueKind::I8),
+    ("score".to_string(), ValueKind::F64),
+    ("active".to_string(), ValueKind::Bool),
+    ("big".to_string(), ValueKind::I32),
+    ("note".to_string(), ValueKind::Option(Box::new(ValueKind::String))),
+  ], 2));
+}
+#[test]
+fn interpret_io_read_csv_header_schema() {
+  let path = write_csv_fixture("header.csv", "id<u8>,score<f64?>\n1,\n2,3.5\n");
+  let table = interpret_csv_table(&format!(r#"io/read-csv("{}")"#, path)).unwrap();
+  assert_eq!(table.kind(), ValueKind::Table(vec![
+    ("id".to_string(), ValueKind::U8),
+    ("score".to_string(), ValueKind::Option(Box::new(ValueKind::F64))),
+  ], 2));
+  let value = interpret_with_fs(&format!(r#"t := io/read-csv("{}"); t.id[2]"#, path)).unwrap();
+  assert_eq!(value, Value::U8(Ref::new(2)));
+}
+#[test]
+fn interpret_io_read_csv_schema_argument() {
+  let path = write_csv_fixture("schema.csv", "x,y\n1,2\n3,4\n");
+  let value = interpret_with_fs(&format!(r#"t := io/read-csv("{}", <|x<f64>|>); t.x[1] + t.x[2]"#, path)).unwrap();
+  assert_eq!(value, Value::F64(Ref::new(4.0)));
+}
+#[test]
+fn interpret_io_read_tsv() {
+  let path = write_csv_fixture("tabs.tsv", "a\tb\n1\tx y\n2\tz\n");
+  let table = interpret_csv_table(&format!(r#"io/read-csv("{}")"#, path)).unwrap();
+  assert_eq!(table.kind(), ValueKind::Table(vec![("a".to_string(), ValueKind::I8), ("b".to_string(), ValueKind::String)], 2));
+}
+#[test]
+fn interpret_io_read_csv_cell_kind_error() {
+  let path = write_csv_fixture("bad.csv", "n<u8>\n1\nabc\n");
+  assert!(interpret_csv_table(&format!(r#"io/read-csv("{}")"#, path)).is_err());
+}
+#[test]
+fn interpret_io_parquet_round_trip() {
+  let path = fixture_path("round-trip.parquet");
+  let src = format!(r#"T := |id<u8> name<string> score<f64> ok<bool> delta<i64>| 1 "a" 1.5 true -3 | 2 "b" 2.5 false 4 |
+io/write-parquet("{}", T)
+io/read-parquet("{}")"#, path, path);
+  let table = interpret_csv_table(&src).unwrap();
+  assert_eq!(table.kind(), ValueKind::Table(vec![
+    ("id".to_string(), ValueKind::U8),
+    ("name".to_string(), ValueKind::String),
+    ("score".to_string(), ValueKind::F64),
+    ("ok".to_string(), ValueKind::Bool),
+    ("delta".to_string(), ValueKind::I64),
+  ], 2));
+  let value = interpret_with_fs(&format!(r#"t := io/read-parquet("{}"); t.delta[1]"#, path)).unwrap();
+  assert_eq!(value, Value::I64(Ref::new(-3)));
+  let written = interpret_with_fs(&format!(r#"w := io/write-parquet("{}", |x<f64>| 7 |); w.x[1]"#, fixture_path("returned.parquet"))).unwrap();
+  assert_eq!(written, Value::F64(Ref::new(7.0)));
+}
+#[test]
+fn interpret_io_parquet_optional_columns_are_nullable() {
+  let csv = write_csv_fixture("nullable.csv", "id<u8>,score<f64?>\n1,\n2,3.5\n");
+  let path = fixture_path("nullable.parquet");
+  let src = format!(r#"T := io/read-csv("{}")
+io/write-parquet("{}", T)
+io/read-parquet("{}")"#, csv, path, path);
+  let table = interpret_csv_table(&src).unwrap();
+  assert_eq!(table.kind(), ValueKind::Table(vec![
+    ("id".to_string(), ValueKind::U8),
+    ("score".to_string(), ValueKind::Option(Box::new(ValueKind::F64))),
+  ], 2));
+  let score = table.get(&hash_str("score")).unwrap().1.as_vec();
+  assert_eq!(score, vec![Value::Empty, Value::F64(Ref::new(3.5))]);
+}
+#[test]
+fn interpret_io_write_parquet_unsupported_kind() {
+  let path = fixture_path("unsupported.parquet");
+  let err = interpret_with_fs(&format!(r#"T := |x<u128>| 1 |; io/write-parquet("{}", T)"#, path)).unwrap_err();
+  assert!(format!("{:?}", err).contains("ArrowUnsupportedKind"));
+}
+#[test]
+fn interpret_io_read_csv_requires_capability() {
+  let path = write_csv_fixture("denied.csv", "x\n1\n");
+  let err = interpret_value(&format!(r#"io/read-csv("{}")"#, path)).unwrap_err();
+  assert_eq!(err.kind_name(), "MissingCapability");
+}
+#[test]
+fn interpret_io_read_parquet_requires_capability() {
+  let path = fixture_path("denied-read.parquet");
+  interpret_with_fs(&format!(r#"io/write-parquet("{}", |x<f64>| 1 |)"#, path)).unwrap();
+  let err = interpret_value(&format!(r#"io/read-parquet("{}")"#, path)).unwrap_err();
+  assert_eq!(err.kind_name(), "MissingCapability");
+}
+#[test]
+fn interpret_io_write_parquet_requires_capability() {
+  let path = fixture_path("denied-write.parquet");
+  let _ = std::fs::remove_file(&path);
+  let err = interpret_value(&format!(r#"io/write-parquet("{}", |x<f64>| 1 |)"#, path)).unwrap_err();
+  assert_eq!(err.kind_name(), "MissingCapability");
+  assert!(!std::path::Path::new(&path).exists());
+}
+fn interpret_value(src: &str) -> MResult<Value> {
+  let tree = parser::parse(src).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree)
+}
+test_interpreter!(interpret_io_to_json_record, r#"x := {a: 1, b: "x", c: [1 2; 3 4], d: [5 6]}; io/to-json(x)"#, Value::String(Ref::new(r#"{"a":1.0,"b":"x","c":[[1.0,2.0],[3.0,4.0]],"d":[5.0,6.0]}"#.to_string())));
+test_interpreter!(interpret_io_to_json_table, r#"T := |id<u8> name<string>| 1 "a" | 2 "b" |; io/to-json(T)"#, Value::String(Ref::new(r#"[{"id":1,"name":"a"},{"id":2,"name":"b"}]"#.to_string())));
+test_interpreter!(interpret_io_to_json_tagged, r#"x := (1u8, :foo, {true}); io/to-json(x)"#, Value::String(Ref::new(r#"{"$mech":"tuple","value":[1,{"$mech":"atom","value":"foo"},{"$mech":"set","value":[true]}]}"#.to_string())));
+test_interpreter!(interpret_io_from_json_untagged_record, r#"x := io/from-json("{\"tuple\": [1, 2]}"); x.tuple"#, Value::MatrixF64(Matrix::from_vec(vec![1.0, 2.0], 1, 2)));
+test_interpreter!(interpret_io_to_json_map, r#"x := {"a": 1, "b": 2}; io/to-json(x)"#, Value::String(Ref::new(r#"{"a":1.0,"b":2.0}"#.to_string())));
+#[test]
+fn interpret_io_json_round_trip() {
+  for src in [r#"[1 2; 3 4]"#, r#"[true false]"#, r#"{a: 1, b: "x", c: [1 2]}"#, r#"(1, "a", :foo)"#] {
+    let expected = interpret_value(src).unwrap();
+    let round_trip = interpret_value(&format!("io/from-json(io/to-json({}))", src)).unwrap();
+    assert_eq!(round_trip, expected, "{}", src);
+  }
+}
+fn interpret_with_fs(src: &str) -> MResult<Value> {
+  let tree = parser::parse(src).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.grant_capability(Capability::FileSystem);
+  intrp.interpret(&tree)
+}
+#[test]
+fn interpret_io_file_text_round_trip() {
+  let path = fixture_path("text.txt");
+  let src = format!(r#"io/write-file("{}", "héllo\nworld")
+io/read-file("{}")"#, path, path);
+  assert_eq!(interpret_with_fs(&src).unwrap(), Value::String(Ref::new("héllo\nworld".to_string())));
+}
+#[test]
+fn interpret_io_file_bytes_round_trip() {
+  let path = fixture_path("bytes.bin");
+  let src = format!(r#"x<[u8]> := [0 104 255]
+io/write-file("{}", x)
+io/read-file("{}", <[u8]>)"#, path, path);
+  assert_eq!(interpret_with_fs(&src).unwrap(), Value::MatrixU8(Matrix::from_vec(vec![0, 104, 255], 1, 3)));
+}
+#[test]
+fn interpret_io_append_file() {
+  let path = fixture_path("append.txt");
+  let src = format!(r#"io/write-file("{}", "a,")
+io/append-file("{}", "b,")
+io/append-file("{}", "c")
+io/read-file("{}")"#, path, path, path, path);
+  assert_eq!(interpret_with_fs(&src).unwrap(), Value::String(Ref::new("a,b,c".to_string())));
+}
+#[test]
+fn interpret_io_list_dir_and_exists() {
+  let dir = fixture_path("listing");
+  let _ = std::fs::remove_dir_all(&dir);
+  std::fs::create_dir(&dir).unwrap();
+  std::fs::write(format!("{}/b.txt", dir), "").unwrap();
+  std::fs::write(format!("{}/a.txt", dir), "").unwrap();
+  let names = interpret_with_fs(&format!(r#"io/list-dir("{}")"#, dir)).unwrap();
+  assert_eq!(names, Value::MatrixString(Matrix::from_vec(vec!["a.txt".to_string(), "b.txt".to_string()], 1, 2)));
+  let exists = interpret_with_fs(&format!(r#"[io/exists("{}/a.txt") io/exists("{}/c.txt")]"#, dir, dir)).unwrap();
+  assert_eq!(exists, Value::MatrixBool(Matrix::from_vec(vec![true, false], 1, 2)));
+}
+#[test]
+fn interpret_io_value_round_trip() {
+  let path = fixture_path("state.mecv");
+  for src in [r#"[1 2; 3 4]"#, r#""hi""#, r#"42<u16>"#, r#"{a: 1, b: "x", c: true}"#, r#"{1, 2, 3}"#, r#"|id<u8> name<string>| 1 "a" | 2 "b" |"#] {
+    let expected = interpret_value(src).unwrap();
+    let round_trip = interpret_with_fs(&format!(r#"x := {}
+io/write-value("{}", x)
+io/read-value("{}")"#, src, path, path)).unwrap();
+    assert_eq!(round_trip, expected, "{}", src);
+  }
+}
+#[test]
+fn interpret_io_read_file_missing() {
+  let err = interpret_with_fs(&format!(r#"io/read-file("{}")"#, fixture_path("missing.txt"))).unwrap_err();
+  assert_eq!(err.kind_name(), "FileRead");
+}
+#[test]
+fn interpret_io_write_file_non_string() {
+  let err = interpret_with_fs(&format!(r#"io/write-file("{}", 1)"#, fixture_path("number.txt"))).unwrap_err();
+  assert_eq!(err.kind_name(), "UnhandledFunctionArgumentKind1");
+}
+#[test]
+fn interpret_io_write_value_unsupported_kind() {
+  let err = interpret_with_fs(&format!(r#"io/write-value("{}", (1, 2))"#, fixture_path("tuple.mecv"))).unwrap_err();
+  assert_eq!(err.kind_name(), "ValueFileUnsupportedKind");
+}
+#[test]
+fn interpret_io_read_value_corrupt() {
+  let path = fixture_path("corrupt.mecv");
+  std::fs::write(&path, "not a value").unwrap();
+  let err = interpret_with_fs(&format!(r#"io/read-value("{}")"#, path)).unwrap_err();
+  assert_eq!(err.kind_name(), "CrcMismatch");
+}
+#[test]
+fn interpret_io_read_file_requires_capability() {
+  let path = fixture_path("denied.txt");
+  std::fs::write(&path, "secret").unwrap();
+  let err = interpret_value(&format!(r#"io/read-file("{}")"#, path)).unwrap_err();
+  assert_eq!(err.kind_name(), "MissingCapability");
+}
+test_interpreter!(interpret_io_from_json_kind_matrix, r#"io/from-json("[1,2,3]", <[u8]:1,3>)"#, Value::MatrixU8(Matrix::from_vec(vec![1, 2, 3], 1, 3)));
+test_interpreter!(interpret_io_from_json_kind_record, r#"x := io/from-json("{\"a\": 7, \"c\": null}", <{a<i32> b<string?> c<f64?>}>); x.a"#, Value::I32(Ref::new(7)));
+test_interpreter!(interpret_io_from_json_kind_map, r#"x := io/from-json("{\"1\": \"a\", \"2\": \"b\"}", <{u8:string}>); x{2u8}"#, Value::String(Ref::new("b".to_string())));
+test_interpreter!(interpret_io_from_json_kind_enum, r#"
+<response> := ok<u64> | error<string> | timeout
+r := io/from-json("{\"$mech\": \"enum\", \"name\": \"response\", \"variant\": \"ok\", \"value\": 200}", <response>)
+code := r?
+  | :ok(c) => c
+  | * => 0.
+code + 1"#, Value::F64(Ref::new(201.0)));
+fn write_json_fixture(name: &str, contents: &str) -> String {
+  let path = fixture_path(name);
+  std::fs::write(&path, contents).unwrap();
+  path
+}
+fn interpret_json_table(src: &str) -> MResult<MechTable> {
+  match interpret_value(src)? {
+    Value::Table(table) => Ok(table.borrow().clone()),
+    x => panic!("Expected a table, found {:?}", x),
+  }
+}
+#[test]
+fn interpret_io_from_json_table_with_nulls() {
+  let table = interpret_json_table(r#"io/from-json("[{\"city\": \"Oslo\", \"temp\": 3.5}, {\"city\": \"Rome\", \"temp\": null}]")"#).unwrap();
+  assert_eq!(table.kind(), ValueKind::Table(vec![
+    ("city".to_string(), ValueKind::String),
+    ("temp".to_string(), ValueKind::Option(Box::new(ValueKind::F64))),
+  ], 2));
+  let temp = table.get(&hash_str("temp")).unwrap().1.as_vec();
+  assert_eq!(temp, vec![Value::F64(Ref::new(3.5)), Value::Empty]);
+}
+#[test]
+fn interpret_io_from_json_kind_table() {
+  let table = interpret_json_table(r#"io/from-json("[{\"id\": 1}, {\"id\": 2.0}]", <|id<i32>|>)"#).unwrap();
+  assert_eq!(table.get(&hash_str("id")).unwrap().1.as_vec(), vec![Value::I32(Ref::new(1)), Value::I32(Ref::new(2))]);
+}
+test_interpreter_error!(interpret_io_from_json_out_of_range, r#"io/from-json("[300]", <[u8]>)"#, "JsonKindMismatch");
+test_interpreter_error!(interpret_io_from_json_missing_field, r#"io/from-json("{\"a\": 1}", <{a<u8> b<string>}>)"#, "JsonMissingField");
+test_interpreter_error!(interpret_io_from_json_shape_mismatch, r#"io/from-json("[1, 2]", <[u8]:1,3>)"#, "JsonShape");
+test_interpreter_error!(interpret_io_from_json_malformed, r#"io/from-json("{bad")"#, "JsonParse");
+#[test]
+fn interpret_io_read_json() {
+  let path = write_json_fixture("read.json", r#"{"name": "mech", "tags": ["a", "b"]}"#);
+  let value = interpret_with_fs(&format!(r#"x := io/read-json("{}"); x.tags"#, path)).unwrap();
+  assert_eq!(value, Value::MatrixString(Matrix::from_vec(vec!["a".to_string(), "b".to_string()], 1, 2)));
+}
+#[test]
+fn interpret_io_read_json_requires_capability() {
+  let path = write_json_fixture("denied.json", "[1, 2]");
+  let err = interpret_value(&format!(r#"io/read-json("{}")"#, path)).unwrap_err();
+  assert_eq!(err.kind_name(), "MissingCapability");
+}
+
+fn persist_dir(name: &str) -> String {
+  let dir = fixture_path(name);
+  let _ = std::fs::remove_dir_all(&dir);
+  dir
+}
+fn persist_session(dir: &str, src: &str) -> (Interpreter, Persister) {
+  let tree = parser::parse(src).unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  let mut persister = Persister::open(dir).unwrap();
+  persister.restore(&intrp).unwrap();
+  persister.record(&intrp).unwrap();
+  (intrp, persister)
+}
+fn symbol_value(intrp: &Interpreter, name: &str) -> Value {
+  intrp.symbols().borrow().get(hash_str(name)).unwrap().borrow().clone()
+}
+#[test]
+fn interpret_persist_restore() {
+  let dir = persist_dir("persist-restore");
+  persist_session(&dir, "~count := 0; count = count + 10; ~name := \"a\"; name = \"b\"");
+  let (intrp, _) = persist_session(&dir, "~count := 0; ~name := \"a\"; total := 1");
+  assert_eq!(symbol_value(&intrp, "count"), Value::F64(Ref::new(10.0)));
+  assert_eq!(symbol_value(&intrp, "name"), Value::String(Ref::new("b".to_string())));
+}
+#[test]
+fn interpret_persist_restore_reaches_plan() {
+  let dir = persist_dir("persist-plan");
+  persist_session(&dir, "~x := 1; x = 5");
+  let (mut intrp, _) = persist_session(&dir, "~x := 1; y := x * 2");
+  assert_eq!(symbol_value(&intrp, "y"), Value::F64(Ref::new(2.0)));
+  intrp.step(0, 1).unwrap();
+  assert_eq!(symbol_value(&intrp, "y"), Value::F64(Ref::new(10.0)));
+}
+#[test]
+fn interpret_persist_restore_solves_dependents() {
+  let dir = persist_dir("persist-dependents");
+  persist_session(&dir, "~x := 1; x = 5");
+  let tree = parser::parse("~x := 1; y := x * 2").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  let (_, result) = mech::persist_mech_code(&mut intrp, &dir, None).unwrap();
+  assert_eq!(result, Some(Value::F64(Ref::new(10.0))));
+  assert_eq!(symbol_value(&intrp, "y"), Value::F64(Ref::new(10.0)));
+}
+#[test]
+fn interpret_persist_skips_unsupported_kinds() {
+  let dir = persist_dir("persist-unsupported");
+  let tree = parser::parse("~f := \\(x) => x + 1; ~n := 1").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  let mut persister = Persister::open(&dir).unwrap();
+  assert_eq!(persister.record(&intrp).unwrap(), 1);
+  assert_eq!(persister.entries.keys().collect::<Vec<_>>(), vec!["n"]);
+  assert_eq!(persister.skipped.keys().collect::<Vec<_>>(), vec!["f"]);
+}
+#[test]
+fn interpret_persist_restore_table() {
+  let dir = persist_dir("persist-table");
+  persist_session(&dir, "~T := |a<f64> b<string>| 1 \"x\" | 2 \"y\" | 3 \"z\" |");
+  let (intrp, _) = persist_session(&dir, "~T := |a<f64> b<string>| 7 \"w\" |");
+  let expected = interpret_value("|a<f64> b<string>| 1 \"x\" | 2 \"y\" | 3 \"z\" |").unwrap();
+  assert_eq!(symbol_value(&intrp, "T"), expected);
+}
+#[test]
+fn interpret_persist_selected_symbols() {
+  let dir = persist_dir("persist-selected");
+  let tree = parser::parse("~a := 1; ~b := 2").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  let mut persister = Persister::open(&dir).unwrap().with_symbols(vec!["b".to_string()]);
+  assert_eq!(persister.record(&intrp).unwrap(), 1);
+  assert_eq!(persister.entries.keys().collect::<Vec<_>>(), vec!["b"]);
+  // Unchanged values are not logged again.
+  assert_eq!(persister.record(&intrp).unwrap(), 0);
+}
+#[test]
+fn interpret_persist_compaction() {
+  let dir = persist_dir("persist-compact");
+  let tree = parser::parse("~x := 1; x = 2").unwrap();
+  let mut intrp = Interpreter::new(0);
+  intrp.interpret(&tree).unwrap();
+  let mut persister = Persister::open(&dir).unwrap().with_compact_after(3);
+  for i in 0..5 {
+    *intrp.symbols().borrow().get(hash_str("x")).unwrap().borrow_mut() = Value::F64(Ref::new(i as f64));
+    persister.record(&intrp).unwrap();
+  }
+  assert_eq!(persister.log_len(), 2);
+  assert!(std::path::Path::new(&dir).join(PERSIST_SNAPSHOT_FILE).exists());
+  drop(persister);
+  // A torn write at the end of the log is dropped on replay.
+  let log_path = std::path::Path::new(&dir).join(PERSIST_LOG_FILE);
+  let mut log = std::fs::read_to_string(&log_path).unwrap();
+  log.push_str(r#"{"seq":99,"name":"x","#);
+  std::fs::write(&log_path, log).unwrap();
+  let mut persister = Persister::open(&dir).unwrap();
+  assert_eq!(persister.entries["x"].value, serde_json::json!(4.0));
+  assert_eq!(persister.log_len(), 2);
+  // The torn line is cut off, so the next entry lands on a line of its own.
+  *intrp.symbols().borrow().get(hash_str("x")).unwrap().borrow_mut() = Value::F64(Ref::new(7.0));
+  assert_eq!(persister.record(&intrp).unwrap(), 1);
+  drop(persister);
+  let persister = Persister::open(&dir).unwrap();
+  assert_eq!(persister.entries["x"].value, serde_json::json!(7.0));
+  assert_eq!(persister.log_len(), 3);
+}
+#[test]
+fn interpret_persist_corrupt_log() {
+  let dir = persist_dir("persist-corrupt");
+  std::fs::create_dir_all(&dir).unwrap();
+  std::fs::write(std::path::Path::new(&dir).join(PERSIST_LOG_FILE), "not json\n{\"seq\":1,\"name\":\"x\",\"kind\":\"F64\",\"value\":1.0}\n").unwrap();
+  let err = Persister::open(&dir).unwrap_err();
+  assert_eq!(err.kind_name(), "PersistCorruptLog");
+}
 
 test_interpreter!(interpret_table_record_mutation, r#"~T:=|x<f64> y<bool>|1.2 true|1.3 false|;~r:=T[1];r.x=42;T.x[1]"#, Value::F64(Ref::new(42.0)));
+test_interpreter!(interpret_table_record_mutation_bool, r#"~T:=|x<u8> y<bool>|1 true|2 false|;~r:=T[2];r.y=true;T.y[2]"#, Value::Bool(Ref::new(true)));
+#[test]
+fn interpret_table_columns_are_stored_typed() {
+  let tree = parser::parse(r#"|x<f64> y<u8> z<string> w<bool> v<*>| 1 2 "a" true 1 | 3 4 "b" false "c" |"#).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let table = match intrp.interpret(&tree).unwrap() {
+    Value::Table(table) => table,
+    x => panic!("Expected a table, found {:?}", x),
+  };
+  let table = table.borrow();
+  assert!(matches!(table.data[&hash_str("x")].1, Column::F64(_)));
+  assert!(matches!(table.data[&hash_str("y")].1, Column::U8(_)));
+  assert!(matches!(table.data[&hash_str("z")].1, Column::String(_)));
+  assert!(matches!(table.data[&hash_str("w")].1, Column::Bool(_)));
+  assert!(matches!(table.data[&hash_str("v")].1, Column::Value(_)));
+}
+fn long_table(rows: usize) -> MechTable {
+  let a = (1..=rows).map(|i| Value::F64(Ref::new(i as f64))).collect();
+  let b = (1..=rows).map(|i| if i % 4 == 0 { Value::Empty } else { Value::String(Ref::new(format!("s{}", i % 7))) }).collect();
+  MechTable::new_table(vec!["a".to_string(), "b".to_string()], vec![ValueKind::F64, ValueKind::Option(Box::new(ValueKind::String))], vec![a, b])
+}
+#[test]
+fn interpret_table_column_summaries() {
+  let summaries = long_table(22).column_summaries();
+  assert_eq!(summaries[0].name, "a");
+  assert_eq!(summaries[0].min, Some(Value::F64(Ref::new(1.0))));
+  assert_eq!(summaries[0].max, Some(Value::F64(Ref::new(22.0))));
+  assert_eq!(summaries[0].nulls, 0);
+  assert_eq!(summaries[1].kind, ValueKind::Option(Box::new(ValueKind::String)));
+  assert_eq!(summaries[1].min, Some(Value::String(Ref::new("s0".to_string()))));
+  assert_eq!(summaries[1].max, Some(Value::String(Ref::new("s6".to_string()))));
+  assert_eq!(summaries[1].nulls, 5);
+}
+#[test]
+fn interpret_table_html_truncates_long_tables() {
+  let short = long_table(TABLE_MAX_ROWS).to_html();
+  assert_eq!(short.matches("<tr class=\"mech-table-row\">").count(), TABLE_MAX_ROWS);
+  assert!(!short.contains("mech-table-summary"));
+  let long = long_table(100).to_html();
+  assert_eq!(long.matches("<tr class=\"mech-table-row\">").count(), 2 * TABLE_PREVIEW_ROWS);
+  assert!(long.contains("90 more rows"));
+  assert!(long.contains("mech-table-summary"));
+  assert!(long_table(100).pretty_print().ends_with("90 more rows"));
+}
+#[test]
+fn interpret_table_html_page() {
+  let table = long_table(22);
+  let page = table.to_html_page(1, 10);
+  assert_eq!(page.matches("<tr class=\"mech-table-row\">").count(), 10);
+  assert!(page.contains("Rows 11–20 of 22"));
+  assert!(page.contains("<button class=\"mech-table-page-prev\" data-page=\"0\">"));
+  assert!(page.contains("<button class=\"mech-table-page-next\" data-page=\"2\">"));
+  let last = table.to_html_page(5, 10);
+  assert_eq!(last.matches("<tr class=\"mech-table-row\">").count(), 2);
+  assert!(last.contains("<button class=\"mech-table-page-next\" disabled>"));
+  assert!(!long_table(5).to_html_page(0, 10).contains("mech-table-pager"));
+}
+#[test]
+fn interpret_block_page_size_config() {
+  let tree = parser::parse("Title\n======\n\n```mech{page-size: \"25\"}\nx := 1\n```\n").unwrap();
+  let debug = format!("{:?}", tree);
+  assert!(debug.contains("page_size: Some(25)"), "{}", debug);
+}
 //test_interpreter!("interpret_table_record_mutation_fail", r#"T := | x<f64>  y<bool> |  1.2     true   |  1.3     false  |;~r := T{1};r.x = 42;T.x[1]"#, Value::F64(Ref::new(1.2)));
 
 test_interpreter!(interpret_define_custom_enum, r#"<color>:=:red|:green|:blue; x<color>:=:red;"#, Value::Atom(Ref::new(MechAtom::new(hash_str("red")))));
@@ -1450,6 +2653,42 @@ unwrap(x)
   assert!(msg.contains(":none"));
   assert!(msg.contains("wildcard"));
 }
+#[test]
+fn interpret_function_match_guarded_arm_is_partial() {
+  let s = r#"
+<light> := :red | :green
+x<light> := :red
+
+wait(x<light>, n<f64>) => <f64>
+  | (:red, n), n > 10 => 0
+  | (:green, n) => 1.
+
+wait(x, 5)
+"#;
+  let tree = parser::parse(s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  assert!(format!("{:?}", err).contains("FunctionOutputUndefined"));
+}
+#[test]
+fn interpret_function_match_guarded_enum_arm_not_exhaustive() {
+  let s = r#"
+<light> := :red | :green
+x<light> := :red
+
+go(x<light>) => <f64>
+  | :red, 1 > 0 => 0
+  | :green => 1.
+
+go(x)
+"#;
+  let tree = parser::parse(s).unwrap();
+  let mut intrp = Interpreter::new(0);
+  let err = intrp.interpret(&tree).unwrap_err();
+  let msg = format!("{:?}", err);
+  assert!(msg.contains("FunctionMatchNonExhaustive"));
+  assert!(msg.contains(":red"));
+}
 test_interpreter!(interpret_enum_qualified_name, r#"
 <color> := :red | :green | :blue; 
 x<color> := :red; 
@@ -1460,6 +2699,27 @@ test_interpreter!(interpret_string_concatenation_var, r#"greeting := "Hello"; na
 test_interpreter!(interpret_string_concatenation_matrix, r#"["a" "b"] + "c""#, Value::MatrixString(Matrix::from_vec(vec!["ac".to_string(), "bc".to_string()], 1, 2)));
 test_interpreter!(interpret_string_concatenation_matrix2, r#"["a" "b"; "c" "d"] + ["1" "2"; "3" "4"]"#, Value::MatrixString(Matrix::from_vec(vec!["a1".to_string(), "c3".to_string(), "b2".to_string(), "d4".to_string()], 2, 2)));
 test_interpreter!(interpret_string_concatenation_matrix3, r#"prefix := "Item"; letters := ["A" "B" "C"]; labels := prefix + letters"#, Value::MatrixString(Matrix::from_vec(vec!["ItemA".to_string(), "ItemB".to_string(), "ItemC".to_string()], 1, 3)));
+test_interpreter!(interpret_string_length_graphemes, r#"string/length(["née" "👩‍🚀!" ""])"#, Value::MatrixU64(Matrix::from_vec(vec![3, 2, 0], 1, 3)));
+test_interpreter!(interpret_string_case, r#"string/to-uppercase(string/to-lowercase(["MiXed" "ß"]))"#, Value::MatrixString(Matrix::from_vec(vec!["MIXED".to_string(), "SS".to_string()], 1, 2)));
+test_interpreter!(interpret_string_trim_pad, r#"[string/trim("  a  ") string/trim-start(" b ") string/trim-end(" c ") string/pad-left("7", 3, "0") string/pad-right("ab", 4)]"#, Value::MatrixString(Matrix::from_vec(vec!["a".to_string(), "b ".to_string(), " c".to_string(), "007".to_string(), "ab  ".to_string()], 1, 5)));
+test_interpreter!(interpret_string_search, r#"x := ["apple" "banana" "cherry"]; [string/contains(x, "an"); string/starts-with(x, "b"); string/ends-with(x, "y")]"#, Value::MatrixBool(Matrix::from_vec(vec![false, false, false, true, true, false, false, false, true], 3, 3)));
+test_interpreter!(interpret_string_search_broadcast, r#"string/contains("banana", ["nan" "x"])"#, Value::MatrixBool(Matrix::from_vec(vec![true, false], 1, 2)));
+test_interpreter!(interpret_string_find, r#"[string/find("née-👩‍🚀!", "!") string/find("abc", "z") string/find("a1b22", "[0-9]{2}", true)]"#, Value::MatrixU64(Matrix::from_vec(vec![6, 0, 4], 1, 3)));
+test_interpreter!(interpret_string_regex_match, r#"[string/starts-with("Mech", "[A-Z]", true) string/ends-with("v0.3", "[0-9]", true) string/contains("abc", "^b", true)]"#, Value::MatrixBool(Matrix::from_vec(vec![true, true, false], 1, 3)));
+test_interpreter!(interpret_string_replace, r##"[string/replace("a.b.c", ".", "-") string/replace("x12y345", "[0-9]+", "#", true) string/replace("2024-05", """(\d+)-(\d+)""", "$2/$1", true)]"##, Value::MatrixString(Matrix::from_vec(vec!["a-b-c".to_string(), "x#y#".to_string(), "05/2024".to_string()], 1, 3)));
+test_interpreter!(interpret_string_split, r#"string/split("a,b,,c", ",")"#, Value::MatrixString(Matrix::from_vec(vec!["a".to_string(), "b".to_string(), "".to_string(), "c".to_string()], 1, 4)));
+test_interpreter!(interpret_string_split_graphemes, r#"string/split("né👩‍🚀", "")"#, Value::MatrixString(Matrix::from_vec(vec!["n".to_string(), "é".to_string(), "👩‍🚀".to_string()], 1, 3)));
+test_interpreter!(interpret_string_split_matrix, r#"string/split(["a:1"; "b:2"; "c:3"], "\\s*:\\s*", true)"#, Value::MatrixString(Matrix::from_vec(vec!["a".to_string(), "b".to_string(), "c".to_string(), "1".to_string(), "2".to_string(), "3".to_string()], 3, 2)));
+test_interpreter!(interpret_string_join, r#"[string/join(["x" "y" "z"], ", ") string/join(["a";"b"])]"#, Value::MatrixString(Matrix::from_vec(vec!["x, y, z".to_string(), "ab".to_string()], 1, 2)));
+test_interpreter!(interpret_string_join_rows, r#"string/join(string/split(["a=1"; "b=2"], "="), ":")"#, Value::MatrixString(Matrix::from_vec(vec!["a:1".to_string(), "b:2".to_string()], 2, 1)));
+test_interpreter!(interpret_string_format, r#"string/format("{2}, {1}! {{{3}}}", "world", "Hello", 42)"#, Value::String(Ref::new("Hello, world! {42}".to_string())));
+test_interpreter!(interpret_string_format_broadcast, r#"string/format("{1}: {2}", ["a" "b"], [1 2])"#, Value::MatrixString(Matrix::from_vec(vec!["a: 1".to_string(), "b: 2".to_string()], 1, 2)));
+test_interpreter_error!(interpret_string_format_missing_argument, r#"string/format("{2}", "a")"#, "StringFormat");
+test_interpreter_error!(interpret_string_format_named_placeholder, r#"string/format("{x}", "a")"#, "StringFormat");
+test_interpreter_error!(interpret_string_replace_invalid_regex, r#"string/replace("a", "(", "b", true)"#, "StringRegex");
+test_interpreter_error!(interpret_string_contains_shape_mismatch, r#"string/contains(["a" "b"], ["a" "b" "c"])"#, "StringShapeMismatch");
+test_interpreter_error!(interpret_string_split_ragged_rows, r#"string/split(["a,b"; "c"], ",")"#, "StringSplitShape");
+test_interpreter_error!(interpret_string_pad_left_multi_char_fill, r#"string/pad-left("a", 3, "ab")"#, "StringPadFill");
 test_interpreter!(interpret_string_raw_literal, r#""""C:\Users\Name\Documents""""#, Value::String(Ref::new(r"C:\Users\Name\Documents".to_string())));
 test_interpreter!(interpret_string_raw_literal_multiline, r#""""This is a raw string literal.It can span multiple lines
 and include "quotes" and \backslashes\ without needing escapes.""""#, Value::String(Ref::new(r#"This is a raw string literal.It can span multiple lines